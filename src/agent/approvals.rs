@@ -0,0 +1,143 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::sync::{oneshot, Notify};
+
+use crate::tools::ApprovalVerdict;
+
+/// Turns currently holding a gated tool call, keyed by queued approval id.
+/// `Agent::resolve_approval` delivers the operator's decision here first and
+/// only re-runs the call itself when no turn is waiting.
+#[derive(Default)]
+pub struct ApprovalWaiters {
+    pending: Mutex<HashMap<String, oneshot::Sender<bool>>>,
+    stopped: Notify,
+}
+
+impl ApprovalWaiters {
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, oneshot::Sender<bool>>> {
+        self.pending
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Wait up to `limit` for a decision on `id`, giving up as `Undecided`
+    /// once `is_stopped` holds after a `notify_stop`. A later waiter on the
+    /// same id replaces an earlier one, which then gives up as `Undecided`.
+    pub async fn wait(
+        &self,
+        id: &str,
+        limit: Duration,
+        is_stopped: impl Fn() -> bool,
+    ) -> ApprovalVerdict {
+        let (tx, mut rx) = oneshot::channel();
+        self.lock().insert(id.to_string(), tx);
+        let deadline = tokio::time::Instant::now() + limit;
+        let decision = loop {
+            let stopped = self.stopped.notified();
+            tokio::pin!(stopped);
+            stopped.as_mut().enable();
+            if is_stopped() {
+                break None;
+            }
+            tokio::select! {
+                decision = &mut rx => break decision.ok(),
+                _ = tokio::time::sleep_until(deadline) => break None,
+                _ = &mut stopped => {}
+            }
+        };
+        match decision {
+            Some(true) => ApprovalVerdict::Approved,
+            Some(false) => ApprovalVerdict::Denied,
+            None => {
+                drop(rx);
+                let mut pending = self.lock();
+                if pending.get(id).is_some_and(|tx| tx.is_closed()) {
+                    pending.remove(id);
+                }
+                ApprovalVerdict::Undecided
+            }
+        }
+    }
+
+    /// Wake every waiting turn so it re-checks its stop condition.
+    pub fn notify_stop(&self) {
+        self.stopped.notify_waiters();
+    }
+
+    /// Hand a decision to the turn waiting on `id`. Returns false when no turn
+    /// is waiting, so the caller has to act on the decision itself.
+    pub fn deliver(&self, id: &str, approved: bool) -> bool {
+        self.lock()
+            .remove(id)
+            .is_some_and(|tx| tx.send(approved).is_ok())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn waiting_turn_receives_the_operator_decision() {
+        let waiters = Arc::new(ApprovalWaiters::default());
+        let waiting = {
+            let waiters = Arc::clone(&waiters);
+            tokio::spawn(async move { waiters.wait("a1", Duration::from_secs(5), || false).await })
+        };
+        while !waiters.lock().contains_key("a1") {
+            tokio::task::yield_now().await;
+        }
+
+        assert!(waiters.deliver("a1", false));
+        assert_eq!(waiting.await.unwrap(), ApprovalVerdict::Denied);
+        assert!(!waiters.deliver("a1", true));
+    }
+
+    #[tokio::test]
+    async fn wait_gives_up_after_the_limit() {
+        let waiters = ApprovalWaiters::default();
+        let verdict = waiters
+            .wait("a2", Duration::from_millis(10), || false)
+            .await;
+
+        assert_eq!(verdict, ApprovalVerdict::Undecided);
+        assert!(!waiters.deliver("a2", true));
+    }
+
+    #[tokio::test]
+    async fn stopping_unblocks_a_pending_wait() {
+        let waiters = Arc::new(ApprovalWaiters::default());
+        let generation = Arc::new(AtomicU64::new(0));
+        let waiting = {
+            let waiters = Arc::clone(&waiters);
+            let generation = Arc::clone(&generation);
+            tokio::spawn(async move {
+                waiters
+                    .wait("a3", Duration::from_secs(600), move || {
+                        generation.load(Ordering::SeqCst) != 0
+                    })
+                    .await
+            })
+        };
+        while !waiters.lock().contains_key("a3") {
+            tokio::task::yield_now().await;
+        }
+
+        // A notify without a stop keeps the turn waiting.
+        waiters.notify_stop();
+        tokio::task::yield_now().await;
+        assert!(!waiting.is_finished());
+
+        generation.fetch_add(1, Ordering::SeqCst);
+        waiters.notify_stop();
+        let verdict = tokio::time::timeout(Duration::from_secs(5), waiting)
+            .await
+            .expect("stop should end the wait")
+            .unwrap();
+        assert_eq!(verdict, ApprovalVerdict::Undecided);
+        assert!(!waiters.deliver("a3", true));
+    }
+}
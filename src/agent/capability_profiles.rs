@@ -1,5 +1,11 @@
-use crate::config::{AgentConfig, CapabilityProfileConfig, CapabilityProfileOverride};
-use crate::tools::ToolContext;
+use crate::config::{
+    AgentConfig, CapabilityProfileConfig, CapabilityProfileOverride, CustomCapabilityProfile,
+};
+use crate::tools::agentic::AgenticConfig;
+use crate::tools::{ToolCategory, ToolContext, ToolTimeoutPolicy};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::Duration;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum AgentCapabilityProfile {
@@ -14,6 +20,42 @@ pub enum AgentCapabilityProfile {
     Dream,
 }
 
+impl AgentCapabilityProfile {
+    pub const ALL: [Self; 9] = [
+        Self::PrivateChat,
+        Self::Scheduled,
+        Self::Background,
+        Self::SelfDirected,
+        Self::Loose,
+        Self::SkillEvents,
+        Self::Heartbeat,
+        Self::Ambient,
+        Self::Dream,
+    ];
+
+    /// Profile name, matching its `capability_profiles` config key.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::PrivateChat => "private_chat",
+            Self::Scheduled => "scheduled",
+            Self::Background => "background",
+            Self::SelfDirected => "self_directed",
+            Self::Loose => "loose",
+            Self::SkillEvents => "skill_events",
+            Self::Heartbeat => "heartbeat",
+            Self::Ambient => "ambient",
+            Self::Dream => "dream",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.trim();
+        Self::ALL
+            .into_iter()
+            .find(|profile| profile.name().eq_ignore_ascii_case(name))
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ToolCapabilityPolicy {
     pub autonomous: bool,
@@ -26,6 +68,7 @@ impl ToolCapabilityPolicy {
     fn into_tool_context(self, working_directory: String, username: String) -> ToolContext {
         ToolContext {
             working_directory,
+            allowed_roots: Vec::new(),
             username,
             conversation_id: None,
             autonomous: self.autonomous,
@@ -34,6 +77,9 @@ impl ToolCapabilityPolicy {
             disallowed_tools: self.disallowed_tools,
             outbound_action_rate_limit: None,
             generation_observer: None,
+            progress: None,
+            approval_waiter: None,
+            timeouts: ToolTimeoutPolicy::default(),
         }
     }
 }
@@ -44,16 +90,228 @@ pub fn build_tool_context_for_profile(
     working_directory: String,
     username: String,
 ) -> ToolContext {
-    resolve_capability_policy(profile, &config.capability_profiles)
-        .into_tool_context(working_directory, username)
+    let mut context = resolve_capability_policy(profile, &config.capability_profiles)
+        .into_tool_context(working_directory, username);
+    context.timeouts = tool_timeout_policy(config);
+    context.allowed_roots = config
+        .file_tool_allowed_roots
+        .iter()
+        .map(PathBuf::from)
+        .collect();
+    context
+}
+
+/// Resolve `tool_timeout_secs` / `tool_category_timeout_secs` into the
+/// registry's execution deadlines. Zero disables a deadline; unknown category
+/// keys are ignored with a warning.
+pub fn tool_timeout_policy(config: &AgentConfig) -> ToolTimeoutPolicy {
+    let default =
+        (config.tool_timeout_secs > 0).then(|| Duration::from_secs(config.tool_timeout_secs));
+    let mut by_category = std::collections::HashMap::new();
+    for (key, secs) in &config.tool_category_timeout_secs {
+        let Some(category) = ToolCategory::from_config_key(key) else {
+            tracing::warn!("Ignoring tool timeout for unknown category '{}'", key);
+            continue;
+        };
+        if *secs > 0 {
+            by_category.insert(category, Duration::from_secs(*secs));
+        }
+    }
+    ToolTimeoutPolicy {
+        default,
+        by_category,
+    }
+}
+
+/// A profile selected by name: either a built-in profile or a custom profile
+/// layered over the built-in profile of the loop that runs it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResolvedCapabilityProfile {
+    pub name: String,
+    pub policy: ToolCapabilityPolicy,
+    pub system_prompt_suffix: Option<String>,
+    pub temperature: Option<f32>,
+    pub max_tokens: Option<u32>,
+}
+
+/// Resolve `name` to a built-in profile or an entry in
+/// `custom_capability_profiles`. Custom profiles start from `base`'s resolved
+/// policy and can only narrow it: a non-empty allow-list is intersected with
+/// the base allow-list, and the deny-list is added to the base deny-list.
+/// Autonomy always comes from `base`. Returns `None` for unknown names.
+pub fn resolve_named_profile(
+    config: &AgentConfig,
+    name: &str,
+    base: AgentCapabilityProfile,
+) -> Option<ResolvedCapabilityProfile> {
+    if let Some(profile) = AgentCapabilityProfile::from_name(name) {
+        return Some(ResolvedCapabilityProfile {
+            name: profile.name().to_string(),
+            policy: resolve_capability_policy(profile, &config.capability_profiles),
+            system_prompt_suffix: None,
+            temperature: None,
+            max_tokens: None,
+        });
+    }
+    let (name, custom) = find_custom_profile(&config.custom_capability_profiles, name)?;
+    let mut policy = resolve_capability_policy(base, &config.capability_profiles);
+    let allowed = normalize_tool_names(&custom.allowed_tools);
+    if !allowed.is_empty() {
+        policy.allowed_tools = Some(match policy.allowed_tools {
+            Some(base_allowed) => allowed
+                .into_iter()
+                .filter(|tool| {
+                    base_allowed
+                        .iter()
+                        .any(|existing| existing.eq_ignore_ascii_case(tool))
+                })
+                .collect(),
+            None => allowed,
+        });
+    }
+    policy
+        .disallowed_tools
+        .extend(custom.disallowed_tools.iter().cloned());
+    policy.disallowed_tools = normalize_tool_names(&policy.disallowed_tools);
+
+    Some(ResolvedCapabilityProfile {
+        name: name.to_string(),
+        policy,
+        system_prompt_suffix: custom
+            .system_prompt_suffix
+            .as_deref()
+            .map(str::trim)
+            .filter(|suffix| !suffix.is_empty())
+            .map(str::to_string),
+        temperature: custom
+            .temperature
+            .filter(|temperature| (0.0..=2.0).contains(temperature)),
+        max_tokens: custom.max_tokens.filter(|max_tokens| *max_tokens > 0),
+    })
+}
+
+/// Whether `name` resolves to a built-in or configured custom profile.
+pub fn is_known_profile_name(config: &AgentConfig, name: &str) -> bool {
+    AgentCapabilityProfile::from_name(name).is_some()
+        || find_custom_profile(&config.custom_capability_profiles, name).is_some()
+}
+
+fn find_custom_profile<'a>(
+    profiles: &'a HashMap<String, CustomCapabilityProfile>,
+    name: &str,
+) -> Option<(&'a str, &'a CustomCapabilityProfile)> {
+    let name = name.trim();
+    if name.is_empty() {
+        return None;
+    }
+    profiles
+        .iter()
+        .find(|(key, _)| key.trim().eq_ignore_ascii_case(name))
+        .map(|(key, profile)| (key.trim(), profile))
+}
+
+/// Problems in `custom_capability_profiles`, one message each: names that
+/// shadow a built-in profile, tool names absent from `known_tools`, and
+/// out-of-range overrides (which resolution ignores).
+pub fn validate_custom_profiles(
+    profiles: &HashMap<String, CustomCapabilityProfile>,
+    known_tools: &[String],
+) -> Vec<String> {
+    let mut names: Vec<&String> = profiles.keys().collect();
+    names.sort();
+    let mut problems = Vec::new();
+    for name in names {
+        let profile = &profiles[name];
+        if name.trim().is_empty() {
+            problems.push("Custom capability profile with an empty name is ignored".to_string());
+            continue;
+        }
+        if AgentCapabilityProfile::from_name(name).is_some() {
+            problems.push(format!(
+                "Custom capability profile '{}' shadows a built-in profile and is ignored",
+                name
+            ));
+            continue;
+        }
+        for tool in profile
+            .allowed_tools
+            .iter()
+            .chain(&profile.disallowed_tools)
+            .map(|tool| tool.trim())
+            .filter(|tool| !tool.is_empty())
+        {
+            if !known_tools
+                .iter()
+                .any(|known| known.eq_ignore_ascii_case(tool))
+            {
+                problems.push(format!(
+                    "Custom capability profile '{}' references unknown tool '{}'",
+                    name, tool
+                ));
+            }
+        }
+        if let Some(temperature) = profile.temperature {
+            if !(0.0..=2.0).contains(&temperature) {
+                problems.push(format!(
+                    "Custom capability profile '{}' temperature {} is outside 0.0-2.0 and is ignored",
+                    name, temperature
+                ));
+            }
+        }
+        if profile.max_tokens == Some(0) {
+            problems.push(format!(
+                "Custom capability profile '{}' max_tokens of 0 is ignored",
+                name
+            ));
+        }
+    }
+    problems
 }
 
 pub fn resolve_capability_policy(
     profile: AgentCapabilityProfile,
     config: &CapabilityProfileConfig,
 ) -> ToolCapabilityPolicy {
-    let default_policy = default_policy(profile);
-    let override_cfg = match profile {
+    apply_override(default_policy(profile), profile_override(profile, config))
+}
+
+/// Working-memory tags configured to scope `profile`'s context; empty means unscoped.
+pub fn memory_tags_for_profile(
+    profile: AgentCapabilityProfile,
+    config: &CapabilityProfileConfig,
+) -> Vec<String> {
+    profile_override(profile, config)
+        .memory_tags
+        .clone()
+        .unwrap_or_default()
+}
+
+/// Layer `profile`'s configured sampling over a loop's built-in values:
+/// valid `temperature` / `max_tokens` overrides replace the hardcoded
+/// defaults, and `sampling_schedule` is handed to the loop for later turns.
+pub fn apply_profile_sampling(
+    loop_config: &mut AgenticConfig,
+    profile: AgentCapabilityProfile,
+    config: &CapabilityProfileConfig,
+) {
+    let override_cfg = profile_override(profile, config);
+    if let Some(temperature) = override_cfg
+        .temperature
+        .filter(|temperature| (0.0..=2.0).contains(temperature))
+    {
+        loop_config.temperature = temperature;
+    }
+    if let Some(max_tokens) = override_cfg.max_tokens.filter(|max_tokens| *max_tokens > 0) {
+        loop_config.max_tokens = max_tokens;
+    }
+    loop_config.sampling_schedule = override_cfg.sampling_schedule.clone();
+}
+
+fn profile_override(
+    profile: AgentCapabilityProfile,
+    config: &CapabilityProfileConfig,
+) -> &CapabilityProfileOverride {
+    match profile {
         AgentCapabilityProfile::PrivateChat => &config.private_chat,
         AgentCapabilityProfile::Scheduled => &config.scheduled,
         AgentCapabilityProfile::Background => &config.background,
@@ -63,8 +321,7 @@ pub fn resolve_capability_policy(
         AgentCapabilityProfile::Heartbeat => &config.heartbeat,
         AgentCapabilityProfile::Ambient => &config.ambient,
         AgentCapabilityProfile::Dream => &config.dream,
-    };
-    apply_override(default_policy, override_cfg)
+    }
 }
 
 fn default_policy(profile: AgentCapabilityProfile) -> ToolCapabilityPolicy {
@@ -111,6 +368,8 @@ fn default_policy(profile: AgentCapabilityProfile) -> ToolCapabilityPolicy {
             disallowed_tools: vec![
                 "write_file".to_string(),
                 "patch_file".to_string(),
+                "move_file".to_string(),
+                "delete_file".to_string(),
                 "shell".to_string(),
                 "write_memory".to_string(),
                 "publish_media_to_chat".to_string(),
@@ -164,6 +423,44 @@ fn normalize_tool_names(items: &[String]) -> Vec<String> {
 mod tests {
     use super::*;
     use crate::config::AgentConfig;
+    use crate::config::SamplingScheduleStep;
+
+    #[test]
+    fn profile_sampling_overrides_defaults_and_threads_the_schedule() {
+        let mut profiles = CapabilityProfileConfig::default();
+        profiles.background.temperature = Some(0.5);
+        profiles.background.max_tokens = Some(0);
+        profiles.background.sampling_schedule = vec![
+            SamplingScheduleStep {
+                after_continuations: 2,
+                temperature: Some(0.2),
+                max_tokens: None,
+            },
+            SamplingScheduleStep {
+                after_continuations: 5,
+                temperature: Some(0.05),
+                max_tokens: Some(1024),
+            },
+        ];
+        let mut loop_config = AgenticConfig {
+            temperature: 0.35,
+            max_tokens: 2048,
+            ..AgenticConfig::default()
+        };
+        apply_profile_sampling(
+            &mut loop_config,
+            AgentCapabilityProfile::Background,
+            &profiles,
+        );
+
+        assert_eq!(loop_config.sampling_for_turn(1), (0.5, 2048));
+        assert_eq!(loop_config.sampling_for_turn(3), (0.2, 2048));
+        assert_eq!(loop_config.sampling_for_turn(9), (0.05, 1024));
+
+        let mut untouched = AgenticConfig::default();
+        apply_profile_sampling(&mut untouched, AgentCapabilityProfile::Heartbeat, &profiles);
+        assert_eq!(untouched.sampling_for_turn(4), (0.7, 4096));
+    }
 
     #[test]
     fn private_chat_does_not_hardcode_plugin_tool_names() {
@@ -323,4 +620,119 @@ mod tests {
         assert_eq!(ctx.allowed_tools, Some(vec!["external_reply".to_string()]));
         assert_eq!(ctx.disallowed_tools, Vec::<String>::new());
     }
+
+    fn research_only_profile() -> CustomCapabilityProfile {
+        CustomCapabilityProfile {
+            allowed_tools: vec![
+                "http_fetch".to_string(),
+                "web_search".to_string(),
+                "search_memory".to_string(),
+            ],
+            disallowed_tools: vec!["shell".to_string()],
+            system_prompt_suffix: Some("  Research only; cite sources.  ".to_string()),
+            temperature: Some(0.2),
+            max_tokens: Some(0),
+        }
+    }
+
+    #[test]
+    fn custom_profiles_resolve_by_name_over_the_base_profile() {
+        let mut cfg = AgentConfig::default();
+        cfg.custom_capability_profiles
+            .insert("research-only".to_string(), research_only_profile());
+
+        let resolved =
+            resolve_named_profile(&cfg, "Research-Only", AgentCapabilityProfile::Scheduled)
+                .expect("custom profile resolves");
+        assert_eq!(resolved.name, "research-only");
+        assert!(resolved.policy.autonomous);
+        assert_eq!(
+            resolved.policy.allowed_tools,
+            Some(vec![
+                "http_fetch".to_string(),
+                "web_search".to_string(),
+                "search_memory".to_string()
+            ])
+        );
+        assert_eq!(resolved.policy.disallowed_tools, vec!["shell".to_string()]);
+        assert_eq!(
+            resolved.system_prompt_suffix.as_deref(),
+            Some("Research only; cite sources.")
+        );
+        assert_eq!(resolved.temperature, Some(0.2));
+        assert_eq!(resolved.max_tokens, None);
+
+        // A custom allow-list cannot widen a base profile that already has one.
+        let dream = resolve_named_profile(&cfg, "research-only", AgentCapabilityProfile::Dream)
+            .expect("custom profile resolves");
+        assert_eq!(
+            dream.policy.allowed_tools,
+            Some(vec!["search_memory".to_string()])
+        );
+
+        let builtin = resolve_named_profile(&cfg, "ambient", AgentCapabilityProfile::Scheduled)
+            .expect("built-in profile resolves");
+        assert_eq!(
+            builtin.policy,
+            resolve_capability_policy(AgentCapabilityProfile::Ambient, &cfg.capability_profiles)
+        );
+        assert!(
+            resolve_named_profile(&cfg, "unknown", AgentCapabilityProfile::Scheduled).is_none()
+        );
+    }
+
+    #[test]
+    fn custom_profile_validation_reports_unknown_tools_and_shadowed_names() {
+        let mut profiles = HashMap::new();
+        let mut research = research_only_profile();
+        research.allowed_tools.push("web_serch".to_string());
+        profiles.insert("research-only".to_string(), research);
+        profiles.insert("Dream".to_string(), CustomCapabilityProfile::default());
+        let known: Vec<String> = ["http_fetch", "web_search", "search_memory", "shell"]
+            .iter()
+            .map(|tool| tool.to_string())
+            .collect();
+
+        let problems = validate_custom_profiles(&profiles, &known);
+        assert_eq!(problems.len(), 3, "{problems:?}");
+        assert!(problems[0].contains("'Dream' shadows a built-in"));
+        assert!(problems[1].contains("unknown tool 'web_serch'"));
+        assert!(problems[2].contains("max_tokens of 0"));
+    }
+
+    #[test]
+    fn tool_context_carries_configured_timeouts() {
+        let mut cfg = AgentConfig::default();
+        cfg.tool_timeout_secs = 45;
+        cfg.tool_category_timeout_secs
+            .insert("network".to_string(), 10);
+        cfg.tool_category_timeout_secs
+            .insert("bogus".to_string(), 5);
+
+        let ctx = build_tool_context_for_profile(
+            &cfg,
+            AgentCapabilityProfile::PrivateChat,
+            "/tmp".to_string(),
+            "ponderer".to_string(),
+        );
+
+        assert_eq!(
+            ctx.timeouts.for_category(&ToolCategory::Network),
+            Some(Duration::from_secs(10))
+        );
+        assert_eq!(
+            ctx.timeouts.for_category(&ToolCategory::Shell),
+            Some(Duration::from_secs(310))
+        );
+        assert_eq!(
+            ctx.timeouts.for_category(&ToolCategory::Memory),
+            Some(Duration::from_secs(45))
+        );
+
+        cfg.tool_timeout_secs = 0;
+        assert_eq!(
+            tool_timeout_policy(&cfg).for_category(&ToolCategory::Memory),
+            None
+        );
+    }
 }
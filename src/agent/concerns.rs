@@ -1,5 +1,5 @@
 use anyhow::Result;
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
 
@@ -9,6 +9,7 @@ pub const CONCERN_DECAY_TO_MONITORING_DAYS: i64 = 7;
 pub const CONCERN_DECAY_TO_BACKGROUND_DAYS: i64 = 30;
 pub const CONCERN_DECAY_TO_DORMANT_DAYS: i64 = 90;
 pub const CONCERN_SIGNAL_MIN_CONFIDENCE: f32 = 0.35;
+pub const CONCERN_MERGE_SIMILARITY_THRESHOLD: f64 = 0.6;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Concern {
@@ -21,6 +22,10 @@ pub struct Concern {
     pub my_thoughts: String,
     pub related_memory_keys: Vec<String>,
     pub context: ConcernContext,
+    /// Operator-pinned concerns are exempt from salience decay and sort first
+    /// in priority context.
+    #[serde(default)]
+    pub pinned: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -89,6 +94,48 @@ impl Salience {
     }
 }
 
+/// Typed edge between two concerns, read as `from <relation> to`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConcernRelation {
+    Blocks,
+    PartOf,
+    Related,
+}
+
+impl ConcernRelation {
+    pub fn as_db_str(self) -> &'static str {
+        match self {
+            ConcernRelation::Blocks => "blocks",
+            ConcernRelation::PartOf => "part_of",
+            ConcernRelation::Related => "related",
+        }
+    }
+
+    pub fn from_db(raw: &str) -> Self {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "blocks" => ConcernRelation::Blocks,
+            "part_of" | "part-of" | "partof" => ConcernRelation::PartOf,
+            _ => ConcernRelation::Related,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConcernEdge {
+    pub from_id: String,
+    pub to_id: String,
+    pub relation: ConcernRelation,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Concerns plus the edges between them, for graph views.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ConcernGraph {
+    pub nodes: Vec<Concern>,
+    pub edges: Vec<ConcernEdge>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct ConcernContext {
     pub how_it_started: String,
@@ -109,6 +156,29 @@ pub struct ConcernSignal {
     pub notes: Option<String>,
     #[serde(default)]
     pub related_memory_keys: Vec<String>,
+    /// Id or summary of an existing concern this one relates to.
+    #[serde(default)]
+    pub parent: Option<String>,
+    /// `part_of` (default), `blocks`, or `related`; only used with `parent`.
+    #[serde(default)]
+    pub relation: Option<String>,
+    /// When a `reminder` concern falls due; stored as its `trigger_time`.
+    /// A value that is not RFC 3339 (such as a copied placeholder) reads as
+    /// `None` rather than failing the whole block.
+    #[serde(default, deserialize_with = "deserialize_lenient_due_at")]
+    pub due_at: Option<DateTime<Utc>>,
+}
+
+fn deserialize_lenient_due_at<'de, D>(deserializer: D) -> Result<Option<DateTime<Utc>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let raw = Option::<String>::deserialize(deserializer)?;
+    Ok(raw.and_then(|raw| {
+        DateTime::parse_from_rfc3339(raw.trim())
+            .ok()
+            .map(|at| at.with_timezone(&Utc))
+    }))
 }
 
 #[derive(Debug, Default)]
@@ -118,6 +188,118 @@ pub struct ConcernIngestReport {
     pub skipped: usize,
 }
 
+/// Inactivity thresholds, in days, for one concern kind's decay through
+/// monitoring, background, and dormant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConcernDecayCurve {
+    pub to_monitoring_days: i64,
+    pub to_background_days: i64,
+    pub to_dormant_days: i64,
+}
+
+impl ConcernDecayCurve {
+    pub const fn new(
+        to_monitoring_days: i64,
+        to_background_days: i64,
+        to_dormant_days: i64,
+    ) -> Self {
+        Self {
+            to_monitoring_days,
+            to_background_days,
+            to_dormant_days,
+        }
+    }
+
+    /// Tier reached after `days_since_touch` idle days. Out-of-order
+    /// thresholds are raised to the previous tier's so the tiers stay in order.
+    pub fn salience_after(&self, days_since_touch: i64) -> Salience {
+        let to_monitoring = self.to_monitoring_days.max(0);
+        let to_background = self.to_background_days.max(to_monitoring);
+        let to_dormant = self.to_dormant_days.max(to_background);
+        if days_since_touch >= to_dormant {
+            Salience::Dormant
+        } else if days_since_touch >= to_background {
+            Salience::Background
+        } else if days_since_touch >= to_monitoring {
+            Salience::Monitoring
+        } else {
+            Salience::Active
+        }
+    }
+}
+
+impl Default for ConcernDecayCurve {
+    fn default() -> Self {
+        Self::new(
+            CONCERN_DECAY_TO_MONITORING_DAYS,
+            CONCERN_DECAY_TO_BACKGROUND_DAYS,
+            CONCERN_DECAY_TO_DORMANT_DAYS,
+        )
+    }
+}
+
+/// Per-kind decay curves read by `ConcernsManager::apply_salience_decay`.
+/// Keys match the `kind` tag of `ConcernType`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConcernDecayPolicy {
+    #[serde(default = "default_project_decay")]
+    pub collaborative_project: ConcernDecayCurve,
+    #[serde(default)]
+    pub household_awareness: ConcernDecayCurve,
+    #[serde(default = "default_system_health_decay")]
+    pub system_health: ConcernDecayCurve,
+    #[serde(default = "default_personal_interest_decay")]
+    pub personal_interest: ConcernDecayCurve,
+    /// Reminders keep the baseline curve; a due reminder is surfaced by
+    /// `due_reminders` regardless of how long it sat untouched.
+    #[serde(default)]
+    pub reminder: ConcernDecayCurve,
+    #[serde(default = "default_conversation_decay")]
+    pub ongoing_conversation: ConcernDecayCurve,
+}
+
+fn default_project_decay() -> ConcernDecayCurve {
+    ConcernDecayCurve::new(14, 60, 180)
+}
+
+fn default_system_health_decay() -> ConcernDecayCurve {
+    ConcernDecayCurve::new(3, 14, 45)
+}
+
+fn default_personal_interest_decay() -> ConcernDecayCurve {
+    ConcernDecayCurve::new(10, 45, 120)
+}
+
+fn default_conversation_decay() -> ConcernDecayCurve {
+    ConcernDecayCurve::new(2, 7, 21)
+}
+
+impl Default for ConcernDecayPolicy {
+    fn default() -> Self {
+        Self {
+            collaborative_project: default_project_decay(),
+            household_awareness: ConcernDecayCurve::default(),
+            system_health: default_system_health_decay(),
+            personal_interest: default_personal_interest_decay(),
+            reminder: ConcernDecayCurve::default(),
+            ongoing_conversation: default_conversation_decay(),
+        }
+    }
+}
+
+impl ConcernDecayPolicy {
+    pub fn curve_for(&self, concern_type: &ConcernType) -> ConcernDecayCurve {
+        match concern_type {
+            ConcernType::CollaborativeProject { .. } => self.collaborative_project,
+            ConcernType::HouseholdAwareness { .. } => self.household_awareness,
+            ConcernType::SystemHealth { .. } => self.system_health,
+            ConcernType::PersonalInterest { .. } => self.personal_interest,
+            ConcernType::Reminder { .. } => self.reminder,
+            ConcernType::OngoingConversation { .. } => self.ongoing_conversation,
+        }
+    }
+}
+
 #[derive(Debug, Default)]
 pub struct ConcernDecayReport {
     pub to_monitoring: usize,
@@ -131,6 +313,14 @@ impl ConcernDecayReport {
     }
 }
 
+#[derive(Debug, Default)]
+pub struct ConcernMergeReport {
+    /// Concerns that absorbed at least one duplicate.
+    pub survivors: usize,
+    /// Duplicate concerns folded into a survivor and removed.
+    pub retired: usize,
+}
+
 pub struct ConcernsManager;
 
 impl ConcernsManager {
@@ -174,7 +364,13 @@ impl ConcernsManager {
                     &signal.related_memory_keys,
                 );
                 merge_notes(&mut concern.my_thoughts, signal.notes.as_deref());
+                if let (ConcernType::Reminder { trigger_time, .. }, Some(due_at)) =
+                    (&mut concern.concern_type, signal.due_at)
+                {
+                    *trigger_time = Some(due_at);
+                }
                 db.save_concern(&concern)?;
+                link_signal_parent(db, &concerns, &concern.id, signal)?;
                 concerns[index] = concern.clone();
                 report.touched.push(concern);
                 continue;
@@ -199,8 +395,10 @@ impl ConcernsManager {
                     key_events: vec![format!("Created from signal: {}", summary)],
                     last_update_reason: "created from signal".to_string(),
                 },
+                pinned: false,
             };
             db.save_concern(&concern)?;
+            link_signal_parent(db, &concerns, &concern.id, signal)?;
             concerns.push(concern.clone());
             report.created.push(concern);
         }
@@ -236,14 +434,22 @@ impl ConcernsManager {
         Ok(updated)
     }
 
+    /// Moves each unpinned concern to the tier its kind's curve in `policy`
+    /// assigns for its idle time. Pinned concerns are left untouched.
     pub fn apply_salience_decay(
         db: &AgentDatabase,
         now: DateTime<Utc>,
+        policy: &ConcernDecayPolicy,
     ) -> Result<ConcernDecayReport> {
         let mut report = ConcernDecayReport::default();
         for mut concern in db.get_all_concerns()? {
+            if concern.pinned {
+                continue;
+            }
             let days_since_touch = (now - concern.last_touched).num_days();
-            let target = salience_for_days_since_touch(days_since_touch);
+            let target = policy
+                .curve_for(&concern.concern_type)
+                .salience_after(days_since_touch);
             if target == concern.salience {
                 continue;
             }
@@ -269,6 +475,162 @@ impl ConcernsManager {
         Ok(report)
     }
 
+    /// Non-dormant reminder concerns whose `trigger_time` has passed, most
+    /// overdue first. A reminder already notified for its current
+    /// `trigger_time` stays quiet; one moved to a new time is due again once
+    /// `cooldown` has passed since the last notification. Pinned reminders
+    /// count even when dormant.
+    pub fn due_reminders(
+        db: &AgentDatabase,
+        now: DateTime<Utc>,
+        cooldown: Duration,
+    ) -> Result<Vec<Concern>> {
+        let mut due = Vec::new();
+        for concern in db.get_all_concerns()? {
+            let ConcernType::Reminder {
+                trigger_time: Some(trigger_time),
+                ..
+            } = concern.concern_type
+            else {
+                continue;
+            };
+            if trigger_time > now || (concern.salience == Salience::Dormant && !concern.pinned) {
+                continue;
+            }
+            let already_notified =
+                db.get_concern_notified_trigger_time(&concern.id)? == Some(trigger_time);
+            let recently_notified = db
+                .get_concern_last_notified(&concern.id)?
+                .is_some_and(|last| now - last < cooldown);
+            if !already_notified && !recently_notified {
+                due.push((trigger_time, concern));
+            }
+        }
+        due.sort_by_key(|(trigger_time, _)| *trigger_time);
+        Ok(due.into_iter().map(|(_, concern)| concern).collect())
+    }
+
+    /// Pins or unpins a concern. Returns the updated concern, or `None` when
+    /// no concern has that id.
+    pub fn pin_concern(db: &AgentDatabase, id: &str, pinned: bool) -> Result<Option<Concern>> {
+        let Some(mut concern) = db.get_concern(id)? else {
+            return Ok(None);
+        };
+        if concern.pinned == pinned {
+            return Ok(Some(concern));
+        }
+        concern.pinned = pinned;
+        if pinned && concern.salience == Salience::Dormant {
+            concern.salience = Salience::Background;
+        }
+        concern.context.last_update_reason = if pinned {
+            "pinned by operator".to_string()
+        } else {
+            "unpinned by operator".to_string()
+        };
+        append_key_event(
+            &mut concern.context,
+            if pinned { "Pinned" } else { "Unpinned" }.to_string(),
+        );
+        db.save_concern(&concern)?;
+        Ok(Some(concern))
+    }
+
+    /// Folds near-duplicate non-dormant concerns into the oldest member of
+    /// each group. Survivors keep the latest touch time, the strongest
+    /// salience, and the union of related memory keys and notes; the
+    /// redundant concerns are deleted.
+    pub fn merge_similar(
+        db: &AgentDatabase,
+        similarity_threshold: f64,
+    ) -> Result<ConcernMergeReport> {
+        let mut report = ConcernMergeReport::default();
+        let mut concerns = db.get_all_concerns()?;
+        concerns.retain(|concern| concern.salience != Salience::Dormant);
+        concerns.sort_by_key(|concern| concern.created_at);
+
+        let mut retired = vec![false; concerns.len()];
+        for i in 0..concerns.len() {
+            if retired[i] {
+                continue;
+            }
+            let mut survivor = concerns[i].clone();
+            let mut absorbed_ids = Vec::new();
+            for j in (i + 1)..concerns.len() {
+                if retired[j]
+                    || !concerns_are_similar(
+                        &survivor.summary,
+                        &concerns[j].summary,
+                        similarity_threshold,
+                    )
+                {
+                    continue;
+                }
+                let duplicate = &concerns[j];
+                survivor.last_touched = survivor.last_touched.max(duplicate.last_touched);
+                if duplicate.salience.rank() > survivor.salience.rank() {
+                    survivor.salience = duplicate.salience;
+                }
+                survivor.pinned |= duplicate.pinned;
+                merge_related_keys(
+                    &mut survivor.related_memory_keys,
+                    &duplicate.related_memory_keys,
+                );
+                merge_notes(&mut survivor.my_thoughts, Some(&duplicate.my_thoughts));
+                append_key_event(
+                    &mut survivor.context,
+                    format!(
+                        "Merged duplicate concern: {}",
+                        truncate_for_log(&duplicate.summary, 80)
+                    ),
+                );
+                absorbed_ids.push(duplicate.id.clone());
+                retired[j] = true;
+            }
+            if absorbed_ids.is_empty() {
+                continue;
+            }
+
+            survivor.context.last_update_reason =
+                format!("merged {} duplicate concern(s)", absorbed_ids.len());
+            db.save_concern(&survivor)?;
+            for id in &absorbed_ids {
+                db.reassign_concern_edges(id, &survivor.id)?;
+                db.delete_concern(id)?;
+            }
+            report.survivors += 1;
+            report.retired += absorbed_ids.len();
+        }
+
+        Ok(report)
+    }
+
+    /// Records `from <relation> to`. Self-edges and unknown ids are
+    /// rejected; re-adding an existing edge is a no-op.
+    pub fn link_concerns(
+        db: &AgentDatabase,
+        from_id: &str,
+        to_id: &str,
+        relation: ConcernRelation,
+    ) -> Result<bool> {
+        if from_id == to_id
+            || db.get_concern(from_id)?.is_none()
+            || db.get_concern(to_id)?.is_none()
+        {
+            return Ok(false);
+        }
+        db.save_concern_edge(from_id, to_id, relation)?;
+        Ok(true)
+    }
+
+    /// Every concern as a node plus every stored edge between them.
+    pub fn get_concern_graph(db: &AgentDatabase) -> Result<ConcernGraph> {
+        Ok(ConcernGraph {
+            nodes: db.get_all_concerns()?,
+            edges: db.list_concern_edges()?,
+        })
+    }
+
     pub fn build_priority_context(
         db: &AgentDatabase,
         max_concerns: usize,
@@ -285,9 +647,9 @@ impl ConcernsManager {
         }
 
         concerns.sort_by(|a, b| {
-            b.salience
-                .rank()
-                .cmp(&a.salience.rank())
+            b.pinned
+                .cmp(&a.pinned)
+                .then_with(|| b.salience.rank().cmp(&a.salience.rank()))
                 .then_with(|| b.last_touched.cmp(&a.last_touched))
         });
 
@@ -299,8 +661,9 @@ impl ConcernsManager {
         let mut seen_memory_keys = HashSet::new();
         for concern in &selected {
             lines.push(format!(
-                "- [{}] {}",
+                "- [{}{}] {}",
                 concern.salience.as_db_str(),
+                if concern.pinned { ", pinned" } else { "" },
                 truncate_for_log(&concern.summary, 120)
             ));
             for key in &concern.related_memory_keys {
@@ -332,16 +695,9 @@ impl ConcernsManager {
     }
 }
 
+/// Tier under the baseline 7/30/90-day curve.
 pub fn salience_for_days_since_touch(days_since_touch: i64) -> Salience {
-    if days_since_touch >= CONCERN_DECAY_TO_DORMANT_DAYS {
-        Salience::Dormant
-    } else if days_since_touch >= CONCERN_DECAY_TO_BACKGROUND_DAYS {
-        Salience::Background
-    } else if days_since_touch >= CONCERN_DECAY_TO_MONITORING_DAYS {
-        Salience::Monitoring
-    } else {
-        Salience::Active
-    }
+    ConcernDecayCurve::default().salience_after(days_since_touch)
 }
 
 fn concern_type_from_signal(signal: &ConcernSignal, summary: &str) -> ConcernType {
@@ -364,7 +720,7 @@ fn concern_type_from_signal(signal: &ConcernSignal, summary: &str) -> ConcernTyp
             monitoring_since: Utc::now(),
         },
         Some("reminder") => ConcernType::Reminder {
-            trigger_time: None,
+            trigger_time: signal.due_at,
             trigger_condition: None,
         },
         Some("ongoing_conversation") | Some("conversation") => ConcernType::OngoingConversation {
@@ -402,12 +758,71 @@ fn summary_similarity(a: &str, b: &str) -> bool {
     false
 }
 
+/// Token Jaccard similarity over lowercase alphanumeric words, in the style of
+/// the loop detector's `token_jaccard_similarity`.
+fn summary_token_jaccard(a: &str, b: &str) -> f64 {
+    let tokens = |text: &str| {
+        text.split(|ch: char| !ch.is_alphanumeric())
+            .filter(|token| !token.is_empty())
+            .map(str::to_lowercase)
+            .collect::<HashSet<_>>()
+    };
+    let a_tokens = tokens(a);
+    let b_tokens = tokens(b);
+    if a_tokens.is_empty() || b_tokens.is_empty() {
+        return 0.0;
+    }
+    let intersection = a_tokens.intersection(&b_tokens).count() as f64;
+    let union = a_tokens.union(&b_tokens).count() as f64;
+    intersection / union
+}
+
+fn concerns_are_similar(a: &str, b: &str, similarity_threshold: f64) -> bool {
+    summary_similarity(a, b) || summary_token_jaccard(a, b) >= similarity_threshold
+}
+
 fn find_existing_concern_index(concerns: &[Concern], summary: &str) -> Option<usize> {
     concerns
         .iter()
         .position(|concern| summary_similarity(&concern.summary, summary))
 }
 
+/// Links a concern to the signal's `parent`, resolved by id first and then
+/// by summary similarity. An unresolvable parent is ignored.
+fn link_signal_parent(
+    db: &AgentDatabase,
+    concerns: &[Concern],
+    concern_id: &str,
+    signal: &ConcernSignal,
+) -> Result<()> {
+    let Some(parent) = signal.parent.as_deref().map(str::trim) else {
+        return Ok(());
+    };
+    if parent.is_empty() {
+        return Ok(());
+    }
+    let parent_id = concerns
+        .iter()
+        .find(|concern| concern.id == parent)
+        .or_else(|| {
+            find_existing_concern_index(concerns, &normalize_summary(parent))
+                .map(|index| &concerns[index])
+        })
+        .map(|concern| concern.id.clone());
+    let Some(parent_id) = parent_id else {
+        return Ok(());
+    };
+    if parent_id == concern_id {
+        return Ok(());
+    }
+    let relation = signal
+        .relation
+        .as_deref()
+        .map(ConcernRelation::from_db)
+        .unwrap_or(ConcernRelation::PartOf);
+    db.save_concern_edge(concern_id, &parent_id, relation)
+}
+
 fn merge_related_keys(current: &mut Vec<String>, incoming: &[String]) {
     let mut seen = current
         .iter()
@@ -538,6 +953,7 @@ mod tests {
             confidence: Some(0.9),
             notes: Some("Track this until phase 5".to_string()),
             related_memory_keys: vec!["phase-plan".to_string()],
+            ..Default::default()
         }];
 
         let ingest =
@@ -552,10 +968,12 @@ mod tests {
             .expect("exists");
         assert_eq!(concern.salience, Salience::Active);
 
-        concern.last_touched = Utc::now() - ChronoDuration::days(95);
+        concern.last_touched = Utc::now() - ChronoDuration::days(185);
         db.save_concern(&concern).expect("set stale touch time");
 
-        let decay = ConcernsManager::apply_salience_decay(&db, Utc::now()).expect("decay");
+        let decay =
+            ConcernsManager::apply_salience_decay(&db, Utc::now(), &ConcernDecayPolicy::default())
+                .expect("decay");
         assert_eq!(decay.to_dormant, 1);
         assert_eq!(decay.total_changes(), 1);
 
@@ -580,6 +998,294 @@ mod tests {
         assert_eq!(reactivated.salience, Salience::Active);
     }
 
+    #[test]
+    fn pinned_concern_survives_decay_and_leads_priority_context() {
+        let (_dir, db) = temp_db();
+        let signals = ["File quarterly taxes", "Refactor the scheduler"]
+            .iter()
+            .map(|summary| ConcernSignal {
+                summary: summary.to_string(),
+                kind: Some("project".to_string()),
+                confidence: Some(0.9),
+                ..Default::default()
+            })
+            .collect::<Vec<_>>();
+        let created = ConcernsManager::ingest_signals(&db, &signals, "test")
+            .expect("ingest")
+            .created;
+        let (taxes, scheduler) = (&created[0], &created[1]);
+
+        let mut stale = taxes.clone();
+        stale.last_touched = Utc::now() - ChronoDuration::days(200);
+        db.save_concern(&stale).expect("backdate");
+        let pinned = ConcernsManager::pin_concern(&db, &taxes.id, true)
+            .expect("pin")
+            .expect("exists");
+        assert!(pinned.pinned);
+
+        let policy = ConcernDecayPolicy::default();
+        let decay = ConcernsManager::apply_salience_decay(&db, Utc::now(), &policy).expect("decay");
+        assert_eq!(decay.total_changes(), 0);
+        let after_decay = db.get_concern(&taxes.id).expect("load").expect("exists");
+        assert_eq!(after_decay.salience, Salience::Active);
+
+        let context = ConcernsManager::build_priority_context(&db, 5, 200).expect("context");
+        let taxes_at = context.find("File quarterly taxes").expect("pinned listed");
+        let scheduler_at = context.find(&scheduler.summary).expect("active listed");
+        assert!(taxes_at < scheduler_at);
+        assert!(context.contains("[active, pinned] File quarterly taxes"));
+
+        ConcernsManager::pin_concern(&db, &taxes.id, false).expect("unpin");
+        let decay = ConcernsManager::apply_salience_decay(&db, Utc::now(), &policy).expect("decay");
+        assert_eq!(decay.to_dormant, 1);
+        assert!(ConcernsManager::pin_concern(&db, "missing", true)
+            .expect("pin missing")
+            .is_none());
+    }
+
+    #[test]
+    fn project_and_conversation_concerns_diverge_over_the_same_idle_time() {
+        let (_dir, db) = temp_db();
+        let signals = [
+            ("Migrate the backup server", "project"),
+            ("Weekend hiking plans", "conversation"),
+        ]
+        .iter()
+        .map(|(summary, kind)| ConcernSignal {
+            summary: summary.to_string(),
+            kind: Some(kind.to_string()),
+            confidence: Some(0.9),
+            ..Default::default()
+        })
+        .collect::<Vec<_>>();
+        let created = ConcernsManager::ingest_signals(&db, &signals, "test")
+            .expect("ingest")
+            .created;
+        for concern in &created {
+            let mut stale = concern.clone();
+            stale.last_touched = Utc::now() - ChronoDuration::days(10);
+            db.save_concern(&stale).expect("backdate");
+        }
+
+        let decay =
+            ConcernsManager::apply_salience_decay(&db, Utc::now(), &ConcernDecayPolicy::default())
+                .expect("decay");
+        assert_eq!(decay.to_background, 1);
+        assert_eq!(decay.total_changes(), 1);
+        let project = db
+            .get_concern(&created[0].id)
+            .expect("load")
+            .expect("exists");
+        let conversation = db
+            .get_concern(&created[1].id)
+            .expect("load")
+            .expect("exists");
+        assert_eq!(project.salience, Salience::Active);
+        assert_eq!(conversation.salience, Salience::Background);
+
+        let out_of_order = ConcernDecayCurve::new(30, 5, 1);
+        assert_eq!(out_of_order.salience_after(29), Salience::Active);
+        assert_eq!(out_of_order.salience_after(30), Salience::Dormant);
+    }
+
+    #[test]
+    fn due_reminders_respect_trigger_time_and_notification_cooldown() {
+        let (_dir, db) = temp_db();
+        let now = Utc::now();
+        let signals = vec![
+            ConcernSignal {
+                summary: "Renew the passport".to_string(),
+                kind: Some("reminder".to_string()),
+                due_at: Some(now - ChronoDuration::minutes(5)),
+                ..Default::default()
+            },
+            ConcernSignal {
+                summary: "Book the dentist".to_string(),
+                kind: Some("reminder".to_string()),
+                due_at: Some(now + ChronoDuration::days(1)),
+                ..Default::default()
+            },
+            ConcernSignal {
+                summary: "Garden irrigation project".to_string(),
+                kind: Some("project".to_string()),
+                due_at: Some(now - ChronoDuration::days(1)),
+                ..Default::default()
+            },
+        ];
+        let created = ConcernsManager::ingest_signals(&db, &signals, "test")
+            .expect("ingest")
+            .created;
+        let passport = &created[0];
+        let cooldown = ChronoDuration::hours(1);
+
+        let due = ConcernsManager::due_reminders(&db, now, cooldown).expect("due");
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].id, passport.id);
+
+        let ConcernType::Reminder { trigger_time, .. } = passport.concern_type.clone() else {
+            panic!("passport is a reminder");
+        };
+        db.mark_concern_notified(&passport.id, now, trigger_time)
+            .expect("mark notified");
+        assert!(
+            ConcernsManager::due_reminders(&db, now + ChronoDuration::hours(12), cooldown)
+                .expect("notified reminders stay quiet")
+                .is_empty()
+        );
+
+        let mut rescheduled = passport.clone();
+        rescheduled.concern_type = ConcernType::Reminder {
+            trigger_time: Some(now + ChronoDuration::minutes(10)),
+            trigger_condition: None,
+        };
+        db.save_concern(&rescheduled).expect("reschedule");
+        assert!(
+            ConcernsManager::due_reminders(&db, now + ChronoDuration::minutes(30), cooldown)
+                .expect("due within cooldown")
+                .is_empty()
+        );
+        let due_again =
+            ConcernsManager::due_reminders(&db, now + ChronoDuration::minutes(61), cooldown)
+                .expect("due after cooldown");
+        assert_eq!(due_again.len(), 1);
+        assert_eq!(due_again[0].id, passport.id);
+
+        assert!(db.delete_concern(&passport.id).expect("delete"));
+        assert!(db
+            .get_concern_last_notified(&passport.id)
+            .expect("last notified")
+            .is_none());
+    }
+
+    #[test]
+    fn merge_similar_folds_obvious_duplicates() {
+        let (_dir, db) = temp_db();
+        let signal = |summary: &str, key: &str| ConcernSignal {
+            summary: summary.to_string(),
+            kind: Some("project".to_string()),
+            touch_only: false,
+            confidence: Some(0.9),
+            notes: Some(format!("note for {}", key)),
+            related_memory_keys: vec![key.to_string()],
+            ..Default::default()
+        };
+        let first = ConcernsManager::ingest_signals(
+            &db,
+            &[signal("Finish the concerns manager work", "plan-a")],
+            "test",
+        )
+        .expect("ingest first")
+        .created
+        .remove(0);
+        let mut first_stale = first.clone();
+        first_stale.created_at = Utc::now() - ChronoDuration::days(2);
+        first_stale.last_touched = Utc::now() - ChronoDuration::days(2);
+        db.save_concern(&first_stale).expect("backdate first");
+        ConcernsManager::ingest_signals(
+            &db,
+            &[
+                signal("Finish concerns manager work", "plan-b"),
+                signal("Water the garden", "garden"),
+            ],
+            "test",
+        )
+        .expect("ingest rest");
+        assert_eq!(db.get_all_concerns().expect("all").len(), 3);
+
+        let report =
+            ConcernsManager::merge_similar(&db, CONCERN_MERGE_SIMILARITY_THRESHOLD).expect("merge");
+        assert_eq!(report.survivors, 1);
+        assert_eq!(report.retired, 1);
+
+        let remaining = db.get_all_concerns().expect("all after merge");
+        assert_eq!(remaining.len(), 2);
+        let merged = db
+            .get_concern(&first.id)
+            .expect("load survivor")
+            .expect("oldest concern survives");
+        assert_eq!(merged.related_memory_keys, vec!["plan-a", "plan-b"]);
+        assert!(merged.last_touched > first_stale.last_touched);
+        assert!(merged.my_thoughts.contains("note for plan-b"));
+        assert!(remaining
+            .iter()
+            .any(|concern| concern.summary == "Water the garden"));
+
+        let again = ConcernsManager::merge_similar(&db, CONCERN_MERGE_SIMILARITY_THRESHOLD)
+            .expect("merge again");
+        assert_eq!(again.retired, 0);
+    }
+
+    #[test]
+    fn concern_graph_links_signal_parents_and_survives_merges() {
+        let (_dir, db) = temp_db();
+        let project = ConcernSignal {
+            summary: "Rebuild the greenhouse".to_string(),
+            kind: Some("project".to_string()),
+            confidence: Some(0.9),
+            ..Default::default()
+        };
+        let parent = ConcernsManager::ingest_signals(&db, &[project], "test")
+            .expect("ingest parent")
+            .created
+            .remove(0);
+
+        let children = vec![
+            ConcernSignal {
+                summary: "Order cedar planks".to_string(),
+                kind: Some("project".to_string()),
+                confidence: Some(0.9),
+                parent: Some(parent.id.clone()),
+                ..Default::default()
+            },
+            ConcernSignal {
+                summary: "Waiting on the building permit".to_string(),
+                kind: Some("project".to_string()),
+                confidence: Some(0.9),
+                parent: Some("rebuild the greenhouse".to_string()),
+                relation: Some("blocks".to_string()),
+                ..Default::default()
+            },
+            ConcernSignal {
+                summary: "Unrelated errand".to_string(),
+                confidence: Some(0.9),
+                parent: Some("no such concern".to_string()),
+                ..Default::default()
+            },
+        ];
+        let created = ConcernsManager::ingest_signals(&db, &children, "test")
+            .expect("ingest children")
+            .created;
+
+        let graph = ConcernsManager::get_concern_graph(&db).expect("graph");
+        assert_eq!(graph.nodes.len(), 4);
+        assert_eq!(graph.edges.len(), 2);
+        assert!(graph.edges.iter().any(|edge| edge.from_id == created[0].id
+            && edge.to_id == parent.id
+            && edge.relation == ConcernRelation::PartOf));
+        assert!(graph
+            .edges
+            .iter()
+            .any(|edge| edge.from_id == created[1].id && edge.relation == ConcernRelation::Blocks));
+        assert!(!ConcernsManager::link_concerns(
+            &db,
+            &parent.id,
+            &parent.id,
+            ConcernRelation::Related
+        )
+        .expect("self link"));
+
+        // Edges follow a retired duplicate onto its survivor.
+        db.reassign_concern_edges(&created[0].id, &created[2].id)
+            .expect("reassign");
+        assert!(db.delete_concern(&created[0].id).expect("delete"));
+        let edges = db.list_concern_edges().expect("edges");
+        assert_eq!(edges.len(), 2);
+        assert!(edges.iter().any(|edge| edge.from_id == created[2].id));
+
+        assert!(db.delete_concern(&parent.id).expect("delete parent"));
+        assert!(db.list_concern_edges().expect("edges").is_empty());
+    }
+
     #[test]
     fn priority_context_includes_active_concerns_and_related_memory() {
         let (_dir, db) = temp_db();
@@ -593,6 +1299,7 @@ mod tests {
             confidence: Some(0.9),
             notes: None,
             related_memory_keys: vec!["phase-plan".to_string()],
+            ..Default::default()
         }];
         ConcernsManager::ingest_signals(&db, &signals, "test").expect("ingest");
 
@@ -0,0 +1,184 @@
+use anyhow::Result;
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::agent::orientation::Disposition;
+use crate::database::AgentDatabase;
+
+/// `agent_state` key holding the JSON-encoded active `FocusSession`.
+pub const FOCUS_SESSION_STATE_KEY: &str = "living_loop.focus_session";
+pub const DEFAULT_FOCUS_MINUTES: u64 = 50;
+/// Eight hours; anything longer is a work day, not a focus session.
+pub const MAX_FOCUS_MINUTES: u64 = 480;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FocusKind {
+    /// Holds every proactive chat post, including due reminders.
+    Deep,
+    /// Holds `Surface` and social check-ins; `Interrupt` and reminders still go through.
+    Light,
+}
+
+impl FocusKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Deep => "deep",
+            Self::Light => "light",
+        }
+    }
+
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "deep" => Some(Self::Deep),
+            "light" => Some(Self::Light),
+            _ => None,
+        }
+    }
+}
+
+/// An operator-started window in which the agent stays quiet and orients less often.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FocusSession {
+    pub started_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+    pub kind: FocusKind,
+    /// Post a short chat check-in when the session runs to its end.
+    #[serde(default = "default_check_in")]
+    pub check_in: bool,
+}
+
+fn default_check_in() -> bool {
+    true
+}
+
+impl FocusSession {
+    pub fn start(now: DateTime<Utc>, minutes: u64, kind: FocusKind, check_in: bool) -> Self {
+        let minutes = minutes.clamp(1, MAX_FOCUS_MINUTES);
+        Self {
+            started_at: now,
+            ends_at: now + ChronoDuration::minutes(minutes as i64),
+            kind,
+            check_in,
+        }
+    }
+
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        now < self.ends_at
+    }
+
+    /// Whole minutes left, rounded up so a running session never reports zero.
+    pub fn remaining_minutes(&self, now: DateTime<Utc>) -> i64 {
+        let seconds = (self.ends_at - now).num_seconds().max(0);
+        (seconds + 59) / 60
+    }
+
+    pub fn duration_minutes(&self) -> i64 {
+        (self.ends_at - self.started_at).num_minutes()
+    }
+
+    /// Whether an orientation disposition must be held as `Observe`.
+    pub fn holds_disposition(&self, disposition: Disposition) -> bool {
+        match disposition {
+            Disposition::Surface => true,
+            Disposition::Interrupt => self.kind == FocusKind::Deep,
+            _ => false,
+        }
+    }
+
+    pub fn holds_reminders(&self) -> bool {
+        self.kind == FocusKind::Deep
+    }
+
+    /// Factor applied to the ambient tick, which is also the orientation cadence.
+    pub fn tick_multiplier(&self) -> u64 {
+        match self.kind {
+            FocusKind::Deep => 4,
+            FocusKind::Light => 2,
+        }
+    }
+
+    pub fn check_in_message(&self) -> String {
+        format!(
+            "Your {}-minute {} focus session just ended. How did it go? \
+             Anything you want me to note or pick up from here?",
+            self.duration_minutes(),
+            self.kind.as_str()
+        )
+    }
+}
+
+pub fn load_focus_session(db: &AgentDatabase) -> Result<Option<FocusSession>> {
+    let Some(raw) = db.get_state(FOCUS_SESSION_STATE_KEY)? else {
+        return Ok(None);
+    };
+    if raw.trim().is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(serde_json::from_str(&raw)?))
+}
+
+pub fn save_focus_session(db: &AgentDatabase, session: &FocusSession) -> Result<()> {
+    db.set_state(FOCUS_SESSION_STATE_KEY, &serde_json::to_string(session)?)
+}
+
+/// Ends the session without a check-in; an empty value reads back as `None`.
+pub fn clear_focus_session(db: &AgentDatabase) -> Result<()> {
+    db.set_state(FOCUS_SESSION_STATE_KEY, "")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(minute: i64) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339("2026-03-02T09:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc)
+            + ChronoDuration::minutes(minute)
+    }
+
+    #[test]
+    fn session_is_active_until_its_end_and_clamps_its_length() {
+        let session = FocusSession::start(at(0), 50, FocusKind::Deep, true);
+        assert!(session.is_active(at(49)));
+        assert_eq!(session.remaining_minutes(at(10)), 40);
+        assert!(!session.is_active(at(50)));
+        assert_eq!(session.remaining_minutes(at(60)), 0);
+
+        let long = FocusSession::start(at(0), 10_000, FocusKind::Light, false);
+        assert_eq!(long.duration_minutes(), MAX_FOCUS_MINUTES as i64);
+        assert_eq!(
+            FocusSession::start(at(0), 0, FocusKind::Light, false).duration_minutes(),
+            1
+        );
+    }
+
+    #[test]
+    fn deep_focus_holds_interrupts_and_reminders_but_light_does_not() {
+        let deep = FocusSession::start(at(0), 25, FocusKind::Deep, true);
+        let light = FocusSession::start(at(0), 25, FocusKind::Light, true);
+
+        assert!(deep.holds_disposition(Disposition::Surface));
+        assert!(deep.holds_disposition(Disposition::Interrupt));
+        assert!(deep.holds_reminders());
+        assert!(light.holds_disposition(Disposition::Surface));
+        assert!(!light.holds_disposition(Disposition::Interrupt));
+        assert!(!light.holds_reminders());
+        assert!(!deep.holds_disposition(Disposition::Journal));
+        assert!(deep.tick_multiplier() > light.tick_multiplier());
+    }
+
+    #[test]
+    fn session_round_trips_through_agent_state_and_clears() {
+        let db = AgentDatabase::new(":memory:").expect("database");
+        assert_eq!(load_focus_session(&db).unwrap(), None);
+
+        let session = FocusSession::start(at(0), 50, FocusKind::Light, false);
+        save_focus_session(&db, &session).unwrap();
+        assert_eq!(load_focus_session(&db).unwrap(), Some(session));
+
+        clear_focus_session(&db).unwrap();
+        assert_eq!(load_focus_session(&db).unwrap(), None);
+    }
+}
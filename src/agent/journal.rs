@@ -6,9 +6,11 @@ use crate::agent::concerns::Concern;
 use crate::agent::orientation::{Disposition, Orientation, UserStateEstimate};
 use crate::generation_telemetry::GenerationObserver;
 use crate::llm_client::{LlmClient, Message as LlmMessage};
+use crate::llm_provider::LlmProvider;
 use crate::skills::SkillEvent;
 
 pub const DEFAULT_JOURNAL_MIN_INTERVAL_SECS: u64 = 300;
+pub const DEFAULT_JOURNAL_MAX_PER_DAY: u32 = 48;
 
 /// A private inner-life note captured by the agent.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -94,6 +96,11 @@ impl JournalEngine {
         self
     }
 
+    pub fn with_llm_provider(mut self, provider: LlmProvider) -> Self {
+        self.client = self.client.with_provider(provider);
+        self
+    }
+
     pub async fn maybe_generate_entry(
         &self,
         orientation: &Orientation,
@@ -173,14 +180,33 @@ pub enum JournalSkipReason {
     DispositionNotJournal,
     SameDisposition,
     MinInterval { remaining_secs: u64 },
+    DailyCapReached { entries_today: u32 },
+}
+
+/// Rate limits applied by `journal_skip_reason`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct JournalRateLimits {
+    pub min_interval_secs: u64,
+    /// Maximum entries per UTC day; 0 disables the cap.
+    pub max_per_day: u32,
+}
+
+impl Default for JournalRateLimits {
+    fn default() -> Self {
+        Self {
+            min_interval_secs: DEFAULT_JOURNAL_MIN_INTERVAL_SECS,
+            max_per_day: DEFAULT_JOURNAL_MAX_PER_DAY,
+        }
+    }
 }
 
 pub fn journal_skip_reason(
     now: DateTime<Utc>,
     last_written_at: Option<DateTime<Utc>>,
+    entries_today: u32,
     current_disposition: Disposition,
     previous_disposition: Option<Disposition>,
-    min_interval_secs: u64,
+    limits: JournalRateLimits,
 ) -> Option<JournalSkipReason> {
     if current_disposition != Disposition::Journal {
         return Some(JournalSkipReason::DispositionNotJournal);
@@ -190,6 +216,11 @@ pub fn journal_skip_reason(
         return Some(JournalSkipReason::SameDisposition);
     }
 
+    if limits.max_per_day > 0 && entries_today >= limits.max_per_day {
+        return Some(JournalSkipReason::DailyCapReached { entries_today });
+    }
+
+    let min_interval_secs = limits.min_interval_secs;
     let Some(last_written) = last_written_at else {
         return None;
     };
@@ -361,6 +392,50 @@ fn format_pending_events(events: &[SkillEvent]) -> String {
         .join("\n")
 }
 
+/// Marker opening the machine-readable metadata comment in exported Markdown.
+pub const JOURNAL_MARKDOWN_METADATA_MARKER: &str = "ponderer-journal";
+
+/// Renders entries as Markdown grouped by UTC day (`## YYYY-MM-DD`), one
+/// `### HH:MM UTC · <entry_type>` section per entry in chronological order.
+///
+/// Each section carries an HTML comment with the entry's metadata as JSON
+/// (everything except `content`, which follows verbatim), so an importer can
+/// rebuild the entry from the file while the rendered page stays readable.
+pub fn render_journal_markdown(entries: &[JournalEntry]) -> String {
+    let mut sorted = entries.iter().collect::<Vec<_>>();
+    sorted.sort_by_key(|entry| entry.timestamp);
+
+    let mut out = String::from("# Journal\n");
+    let mut current_day = None;
+    for entry in sorted {
+        let day = entry.timestamp.date_naive();
+        if current_day != Some(day) {
+            out.push_str(&format!("\n## {}\n", day.format("%Y-%m-%d")));
+            current_day = Some(day);
+        }
+        let metadata = serde_json::json!({
+            "id": entry.id,
+            "timestamp": entry.timestamp.to_rfc3339(),
+            "entry_type": entry.entry_type.as_db_str(),
+            "context": entry.context,
+            "related_concerns": entry.related_concerns,
+            "mood_at_time": entry.mood_at_time,
+        })
+        .to_string()
+        // `--` cannot appear inside an HTML comment; JSON escapes keep it lossless.
+        .replace("--", "-\\u002d");
+        out.push_str(&format!(
+            "\n### {} UTC · {}\n\n<!-- {} {} -->\n\n{}\n",
+            entry.timestamp.format("%H:%M"),
+            entry.entry_type.as_db_str(),
+            JOURNAL_MARKDOWN_METADATA_MARKER,
+            metadata,
+            entry.content.trim()
+        ));
+    }
+    out
+}
+
 fn truncate(input: &str, max_chars: usize) -> String {
     let mut out = String::new();
     for (idx, ch) in input.chars().enumerate() {
@@ -439,6 +514,71 @@ mod tests {
         }
     }
 
+    #[test]
+    fn markdown_export_groups_by_day_with_recoverable_metadata() {
+        let at = |raw: &str| raw.parse::<DateTime<Utc>>().expect("timestamp");
+        let entry = |id: &str, timestamp: &str, entry_type, content: &str| JournalEntry {
+            id: id.to_string(),
+            timestamp: at(timestamp),
+            entry_type,
+            content: content.to_string(),
+            context: JournalContext {
+                trigger: "orientation --quiet".to_string(),
+                user_state_at_time: "idle".to_string(),
+                time_of_day: "evening".to_string(),
+            },
+            related_concerns: vec!["c-1".to_string()],
+            mood_at_time: Some(JournalMood {
+                valence: 0.25,
+                arousal: -0.5,
+            }),
+        };
+        let markdown = render_journal_markdown(&[
+            entry(
+                "b",
+                "2026-03-02T08:15:00Z",
+                JournalEntryType::Question,
+                "Why so quiet?",
+            ),
+            entry(
+                "a",
+                "2026-03-01T21:40:00Z",
+                JournalEntryType::Reflection,
+                "A long day.",
+            ),
+        ]);
+
+        let headers = markdown
+            .lines()
+            .filter(|line| line.starts_with('#'))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            headers,
+            vec![
+                "# Journal",
+                "## 2026-03-01",
+                "### 21:40 UTC · reflection",
+                "## 2026-03-02",
+                "### 08:15 UTC · question",
+            ]
+        );
+        assert!(markdown.contains("-->\n\nA long day.\n"));
+
+        let prefix = format!("<!-- {} ", JOURNAL_MARKDOWN_METADATA_MARKER);
+        let metadata = markdown
+            .lines()
+            .filter_map(|line| line.strip_prefix(prefix.as_str())?.strip_suffix(" -->"))
+            .map(|json| serde_json::from_str::<serde_json::Value>(json).expect("metadata json"))
+            .collect::<Vec<_>>();
+        assert_eq!(metadata.len(), 2);
+        assert_eq!(metadata[0]["id"], "a");
+        assert_eq!(metadata[0]["timestamp"], "2026-03-01T21:40:00+00:00");
+        assert_eq!(metadata[0]["entry_type"], "reflection");
+        assert_eq!(metadata[0]["context"]["trigger"], "orientation --quiet");
+        assert_eq!(metadata[0]["related_concerns"][0], "c-1");
+        assert_eq!(metadata[0]["mood_at_time"]["arousal"], -0.5);
+    }
+
     #[test]
     fn journal_prompt_contains_core_sections() {
         let prompt = JournalEngine::build_journal_prompt(&sample_orientation(), &[], &[], &[]);
@@ -454,9 +594,10 @@ mod tests {
         let reason = journal_skip_reason(
             now,
             None,
+            0,
             Disposition::Journal,
             Some(Disposition::Journal),
-            DEFAULT_JOURNAL_MIN_INTERVAL_SECS,
+            JournalRateLimits::default(),
         );
         assert_eq!(reason, Some(JournalSkipReason::SameDisposition));
     }
@@ -465,7 +606,11 @@ mod tests {
     fn rate_limit_skips_when_interval_not_elapsed() {
         let now = Utc::now();
         let last = now - ChronoDuration::seconds(60);
-        let reason = journal_skip_reason(now, Some(last), Disposition::Journal, None, 300);
+        let limits = JournalRateLimits {
+            min_interval_secs: 300,
+            max_per_day: 0,
+        };
+        let reason = journal_skip_reason(now, Some(last), 0, Disposition::Journal, None, limits);
         assert_eq!(
             reason,
             Some(JournalSkipReason::MinInterval {
@@ -481,13 +626,37 @@ mod tests {
         let reason = journal_skip_reason(
             now,
             Some(last),
+            3,
             Disposition::Journal,
             Some(Disposition::Observe),
-            DEFAULT_JOURNAL_MIN_INTERVAL_SECS,
+            JournalRateLimits::default(),
         );
         assert_eq!(reason, None);
     }
 
+    #[test]
+    fn rate_limit_skips_when_daily_cap_reached() {
+        let now = Utc::now();
+        let last = now - ChronoDuration::seconds(1000);
+        let limits = JournalRateLimits {
+            min_interval_secs: 60,
+            max_per_day: 4,
+        };
+        let reason = journal_skip_reason(now, Some(last), 4, Disposition::Journal, None, limits);
+        assert_eq!(
+            reason,
+            Some(JournalSkipReason::DailyCapReached { entries_today: 4 })
+        );
+
+        let uncapped = JournalRateLimits {
+            max_per_day: 0,
+            ..limits
+        };
+        let reason =
+            journal_skip_reason(now, Some(last), 400, Disposition::Journal, None, uncapped);
+        assert_eq!(reason, None);
+    }
+
     #[test]
     fn parses_generated_journal_entry() {
         let orientation = sample_orientation();
@@ -508,6 +677,7 @@ mod tests {
                 key_events: vec![],
                 last_update_reason: "test".to_string(),
             },
+            pinned: false,
         }];
 
         let response = JournalLlmResponse {
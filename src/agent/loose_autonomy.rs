@@ -5,6 +5,7 @@ use serde::Deserialize;
 
 use crate::generation_telemetry::GenerationObserver;
 use crate::llm_client::{LlmClient, Message as LlmMessage};
+use crate::llm_provider::LlmProvider;
 
 pub const LOOSE_STATUS_BLOCK_START: &str = "[intention_status]";
 pub const LOOSE_STATUS_BLOCK_END: &str = "[/intention_status]";
@@ -55,6 +56,11 @@ impl LooseGoalEngine {
         self
     }
 
+    pub fn with_llm_provider(mut self, provider: LlmProvider) -> Self {
+        self.client = self.client.with_provider(provider);
+        self
+    }
+
     pub async fn propose(&self, lived_context: &str) -> Result<Option<LooseGoalSeed>> {
         let messages = vec![
             LlmMessage {
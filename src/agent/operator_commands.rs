@@ -0,0 +1,321 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Duration as ChronoDuration, Local, Utc};
+
+use crate::agent::focus::{DEFAULT_FOCUS_MINUTES, MAX_FOCUS_MINUTES};
+use crate::tools::reminders::MAX_REMINDER_MINUTES;
+
+/// A `/`-prefixed operator chat message that is answered directly instead of
+/// being sent to the model.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OperatorCommand {
+    Pause,
+    Resume,
+    Compact,
+    Reflect,
+    Reminder {
+        in_minutes: u64,
+        content: String,
+    },
+    DoNotDisturb {
+        on: bool,
+        minutes: Option<u64>,
+    },
+    Help,
+    /// A known command with bad arguments; carries the reply.
+    Invalid(String),
+    Unknown(String),
+}
+
+const HELP_TEXT: &str = "Chat commands:\n\
+/pause, /resume: stop or restart the agent loop\n\
+/compact: summarize this conversation's older messages now\n\
+/reflect: run a persona reflection now\n\
+/reminder <25m|2h|1d> <text>: set a reminder\n\
+/dnd on [duration], /dnd off: hold proactive messages (deep focus)\n\
+/help: show this list";
+
+/// Recognize an operator meta-command. Returns `None` for ordinary messages,
+/// including ones that merely start with a path such as `/tmp/log.txt`.
+pub fn parse_operator_command(content: &str) -> Option<OperatorCommand> {
+    let rest = content.trim().strip_prefix('/')?;
+    let (name, args) = match rest.split_once(char::is_whitespace) {
+        Some((name, args)) => (name, args.trim()),
+        None => (rest, ""),
+    };
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return None;
+    }
+
+    let command = match name.to_ascii_lowercase().as_str() {
+        "pause" => OperatorCommand::Pause,
+        "resume" | "unpause" => OperatorCommand::Resume,
+        "compact" => OperatorCommand::Compact,
+        "reflect" => OperatorCommand::Reflect,
+        "reminder" | "remind" => parse_reminder(args),
+        "dnd" => parse_do_not_disturb(args),
+        "help" | "commands" => OperatorCommand::Help,
+        _ => OperatorCommand::Unknown(name.to_string()),
+    };
+    Some(command)
+}
+
+fn parse_reminder(args: &str) -> OperatorCommand {
+    let usage = || {
+        OperatorCommand::Invalid("Usage: /reminder <25m|2h|1d> <what to remind you of>".to_string())
+    };
+    let Some((when, content)) = args.split_once(char::is_whitespace) else {
+        return usage();
+    };
+    let content = content.trim();
+    match parse_minutes(when) {
+        Some(in_minutes) if !content.is_empty() && in_minutes <= MAX_REMINDER_MINUTES => {
+            OperatorCommand::Reminder {
+                in_minutes,
+                content: content.to_string(),
+            }
+        }
+        Some(in_minutes) if in_minutes > MAX_REMINDER_MINUTES => {
+            OperatorCommand::Invalid("Reminders can be at most a year out.".to_string())
+        }
+        _ => usage(),
+    }
+}
+
+fn parse_do_not_disturb(args: &str) -> OperatorCommand {
+    let mut words = args.split_whitespace();
+    let state = words.next().map(str::to_ascii_lowercase);
+    let duration = words.next();
+    match (state.as_deref(), duration, words.next()) {
+        (Some("on"), None, None) => OperatorCommand::DoNotDisturb {
+            on: true,
+            minutes: None,
+        },
+        (Some("on"), Some(duration), None) => match parse_minutes(duration) {
+            Some(minutes) if minutes <= MAX_FOCUS_MINUTES => OperatorCommand::DoNotDisturb {
+                on: true,
+                minutes: Some(minutes),
+            },
+            Some(_) => OperatorCommand::Invalid(format!(
+                "Do not disturb can last at most {}h.",
+                MAX_FOCUS_MINUTES / 60
+            )),
+            None => OperatorCommand::Invalid(format!(
+                "'{}' is not a duration; try /dnd on 45m",
+                duration
+            )),
+        },
+        (Some("off"), None, None) => OperatorCommand::DoNotDisturb {
+            on: false,
+            minutes: None,
+        },
+        _ => OperatorCommand::Invalid("Usage: /dnd on [duration] or /dnd off".to_string()),
+    }
+}
+
+/// `25m`, `2h`, `1d`, or bare minutes. Zero is rejected.
+fn parse_minutes(raw: &str) -> Option<u64> {
+    let raw = raw.trim().to_ascii_lowercase();
+    let (digits, per_unit) = match raw.char_indices().last()? {
+        (idx, 'm') => (&raw[..idx], 1),
+        (idx, 'h') => (&raw[..idx], 60),
+        (idx, 'd') => (&raw[..idx], 24 * 60),
+        _ => (raw.as_str(), 1),
+    };
+    let minutes = digits.parse::<u64>().ok()?.checked_mul(per_unit)?;
+    (minutes > 0).then_some(minutes)
+}
+
+/// The agent operations chat commands reach. Implemented by `Agent`; kept as a
+/// trait so dispatch and reply wording can be tested without one.
+#[async_trait]
+pub trait OperatorCommandTarget: Sync {
+    /// Returns the resulting paused state.
+    async fn set_loop_paused(&self, paused: bool) -> bool;
+    /// Returns the operator-facing compaction message.
+    async fn compact_now(&self, conversation_id: &str) -> Result<String>;
+    /// `Ok(None)` when a reflection is already running; otherwise a short summary.
+    async fn reflect_now(&self) -> Result<Option<String>>;
+    async fn add_reminder(&self, content: &str, due_at: DateTime<Utc>) -> Result<()>;
+    /// Starts (or replaces) a deep focus session; returns when it ends.
+    async fn start_do_not_disturb(&self, minutes: u64) -> Result<DateTime<Utc>>;
+    /// Returns whether a session was active.
+    async fn end_do_not_disturb(&self) -> Result<bool>;
+}
+
+/// Run `command` against `target` and return the chat reply. Failures are
+/// reported in the reply rather than as errors, since the operator asked.
+pub async fn dispatch_operator_command(
+    target: &impl OperatorCommandTarget,
+    conversation_id: &str,
+    command: OperatorCommand,
+) -> String {
+    let outcome = match command {
+        OperatorCommand::Pause => {
+            target.set_loop_paused(true).await;
+            Ok("Paused. Chat commands still work; send /resume to continue.".to_string())
+        }
+        OperatorCommand::Resume => {
+            target.set_loop_paused(false).await;
+            Ok("Resumed.".to_string())
+        }
+        OperatorCommand::Compact => target.compact_now(conversation_id).await,
+        OperatorCommand::Reflect => target.reflect_now().await.map(|summary| match summary {
+            Some(summary) => format!("Reflection complete. {}", summary),
+            None => "A reflection is already running.".to_string(),
+        }),
+        OperatorCommand::Reminder {
+            in_minutes,
+            content,
+        } => {
+            let due_at = Utc::now() + ChronoDuration::minutes(in_minutes as i64);
+            target.add_reminder(&content, due_at).await.map(|()| {
+                format!(
+                    "Reminder set for {}: {}",
+                    due_at.with_timezone(&Local).format("%a %H:%M"),
+                    content
+                )
+            })
+        }
+        OperatorCommand::DoNotDisturb { on: true, minutes } => target
+            .start_do_not_disturb(minutes.unwrap_or(DEFAULT_FOCUS_MINUTES))
+            .await
+            .map(|ends_at| {
+                format!(
+                    "Do not disturb until {}. Send /dnd off to end it early.",
+                    ends_at.with_timezone(&Local).format("%H:%M")
+                )
+            }),
+        OperatorCommand::DoNotDisturb { on: false, .. } => {
+            target.end_do_not_disturb().await.map(|was_active| {
+                if was_active {
+                    "Do not disturb is off.".to_string()
+                } else {
+                    "Do not disturb was not on.".to_string()
+                }
+            })
+        }
+        OperatorCommand::Help => Ok(HELP_TEXT.to_string()),
+        OperatorCommand::Invalid(reply) => Ok(reply),
+        OperatorCommand::Unknown(name) => {
+            Ok(format!("Unknown command '/{}'.\n{}", name, HELP_TEXT))
+        }
+    };
+    outcome.unwrap_or_else(|error| format!("Command failed: {}", error))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    #[test]
+    fn slash_commands_parse_and_ordinary_messages_pass_through() {
+        assert_eq!(
+            parse_operator_command(" /Pause "),
+            Some(OperatorCommand::Pause)
+        );
+        assert_eq!(
+            parse_operator_command("/reminder 25m stretch your legs"),
+            Some(OperatorCommand::Reminder {
+                in_minutes: 25,
+                content: "stretch your legs".to_string(),
+            })
+        );
+        assert_eq!(
+            parse_operator_command("/dnd on 2h"),
+            Some(OperatorCommand::DoNotDisturb {
+                on: true,
+                minutes: Some(120),
+            })
+        );
+        assert!(matches!(
+            parse_operator_command("/reminder soon stretch"),
+            Some(OperatorCommand::Invalid(_))
+        ));
+        assert_eq!(
+            parse_operator_command("/dnd on 1d"),
+            Some(OperatorCommand::Invalid(
+                "Do not disturb can last at most 8h.".to_string()
+            ))
+        );
+        assert_eq!(
+            parse_operator_command("/frobnicate"),
+            Some(OperatorCommand::Unknown("frobnicate".to_string()))
+        );
+        assert_eq!(parse_operator_command("/tmp/build.log is empty"), None);
+        assert_eq!(parse_operator_command("pause please"), None);
+        assert_eq!(parse_operator_command("/"), None);
+    }
+
+    #[derive(Default)]
+    struct RecordingTarget {
+        calls: Mutex<Vec<String>>,
+    }
+
+    impl RecordingTarget {
+        fn record(&self, call: String) {
+            self.calls.lock().unwrap().push(call);
+        }
+    }
+
+    #[async_trait]
+    impl OperatorCommandTarget for RecordingTarget {
+        async fn set_loop_paused(&self, paused: bool) -> bool {
+            self.record(format!("paused={}", paused));
+            paused
+        }
+        async fn compact_now(&self, conversation_id: &str) -> Result<String> {
+            self.record(format!("compact {}", conversation_id));
+            Ok("Compacted 12 messages.".to_string())
+        }
+        async fn reflect_now(&self) -> Result<Option<String>> {
+            anyhow::bail!("Self-reflection is disabled")
+        }
+        async fn add_reminder(&self, content: &str, _due_at: DateTime<Utc>) -> Result<()> {
+            self.record(format!("reminder {}", content));
+            Ok(())
+        }
+        async fn start_do_not_disturb(&self, minutes: u64) -> Result<DateTime<Utc>> {
+            self.record(format!("dnd {}", minutes));
+            Ok(Utc::now())
+        }
+        async fn end_do_not_disturb(&self) -> Result<bool> {
+            self.record("dnd off".to_string());
+            Ok(false)
+        }
+    }
+
+    #[tokio::test]
+    async fn dispatch_runs_the_command_and_reports_failures_in_the_reply() {
+        let target = RecordingTarget::default();
+        let reply = |content: &str| {
+            let command = parse_operator_command(content).expect("command");
+            dispatch_operator_command(&target, "conv-1", command)
+        };
+
+        assert!(reply("/pause").await.starts_with("Paused."));
+        assert_eq!(reply("/compact").await, "Compacted 12 messages.");
+        assert!(reply("/reminder 1h stretch").await.ends_with(": stretch"));
+        assert!(reply("/dnd on").await.starts_with("Do not disturb until"));
+        assert_eq!(
+            reply("/reflect").await,
+            "Command failed: Self-reflection is disabled"
+        );
+        assert!(reply("/nope").await.starts_with("Unknown command '/nope'."));
+
+        assert_eq!(
+            *target.calls.lock().unwrap(),
+            vec![
+                "paused=true".to_string(),
+                "compact conv-1".to_string(),
+                "reminder stretch".to_string(),
+                format!("dnd {}", DEFAULT_FOCUS_MINUTES),
+            ]
+        );
+    }
+}
@@ -7,7 +7,8 @@ use crate::agent::journal::JournalEntry;
 use crate::database::PersonaSnapshot;
 use crate::generation_telemetry::GenerationObserver;
 use crate::llm_client::{LlmClient, Message as LlmMessage};
-use crate::presence::{PresenceState, ProcessCategory};
+use crate::llm_provider::LlmProvider;
+use crate::presence::{FocusedWindow, PresenceState, ProcessCategory};
 use crate::runtime_plugin_host::{
     render_prompt_slot_addendum, PromptContribution, PromptContributionMergeLimits,
     PromptContributionSlot,
@@ -19,8 +20,12 @@ const ORIENTATION_SYSTEM_PROMPT: &str = "You are an orientation engine for a des
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DesktopObservation {
     pub captured_at: DateTime<Utc>,
+    /// Empty when only the focused window was sampled.
     pub screenshot_path: String,
+    /// Empty when only the focused window was sampled.
     pub summary: String,
+    #[serde(default)]
+    pub focused_window: Option<FocusedWindow>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -54,9 +59,16 @@ impl OrientationContext {
 
     pub fn format_system(&self) -> String {
         let load = &self.presence.system_load;
+        let power = &self.presence.power;
         format!(
-            "cpu={:.1}% mem={:.1}% gpu_temp={:?} gpu_util={:?}",
-            load.cpu_percent, load.memory_percent, load.gpu_temp_celsius, load.gpu_util_percent
+            "cpu={:.1}% mem={:.1}% gpu_temp={:?} gpu_util={:?} on_ac={} battery={:?} charging={}",
+            load.cpu_percent,
+            load.memory_percent,
+            load.gpu_temp_celsius,
+            load.gpu_util_percent,
+            power.on_ac,
+            power.battery_percent,
+            power.charging
         )
     }
 
@@ -159,13 +171,27 @@ impl OrientationContext {
             return "None".to_string();
         };
 
-        format!(
-            "observed_at={} age_seconds={} path={}\nsummary={}",
+        let mut formatted = format!(
+            "observed_at={} age_seconds={}",
             obs.captured_at.to_rfc3339(),
-            observed_age_seconds(obs.captured_at),
-            obs.screenshot_path,
-            obs.summary.trim()
-        )
+            observed_age_seconds(obs.captured_at)
+        );
+        if let Some(window) = &obs.focused_window {
+            formatted.push_str(&format!(
+                "\nfocused_app={} focused_category={:?}\nfocused_title={}",
+                window.app,
+                window.category(),
+                window.title
+            ));
+        }
+        if !obs.screenshot_path.trim().is_empty() {
+            formatted.push_str(&format!(
+                "\npath={}\nsummary={}",
+                obs.screenshot_path,
+                obs.summary.trim()
+            ));
+        }
+        formatted
     }
 
     pub fn format_recent_action_digest(&self) -> String {
@@ -310,7 +336,32 @@ pub struct Anomaly {
     pub related_concerns: Vec<String>,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+impl Anomaly {
+    /// Stable identity for dedupe across ticks: FNV-1a over the lowercased
+    /// description with digits dropped, so a reading that changes from
+    /// 91.2C to 92.0C is still the same anomaly.
+    pub fn fingerprint(&self) -> String {
+        let normalized = self
+            .description
+            .chars()
+            .filter(|c| !c.is_ascii_digit())
+            .collect::<String>()
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ")
+            .to_lowercase();
+        let mut hash = 0xcbf29ce484222325_u64;
+        for byte in normalized.bytes() {
+            hash ^= u64::from(byte);
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+        format!("{:016x}", hash)
+    }
+}
+
+/// Ordered from least to most severe, so escalation is `>`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum AnomalySeverity {
     Interesting,
@@ -319,6 +370,21 @@ pub enum AnomalySeverity {
     Urgent,
 }
 
+impl AnomalySeverity {
+    pub fn as_db_str(self) -> &'static str {
+        match self {
+            Self::Interesting => "interesting",
+            Self::Notable => "notable",
+            Self::Concerning => "concerning",
+            Self::Urgent => "urgent",
+        }
+    }
+
+    pub fn from_db(raw: &str) -> Self {
+        parse_anomaly_severity(raw)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PendingThought {
     pub id: String,
@@ -377,6 +443,11 @@ impl OrientationEngine {
         self
     }
 
+    pub fn with_llm_provider(mut self, provider: LlmProvider) -> Self {
+        self.client = self.client.with_provider(provider);
+        self
+    }
+
     pub async fn orient(&self, context: OrientationContext) -> Result<Orientation> {
         let prompt = Self::build_orientation_prompt(&context);
         let messages = vec![
@@ -595,6 +666,12 @@ impl OrientationEngine {
         let cpu = ctx.presence.system_load.cpu_percent;
         let mem = ctx.presence.system_load.memory_percent;
 
+        let focused_window = ctx
+            .desktop_observation
+            .as_ref()
+            .and_then(|obs| obs.focused_window.as_ref());
+        let focused_category = focused_window.map(FocusedWindow::category);
+
         let user_state = if idle > 1800 {
             UserStateEstimate::Away {
                 since_secs: idle,
@@ -606,10 +683,20 @@ impl OrientationEngine {
                 since_secs: idle,
                 confidence: 0.75,
             }
+        } else if let (
+            Some(window),
+            Some(ProcessCategory::Development | ProcessCategory::Creative | ProcessCategory::Focus),
+        ) = (focused_window, focused_category)
+        {
+            UserStateEstimate::DeepWork {
+                activity: format!("focused in {}", window.app),
+                duration_estimate_secs: idle,
+                confidence: 0.68,
+            }
         } else if ctx.presence.active_processes.iter().any(|proc| {
             matches!(
                 proc.category,
-                ProcessCategory::Development | ProcessCategory::Creative
+                ProcessCategory::Development | ProcessCategory::Creative | ProcessCategory::Focus
             )
         }) && cpu > 20.0
         {
@@ -618,6 +705,11 @@ impl OrientationEngine {
                 duration_estimate_secs: idle,
                 confidence: 0.62,
             }
+        } else if let Some(window) = focused_window {
+            UserStateEstimate::LightWork {
+                activity: format!("using {}", window.app),
+                confidence: 0.6,
+            }
         } else {
             UserStateEstimate::LightWork {
                 activity: "active desktop usage".to_string(),
@@ -665,7 +757,19 @@ impl OrientationEngine {
             relevance: 0.65,
             relates_to: vec![concern.id.clone()],
         }));
-        if let Some(obs) = &ctx.desktop_observation {
+        if let Some(window) = focused_window {
+            salience_map.push(SalientItem {
+                source: "focused_window".to_string(),
+                summary: format!("Focused on {}: {}", window.app, window.title),
+                relevance: 0.75,
+                relates_to: Vec::new(),
+            });
+        }
+        if let Some(obs) = ctx
+            .desktop_observation
+            .as_ref()
+            .filter(|obs| !obs.summary.trim().is_empty())
+        {
             salience_map.push(SalientItem {
                 source: "desktop_observation".to_string(),
                 summary: obs.summary.clone(),
@@ -689,7 +793,14 @@ impl OrientationEngine {
             mem,
             ctx.pending_events.len()
         );
-        if let Some(obs) = &ctx.desktop_observation {
+        if let Some(window) = focused_window {
+            synthesis.push_str(&format!(" focused=\"{}\"", window.app));
+        }
+        if let Some(obs) = ctx
+            .desktop_observation
+            .as_ref()
+            .filter(|obs| !obs.summary.trim().is_empty())
+        {
             synthesis.push_str(&format!(
                 " desktop=\"{}\"",
                 obs.summary.replace('\n', " ").trim()
@@ -716,14 +827,56 @@ impl OrientationEngine {
     }
 }
 
-pub fn context_signature(ctx: &OrientationContext) -> String {
+/// How `context_signature` coarsens volatile presence readings, so steady
+/// work does not force a fresh orientation call every tick.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SignatureBuckets {
+    /// Idle-time band edges in seconds; only the band the idle time falls in
+    /// is part of the signature.
+    pub idle_band_edges_secs: Vec<u64>,
+    /// Width of the CPU and memory buckets in percentage points. 0 leaves
+    /// load out of the signature.
+    pub load_percent: f32,
+    /// Width of the local time-of-day bucket in minutes. 0 keeps only the hour.
+    pub time_of_day_minutes: u8,
+}
+
+impl Default for SignatureBuckets {
+    fn default() -> Self {
+        Self {
+            idle_band_edges_secs: vec![60, 300, 900, 3600, 10800],
+            load_percent: 10.0,
+            time_of_day_minutes: 15,
+        }
+    }
+}
+
+impl SignatureBuckets {
+    fn idle_band(&self, idle_seconds: u64) -> usize {
+        self.idle_band_edges_secs
+            .iter()
+            .filter(|edge| idle_seconds >= **edge)
+            .count()
+    }
+
+    fn load_bucket(&self, percent: f32) -> Option<u16> {
+        (self.load_percent > 0.0).then(|| (percent.max(0.0) / self.load_percent).floor() as u16)
+    }
+
+    fn minute_bucket(&self, minute: u8) -> u8 {
+        minute.checked_div(self.time_of_day_minutes).unwrap_or(0)
+    }
+}
+
+pub fn context_signature(ctx: &OrientationContext, buckets: &SignatureBuckets) -> String {
     #[derive(Serialize)]
     struct Signature<'a> {
-        idle_bucket: u64,
+        idle_band: usize,
         hour: u8,
         minute_bucket: u8,
-        cpu_bucket: u8,
-        memory_bucket: u8,
+        cpu_bucket: Option<u16>,
+        memory_bucket: Option<u16>,
         process_labels: Vec<String>,
         concern_ids: Vec<&'a str>,
         journal_ids: Vec<&'a str>,
@@ -734,6 +887,7 @@ pub fn context_signature(ctx: &OrientationContext) -> String {
         latest_dream: Option<String>,
         open_intentions: Vec<String>,
         desktop_observation: Option<String>,
+        focused_window: Option<String>,
     }
 
     let process_labels = ctx
@@ -764,11 +918,11 @@ pub fn context_signature(ctx: &OrientationContext) -> String {
         .collect::<Vec<_>>();
 
     let sig = Signature {
-        idle_bucket: ctx.presence.user_idle_seconds / 300,
+        idle_band: buckets.idle_band(ctx.presence.user_idle_seconds),
         hour: ctx.presence.time_context.local_hour,
-        minute_bucket: ctx.presence.time_context.local_minute / 5,
-        cpu_bucket: (ctx.presence.system_load.cpu_percent / 5.0).floor() as u8,
-        memory_bucket: (ctx.presence.system_load.memory_percent / 5.0).floor() as u8,
+        minute_bucket: buckets.minute_bucket(ctx.presence.time_context.local_minute),
+        cpu_bucket: buckets.load_bucket(ctx.presence.system_load.cpu_percent),
+        memory_bucket: buckets.load_bucket(ctx.presence.system_load.memory_percent),
         process_labels,
         concern_ids,
         journal_ids,
@@ -802,6 +956,16 @@ pub fn context_signature(ctx: &OrientationContext) -> String {
             .desktop_observation
             .as_ref()
             .map(|obs| obs.summary.trim().chars().take(220).collect()),
+        focused_window: ctx
+            .desktop_observation
+            .as_ref()
+            .and_then(|obs| obs.focused_window.as_ref())
+            .map(|window| {
+                format!("{}|{}", window.app, window.title)
+                    .chars()
+                    .take(220)
+                    .collect()
+            }),
     };
 
     serde_json::to_string(&sig).unwrap_or_else(|_| String::new())
@@ -1122,7 +1286,7 @@ struct LlmMood {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::presence::{SystemLoad, TimeContext};
+    use crate::presence::{PowerState, SystemLoad, TimeContext};
     use chrono::Weekday;
     use std::time::Duration;
 
@@ -1146,7 +1310,9 @@ mod tests {
                     memory_percent: 56.0,
                     gpu_temp_celsius: None,
                     gpu_util_percent: None,
+                    screen_sharing: false,
                 },
+                power: PowerState::default(),
                 active_processes: Vec::new(),
             },
             concerns: Vec::new(),
@@ -1219,6 +1385,7 @@ mod tests {
             captured_at: Utc::now() - chrono::Duration::seconds(30),
             screenshot_path: "/tmp/current.png".to_string(),
             summary: "FRESH DESKTOP EVIDENCE".to_string(),
+            focused_window: None,
         });
         ctx.latest_dream = Some("OLDER DREAM".to_string());
         ctx.persona = Some(PersonaSnapshot {
@@ -1250,6 +1417,7 @@ mod tests {
             captured_at: Utc::now() - chrono::Duration::seconds(90),
             screenshot_path: "/tmp/shot.png".to_string(),
             summary: "current desktop".to_string(),
+            focused_window: None,
         });
 
         let formatted = ctx.format_desktop_observation();
@@ -1257,6 +1425,40 @@ mod tests {
         assert!(formatted.contains("age_seconds="));
     }
 
+    #[test]
+    fn focused_window_without_screenshot_drives_heuristic_orientation() {
+        let mut ctx = sample_context();
+        ctx.desktop_observation = Some(DesktopObservation {
+            captured_at: Utc::now(),
+            screenshot_path: String::new(),
+            summary: String::new(),
+            focused_window: Some(FocusedWindow {
+                app: "Code".to_string(),
+                title: "orientation.rs - ponderer".to_string(),
+            }),
+        });
+
+        let formatted = ctx.format_desktop_observation();
+        assert!(formatted.contains("focused_app=Code"));
+        assert!(!formatted.contains("summary="));
+
+        let engine =
+            OrientationEngine::new("http://127.0.0.1:9".to_string(), "test".to_string(), None);
+        let orientation = engine.fallback_orientation(&ctx, None);
+        assert!(matches!(
+            orientation.user_state,
+            UserStateEstimate::DeepWork { ref activity, .. } if activity == "focused in Code"
+        ));
+        assert!(orientation
+            .salience_map
+            .iter()
+            .any(|item| item.source == "focused_window"));
+        assert!(orientation
+            .salience_map
+            .iter()
+            .all(|item| item.source != "desktop_observation"));
+    }
+
     #[test]
     fn orientation_rehydrates_from_durable_snapshot() {
         let snapshot = crate::database::OrientationSnapshotRecord {
@@ -1287,20 +1489,59 @@ mod tests {
 
     #[test]
     fn context_signature_changes_with_idle_bucket() {
+        let buckets = SignatureBuckets::default();
         let mut ctx_a = sample_context();
         let mut ctx_b = sample_context();
         ctx_b.presence.user_idle_seconds = 310;
-        let sig_a = context_signature(&ctx_a);
-        let sig_b = context_signature(&ctx_b);
+        let sig_a = context_signature(&ctx_a, &buckets);
+        let sig_b = context_signature(&ctx_b, &buckets);
         assert_ne!(sig_a, sig_b);
 
         // deterministic for same input
-        let sig_a2 = context_signature(&ctx_a);
+        let sig_a2 = context_signature(&ctx_a, &buckets);
         assert_eq!(sig_a, sig_a2);
 
         ctx_a.presence.user_idle_seconds = 45;
     }
 
+    #[test]
+    fn context_signature_ignores_small_presence_drift_but_not_large_changes() {
+        let buckets = SignatureBuckets::default();
+        let base = sample_context();
+        let signature = context_signature(&base, &buckets);
+
+        let mut drift = sample_context();
+        drift.presence.user_idle_seconds = 58;
+        drift.presence.time_context.local_minute = 44;
+        drift.presence.system_load.cpu_percent = 27.5;
+        drift.presence.system_load.memory_percent = 51.0;
+        assert_eq!(context_signature(&drift, &buckets), signature);
+
+        let mut away = sample_context();
+        away.presence.user_idle_seconds = 1_200;
+        assert_ne!(context_signature(&away, &buckets), signature);
+
+        let mut busy = sample_context();
+        busy.presence.system_load.cpu_percent = 85.0;
+        assert_ne!(context_signature(&busy, &buckets), signature);
+
+        let mut later = sample_context();
+        later.presence.time_context.local_minute = 46;
+        assert_ne!(context_signature(&later, &buckets), signature);
+
+        let coarse = SignatureBuckets {
+            idle_band_edges_secs: vec![3600],
+            load_percent: 0.0,
+            time_of_day_minutes: 0,
+        };
+        for changed in [&away, &busy, &later] {
+            assert_eq!(
+                context_signature(changed, &coarse),
+                context_signature(&base, &coarse)
+            );
+        }
+    }
+
     #[test]
     fn context_signature_changes_with_desktop_observation() {
         let mut ctx_a = sample_context();
@@ -1309,12 +1550,20 @@ mod tests {
             captured_at: Utc::now(),
             screenshot_path: "/tmp/shot.png".to_string(),
             summary: "User is editing Rust source in terminal".to_string(),
+            focused_window: None,
         });
 
-        assert_ne!(context_signature(&ctx_a), context_signature(&ctx_b));
+        let buckets = SignatureBuckets::default();
+        assert_ne!(
+            context_signature(&ctx_a, &buckets),
+            context_signature(&ctx_b, &buckets)
+        );
 
         ctx_a.desktop_observation = ctx_b.desktop_observation.clone();
-        assert_eq!(context_signature(&ctx_a), context_signature(&ctx_b));
+        assert_eq!(
+            context_signature(&ctx_a, &buckets),
+            context_signature(&ctx_b, &buckets)
+        );
     }
 
     #[test]
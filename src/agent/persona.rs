@@ -0,0 +1,130 @@
+use serde::{Deserialize, Serialize};
+
+/// Deterministic voice rules applied to operator-facing chat replies, so the
+/// character card's register holds without a second model call. Empty rules
+/// leave text untouched.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VoiceRules {
+    /// Phrases the persona never uses, replaced wherever they appear.
+    pub banned_phrases: Vec<PhraseSubstitution>,
+    /// Line appended to every reply that does not already end with it.
+    pub sign_off: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PhraseSubstitution {
+    /// Matched case-insensitively (ASCII) on word boundaries.
+    pub phrase: String,
+    /// Inserted verbatim; empty deletes the phrase.
+    #[serde(default)]
+    pub replacement: String,
+}
+
+impl VoiceRules {
+    pub fn is_empty(&self) -> bool {
+        self.banned_phrases
+            .iter()
+            .all(|rule| rule.phrase.trim().is_empty())
+            && self.sign_off.trim().is_empty()
+    }
+}
+
+/// Apply `rules` to an operator-facing reply: banned-phrase substitution in
+/// rule order, then the sign-off. Blank text is returned unchanged.
+pub fn apply_voice(text: &str, rules: &VoiceRules) -> String {
+    if text.trim().is_empty() || rules.is_empty() {
+        return text.to_string();
+    }
+    let mut voiced = text.to_string();
+    for rule in &rules.banned_phrases {
+        let phrase = rule.phrase.trim();
+        if !phrase.is_empty() {
+            voiced = replace_phrase(&voiced, phrase, &rule.replacement);
+        }
+    }
+
+    let sign_off = rules.sign_off.trim();
+    if !sign_off.is_empty() && !voiced.trim_end().ends_with(sign_off) {
+        voiced = format!("{}\n\n{}", voiced.trim_end(), sign_off);
+    }
+    voiced
+}
+
+/// Replace every word-bounded, ASCII-case-insensitive occurrence of `phrase`.
+fn replace_phrase(text: &str, phrase: &str, replacement: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = find_phrase(rest, phrase) {
+        out.push_str(&rest[..start]);
+        out.push_str(replacement);
+        rest = &rest[start + phrase.len()..];
+    }
+    out.push_str(rest);
+    out
+}
+
+fn find_phrase(haystack: &str, phrase: &str) -> Option<usize> {
+    let needle = phrase.as_bytes();
+    let is_word = |c: char| c.is_alphanumeric() || c == '_';
+    let starts_word = phrase.chars().next().is_some_and(is_word);
+    let ends_word = phrase.chars().next_back().is_some_and(is_word);
+    haystack
+        .as_bytes()
+        .windows(needle.len())
+        .enumerate()
+        .filter(|(_, window)| window.eq_ignore_ascii_case(needle))
+        .map(|(start, _)| start)
+        .find(|&start| {
+            let joins_before = haystack[..start].chars().next_back().is_some_and(is_word);
+            let joins_after = haystack[start + needle.len()..]
+                .chars()
+                .next()
+                .is_some_and(is_word);
+            let inside_word = (starts_word && joins_before) || (ends_word && joins_after);
+            !inside_word
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules(phrases: &[(&str, &str)], sign_off: &str) -> VoiceRules {
+        VoiceRules {
+            banned_phrases: phrases
+                .iter()
+                .map(|(phrase, replacement)| PhraseSubstitution {
+                    phrase: phrase.to_string(),
+                    replacement: replacement.to_string(),
+                })
+                .collect(),
+            sign_off: sign_off.to_string(),
+        }
+    }
+
+    #[test]
+    fn banned_phrases_are_replaced_on_word_boundaries() {
+        let rules = rules(&[("as an AI", "as myself"), ("delve", "dig")], "");
+
+        assert_eq!(
+            apply_voice("As an AI, I'd delve into it. Delve deeper!", &rules),
+            "as myself, I'd dig into it. dig deeper!"
+        );
+        assert_eq!(
+            apply_voice("Delves and redelve stay put.", &rules),
+            "Delves and redelve stay put."
+        );
+    }
+
+    #[test]
+    fn sign_off_is_appended_once_and_blank_text_is_left_alone() {
+        let rules = rules(&[], "— P.");
+
+        let signed = apply_voice("Done.\n", &rules);
+        assert_eq!(signed, "Done.\n\n— P.");
+        assert_eq!(apply_voice(&signed, &rules), signed);
+        assert_eq!(apply_voice("  ", &rules), "  ");
+        assert_eq!(apply_voice("Done.", &VoiceRules::default()), "Done.");
+    }
+}
@@ -51,6 +51,184 @@ pub struct TrajectoryAnalysis {
     pub confidence: f64,
 }
 
+/// Trait scores closer than this between snapshots are treated as unchanged.
+const TRAIT_CHANGE_EPSILON: f64 = 0.005;
+
+/// One personality dimension's movement between two snapshots.
+/// `previous`/`current` are `None` when the dimension was added or dropped.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TraitChange {
+    pub dimension: String,
+    pub previous: Option<f64>,
+    pub current: Option<f64>,
+    pub delta: f64,
+}
+
+/// Structured delta between two persona snapshots, so a timeline can show
+/// what moved instead of two blocks of prose.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PersonaDiff {
+    pub from_id: String,
+    pub to_id: String,
+    /// Self-description sentences/clauses that are new in `to`
+    pub added_phrases: Vec<String>,
+    /// Self-description sentences/clauses that no longer appear
+    pub removed_phrases: Vec<String>,
+    /// Formative experiences that are new in `to`
+    pub added_themes: Vec<String>,
+    /// Formative experiences that dropped out
+    pub removed_themes: Vec<String>,
+    /// Changed dimensions, largest movement first
+    pub trait_changes: Vec<TraitChange>,
+    /// Mean signed score change across dimensions present in both snapshots
+    pub valence_drift: f64,
+    pub trajectory_changed: bool,
+}
+
+impl PersonaDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added_phrases.is_empty()
+            && self.removed_phrases.is_empty()
+            && self.added_themes.is_empty()
+            && self.removed_themes.is_empty()
+            && self.trait_changes.is_empty()
+            && !self.trajectory_changed
+    }
+
+    /// One-line summary for reasoning traces.
+    pub fn summary(&self) -> String {
+        if self.is_empty() {
+            return "no persona changes since the previous snapshot".to_string();
+        }
+        let mut parts = Vec::new();
+        if !self.trait_changes.is_empty() {
+            let traits: Vec<String> = self
+                .trait_changes
+                .iter()
+                .take(5)
+                .map(|change| format!("{} {:+.2}", change.dimension, change.delta))
+                .collect();
+            parts.push(format!("traits {}", traits.join(", ")));
+        }
+        parts.push(format!("valence drift {:+.2}", self.valence_drift));
+        if !self.added_phrases.is_empty() || !self.removed_phrases.is_empty() {
+            parts.push(format!(
+                "self-description +{}/-{} phrases",
+                self.added_phrases.len(),
+                self.removed_phrases.len()
+            ));
+        }
+        if !self.added_themes.is_empty() || !self.removed_themes.is_empty() {
+            parts.push(format!(
+                "themes +{}/-{}",
+                self.added_themes.len(),
+                self.removed_themes.len()
+            ));
+        }
+        if self.trajectory_changed {
+            parts.push("trajectory changed".to_string());
+        }
+        parts.join("; ")
+    }
+}
+
+fn normalize_phrase(phrase: &str) -> String {
+    phrase
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+fn description_phrases(description: &str) -> Vec<String> {
+    description
+        .split(['.', '!', '?', ';', '\n'])
+        .map(str::trim)
+        .filter(|phrase| !phrase.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Items of `next` missing from `prev` (case/whitespace-insensitive), in `next` order.
+fn added_items(prev: &[String], next: &[String]) -> Vec<String> {
+    let known: std::collections::HashSet<String> =
+        prev.iter().map(|item| normalize_phrase(item)).collect();
+    let mut seen = std::collections::HashSet::new();
+    next.iter()
+        .filter(|item| {
+            let key = normalize_phrase(item);
+            !key.is_empty() && !known.contains(&key) && seen.insert(key)
+        })
+        .cloned()
+        .collect()
+}
+
+/// Compute what changed from `prev` to `next`.
+pub fn diff_snapshots(prev: &PersonaSnapshot, next: &PersonaSnapshot) -> PersonaDiff {
+    let prev_phrases = description_phrases(&prev.self_description);
+    let next_phrases = description_phrases(&next.self_description);
+
+    let mut dimensions: Vec<&String> = prev
+        .traits
+        .dimensions
+        .keys()
+        .chain(next.traits.dimensions.keys())
+        .collect();
+    dimensions.sort();
+    dimensions.dedup();
+
+    let mut trait_changes = Vec::new();
+    let mut shared_delta_sum = 0.0;
+    let mut shared_count = 0usize;
+    for dimension in dimensions {
+        let previous = prev.traits.dimensions.get(dimension).copied();
+        let current = next.traits.dimensions.get(dimension).copied();
+        let delta = current.unwrap_or(0.0) - previous.unwrap_or(0.0);
+        if previous.is_some() && current.is_some() {
+            shared_delta_sum += delta;
+            shared_count += 1;
+        }
+        if previous.is_none() || current.is_none() || delta.abs() >= TRAIT_CHANGE_EPSILON {
+            trait_changes.push(TraitChange {
+                dimension: dimension.clone(),
+                previous,
+                current,
+                delta,
+            });
+        }
+    }
+    trait_changes.sort_by(|a, b| {
+        b.delta
+            .abs()
+            .partial_cmp(&a.delta.abs())
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| a.dimension.cmp(&b.dimension))
+    });
+
+    let normalized_trajectory = |snapshot: &PersonaSnapshot| {
+        snapshot
+            .inferred_trajectory
+            .as_deref()
+            .map(normalize_phrase)
+    };
+
+    PersonaDiff {
+        from_id: prev.id.clone(),
+        to_id: next.id.clone(),
+        added_phrases: added_items(&prev_phrases, &next_phrases),
+        removed_phrases: added_items(&next_phrases, &prev_phrases),
+        added_themes: added_items(&prev.formative_experiences, &next.formative_experiences),
+        removed_themes: added_items(&next.formative_experiences, &prev.formative_experiences),
+        trait_changes,
+        valence_drift: if shared_count == 0 {
+            0.0
+        } else {
+            shared_delta_sum / shared_count as f64
+        },
+        trajectory_changed: normalized_trajectory(prev) != normalized_trajectory(next),
+    }
+}
+
 /// Engine for inferring persona trajectories
 pub struct TrajectoryEngine {
     client: Client,
@@ -655,6 +833,71 @@ mod tests {
         assert!(traits.dimensions.is_empty());
     }
 
+    fn snapshot(
+        id: &str,
+        description: &str,
+        traits: &[(&str, f64)],
+        experiences: &[&str],
+        trajectory: Option<&str>,
+    ) -> PersonaSnapshot {
+        PersonaSnapshot {
+            id: id.to_string(),
+            captured_at: Utc::now(),
+            traits: PersonaTraits {
+                dimensions: traits
+                    .iter()
+                    .map(|(name, score)| (name.to_string(), *score))
+                    .collect(),
+            },
+            system_prompt: "prompt".to_string(),
+            trigger: "scheduled_reflection".to_string(),
+            self_description: description.to_string(),
+            inferred_trajectory: trajectory.map(str::to_string),
+            formative_experiences: experiences.iter().map(|item| item.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn diff_snapshots_reports_phrases_themes_and_trait_drift() {
+        let prev = snapshot(
+            "a",
+            "I am a careful helper. I enjoy small puzzles.",
+            &[("curious", 0.5), ("helpful", 0.8), ("playful", 0.4)],
+            &["Fixed a flaky build", "Talked about tide pools"],
+            Some("toward patience"),
+        );
+        let next = snapshot(
+            "b",
+            "I am a careful helper.  I have started writing poetry!",
+            &[("curious", 0.7), ("helpful", 0.8), ("wry", 0.3)],
+            &["talked about tide pools", "Wrote a sonnet for the operator"],
+            Some("Toward patience"),
+        );
+
+        let diff = diff_snapshots(&prev, &next);
+
+        assert_eq!((diff.from_id.as_str(), diff.to_id.as_str()), ("a", "b"));
+        assert_eq!(diff.added_phrases, vec!["I have started writing poetry"]);
+        assert_eq!(diff.removed_phrases, vec!["I enjoy small puzzles"]);
+        assert_eq!(diff.added_themes, vec!["Wrote a sonnet for the operator"]);
+        assert_eq!(diff.removed_themes, vec!["Fixed a flaky build"]);
+        let dimensions: Vec<&str> = diff
+            .trait_changes
+            .iter()
+            .map(|change| change.dimension.as_str())
+            .collect();
+        assert_eq!(dimensions, vec!["playful", "wry", "curious"]);
+        assert_eq!(diff.trait_changes[0].current, None);
+        assert_eq!(diff.trait_changes[1].previous, None);
+        // Only shared dimensions count toward drift: (0.2 + 0.0) / 2.
+        assert!((diff.valence_drift - 0.1).abs() < 1e-9);
+        assert!(!diff.trajectory_changed);
+        assert!(diff.summary().contains("valence drift +0.10"));
+
+        let unchanged = diff_snapshots(&next, &next);
+        assert!(unchanged.is_empty());
+    }
+
     #[test]
     fn test_extract_json_simple() {
         let input = r#"{"self_description": "test", "traits": {}}"#;
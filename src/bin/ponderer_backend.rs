@@ -1,6 +1,7 @@
 use anyhow::{Context, Result};
 use flume::unbounded;
 use ponderer_backend::config::AgentConfig;
+use ponderer_backend::database::AgentDatabase;
 use ponderer_backend::runtime::BackendRuntime;
 use ponderer_backend::server::serve_backend;
 use tracing_subscriber::EnvFilter;
@@ -13,7 +14,14 @@ fn main() -> Result<()> {
         )
         .init();
 
-    let config = AgentConfig::load();
+    let mut config = AgentConfig::load();
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args.as_slice() {
+        [flag, path] if flag == "--export-bundle" => return export_bundle(&config, path),
+        [flag, path] if flag == "--import-bundle" => return import_bundle(&mut config, path),
+        _ => {}
+    }
+
     let (event_tx, event_rx) = unbounded();
     let runtime = BackendRuntime::bootstrap(config, event_tx)
         .context("failed to bootstrap backend runtime")?;
@@ -25,3 +33,38 @@ fn main() -> Result<()> {
     let server_rt = tokio::runtime::Runtime::new().context("failed to start server runtime")?;
     server_rt.block_on(serve_backend(runtime, event_rx))
 }
+
+fn export_bundle(config: &AgentConfig, path: &str) -> Result<()> {
+    let db = AgentDatabase::new(&config.database_path).context("failed to open database")?;
+    let report = db.export_bundle(path, Some(&config.to_bundle_toml()?))?;
+    tracing::info!(
+        "Exported state bundle to {} ({} media file(s), {} missing)",
+        path,
+        report.media_included,
+        report.media_missing
+    );
+    Ok(())
+}
+
+/// Restores a bundle into the configured database, writing its media beside
+/// the database and merging its config into the local one.
+fn import_bundle(config: &mut AgentConfig, path: &str) -> Result<()> {
+    let db = AgentDatabase::new(&config.database_path).context("failed to open database")?;
+    let media_dir = std::path::Path::new(&config.database_path)
+        .parent()
+        .unwrap_or_else(|| std::path::Path::new("."))
+        .join("imported_media");
+    let report = db.import_bundle(path, &media_dir)?;
+    if let Some(bundled) = report.config_toml.as_deref() {
+        config.merge_bundled_toml(bundled)?;
+        config.save()?;
+    }
+    tracing::info!(
+        "Imported state bundle from {} exported at {} ({} media file(s) restored, {} missing)",
+        path,
+        report.header.exported_at,
+        report.media_restored,
+        report.media_missing
+    );
+    Ok(())
+}
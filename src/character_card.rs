@@ -12,7 +12,7 @@ pub struct TavernAICardV2 {
     pub data: TavernAIData,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct TavernAIData {
     pub name: String,
     #[serde(default)]
@@ -40,16 +40,31 @@ pub struct TavernAIData {
 }
 
 /// Unified character representation
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default, PartialEq)]
 pub struct ParsedCharacter {
     pub name: String,
     pub description: String,
     pub personality: String,
     pub scenario: String,
     pub example_dialogue: String,
+    pub first_message: String,
     pub system_prompt: String,
 }
 
+impl From<TavernAIData> for ParsedCharacter {
+    fn from(data: TavernAIData) -> Self {
+        Self {
+            name: data.name,
+            description: data.description,
+            personality: data.personality,
+            scenario: data.scenario,
+            example_dialogue: data.mes_example,
+            first_message: data.first_mes,
+            system_prompt: data.system_prompt,
+        }
+    }
+}
+
 /// Parse a character card from a file (supports PNG with embedded data and text formats)
 pub fn parse_character_card<P: AsRef<Path>>(path: P) -> Result<(ParsedCharacter, String, String)> {
     let path_ref = path.as_ref();
@@ -73,6 +88,10 @@ pub fn parse_character_card<P: AsRef<Path>>(path: P) -> Result<(ParsedCharacter,
         return Ok((parsed, "tavernai_v2".to_string(), content));
     }
 
+    if let Ok(parsed) = parse_tavernai_v1(&content) {
+        return Ok((parsed, "tavernai_v1".to_string(), content));
+    }
+
     if let Ok(parsed) = parse_wpp_format(&content) {
         return Ok((parsed, "wpp".to_string(), content));
     }
@@ -88,9 +107,14 @@ pub fn parse_character_card<P: AsRef<Path>>(path: P) -> Result<(ParsedCharacter,
 pub fn parse_png_character_card<P: AsRef<Path>>(path: P) -> Result<(ParsedCharacter, String)> {
     let bytes = fs::read(&path)
         .with_context(|| format!("Failed to read PNG file from {:?}", path.as_ref()))?;
+    parse_png_character_card_bytes(&bytes)
+}
 
+/// Extract a character card from in-memory PNG bytes (for uploads that never
+/// touch disk).
+pub fn parse_png_character_card_bytes(bytes: &[u8]) -> Result<(ParsedCharacter, String)> {
     // Parse PNG chunks manually to find tEXt chunk with "chara" keyword
-    let json_data = extract_png_text_chunk(&bytes, "chara")
+    let json_data = extract_png_text_chunk(bytes, "chara")
         .context("Failed to find 'chara' tEXt chunk in PNG")?;
 
     // Decode base64
@@ -100,8 +124,8 @@ pub fn parse_png_character_card<P: AsRef<Path>>(path: P) -> Result<(ParsedCharac
 
     let json_str = String::from_utf8(decoded).context("Character data is not valid UTF-8")?;
 
-    // Parse as TavernAI V2
-    let parsed = parse_tavernai_v2(&json_str)?;
+    // PNG cards in the wild carry either V2 or flat V1 JSON
+    let parsed = parse_tavern_card_json(&json_str)?;
 
     Ok((parsed, json_str))
 }
@@ -150,19 +174,46 @@ fn extract_png_text_chunk(png_bytes: &[u8], keyword: &str) -> Result<String> {
     anyhow::bail!("PNG tEXt chunk with keyword '{}' not found", keyword)
 }
 
+/// Parse Tavern card JSON, accepting the V2 `data` envelope or a flat V1 card
+pub fn parse_tavern_card_json(content: &str) -> Result<ParsedCharacter> {
+    parse_tavernai_v2(content).or_else(|_| parse_tavernai_v1(content))
+}
+
 /// Parse TavernAI V2 format
 fn parse_tavernai_v2(content: &str) -> Result<ParsedCharacter> {
     let card: TavernAICardV2 =
         serde_json::from_str(content).context("Failed to parse as TavernAI V2 JSON")?;
 
-    Ok(ParsedCharacter {
-        name: card.data.name,
-        description: card.data.description,
-        personality: card.data.personality,
-        scenario: card.data.scenario,
-        example_dialogue: card.data.mes_example,
-        system_prompt: card.data.system_prompt,
-    })
+    Ok(card.data.into())
+}
+
+/// Parse TavernAI V1 format (the V2 `data` fields at the top level)
+fn parse_tavernai_v1(content: &str) -> Result<ParsedCharacter> {
+    let data: TavernAIData =
+        serde_json::from_str(content).context("Failed to parse as TavernAI V1 JSON")?;
+    if data.name.trim().is_empty() {
+        anyhow::bail!("TavernAI V1 parsing failed: no character name found");
+    }
+
+    Ok(data.into())
+}
+
+/// Build a shareable TavernAI V2 card from a character
+pub fn character_to_tavern_card(character: &ParsedCharacter) -> TavernAICardV2 {
+    TavernAICardV2 {
+        spec: "chara_card_v2".to_string(),
+        spec_version: "2.0".to_string(),
+        data: TavernAIData {
+            name: character.name.clone(),
+            description: character.description.clone(),
+            personality: character.personality.clone(),
+            scenario: character.scenario.clone(),
+            first_mes: character.first_message.clone(),
+            mes_example: character.example_dialogue.clone(),
+            system_prompt: character.system_prompt.clone(),
+            ..TavernAIData::default()
+        },
+    }
 }
 
 /// Parse W++ format (e.g., [character("Name"){Personality("traits")}])
@@ -240,6 +291,7 @@ fn parse_wpp_format(content: &str) -> Result<ParsedCharacter> {
         personality,
         scenario: String::new(),
         example_dialogue: String::new(),
+        first_message: String::new(),
         system_prompt: String::new(),
     })
 }
@@ -287,6 +339,7 @@ fn parse_boostyle_format(content: &str) -> Result<ParsedCharacter> {
         personality,
         scenario,
         example_dialogue,
+        first_message: String::new(),
         system_prompt: String::new(),
     })
 }
@@ -329,3 +382,60 @@ pub fn character_to_system_prompt(character: &ParsedCharacter) -> String {
 
     parts.join("\n\n")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn png_with_text_chunk(keyword: &str, text: &str) -> Vec<u8> {
+        let mut data = keyword.as_bytes().to_vec();
+        data.push(0);
+        data.extend_from_slice(text.as_bytes());
+
+        let mut png = b"\x89PNG\r\n\x1a\n".to_vec();
+        png.extend_from_slice(&(data.len() as u32).to_be_bytes());
+        png.extend_from_slice(b"tEXt");
+        png.extend_from_slice(&data);
+        // CRC is not validated by the reader.
+        png.extend_from_slice(&[0; 4]);
+        png.extend_from_slice(&[0, 0, 0, 0]);
+        png.extend_from_slice(b"IEND");
+        png.extend_from_slice(&[0; 4]);
+        png
+    }
+
+    #[test]
+    fn parses_v2_envelope_and_flat_v1_cards() {
+        let v2 = r#"{"spec":"chara_card_v2","spec_version":"2.0","data":{"name":"Ada","personality":"curious","first_mes":"Hello!"}}"#;
+        let parsed = parse_tavern_card_json(v2).expect("v2 card");
+        assert_eq!(parsed.name, "Ada");
+        assert_eq!(parsed.first_message, "Hello!");
+
+        let v1 =
+            r#"{"name":"Ada","description":"A tinkerer","scenario":"A workshop","first_mes":"Hi"}"#;
+        let parsed = parse_tavern_card_json(v1).expect("v1 card");
+        assert_eq!(parsed.description, "A tinkerer");
+        assert_eq!(parsed.scenario, "A workshop");
+        assert_eq!(parsed.first_message, "Hi");
+
+        assert!(parse_tavern_card_json(r#"{"description":"nameless"}"#).is_err());
+    }
+
+    #[test]
+    fn decodes_png_embedded_card_and_round_trips_export() {
+        let character = ParsedCharacter {
+            name: "Ada".to_string(),
+            personality: "curious, patient".to_string(),
+            first_message: "Hello!".to_string(),
+            ..ParsedCharacter::default()
+        };
+        let json = serde_json::to_string(&character_to_tavern_card(&character)).unwrap();
+        let encoded = base64::engine::general_purpose::STANDARD.encode(json);
+
+        let (parsed, raw) =
+            parse_png_character_card_bytes(&png_with_text_chunk("chara", &encoded)).unwrap();
+        assert_eq!(parsed, character);
+        assert!(raw.contains("chara_card_v2"));
+        assert!(parse_png_character_card_bytes(&png_with_text_chunk("other", &encoded)).is_err());
+    }
+}
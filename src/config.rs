@@ -1,14 +1,26 @@
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
+
+use crate::agent::concerns::ConcernDecayPolicy;
+use crate::agent::orientation::SignatureBuckets;
+use crate::agent::persona::VoiceRules;
+use crate::character_card::{
+    character_to_system_prompt, character_to_tavern_card, ParsedCharacter, TavernAICardV2,
+};
+use crate::http_client::is_private_or_local_ip;
 use std::cmp::Ordering;
 use std::collections::HashMap;
 use std::env;
 use std::fs;
+use std::net::{IpAddr, ToSocketAddrs};
 use std::path::{Path, PathBuf};
 use std::time::SystemTime;
 
 pub const PRIVATE_CHAT_MODE_AGENTIC: &str = "agentic";
 pub const PRIVATE_CHAT_MODE_DIRECT: &str = "direct";
+/// Upper bound on `tool_approval_wait_secs`, so a forgotten prompt cannot
+/// pin a turn and its conversation lock for hours.
+pub const MAX_TOOL_APPROVAL_WAIT_SECS: u64 = 600;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RespondTo {
@@ -33,6 +45,51 @@ pub struct CapabilityProfileOverride {
     pub allowed_tools: Option<Vec<String>>,
     #[serde(default)]
     pub disallowed_tools: Option<Vec<String>>,
+    /// Working-memory tag scope for this loop's context; tagged notes
+    /// outside the scope are left out. Unset or empty keeps every note.
+    #[serde(default)]
+    pub memory_tags: Option<Vec<String>>,
+    /// Replaces the loop's built-in temperature (0.0-2.0).
+    #[serde(default)]
+    pub temperature: Option<f32>,
+    /// Replaces the loop's built-in response token budget.
+    #[serde(default)]
+    pub max_tokens: Option<u32>,
+    /// Per-turn adjustments for loops that continue across autonomous turns.
+    #[serde(default)]
+    pub sampling_schedule: Vec<SamplingScheduleStep>,
+}
+
+/// Sampling used once a multi-turn loop has chosen to continue at least
+/// `after_continuations` times. The step with the highest threshold reached
+/// wins; unset or out-of-range values keep the profile's base sampling.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct SamplingScheduleStep {
+    pub after_continuations: usize,
+    #[serde(default)]
+    pub temperature: Option<f32>,
+    #[serde(default)]
+    pub max_tokens: Option<u32>,
+}
+
+/// An operator-defined capability profile, selected by name (for example
+/// from a scheduled job) and layered over the built-in profile of the loop
+/// that runs it.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct CustomCapabilityProfile {
+    /// Tool allow-list; empty keeps the base profile's tool set.
+    #[serde(default)]
+    pub allowed_tools: Vec<String>,
+    /// Added to the base profile's deny-list.
+    #[serde(default)]
+    pub disallowed_tools: Vec<String>,
+    /// Appended to the loop's system prompt.
+    #[serde(default)]
+    pub system_prompt_suffix: Option<String>,
+    #[serde(default)]
+    pub temperature: Option<f32>,
+    #[serde(default)]
+    pub max_tokens: Option<u32>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -66,6 +123,43 @@ pub struct AgentConfig {
     pub llm_model: String,
     #[serde(default)]
     pub llm_api_key: Option<String>,
+    /// Chat wire format: `openai` (compatible servers) or `anthropic` (Messages API).
+    #[serde(default = "default_llm_provider")]
+    pub llm_provider: String,
+    /// Total attempts per chat request; transport errors and 5xx responses retry.
+    #[serde(default = "default_llm_retry_max_attempts")]
+    pub llm_retry_max_attempts: u32,
+    /// First retry delay in milliseconds; doubles on each further attempt.
+    #[serde(default = "default_llm_retry_base_delay_ms")]
+    pub llm_retry_base_delay_ms: u64,
+    /// Models tried in order when `llm_model` is missing or unreachable.
+    #[serde(default)]
+    pub fallback_models: Vec<String>,
+    /// Embedding model for semantic memory recall; unset keeps `search_memory` lexical.
+    #[serde(default)]
+    pub embedding_model: Option<String>,
+    /// Embeddings endpoint base URL; falls back to `llm_api_url` when unset.
+    #[serde(default)]
+    pub embedding_api_url: Option<String>,
+    /// Bearer token for the embeddings endpoint. When unset, `llm_api_key` is
+    /// sent only if the endpoint is on `llm_api_url`'s host.
+    #[serde(default)]
+    pub embedding_api_key: Option<String>,
+    /// Inputs sent per embeddings request.
+    #[serde(default = "default_embedding_batch_size")]
+    pub embedding_batch_size: usize,
+    /// Add semantically retrieved earlier messages to private-chat prompts;
+    /// needs `embedding_model`.
+    #[serde(default)]
+    pub enable_semantic_chat_recall: bool,
+    /// Group recent journal entries into themes by embedding similarity for
+    /// the Dream cycle; needs `embedding_model`.
+    #[serde(default)]
+    pub enable_semantic_dreams: bool,
+    /// Refuse to start (or reload) unless every model and outbound endpoint
+    /// resolves to a loopback or private address, and keep the web tools off.
+    #[serde(default)]
+    pub require_local_llm: bool,
 
     // Agent Identity
     #[serde(default = "default_username", alias = "agent_name")]
@@ -82,6 +176,24 @@ pub struct AgentConfig {
     pub max_tool_iterations: u32,
     #[serde(default)]
     pub disable_tool_iteration_limit: bool,
+    /// Tool calls one agentic turn may execute, across all its iterations;
+    /// 0 disables the budget.
+    #[serde(default)]
+    pub max_tool_calls_per_turn: u32,
+    /// Estimated tokens (system prompt plus chat prompt) a chat turn may send;
+    /// 0 disables trimming.
+    #[serde(default)]
+    pub context_token_budget: u32,
+    /// Host-side deadline around each tool execution; 0 disables it.
+    #[serde(default = "default_tool_timeout_secs")]
+    pub tool_timeout_secs: u64,
+    /// Per-category overrides keyed by `shell`, `filesystem`, `network`, `memory`, `general`.
+    /// Configured entries are merged over the defaults rather than replacing them.
+    #[serde(
+        default = "default_tool_category_timeout_secs",
+        deserialize_with = "deserialize_tool_category_timeout_secs"
+    )]
+    pub tool_category_timeout_secs: HashMap<String, u64>,
     #[serde(default = "default_max_chat_autonomous_turns")]
     pub max_chat_autonomous_turns: u32,
     #[serde(default = "default_max_background_subtask_turns")]
@@ -92,6 +204,14 @@ pub struct AgentConfig {
     pub disable_chat_turn_limit: bool,
     #[serde(default)]
     pub disable_background_subtask_turn_limit: bool,
+    /// Automatic retries for a failed background subtask (30 s backoff,
+    /// doubling). 0 = failures wait for the operator to resume them.
+    #[serde(default)]
+    pub background_subtask_max_retries: u32,
+    /// Background subtasks allowed to run at once across all conversations;
+    /// a handoff beyond it is declined. 0 = no cap.
+    #[serde(default = "default_max_concurrent_background_subtasks")]
+    pub max_concurrent_background_subtasks: u32,
     #[serde(default = "default_loop_heat_threshold")]
     pub loop_heat_threshold: u32,
     #[serde(default = "default_loop_similarity_threshold")]
@@ -100,6 +220,13 @@ pub struct AgentConfig {
     pub loop_signature_window: u32,
     #[serde(default = "default_loop_heat_cooldown")]
     pub loop_heat_cooldown: u32,
+    /// Turns the loop-heat detector only observes before it is allowed to trip.
+    /// 0 (the default) keeps the detector live from the first turn.
+    #[serde(default = "default_loop_heat_min_turns")]
+    pub loop_heat_min_turns: u32,
+    /// `recency`, `salience`, or `compressed`; see `database::ActionDigestStrategy`.
+    #[serde(default = "default_action_digest_strategy")]
+    pub action_digest_strategy: String,
     #[serde(default = "default_enabled")]
     pub enable_ambient_loop: bool,
     /// Explicitly armed dedicated-machine autonomy. Each episode remains bounded.
@@ -113,17 +240,68 @@ pub struct AgentConfig {
     pub loose_cooldown_secs: u64,
     #[serde(default = "default_ambient_min_interval_secs")]
     pub ambient_min_interval_secs: u64,
+    /// Linux idle probe order (`xprintidle`, `mutter`, `screensaver`). Empty
+    /// auto-detects: D-Bus probes first under Wayland, `xprintidle` first otherwise.
+    #[serde(default)]
+    pub idle_detection_order: Vec<String>,
+    /// Extra process keywords by category (`development`, `creative`,
+    /// `research`, `communication`, `media`, `focus`, `system`), checked
+    /// before the built-in keyword lists.
+    #[serde(default)]
+    pub process_categories: HashMap<String, Vec<String>>,
     #[serde(default = "default_enabled")]
     pub enable_journal: bool,
     #[serde(default = "default_journal_min_interval_secs")]
     pub journal_min_interval_secs: u64,
+    /// Maximum journal entries per UTC day; 0 disables the cap.
+    #[serde(default = "default_journal_max_per_day")]
+    pub journal_max_per_day: u32,
     #[serde(default = "default_enabled")]
     pub enable_concerns: bool,
+    /// Minimum seconds between chat notifications for one reminder concern.
+    /// Each due time is posted once; this spaces out rescheduled reminders.
+    #[serde(default = "default_concern_reminder_cooldown_secs")]
+    pub concern_reminder_cooldown_secs: u64,
+    /// Per-kind inactivity thresholds for concern salience decay.
+    #[serde(default)]
+    pub concern_decay: ConcernDecayPolicy,
+    /// Coarsening of idle time, load, and time of day in the orientation
+    /// context signature; an unchanged signature skips the orientation call.
+    #[serde(default)]
+    pub orientation_signature_buckets: SignatureBuckets,
     #[serde(default = "default_enabled")]
     pub enable_dream_cycle: bool,
     #[serde(default = "default_dream_min_interval_secs")]
     pub dream_min_interval_secs: u64,
     #[serde(default)]
+    pub enable_journal_markdown_export: bool,
+    /// UTC hour (0-23) after which the day's report is generated
+    /// automatically; unset disables the automatic run.
+    #[serde(default)]
+    pub daily_report_hour: Option<u32>,
+    /// Post generated daily reports into the dedicated reports conversation.
+    #[serde(default = "default_enabled")]
+    pub daily_report_post_to_chat: bool,
+    /// Minimum battery charge (percent) for heavy background work such as dream
+    /// cycles while running on battery. Ignored on mains power.
+    #[serde(default = "default_min_battery_percent")]
+    pub min_battery_percent: f32,
+    /// Local hour (0-23) at which quiet hours begin. Outward actions and the
+    /// heartbeat pause from `quiet_hours_start` until `quiet_hours_end`; the
+    /// window may wrap past midnight. Both must be set to take effect.
+    #[serde(default, deserialize_with = "deserialize_local_hour")]
+    pub quiet_hours_start: Option<u8>,
+    #[serde(default, deserialize_with = "deserialize_local_hour")]
+    pub quiet_hours_end: Option<u8>,
+    /// Seconds before an already-surfaced anomaly may be surfaced again at
+    /// the same severity.
+    #[serde(default = "default_anomaly_resurface_cooldown_secs")]
+    pub anomaly_resurface_cooldown_secs: u64,
+    /// Operator chat turns (and the background subtasks they spawn) simulate
+    /// tool calls instead of running them, so the model's plan can be reviewed.
+    #[serde(default)]
+    pub chat_plan_only: bool,
+    #[serde(default)]
     pub enable_heartbeat: bool,
     #[serde(default = "default_heartbeat_interval_mins")]
     pub heartbeat_interval_mins: u64,
@@ -135,12 +313,18 @@ pub struct AgentConfig {
     pub memory_evolution_interval_hours: u64,
     #[serde(default)]
     pub memory_eval_trace_set_path: Option<String>,
+    /// Number of recently processed plugin event ids kept for deduplication.
+    #[serde(default = "default_processed_events_retention")]
+    pub processed_events_retention: u32,
 
     #[serde(default)]
     pub respond_to: RespondTo,
 
     #[serde(default)]
     pub capability_profiles: CapabilityProfileConfig,
+    /// Named profiles beyond the built-in loops, keyed by profile name.
+    #[serde(default)]
+    pub custom_capability_profiles: HashMap<String, CustomCapabilityProfile>,
 
     // Self-reflection and evolution
     #[serde(default)]
@@ -164,11 +348,105 @@ pub struct AgentConfig {
     #[serde(default)]
     pub enable_screen_capture_in_loop: bool,
     #[serde(default)]
+    pub enable_ocr_in_loop: bool,
+    #[serde(default)]
+    pub enable_focused_window_in_loop: bool,
+    /// Focused app or window-title keywords (for example `zoom`, `teams`,
+    /// `slide show`) that hold `Surface`/`Interrupt` dispositions.
+    #[serde(default)]
+    pub dnd_apps: Vec<String>,
+    /// Focused-app or window-title keywords whose window is blacked out of
+    /// loop screenshots before vision evaluation.
+    #[serde(default)]
+    pub screenshot_redaction_apps: Vec<String>,
+    /// Focused-app or window-title keywords that skip loop screenshots entirely.
+    #[serde(default)]
+    pub disable_capture_when_focused: Vec<String>,
+    #[serde(default)]
     pub enable_camera_capture_tool: bool,
 
+    // Audio transcription (`transcribe_audio`)
+    /// whisper.cpp CLI (for example `whisper-cli`); preferred over
+    /// `transcription_api_url` when both are set.
+    #[serde(default)]
+    pub whisper_binary_path: Option<String>,
+    /// ggml model passed to whisper.cpp with `-m`.
+    #[serde(default)]
+    pub whisper_model_path: Option<String>,
+    /// OpenAI-compatible `/audio/transcriptions` endpoint or its base URL.
+    #[serde(default)]
+    pub transcription_api_url: Option<String>,
+    /// Model sent to the endpoint. None = `whisper-1`.
+    #[serde(default)]
+    pub transcription_model: Option<String>,
+    /// Bearer token for `transcription_api_url`; the LLM key is never sent there.
+    #[serde(default)]
+    pub transcription_api_key: Option<String>,
+
+    // Text-to-speech (`speak`)
+    /// Registers the `speak` tool.
+    #[serde(default)]
+    pub enable_tts: bool,
+    /// piper CLI; preferred over `tts_api_url` when both are set.
+    #[serde(default)]
+    pub tts_piper_binary: Option<String>,
+    /// Default piper `.onnx` voice model.
+    #[serde(default)]
+    pub tts_piper_model: Option<String>,
+    /// OpenAI-compatible `/audio/speech` endpoint or its base URL.
+    #[serde(default)]
+    pub tts_api_url: Option<String>,
+    /// Default endpoint voice. None = `alloy`.
+    #[serde(default)]
+    pub tts_voice: Option<String>,
+    /// Bearer token for `tts_api_url`; the LLM key is never sent there.
+    #[serde(default)]
+    pub tts_api_key: Option<String>,
+
+    // Web search tool endpoint (SearXNG or Brave Search API)
+    #[serde(default)]
+    pub web_search_url: Option<String>,
+    #[serde(default = "default_web_search_provider")]
+    pub web_search_provider: String,
+    #[serde(default)]
+    pub web_search_api_key: Option<String>,
+    /// Lets `http_get` reach localhost/private-network addresses.
+    #[serde(default)]
+    pub http_allow_private_hosts: bool,
+    /// Extra shell deny rules (`regex:<pattern>` or `prefix:<text>`) checked
+    /// before `shell` spawns a command.
+    #[serde(default)]
+    pub shell_deny_rules: Vec<String>,
+    /// Built-in shell deny rules to drop, by name (for example `mkfs`).
+    #[serde(default)]
+    pub shell_disabled_rules: Vec<String>,
+    /// Command prefixes (whole words, e.g. `git status`) whose `shell` calls
+    /// skip autonomous approval. Commands with shell metacharacters never match.
+    #[serde(default)]
+    pub auto_approved_shell_prefixes: Vec<String>,
+    /// Seconds a turn holds a call that needs approval, waiting for the
+    /// operator to resolve it; 0 reports it as pending right away. Capped at
+    /// `MAX_TOOL_APPROVAL_WAIT_SECS`.
+    #[serde(default)]
+    pub tool_approval_wait_secs: u64,
+    /// Byte cap for a `read_file` call without a line or byte range.
+    #[serde(default = "default_read_file_max_bytes")]
+    pub read_file_max_bytes: usize,
+    /// `delete_file` unlinks instead of moving into `.ponderer/trash/`.
+    #[serde(default)]
+    pub file_delete_hard: bool,
+    /// Directories besides the working directory that confined file tools
+    /// (`move_file`, `delete_file`) may reach.
+    #[serde(default)]
+    pub file_tool_allowed_roots: Vec<String>,
+
     // Plugin-owned settings
     #[serde(default)]
     pub plugin_settings: HashMap<String, serde_json::Value>,
+    /// Per-plugin outbound-action caps within the rolling outbound window,
+    /// keyed by plugin id. Unlisted plugins share only `max_posts_per_hour`.
+    #[serde(default)]
+    pub plugin_action_limits: HashMap<String, u32>,
 
     // Character Card (optional)
     #[serde(default)]
@@ -182,7 +460,12 @@ pub struct AgentConfig {
     #[serde(default)]
     pub character_example_dialogue: String,
     #[serde(default)]
+    pub character_first_message: String,
+    #[serde(default)]
     pub character_avatar_path: Option<String>,
+    /// Deterministic voice rules applied to operator-facing chat replies.
+    #[serde(default)]
+    pub persona_voice: VoiceRules,
 
     // Animated avatars for UI (local display only, not transmitted)
     #[serde(default)]
@@ -199,9 +482,26 @@ pub struct AgentConfig {
     #[serde(default)]
     pub telegram_chat_id: Option<i64>,
 
+    // Outbound event webhook
+    /// POST target for `ApiEventEnvelope` JSON. None = webhook disabled.
+    #[serde(default)]
+    pub event_webhook_url: Option<String>,
+    /// Event types to forward (e.g. `chat_reply`, `action_taken`). Empty = all.
+    #[serde(default)]
+    pub event_webhook_events: Vec<String>,
+
+    // Monitoring
+    /// Bind address for the read-only `/status` server; a bare port binds
+    /// 127.0.0.1. None = disabled.
+    #[serde(default)]
+    pub status_server_addr: Option<String>,
+
     // Legacy fields for backward compatibility
     #[serde(default)]
     pub max_posts_per_hour: u32,
+    /// Rolling window length for the `max_posts_per_hour` outbound-action quota.
+    #[serde(default = "default_outbound_action_window_secs")]
+    pub outbound_action_window_secs: u64,
 }
 
 fn default_llm_url() -> String {
@@ -231,6 +531,43 @@ fn default_max_tool_iterations() -> u32 {
     10
 }
 
+fn default_tool_timeout_secs() -> u64 {
+    120
+}
+
+fn default_tool_category_timeout_secs() -> HashMap<String, u64> {
+    // The shell tool enforces its own 300-second command cap; leave headroom
+    // so that limit, not the registry backstop, governs long commands.
+    HashMap::from([("shell".to_string(), 310)])
+}
+
+fn deserialize_tool_category_timeout_secs<'de, D>(
+    deserializer: D,
+) -> std::result::Result<HashMap<String, u64>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let mut merged = default_tool_category_timeout_secs();
+    merged.extend(HashMap::<String, u64>::deserialize(deserializer)?);
+    Ok(merged)
+}
+
+/// Drops an out-of-range hour instead of letting it silently disable the
+/// window or fail the whole config.
+fn deserialize_local_hour<'de, D>(deserializer: D) -> std::result::Result<Option<u8>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let Some(hour) = Option::<i64>::deserialize(deserializer)? else {
+        return Ok(None);
+    };
+    let valid = u8::try_from(hour).ok().filter(|hour| *hour < 24);
+    if valid.is_none() {
+        tracing::warn!("Ignoring quiet hour {}; expected a local hour 0-23", hour);
+    }
+    Ok(valid)
+}
+
 fn default_max_chat_autonomous_turns() -> u32 {
     4
 }
@@ -239,6 +576,10 @@ fn default_max_background_subtask_turns() -> u32 {
     8
 }
 
+fn default_max_concurrent_background_subtasks() -> u32 {
+    3
+}
+
 fn default_private_chat_mode() -> String {
     PRIVATE_CHAT_MODE_AGENTIC.to_string()
 }
@@ -259,6 +600,18 @@ fn default_loop_heat_cooldown() -> u32 {
     1
 }
 
+fn default_loop_heat_min_turns() -> u32 {
+    0
+}
+
+fn default_action_digest_strategy() -> String {
+    "recency".to_string()
+}
+
+fn default_read_file_max_bytes() -> usize {
+    64 * 1024
+}
+
 fn default_ambient_min_interval_secs() -> u64 {
     30
 }
@@ -286,7 +639,23 @@ fn parse_env_bool(value: &str) -> bool {
 }
 
 fn default_journal_min_interval_secs() -> u64 {
-    300
+    crate::agent::journal::DEFAULT_JOURNAL_MIN_INTERVAL_SECS
+}
+
+fn default_journal_max_per_day() -> u32 {
+    crate::agent::journal::DEFAULT_JOURNAL_MAX_PER_DAY
+}
+
+fn default_concern_reminder_cooldown_secs() -> u64 {
+    3600
+}
+
+fn default_anomaly_resurface_cooldown_secs() -> u64 {
+    6 * 3600
+}
+
+fn default_min_battery_percent() -> f32 {
+    20.0
 }
 
 fn default_dream_min_interval_secs() -> u64 {
@@ -309,6 +678,34 @@ fn default_memory_evolution_interval_hours() -> u64 {
     24
 }
 
+fn default_embedding_batch_size() -> usize {
+    64
+}
+
+fn default_outbound_action_window_secs() -> u64 {
+    3600
+}
+
+fn default_processed_events_retention() -> u32 {
+    1_024
+}
+
+fn default_llm_provider() -> String {
+    crate::llm_provider::LLM_PROVIDER_OPENAI.to_string()
+}
+
+fn default_llm_retry_max_attempts() -> u32 {
+    3
+}
+
+fn default_llm_retry_base_delay_ms() -> u64 {
+    500
+}
+
+fn default_web_search_provider() -> String {
+    "searxng".to_string()
+}
+
 fn default_database_path() -> String {
     "ponderer_memory.db".to_string()
 }
@@ -323,13 +720,30 @@ impl Default for AgentConfig {
             llm_api_url: default_llm_url(),
             llm_model: default_llm_model(),
             llm_api_key: None,
+            llm_provider: default_llm_provider(),
+            llm_retry_max_attempts: default_llm_retry_max_attempts(),
+            llm_retry_base_delay_ms: default_llm_retry_base_delay_ms(),
+            fallback_models: Vec::new(),
+            embedding_model: None,
+            embedding_api_url: None,
+            embedding_api_key: None,
+            embedding_batch_size: default_embedding_batch_size(),
+            enable_semantic_chat_recall: false,
+            enable_semantic_dreams: false,
+            require_local_llm: false,
             username: default_username(),
             system_prompt: default_system_prompt(),
             poll_interval_secs: default_poll_interval(),
             max_tool_iterations: default_max_tool_iterations(),
             disable_tool_iteration_limit: false,
+            max_tool_calls_per_turn: 0,
+            context_token_budget: 0,
+            tool_timeout_secs: default_tool_timeout_secs(),
+            tool_category_timeout_secs: default_tool_category_timeout_secs(),
             max_chat_autonomous_turns: default_max_chat_autonomous_turns(),
             max_background_subtask_turns: default_max_background_subtask_turns(),
+            background_subtask_max_retries: 0,
+            max_concurrent_background_subtasks: default_max_concurrent_background_subtasks(),
             private_chat_mode: default_private_chat_mode(),
             disable_chat_turn_limit: true,
             disable_background_subtask_turn_limit: true,
@@ -337,25 +751,43 @@ impl Default for AgentConfig {
             loop_similarity_threshold: default_loop_similarity_threshold(),
             loop_signature_window: default_loop_signature_window(),
             loop_heat_cooldown: default_loop_heat_cooldown(),
+            loop_heat_min_turns: default_loop_heat_min_turns(),
+            action_digest_strategy: default_action_digest_strategy(),
             enable_ambient_loop: true,
             loose_mode: false,
             loose_episode_interval_secs: default_loose_episode_interval_secs(),
             loose_max_consecutive_episodes: default_loose_max_consecutive_episodes(),
             loose_cooldown_secs: default_loose_cooldown_secs(),
             ambient_min_interval_secs: default_ambient_min_interval_secs(),
+            idle_detection_order: Vec::new(),
+            process_categories: HashMap::new(),
             enable_journal: true,
             journal_min_interval_secs: default_journal_min_interval_secs(),
+            journal_max_per_day: default_journal_max_per_day(),
             enable_concerns: true,
+            concern_reminder_cooldown_secs: default_concern_reminder_cooldown_secs(),
+            concern_decay: ConcernDecayPolicy::default(),
+            orientation_signature_buckets: SignatureBuckets::default(),
             enable_dream_cycle: true,
             dream_min_interval_secs: default_dream_min_interval_secs(),
+            enable_journal_markdown_export: false,
+            daily_report_hour: None,
+            daily_report_post_to_chat: true,
+            min_battery_percent: default_min_battery_percent(),
+            quiet_hours_start: None,
+            quiet_hours_end: None,
+            anomaly_resurface_cooldown_secs: default_anomaly_resurface_cooldown_secs(),
+            chat_plan_only: false,
             enable_heartbeat: false,
             heartbeat_interval_mins: default_heartbeat_interval_mins(),
             heartbeat_checklist_path: default_heartbeat_checklist_path(),
             enable_memory_evolution: false,
             memory_evolution_interval_hours: default_memory_evolution_interval_hours(),
             memory_eval_trace_set_path: None,
+            processed_events_retention: default_processed_events_retention(),
             respond_to: RespondTo::default(),
             capability_profiles: CapabilityProfileConfig::default(),
+            custom_capability_profiles: HashMap::new(),
             enable_self_reflection: false,
             reflection_interval_hours: default_reflection_interval(),
             reflection_model: None,
@@ -367,20 +799,54 @@ impl Default for AgentConfig {
             database_path: default_database_path(),
             max_important_posts: default_max_important_posts(),
             enable_screen_capture_in_loop: false,
+            enable_ocr_in_loop: false,
+            enable_focused_window_in_loop: false,
+            dnd_apps: Vec::new(),
+            screenshot_redaction_apps: Vec::new(),
+            disable_capture_when_focused: Vec::new(),
             enable_camera_capture_tool: false,
+            whisper_binary_path: None,
+            whisper_model_path: None,
+            transcription_api_url: None,
+            transcription_model: None,
+            transcription_api_key: None,
+            enable_tts: false,
+            tts_piper_binary: None,
+            tts_piper_model: None,
+            tts_api_url: None,
+            tts_voice: None,
+            tts_api_key: None,
+            web_search_url: None,
+            web_search_provider: default_web_search_provider(),
+            web_search_api_key: None,
+            http_allow_private_hosts: false,
+            shell_deny_rules: Vec::new(),
+            shell_disabled_rules: Vec::new(),
+            auto_approved_shell_prefixes: Vec::new(),
+            tool_approval_wait_secs: 0,
+            read_file_max_bytes: default_read_file_max_bytes(),
+            file_delete_hard: false,
+            file_tool_allowed_roots: Vec::new(),
             plugin_settings: HashMap::new(),
+            plugin_action_limits: HashMap::new(),
             character_name: String::new(),
             character_description: String::new(),
             character_personality: String::new(),
             character_scenario: String::new(),
             character_example_dialogue: String::new(),
+            character_first_message: String::new(),
             character_avatar_path: None,
+            persona_voice: VoiceRules::default(),
             avatar_idle: None,
             avatar_thinking: None,
             avatar_active: None,
             telegram_bot_token: None,
             telegram_chat_id: None,
+            event_webhook_url: None,
+            status_server_addr: None,
+            event_webhook_events: Vec::new(),
             max_posts_per_hour: 10,
+            outbound_action_window_secs: default_outbound_action_window_secs(),
         }
     }
 }
@@ -405,6 +871,131 @@ impl AgentConfig {
         std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."))
     }
 
+    /// Adopt a character card as the agent persona: the name becomes
+    /// `username`, the card is rendered into `system_prompt`, and personality
+    /// traits (comma, semicolon, or line separated) replace
+    /// `guiding_principles`. Empty card fields leave the current values alone.
+    pub fn apply_character_card(&mut self, card: &ParsedCharacter) {
+        if !card.name.trim().is_empty() {
+            self.username = card.name.trim().to_string();
+        }
+        self.system_prompt = character_to_system_prompt(card);
+        let principles: Vec<String> = card
+            .personality
+            .split([',', ';', '\n'])
+            .map(str::trim)
+            .filter(|trait_text| !trait_text.is_empty())
+            .map(str::to_string)
+            .collect();
+        if !principles.is_empty() {
+            self.guiding_principles = principles;
+        }
+
+        self.character_name = card.name.clone();
+        self.character_description = card.description.clone();
+        self.character_personality = card.personality.clone();
+        self.character_scenario = card.scenario.clone();
+        self.character_example_dialogue = card.example_dialogue.clone();
+        self.character_first_message = card.first_message.clone();
+    }
+
+    /// Export the current persona as a TavernAI V2 card.
+    ///
+    /// The system prompt is only exported when it was edited after import;
+    /// one still equal to the card's rendered prompt would otherwise repeat
+    /// the description and personality when the card is imported again.
+    pub fn to_character_card(&self) -> TavernAICardV2 {
+        let mut character = ParsedCharacter {
+            name: if self.character_name.trim().is_empty() {
+                self.username.clone()
+            } else {
+                self.character_name.clone()
+            },
+            description: self.character_description.clone(),
+            personality: if self.character_personality.trim().is_empty() {
+                self.guiding_principles.join(", ")
+            } else {
+                self.character_personality.clone()
+            },
+            scenario: self.character_scenario.clone(),
+            example_dialogue: self.character_example_dialogue.clone(),
+            first_message: self.character_first_message.clone(),
+            system_prompt: String::new(),
+        };
+        if self.system_prompt != character_to_system_prompt(&character) {
+            character.system_prompt = self.system_prompt.clone();
+        }
+        character_to_tavern_card(&character)
+    }
+
+    /// Settings that would send data off the local network while
+    /// `require_local_llm` is set, one line per violation. Hostnames are
+    /// resolved and every address must be loopback or private; any
+    /// `http(s)` URL string inside `plugin_settings` counts too. Always empty
+    /// when the mode is off.
+    pub fn local_llm_violations(&self) -> Vec<String> {
+        if !self.require_local_llm {
+            return Vec::new();
+        }
+        let endpoints = [
+            ("llm_api_url", Some(self.llm_api_url.as_str())),
+            ("embedding_api_url", self.embedding_api_url.as_deref()),
+            ("event_webhook_url", self.event_webhook_url.as_deref()),
+            (
+                "transcription_api_url",
+                self.transcription_api_url.as_deref(),
+            ),
+            ("tts_api_url", self.tts_api_url.as_deref()),
+        ];
+        let mut plugin_urls = Vec::new();
+        let mut plugin_ids: Vec<&String> = self.plugin_settings.keys().collect();
+        plugin_ids.sort();
+        for plugin_id in plugin_ids {
+            collect_setting_urls(
+                format!("plugin_settings.{plugin_id}"),
+                &self.plugin_settings[plugin_id],
+                &mut plugin_urls,
+            );
+        }
+        let mut violations: Vec<String> = endpoints
+            .into_iter()
+            .map(|(field, url)| (field.to_string(), url))
+            .chain(
+                plugin_urls
+                    .iter()
+                    .map(|(field, url)| (field.clone(), Some(url.as_str()))),
+            )
+            .filter_map(|(field, url)| {
+                let url = url.map(str::trim).filter(|url| !url.is_empty())?;
+                check_local_endpoint(url)
+                    .err()
+                    .map(|reason| format!("{field} ({url}): {reason}"))
+            })
+            .collect();
+        if self
+            .telegram_bot_token
+            .as_deref()
+            .is_some_and(|token| !token.trim().is_empty())
+        {
+            violations.push(
+                "telegram_bot_token: the Telegram Bot API (api.telegram.org) is remote".to_string(),
+            );
+        }
+        violations
+    }
+
+    /// Fails with every `local_llm_violations` entry listed.
+    pub fn check_local_llm_guard(&self) -> Result<()> {
+        let violations = self.local_llm_violations();
+        if violations.is_empty() {
+            return Ok(());
+        }
+        anyhow::bail!(
+            "require_local_llm is set but these settings reach non-local addresses:\n- {}",
+            violations.join("\n- ")
+        )
+    }
+
     /// Get the path to the primary config file (relative to executable directory).
     pub fn config_path() -> PathBuf {
         Self::get_base_dir().join("ponderer_config.toml")
@@ -449,6 +1040,51 @@ impl AgentConfig {
         Ok(())
     }
 
+    /// Config TOML for a state bundle: the portable persisted copy with the
+    /// LLM, web search, embedding, audio endpoint, and Telegram secrets removed, along
+    /// with secret-named fields in `plugin_settings`.
+    pub fn to_bundle_toml(&self) -> Result<String> {
+        let mut bundled = self.portable_persisted_copy();
+        bundled.llm_api_key = None;
+        bundled.web_search_api_key = None;
+        bundled.embedding_api_key = None;
+        bundled.transcription_api_key = None;
+        bundled.tts_api_key = None;
+        bundled.telegram_bot_token = None;
+        for settings in bundled.plugin_settings.values_mut() {
+            strip_secret_settings(settings);
+        }
+        toml::to_string_pretty(&bundled).context("Failed to serialize bundle config")
+    }
+
+    /// Adopt a bundle's config, keeping this machine's `database_path` and
+    /// any secret the bundle does not carry.
+    pub fn merge_bundled_toml(&mut self, bundled_toml: &str) -> Result<()> {
+        let mut bundled: AgentConfig =
+            toml::from_str(bundled_toml).context("Failed to parse bundle config")?;
+        bundled.private_chat_mode = normalize_private_chat_mode(&bundled.private_chat_mode);
+        bundled.database_path = std::mem::take(&mut self.database_path);
+        bundled.llm_api_key = bundled.llm_api_key.or(self.llm_api_key.take());
+        bundled.web_search_api_key = bundled
+            .web_search_api_key
+            .or(self.web_search_api_key.take());
+        bundled.embedding_api_key = bundled.embedding_api_key.or(self.embedding_api_key.take());
+        bundled.transcription_api_key = bundled
+            .transcription_api_key
+            .or(self.transcription_api_key.take());
+        bundled.tts_api_key = bundled.tts_api_key.or(self.tts_api_key.take());
+        for (plugin_id, settings) in &mut bundled.plugin_settings {
+            if let Some(local) = self.plugin_settings.get(plugin_id) {
+                restore_secret_settings(settings, local);
+            }
+        }
+        bundled.telegram_bot_token = bundled
+            .telegram_bot_token
+            .or(self.telegram_bot_token.take());
+        *self = bundled;
+        Ok(())
+    }
+
     /// Load from environment variables (legacy support)
     pub fn from_env() -> Self {
         let mut config = Self::default();
@@ -465,6 +1101,72 @@ impl AgentConfig {
             config.llm_api_key = Some(key);
         }
 
+        if let Ok(provider) = env::var("LLM_PROVIDER") {
+            if !provider.trim().is_empty() {
+                config.llm_provider = provider.trim().to_ascii_lowercase();
+            }
+        }
+
+        if let Ok(attempts) = env::var("LLM_RETRY_MAX_ATTEMPTS") {
+            if let Ok(attempts) = attempts.parse() {
+                config.llm_retry_max_attempts = attempts;
+            }
+        }
+
+        if let Ok(delay) = env::var("LLM_RETRY_BASE_DELAY_MS") {
+            if let Ok(delay) = delay.parse() {
+                config.llm_retry_base_delay_ms = delay;
+            }
+        }
+
+        if let Ok(models) = env::var("LLM_FALLBACK_MODELS") {
+            config.fallback_models = models
+                .split(',')
+                .map(str::trim)
+                .filter(|model| !model.is_empty())
+                .map(str::to_string)
+                .collect();
+        }
+
+        if let Ok(model) = env::var("LLM_EMBEDDING_MODEL") {
+            if !model.trim().is_empty() {
+                config.embedding_model = Some(model.trim().to_string());
+            }
+        }
+
+        if let Ok(url) = env::var("LLM_EMBEDDING_API_URL") {
+            if !url.trim().is_empty() {
+                config.embedding_api_url = Some(url.trim().to_string());
+            }
+        }
+
+        if let Ok(key) = env::var("LLM_EMBEDDING_API_KEY") {
+            if !key.trim().is_empty() {
+                config.embedding_api_key = Some(key.trim().to_string());
+            }
+        }
+
+        if let Ok(size) = env::var("LLM_EMBEDDING_BATCH_SIZE") {
+            if let Ok(size) = size.trim().parse::<usize>() {
+                config.embedding_batch_size = size.max(1);
+            }
+        }
+
+        if let Ok(enabled) = env::var("AGENT_ENABLE_SEMANTIC_CHAT_RECALL") {
+            config.enable_semantic_chat_recall = parse_env_bool(&enabled);
+        }
+
+        if let Ok(enabled) = env::var("AGENT_ENABLE_SEMANTIC_DREAMS") {
+            config.enable_semantic_dreams = parse_env_bool(&enabled);
+        }
+
+        if let Ok(enabled) = env::var("AGENT_REQUIRE_LOCAL_LLM") {
+            let enabled = enabled.eq_ignore_ascii_case("1")
+                || enabled.eq_ignore_ascii_case("true")
+                || enabled.eq_ignore_ascii_case("yes");
+            config.require_local_llm = enabled;
+        }
+
         if let Ok(interval) = env::var("AGENT_CHECK_INTERVAL") {
             if let Ok(seconds) = interval.parse() {
                 config.poll_interval_secs = seconds;
@@ -484,6 +1186,24 @@ impl AgentConfig {
             config.disable_tool_iteration_limit = disabled;
         }
 
+        if let Ok(limit) = env::var("AGENT_MAX_TOOL_CALLS_PER_TURN") {
+            if let Ok(calls) = limit.trim().parse() {
+                config.max_tool_calls_per_turn = calls;
+            }
+        }
+
+        if let Ok(budget) = env::var("AGENT_CONTEXT_TOKEN_BUDGET") {
+            if let Ok(tokens) = budget.trim().parse() {
+                config.context_token_budget = tokens;
+            }
+        }
+
+        if let Ok(timeout) = env::var("AGENT_TOOL_TIMEOUT_SECS") {
+            if let Ok(seconds) = timeout.parse() {
+                config.tool_timeout_secs = seconds;
+            }
+        }
+
         if let Ok(limit) = env::var("AGENT_MAX_CHAT_AUTONOMOUS_TURNS") {
             if let Ok(turns) = limit.parse() {
                 config.max_chat_autonomous_turns = turns;
@@ -496,6 +1216,18 @@ impl AgentConfig {
             }
         }
 
+        if let Ok(retries) = env::var("AGENT_BACKGROUND_SUBTASK_MAX_RETRIES") {
+            if let Ok(retries) = retries.trim().parse() {
+                config.background_subtask_max_retries = retries;
+            }
+        }
+
+        if let Ok(limit) = env::var("AGENT_MAX_CONCURRENT_BACKGROUND_SUBTASKS") {
+            if let Ok(limit) = limit.trim().parse() {
+                config.max_concurrent_background_subtasks = limit;
+            }
+        }
+
         if let Ok(mode) = env::var("AGENT_PRIVATE_CHAT_MODE") {
             config.private_chat_mode = normalize_private_chat_mode(&mode);
         }
@@ -540,6 +1272,16 @@ impl AgentConfig {
             }
         }
 
+        if let Ok(raw) = env::var("AGENT_LOOP_HEAT_MIN_TURNS") {
+            if let Ok(v) = raw.parse::<u32>() {
+                config.loop_heat_min_turns = v;
+            }
+        }
+
+        if let Ok(strategy) = env::var("AGENT_ACTION_DIGEST_STRATEGY") {
+            config.action_digest_strategy = strategy;
+        }
+
         if let Ok(enabled) = env::var("AGENT_ENABLE_AMBIENT_LOOP") {
             let enabled = enabled.eq_ignore_ascii_case("1")
                 || enabled.eq_ignore_ascii_case("true")
@@ -575,6 +1317,15 @@ impl AgentConfig {
             }
         }
 
+        if let Ok(order) = env::var("AGENT_IDLE_DETECTION_ORDER") {
+            config.idle_detection_order = order
+                .split(',')
+                .map(str::trim)
+                .filter(|probe| !probe.is_empty())
+                .map(str::to_string)
+                .collect();
+        }
+
         if let Ok(enabled) = env::var("AGENT_ENABLE_JOURNAL") {
             let enabled = enabled.eq_ignore_ascii_case("1")
                 || enabled.eq_ignore_ascii_case("true")
@@ -588,6 +1339,12 @@ impl AgentConfig {
             }
         }
 
+        if let Ok(cap) = env::var("AGENT_JOURNAL_MAX_PER_DAY") {
+            if let Ok(cap) = cap.parse() {
+                config.journal_max_per_day = cap;
+            }
+        }
+
         if let Ok(enabled) = env::var("AGENT_ENABLE_CONCERNS") {
             let enabled = enabled.eq_ignore_ascii_case("1")
                 || enabled.eq_ignore_ascii_case("true")
@@ -595,6 +1352,12 @@ impl AgentConfig {
             config.enable_concerns = enabled;
         }
 
+        if let Ok(interval) = env::var("AGENT_CONCERN_REMINDER_COOLDOWN_SECS") {
+            if let Ok(seconds) = interval.parse() {
+                config.concern_reminder_cooldown_secs = seconds;
+            }
+        }
+
         if let Ok(enabled) = env::var("AGENT_ENABLE_DREAM_CYCLE") {
             let enabled = enabled.eq_ignore_ascii_case("1")
                 || enabled.eq_ignore_ascii_case("true")
@@ -608,6 +1371,33 @@ impl AgentConfig {
             }
         }
 
+        if let Ok(percent) = env::var("AGENT_MIN_BATTERY_PERCENT") {
+            if let Ok(percent) = percent.parse() {
+                config.min_battery_percent = percent;
+            }
+        }
+
+        if let Ok(hour) = env::var("AGENT_QUIET_HOURS_START") {
+            config.quiet_hours_start = hour.trim().parse().ok().filter(|hour| *hour < 24);
+        }
+
+        if let Ok(hour) = env::var("AGENT_QUIET_HOURS_END") {
+            config.quiet_hours_end = hour.trim().parse().ok().filter(|hour| *hour < 24);
+        }
+
+        if let Ok(interval) = env::var("AGENT_ANOMALY_RESURFACE_COOLDOWN_SECS") {
+            if let Ok(seconds) = interval.parse() {
+                config.anomaly_resurface_cooldown_secs = seconds;
+            }
+        }
+
+        if let Ok(enabled) = env::var("AGENT_CHAT_PLAN_ONLY") {
+            let enabled = enabled.eq_ignore_ascii_case("1")
+                || enabled.eq_ignore_ascii_case("true")
+                || enabled.eq_ignore_ascii_case("yes");
+            config.chat_plan_only = enabled;
+        }
+
         if let Ok(enabled) = env::var("AGENT_ENABLE_HEARTBEAT") {
             let enabled = enabled.eq_ignore_ascii_case("1")
                 || enabled.eq_ignore_ascii_case("true")
@@ -646,6 +1436,12 @@ impl AgentConfig {
             }
         }
 
+        if let Ok(raw) = env::var("AGENT_PROCESSED_EVENTS_RETENTION") {
+            if let Ok(v) = raw.parse::<u32>() {
+                config.processed_events_retention = v.max(1);
+            }
+        }
+
         if let Ok(enabled) = env::var("AGENT_ENABLE_SCREEN_CAPTURE") {
             let enabled = enabled.eq_ignore_ascii_case("1")
                 || enabled.eq_ignore_ascii_case("true")
@@ -653,6 +1449,65 @@ impl AgentConfig {
             config.enable_screen_capture_in_loop = enabled;
         }
 
+        if let Ok(enabled) = env::var("AGENT_ENABLE_OCR") {
+            let enabled = enabled.eq_ignore_ascii_case("1")
+                || enabled.eq_ignore_ascii_case("true")
+                || enabled.eq_ignore_ascii_case("yes");
+            config.enable_ocr_in_loop = enabled;
+        }
+
+        if let Ok(enabled) = env::var("AGENT_ENABLE_FOCUSED_WINDOW") {
+            let enabled = enabled.eq_ignore_ascii_case("1")
+                || enabled.eq_ignore_ascii_case("true")
+                || enabled.eq_ignore_ascii_case("yes");
+            config.enable_focused_window_in_loop = enabled;
+        }
+
+        if let Ok(apps) = env::var("AGENT_DND_APPS") {
+            config.dnd_apps = apps
+                .split(',')
+                .map(str::trim)
+                .filter(|app| !app.is_empty())
+                .map(str::to_string)
+                .collect();
+        }
+
+        if let Ok(apps) = env::var("AGENT_SCREENSHOT_REDACTION_APPS") {
+            config.screenshot_redaction_apps = apps
+                .split(',')
+                .map(str::trim)
+                .filter(|app| !app.is_empty())
+                .map(str::to_string)
+                .collect();
+        }
+
+        if let Ok(apps) = env::var("AGENT_DISABLE_CAPTURE_WHEN_FOCUSED") {
+            config.disable_capture_when_focused = apps
+                .split(',')
+                .map(str::trim)
+                .filter(|app| !app.is_empty())
+                .map(str::to_string)
+                .collect();
+        }
+
+        if let Ok(enabled) = env::var("AGENT_ENABLE_JOURNAL_EXPORT") {
+            let enabled = enabled.eq_ignore_ascii_case("1")
+                || enabled.eq_ignore_ascii_case("true")
+                || enabled.eq_ignore_ascii_case("yes");
+            config.enable_journal_markdown_export = enabled;
+        }
+
+        if let Ok(hour) = env::var("AGENT_DAILY_REPORT_HOUR") {
+            config.daily_report_hour = hour.trim().parse::<u32>().ok().filter(|hour| *hour < 24);
+        }
+
+        if let Ok(enabled) = env::var("AGENT_DAILY_REPORT_POST_TO_CHAT") {
+            let enabled = enabled.eq_ignore_ascii_case("1")
+                || enabled.eq_ignore_ascii_case("true")
+                || enabled.eq_ignore_ascii_case("yes");
+            config.daily_report_post_to_chat = enabled;
+        }
+
         if let Ok(enabled) = env::var("AGENT_ENABLE_CAMERA_CAPTURE") {
             let enabled = enabled.eq_ignore_ascii_case("1")
                 || enabled.eq_ignore_ascii_case("true")
@@ -660,6 +1515,90 @@ impl AgentConfig {
             config.enable_camera_capture_tool = enabled;
         }
 
+        if let Ok(window) = env::var("AGENT_OUTBOUND_ACTION_WINDOW_SECS") {
+            if let Ok(seconds) = window.parse::<u64>() {
+                config.outbound_action_window_secs = seconds.max(1);
+            }
+        }
+
+        if let Ok(url) = env::var("AGENT_WEB_SEARCH_URL") {
+            if !url.trim().is_empty() {
+                config.web_search_url = Some(url.trim().to_string());
+            }
+        }
+
+        if let Ok(provider) = env::var("AGENT_WEB_SEARCH_PROVIDER") {
+            if !provider.trim().is_empty() {
+                config.web_search_provider = provider.trim().to_ascii_lowercase();
+            }
+        }
+
+        if let Ok(key) = env::var("AGENT_WEB_SEARCH_API_KEY") {
+            if !key.trim().is_empty() {
+                config.web_search_api_key = Some(key.trim().to_string());
+            }
+        }
+
+        if let Ok(enabled) = env::var("AGENT_HTTP_ALLOW_PRIVATE_HOSTS") {
+            let enabled = enabled.eq_ignore_ascii_case("1")
+                || enabled.eq_ignore_ascii_case("true")
+                || enabled.eq_ignore_ascii_case("yes");
+            config.http_allow_private_hosts = enabled;
+        }
+
+        // One rule per line: regex patterns may contain commas.
+        if let Ok(rules) = env::var("AGENT_SHELL_DENY_RULES") {
+            config.shell_deny_rules = rules
+                .lines()
+                .map(str::trim)
+                .filter(|rule| !rule.is_empty())
+                .map(str::to_string)
+                .collect();
+        }
+
+        if let Ok(names) = env::var("AGENT_SHELL_DISABLED_RULES") {
+            config.shell_disabled_rules = names
+                .split(',')
+                .map(str::trim)
+                .filter(|name| !name.is_empty())
+                .map(str::to_string)
+                .collect();
+        }
+
+        if let Ok(prefixes) = env::var("AGENT_AUTO_APPROVED_SHELL_PREFIXES") {
+            config.auto_approved_shell_prefixes = prefixes
+                .split(',')
+                .map(str::trim)
+                .filter(|prefix| !prefix.is_empty())
+                .map(str::to_string)
+                .collect();
+        }
+
+        if let Ok(secs) = env::var("AGENT_TOOL_APPROVAL_WAIT_SECS") {
+            if let Ok(secs) = secs.trim().parse::<u64>() {
+                config.tool_approval_wait_secs = secs.min(MAX_TOOL_APPROVAL_WAIT_SECS);
+            }
+        }
+
+        if let Ok(bytes) = env::var("AGENT_READ_FILE_MAX_BYTES") {
+            if let Ok(bytes) = bytes.trim().parse::<usize>() {
+                config.read_file_max_bytes = bytes.max(1);
+            }
+        }
+
+        if let Ok(enabled) = env::var("AGENT_FILE_DELETE_HARD") {
+            config.file_delete_hard = parse_env_bool(&enabled);
+        }
+
+        if let Ok(roots) = env::var("AGENT_FILE_TOOL_ALLOWED_ROOTS") {
+            config.file_tool_allowed_roots = roots
+                .split(',')
+                .map(str::trim)
+                .filter(|root| !root.is_empty())
+                .map(str::to_string)
+                .collect();
+        }
+
         if let Ok(name) = env::var("AGENT_NAME") {
             config.username = name;
         }
@@ -676,6 +1615,94 @@ impl AgentConfig {
             }
         }
 
+        if let Ok(url) = env::var("AGENT_EVENT_WEBHOOK_URL") {
+            if !url.trim().is_empty() {
+                config.event_webhook_url = Some(url.trim().to_string());
+            }
+        }
+
+        if let Ok(path) = env::var("AGENT_WHISPER_BINARY") {
+            if !path.trim().is_empty() {
+                config.whisper_binary_path = Some(path.trim().to_string());
+            }
+        }
+
+        if let Ok(path) = env::var("AGENT_WHISPER_MODEL") {
+            if !path.trim().is_empty() {
+                config.whisper_model_path = Some(path.trim().to_string());
+            }
+        }
+
+        if let Ok(url) = env::var("AGENT_TRANSCRIPTION_API_URL") {
+            if !url.trim().is_empty() {
+                config.transcription_api_url = Some(url.trim().to_string());
+            }
+        }
+
+        if let Ok(model) = env::var("AGENT_TRANSCRIPTION_MODEL") {
+            if !model.trim().is_empty() {
+                config.transcription_model = Some(model.trim().to_string());
+            }
+        }
+
+        if let Ok(key) = env::var("AGENT_TRANSCRIPTION_API_KEY") {
+            if !key.trim().is_empty() {
+                config.transcription_api_key = Some(key.trim().to_string());
+            }
+        }
+
+        if let Ok(enabled) = env::var("AGENT_ENABLE_TTS") {
+            let enabled = enabled.eq_ignore_ascii_case("1")
+                || enabled.eq_ignore_ascii_case("true")
+                || enabled.eq_ignore_ascii_case("yes");
+            config.enable_tts = enabled;
+        }
+
+        if let Ok(path) = env::var("AGENT_TTS_PIPER_BINARY") {
+            if !path.trim().is_empty() {
+                config.tts_piper_binary = Some(path.trim().to_string());
+            }
+        }
+
+        if let Ok(path) = env::var("AGENT_TTS_PIPER_MODEL") {
+            if !path.trim().is_empty() {
+                config.tts_piper_model = Some(path.trim().to_string());
+            }
+        }
+
+        if let Ok(url) = env::var("AGENT_TTS_API_URL") {
+            if !url.trim().is_empty() {
+                config.tts_api_url = Some(url.trim().to_string());
+            }
+        }
+
+        if let Ok(voice) = env::var("AGENT_TTS_VOICE") {
+            if !voice.trim().is_empty() {
+                config.tts_voice = Some(voice.trim().to_string());
+            }
+        }
+
+        if let Ok(key) = env::var("AGENT_TTS_API_KEY") {
+            if !key.trim().is_empty() {
+                config.tts_api_key = Some(key.trim().to_string());
+            }
+        }
+
+        if let Ok(addr) = env::var("AGENT_STATUS_SERVER_ADDR") {
+            if !addr.trim().is_empty() {
+                config.status_server_addr = Some(addr.trim().to_string());
+            }
+        }
+
+        if let Ok(events) = env::var("AGENT_EVENT_WEBHOOK_EVENTS") {
+            config.event_webhook_events = events
+                .split(',')
+                .map(str::trim)
+                .filter(|name| !name.is_empty())
+                .map(str::to_string)
+                .collect();
+        }
+
         config
     }
 
@@ -780,22 +1807,314 @@ fn normalize_portable_path(raw_path: &str, default_name: String) -> String {
     trimmed.to_string()
 }
 
+/// Built-in tools that would reach the public internet and therefore stay
+/// unregistered while `require_local_llm` is set.
+pub const REQUIRE_LOCAL_LLM_BLOCKED_TOOLS: &[&str] = &["http_fetch", "http_get", "web_search"];
+
+/// Whether a plugin setting's name marks it as a credential: `api_key` or a
+/// name ending in `_api_key`, `secret`, `token` or `password`, compared by
+/// words so `apiKey`, `client-secret` and `authToken` match while `sort_key`
+/// and `max_tokens` do not.
+fn is_secret_setting_name(name: &str) -> bool {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut previous_lower = false;
+    for c in name.chars() {
+        if !c.is_ascii_alphanumeric() {
+            words.push(std::mem::take(&mut word));
+            previous_lower = false;
+            continue;
+        }
+        if c.is_ascii_uppercase() && previous_lower {
+            words.push(std::mem::take(&mut word));
+        }
+        previous_lower = c.is_ascii_lowercase() || c.is_ascii_digit();
+        word.push(c.to_ascii_lowercase());
+    }
+    words.push(word);
+    words.retain(|word| !word.is_empty());
+    match words.as_slice() {
+        [.., api, key] if api == "api" && key == "key" => true,
+        [.., last] => matches!(last.as_str(), "apikey" | "secret" | "token" | "password"),
+        [] => false,
+    }
+}
+
+/// Drop secret-named fields, at any depth, from a plugin's settings.
+fn strip_secret_settings(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Object(fields) => {
+            fields.retain(|name, _| !is_secret_setting_name(name));
+            fields.values_mut().for_each(strip_secret_settings);
+        }
+        serde_json::Value::Array(items) => items.iter_mut().for_each(strip_secret_settings),
+        _ => {}
+    }
+}
+
+/// Put `local`'s secret-named fields back wherever `bundled` lacks them.
+fn restore_secret_settings(bundled: &mut serde_json::Value, local: &serde_json::Value) {
+    let (serde_json::Value::Object(bundled), serde_json::Value::Object(local)) = (bundled, local)
+    else {
+        return;
+    };
+    for (name, local_value) in local {
+        match bundled.get_mut(name) {
+            Some(bundled_value) => restore_secret_settings(bundled_value, local_value),
+            None if is_secret_setting_name(name) => {
+                bundled.insert(name.clone(), local_value.clone());
+            }
+            None => {}
+        }
+    }
+}
+
+/// Every `http(s)` URL string in a plugin's settings, keyed by its dotted path.
+fn collect_setting_urls(path: String, value: &serde_json::Value, out: &mut Vec<(String, String)>) {
+    match value {
+        serde_json::Value::String(text) => {
+            let text = text.trim();
+            let lower = text.to_ascii_lowercase();
+            if lower.starts_with("http://") || lower.starts_with("https://") {
+                out.push((path, text.to_string()));
+            }
+        }
+        serde_json::Value::Array(items) => {
+            for (index, item) in items.iter().enumerate() {
+                collect_setting_urls(format!("{path}[{index}]"), item, out);
+            }
+        }
+        serde_json::Value::Object(fields) => {
+            for (key, field) in fields {
+                collect_setting_urls(format!("{path}.{key}"), field, out);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Accepts `localhost` names and hosts whose every resolved address is
+/// loopback or private.
+fn check_local_endpoint(raw: &str) -> std::result::Result<(), String> {
+    let url = reqwest::Url::parse(raw).map_err(|error| format!("invalid URL ({error})"))?;
+    let host = url
+        .host_str()
+        .ok_or_else(|| "URL has no host".to_string())?
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .to_ascii_lowercase();
+    if host == "localhost" || host.ends_with(".localhost") {
+        return Ok(());
+    }
+    let addresses: Vec<IpAddr> = match host.parse::<IpAddr>() {
+        Ok(ip) => vec![ip],
+        Err(_) => (host.as_str(), url.port_or_known_default().unwrap_or(80))
+            .to_socket_addrs()
+            .map_err(|error| format!("could not resolve host ({error})"))?
+            .map(|address| address.ip())
+            .collect(),
+    };
+    match addresses.iter().find(|ip| !is_private_or_local_ip(**ip)) {
+        Some(ip) => Err(format!("resolves to non-local address {ip}")),
+        None if addresses.is_empty() => Err("host resolved to no addresses".to_string()),
+        None => Ok(()),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn character_card_applies_to_config_and_exports_back() {
+        let card = ParsedCharacter {
+            name: "Ada".to_string(),
+            description: "A patient tinkerer.".to_string(),
+            personality: "curious; patient\nmeticulous".to_string(),
+            first_message: "Hello!".to_string(),
+            ..ParsedCharacter::default()
+        };
+        let mut config = AgentConfig::default();
+        config.apply_character_card(&card);
+
+        assert_eq!(config.username, "Ada");
+        assert!(config.system_prompt.contains("A patient tinkerer."));
+        assert_eq!(
+            config.guiding_principles,
+            vec!["curious", "patient", "meticulous"]
+        );
+
+        let exported = config.to_character_card();
+        assert_eq!(exported.spec, "chara_card_v2");
+        assert_eq!(exported.data.first_mes, "Hello!");
+        // An unedited rendered prompt is not exported, so re-import is stable.
+        assert!(exported.data.system_prompt.is_empty());
+        let mut reimported = AgentConfig::default();
+        reimported.apply_character_card(&exported.data.clone().into());
+        assert_eq!(reimported.system_prompt, config.system_prompt);
+
+        config.system_prompt = "Custom prompt".to_string();
+        assert_eq!(
+            config.to_character_card().data.system_prompt,
+            "Custom prompt"
+        );
+    }
+
     #[test]
     fn living_loop_is_alive_by_default_without_enabling_private_sensors() {
         let config = AgentConfig::default();
         assert!(config.enable_ambient_loop);
         assert!(config.enable_journal);
         assert!(config.enable_concerns);
+        assert_eq!(config.concern_reminder_cooldown_secs, 3600);
+        assert_eq!(config.journal_min_interval_secs, 300);
+        assert_eq!(config.journal_max_per_day, 48);
         assert!(config.enable_dream_cycle);
         assert!(!config.loose_mode);
         assert_eq!(config.loose_max_consecutive_episodes, 8);
         assert!(!config.enable_screen_capture_in_loop);
+        assert!(!config.enable_ocr_in_loop);
+        assert!(!config.enable_focused_window_in_loop);
         assert!(!config.enable_camera_capture_tool);
         assert!(!config.enable_self_reflection);
+        assert_eq!(config.quiet_hours_start, None);
+        assert_eq!(config.quiet_hours_end, None);
+        assert!(!config.http_allow_private_hosts);
+        assert!(!config.chat_plan_only);
+        assert!(config.shell_deny_rules.is_empty());
+        assert!(config.shell_disabled_rules.is_empty());
+        assert!(config.auto_approved_shell_prefixes.is_empty());
+        assert_eq!(config.tool_approval_wait_secs, 0);
+        assert_eq!(config.loop_heat_min_turns, 0);
+        assert!(config.plugin_action_limits.is_empty());
+        assert_eq!(config.embedding_batch_size, 64);
+        assert!(!config.enable_semantic_chat_recall);
+        assert!(!config.enable_semantic_dreams);
+        assert_eq!(config.action_digest_strategy, "recency");
+        assert!(config.event_webhook_url.is_none());
+        assert!(config.event_webhook_events.is_empty());
+        assert_eq!(config.read_file_max_bytes, 64 * 1024);
+        assert!(!config.file_delete_hard);
+        assert!(config.file_tool_allowed_roots.is_empty());
+        assert!(config.process_categories.is_empty());
+        assert!(config.dnd_apps.is_empty());
+        assert!(config.screenshot_redaction_apps.is_empty());
+        assert!(config.disable_capture_when_focused.is_empty());
+        assert!(config.custom_capability_profiles.is_empty());
+        assert_eq!(config.concern_decay, ConcernDecayPolicy::default());
+        assert!(config.persona_voice.is_empty());
+        assert_eq!(
+            config.orientation_signature_buckets,
+            SignatureBuckets::default()
+        );
+        assert_eq!(config.daily_report_hour, None);
+        assert!(config.daily_report_post_to_chat);
+        assert!(!config.require_local_llm);
+        assert!(config.status_server_addr.is_none());
+        assert!(config.whisper_binary_path.is_none());
+        assert!(config.transcription_api_url.is_none());
+        assert!(!config.enable_tts);
+        assert_eq!(config.background_subtask_max_retries, 0);
+        assert_eq!(config.max_concurrent_background_subtasks, 3);
+        assert_eq!(config.max_tool_calls_per_turn, 0);
+        assert_eq!(config.context_token_budget, 0);
+    }
+
+    #[test]
+    fn local_llm_guard_lists_non_local_endpoints() {
+        let config = AgentConfig {
+            require_local_llm: true,
+            llm_api_url: "http://localhost:11434/v1".to_string(),
+            embedding_api_url: Some("http://[::1]:8080".to_string()),
+            event_webhook_url: Some("https://8.8.8.8/hook".to_string()),
+            telegram_bot_token: Some("123:abc".to_string()),
+            plugin_settings: HashMap::from([(
+                "graphchan".to_string(),
+                serde_json::json!({
+                    "api_url": "https://1.1.1.1/api",
+                    "mirrors": ["http://127.0.0.1:8730", "not a url"],
+                }),
+            )]),
+            ..AgentConfig::default()
+        };
+        let violations = config.local_llm_violations();
+        assert_eq!(violations.len(), 3, "{violations:?}");
+        assert!(violations[0].starts_with("event_webhook_url (https://8.8.8.8/hook)"));
+        assert!(
+            violations[1].starts_with("plugin_settings.graphchan.api_url (https://1.1.1.1/api)")
+        );
+        assert!(violations[2].starts_with("telegram_bot_token"));
+        assert!(config
+            .check_local_llm_guard()
+            .unwrap_err()
+            .to_string()
+            .contains("\n- event_webhook_url"));
+
+        let local = AgentConfig {
+            llm_api_url: "http://192.168.1.20:1234/v1".to_string(),
+            event_webhook_url: None,
+            telegram_bot_token: None,
+            plugin_settings: HashMap::new(),
+            ..config.clone()
+        };
+        assert!(local.check_local_llm_guard().is_ok());
+        let off = AgentConfig {
+            require_local_llm: false,
+            ..config
+        };
+        assert!(off.local_llm_violations().is_empty());
+    }
+
+    #[test]
+    fn bundle_config_drops_secrets_and_merge_keeps_local_ones() {
+        let exported = AgentConfig {
+            username: "Wren".to_string(),
+            llm_api_key: Some("sk-exported".to_string()),
+            telegram_bot_token: Some("123:abc".to_string()),
+            plugin_settings: HashMap::from([(
+                "graphchan".to_string(),
+                serde_json::json!({
+                    "board": "tech",
+                    "apiKey": "gc-exported",
+                    "upstream": { "client_secret": "cs-exported", "sort_key": "new" },
+                }),
+            )]),
+            ..AgentConfig::default()
+        };
+        let bundled = exported.to_bundle_toml().expect("bundle toml");
+        assert!(!bundled.contains("sk-exported"));
+        assert!(!bundled.contains("123:abc"));
+        assert!(!bundled.contains("gc-exported"));
+        assert!(!bundled.contains("cs-exported"));
+        assert!(bundled.contains("tech"));
+        assert!(bundled.contains("sort_key"));
+
+        let mut local = AgentConfig {
+            llm_api_key: Some("sk-local".to_string()),
+            database_path: "/srv/ponderer/agent.db".to_string(),
+            plugin_settings: HashMap::from([(
+                "graphchan".to_string(),
+                serde_json::json!({
+                    "board": "art",
+                    "apiKey": "gc-local",
+                    "upstream": { "client_secret": "cs-local" },
+                }),
+            )]),
+            ..AgentConfig::default()
+        };
+        local.merge_bundled_toml(&bundled).expect("merge");
+        assert_eq!(local.username, "Wren");
+        assert_eq!(local.llm_api_key.as_deref(), Some("sk-local"));
+        assert_eq!(
+            local.plugin_settings["graphchan"],
+            serde_json::json!({
+                "board": "tech",
+                "apiKey": "gc-local",
+                "upstream": { "client_secret": "cs-local", "sort_key": "new" },
+            })
+        );
+        assert_eq!(local.telegram_bot_token, None);
+        assert_eq!(local.database_path, "/srv/ponderer/agent.db");
     }
 
     #[test]
@@ -807,4 +2126,29 @@ mod tests {
         assert!(config.enable_dream_cycle);
         assert!(!config.loose_mode);
     }
+
+    #[test]
+    fn configured_category_timeouts_merge_over_the_defaults() {
+        let config: AgentConfig =
+            toml::from_str("[tool_category_timeout_secs]\nnetwork = 30\n").expect("parse");
+        assert_eq!(config.tool_category_timeout_secs.get("network"), Some(&30));
+        assert_eq!(config.tool_category_timeout_secs.get("shell"), Some(&310));
+
+        let config: AgentConfig =
+            toml::from_str("[tool_category_timeout_secs]\nshell = 600\n").expect("parse");
+        assert_eq!(config.tool_category_timeout_secs.get("shell"), Some(&600));
+    }
+
+    #[test]
+    fn out_of_range_quiet_hours_are_dropped_at_load() {
+        let config: AgentConfig =
+            toml::from_str("quiet_hours_start = 25\nquiet_hours_end = 6\n").expect("parse");
+        assert_eq!(config.quiet_hours_start, None);
+        assert_eq!(config.quiet_hours_end, Some(6));
+
+        let config: AgentConfig =
+            toml::from_str("quiet_hours_start = 300\nquiet_hours_end = -1\n").expect("parse");
+        assert_eq!(config.quiet_hours_start, None);
+        assert_eq!(config.quiet_hours_end, None);
+    }
 }
@@ -0,0 +1,249 @@
+use anyhow::Result;
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+
+use super::AgentDatabase;
+
+/// How long a queued call waits for the operator before it expires.
+pub const TOOL_APPROVAL_TTL_HOURS: i64 = 24;
+
+/// Where a queued approval stands. Only `Pending` rows can be resolved.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolApprovalStatus {
+    Pending,
+    Approved,
+    Denied,
+    /// Nobody decided within `TOOL_APPROVAL_TTL_HOURS`.
+    Expired,
+}
+
+impl ToolApprovalStatus {
+    fn as_db_str(self) -> &'static str {
+        match self {
+            ToolApprovalStatus::Pending => "pending",
+            ToolApprovalStatus::Approved => "approved",
+            ToolApprovalStatus::Denied => "denied",
+            ToolApprovalStatus::Expired => "expired",
+        }
+    }
+
+    fn from_db(raw: &str) -> Self {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "approved" => ToolApprovalStatus::Approved,
+            "denied" => ToolApprovalStatus::Denied,
+            "expired" => ToolApprovalStatus::Expired,
+            _ => ToolApprovalStatus::Pending,
+        }
+    }
+}
+
+/// A tool call that hit the approval gate, waiting for the operator.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct QueuedToolApproval {
+    pub id: String,
+    pub tool_name: String,
+    /// The call's arguments, as carried by `NeedsApproval`.
+    pub params: serde_json::Value,
+    pub reason: String,
+    /// Conversation the call was made for; `None` for autonomous cycles.
+    pub conversation_id: Option<String>,
+    /// Capability profile of the turn that made the call, so a late approval
+    /// re-runs it under the same policy; `None` for rows queued before it was kept.
+    #[serde(default)]
+    pub capability_profile: Option<String>,
+    pub status: ToolApprovalStatus,
+    pub created_at: DateTime<Utc>,
+    pub resolved_at: Option<DateTime<Utc>>,
+}
+
+pub(super) fn create_tool_approval_queue_table(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        r#"CREATE TABLE IF NOT EXISTS tool_approval_queue (
+               id TEXT PRIMARY KEY,
+               tool_name TEXT NOT NULL,
+               params TEXT NOT NULL,
+               reason TEXT NOT NULL,
+               conversation_id TEXT,
+               status TEXT NOT NULL DEFAULT 'pending',
+               created_at TEXT NOT NULL,
+               resolved_at TEXT
+           );
+           CREATE INDEX IF NOT EXISTS idx_tool_approval_queue_status
+           ON tool_approval_queue(status, created_at);"#,
+    )?;
+    Ok(())
+}
+
+pub(super) fn add_tool_approval_profile_column(conn: &Connection) -> Result<()> {
+    if !AgentDatabase::table_has_column(conn, "tool_approval_queue", "capability_profile")? {
+        conn.execute(
+            "ALTER TABLE tool_approval_queue ADD COLUMN capability_profile TEXT",
+            [],
+        )?;
+    }
+    Ok(())
+}
+
+fn timestamp_column(idx: usize, raw: &str) -> rusqlite::Result<DateTime<Utc>> {
+    raw.parse().map_err(|error| {
+        rusqlite::Error::FromSqlConversionFailure(idx, rusqlite::types::Type::Text, Box::new(error))
+    })
+}
+
+fn parse_queued_approval_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<QueuedToolApproval> {
+    let params: String = row.get(2)?;
+    let status: String = row.get(5)?;
+    let created_at: String = row.get(6)?;
+    let resolved_at: Option<String> = row.get(7)?;
+    Ok(QueuedToolApproval {
+        id: row.get(0)?,
+        tool_name: row.get(1)?,
+        params: serde_json::from_str(&params).map_err(|error| {
+            rusqlite::Error::FromSqlConversionFailure(
+                2,
+                rusqlite::types::Type::Text,
+                Box::new(error),
+            )
+        })?,
+        reason: row.get(3)?,
+        conversation_id: row.get(4)?,
+        status: ToolApprovalStatus::from_db(&status),
+        created_at: timestamp_column(6, &created_at)?,
+        resolved_at: resolved_at
+            .map(|raw| timestamp_column(7, &raw))
+            .transpose()?,
+        capability_profile: row.get(8)?,
+    })
+}
+
+const QUEUED_APPROVAL_COLUMNS: &str =
+    "id, tool_name, params, reason, conversation_id, status, created_at, resolved_at, capability_profile";
+
+/// Moves pending rows older than `TOOL_APPROVAL_TTL_HOURS` to `expired`, so a
+/// call nobody answered cannot be approved days later out of context.
+fn expire_stale_tool_approvals(conn: &Connection, now: DateTime<Utc>) -> Result<()> {
+    let cutoff = now - ChronoDuration::hours(TOOL_APPROVAL_TTL_HOURS);
+    conn.execute(
+        "UPDATE tool_approval_queue SET status = 'expired', resolved_at = ?1
+         WHERE status = 'pending' AND created_at < ?2",
+        params![now.to_rfc3339(), cutoff.to_rfc3339()],
+    )?;
+    Ok(())
+}
+
+impl AgentDatabase {
+    /// Queue a gated call for the operator. A pending row for the same tool,
+    /// arguments, conversation, and profile is reused, so a model that retries
+    /// the call does not stack prompts. Returns the row and whether it is new.
+    pub fn enqueue_tool_approval(
+        &self,
+        tool_name: &str,
+        params: &serde_json::Value,
+        reason: &str,
+        conversation_id: Option<&str>,
+        capability_profile: Option<&str>,
+    ) -> Result<(QueuedToolApproval, bool)> {
+        let params_json = serde_json::to_string(params)?;
+        let conn = self.lock_conn()?;
+        expire_stale_tool_approvals(&conn, Utc::now())?;
+        let existing = conn
+            .query_row(
+                &format!(
+                    "SELECT {} FROM tool_approval_queue
+                     WHERE status = 'pending' AND tool_name = ?1 AND params = ?2
+                       AND conversation_id IS ?3 AND capability_profile IS ?4
+                     ORDER BY created_at ASC
+                     LIMIT 1",
+                    QUEUED_APPROVAL_COLUMNS
+                ),
+                params![tool_name, params_json, conversation_id, capability_profile],
+                parse_queued_approval_row,
+            )
+            .optional()?;
+        if let Some(existing) = existing {
+            return Ok((existing, false));
+        }
+
+        let approval = QueuedToolApproval {
+            id: uuid::Uuid::new_v4().to_string(),
+            tool_name: tool_name.to_string(),
+            params: params.clone(),
+            reason: reason.to_string(),
+            conversation_id: conversation_id.map(str::to_string),
+            capability_profile: capability_profile.map(str::to_string),
+            status: ToolApprovalStatus::Pending,
+            created_at: Utc::now(),
+            resolved_at: None,
+        };
+        conn.execute(
+            "INSERT INTO tool_approval_queue
+                 (id, tool_name, params, reason, conversation_id, status, created_at,
+                  capability_profile)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                &approval.id,
+                &approval.tool_name,
+                params_json,
+                &approval.reason,
+                &approval.conversation_id,
+                approval.status.as_db_str(),
+                approval.created_at.to_rfc3339(),
+                &approval.capability_profile,
+            ],
+        )?;
+        Ok((approval, true))
+    }
+
+    /// Approvals still waiting for the operator, oldest first.
+    pub fn list_pending_tool_approvals(&self) -> Result<Vec<QueuedToolApproval>> {
+        let conn = self.lock_conn()?;
+        expire_stale_tool_approvals(&conn, Utc::now())?;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM tool_approval_queue
+             WHERE status = 'pending'
+             ORDER BY created_at ASC",
+            QUEUED_APPROVAL_COLUMNS
+        ))?;
+        let approvals = stmt
+            .query_map([], parse_queued_approval_row)?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(approvals)
+    }
+
+    /// Record the operator's decision. Returns the updated row, or `None` when
+    /// the id is unknown, already resolved, or expired.
+    pub fn resolve_tool_approval(
+        &self,
+        id: &str,
+        approved: bool,
+    ) -> Result<Option<QueuedToolApproval>> {
+        let status = if approved {
+            ToolApprovalStatus::Approved
+        } else {
+            ToolApprovalStatus::Denied
+        };
+        let conn = self.lock_conn()?;
+        expire_stale_tool_approvals(&conn, Utc::now())?;
+        let rows = conn.execute(
+            "UPDATE tool_approval_queue SET status = ?1, resolved_at = ?2
+             WHERE id = ?3 AND status = 'pending'",
+            params![status.as_db_str(), Utc::now().to_rfc3339(), id],
+        )?;
+        if rows == 0 {
+            return Ok(None);
+        }
+        let approval = conn
+            .query_row(
+                &format!(
+                    "SELECT {} FROM tool_approval_queue WHERE id = ?1",
+                    QUEUED_APPROVAL_COLUMNS
+                ),
+                [id],
+                parse_queued_approval_row,
+            )
+            .optional()?;
+        Ok(approval)
+    }
+}
@@ -0,0 +1,332 @@
+//! Single-file export and import of the agent's persistent state.
+//!
+//! A bundle is one JSON document: a version header, a consistent SQLite copy
+//! (`VACUUM INTO`), the operator's config as TOML, and the media files that
+//! chat `[media]` blocks point at. Media paths are machine-specific, so
+//! import writes the files under a caller-chosen directory and rewrites the
+//! references in chat history.
+
+use anyhow::{Context, Result};
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::{parse_chat_media_blocks, AgentDatabase};
+
+pub const STATE_BUNDLE_FORMAT: &str = "ponderer_state_bundle";
+pub const STATE_BUNDLE_VERSION: u32 = 1;
+
+/// Tables that are rebuilt locally rather than copied from a bundle.
+const BUNDLE_SKIPPED_TABLES: &[&str] = &["schema_version"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StateBundleHeader {
+    pub format: String,
+    pub version: u32,
+    pub exported_at: DateTime<Utc>,
+    /// Schema version of the exporting database.
+    pub schema_version: u32,
+    /// `table_row_counts` at export time, for verifying an import.
+    pub row_counts: BTreeMap<String, i64>,
+}
+
+/// One file referenced from a chat `[media]` block.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundledMedia {
+    pub original_path: String,
+    pub media_kind: String,
+    pub mime_type: Option<String>,
+    /// Base64 file contents; `None` when the file was gone at export time.
+    pub data: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct StateBundle {
+    header: StateBundleHeader,
+    /// Base64 SQLite database image.
+    database: String,
+    #[serde(default)]
+    config_toml: Option<String>,
+    #[serde(default)]
+    media: Vec<BundledMedia>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StateBundleExport {
+    pub header: StateBundleHeader,
+    pub media_included: usize,
+    pub media_missing: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StateBundleImport {
+    pub header: StateBundleHeader,
+    /// Config carried in the bundle, for the caller to merge
+    /// (`AgentConfig::merge_bundled_toml`).
+    pub config_toml: Option<String>,
+    pub media_restored: usize,
+    pub media_missing: usize,
+}
+
+impl AgentDatabase {
+    /// Writes the whole agent state to one bundle file at `path`.
+    /// `config_toml` should already have secrets removed
+    /// (`AgentConfig::to_bundle_toml`).
+    pub fn export_bundle(
+        &self,
+        path: impl AsRef<Path>,
+        config_toml: Option<&str>,
+    ) -> Result<StateBundleExport> {
+        let path = path.as_ref();
+        let header = StateBundleHeader {
+            format: STATE_BUNDLE_FORMAT.to_string(),
+            version: STATE_BUNDLE_VERSION,
+            exported_at: Utc::now(),
+            schema_version: self.schema_version()?,
+            row_counts: self.table_row_counts()?,
+        };
+
+        let snapshot_path = scratch_database_path("export");
+        let database = {
+            let conn = self.lock_conn()?;
+            conn.execute("VACUUM INTO ?1", [snapshot_path.to_string_lossy()])
+                .context("Failed to snapshot database")?;
+            drop(conn);
+            let bytes = fs::read(&snapshot_path);
+            let _ = fs::remove_file(&snapshot_path);
+            base64::engine::general_purpose::STANDARD.encode(bytes?)
+        };
+
+        let mut media = Vec::new();
+        let mut seen = HashSet::new();
+        for attachment in self.chat_media_attachments()? {
+            if !seen.insert(attachment.path.clone()) {
+                continue;
+            }
+            let data = fs::read(&attachment.path)
+                .ok()
+                .map(|bytes| base64::engine::general_purpose::STANDARD.encode(bytes));
+            media.push(BundledMedia {
+                original_path: attachment.path,
+                media_kind: attachment.media_kind,
+                mime_type: attachment.mime_type,
+                data,
+            });
+        }
+        let media_included = media.iter().filter(|item| item.data.is_some()).count();
+        let media_missing = media.len() - media_included;
+
+        let bundle = StateBundle {
+            header: header.clone(),
+            database,
+            config_toml: config_toml.map(str::to_string),
+            media,
+        };
+        let json = serde_json::to_vec(&bundle).context("Failed to serialize state bundle")?;
+        fs::write(path, json).with_context(|| format!("Failed to write bundle to {:?}", path))?;
+
+        Ok(StateBundleExport {
+            header,
+            media_included,
+            media_missing,
+        })
+    }
+
+    /// Replaces this database's contents with the bundle at `path`. Bundled
+    /// media is written under `media_dir` and chat references are rewritten
+    /// to the new locations. Bundles from older schemas are migrated first.
+    pub fn import_bundle(
+        &self,
+        path: impl AsRef<Path>,
+        media_dir: impl AsRef<Path>,
+    ) -> Result<StateBundleImport> {
+        let path = path.as_ref();
+        let raw = fs::read(path).with_context(|| format!("Failed to read bundle {:?}", path))?;
+        let bundle: StateBundle =
+            serde_json::from_slice(&raw).context("File is not a state bundle")?;
+        if bundle.header.format != STATE_BUNDLE_FORMAT {
+            anyhow::bail!("Unrecognized bundle format '{}'", bundle.header.format);
+        }
+        if bundle.header.version > STATE_BUNDLE_VERSION {
+            anyhow::bail!(
+                "Bundle version {} is newer than this build supports ({})",
+                bundle.header.version,
+                STATE_BUNDLE_VERSION
+            );
+        }
+
+        let snapshot_path = scratch_database_path("import");
+        let result = self.restore_database_image(&bundle.database, &snapshot_path);
+        let _ = fs::remove_file(&snapshot_path);
+        result?;
+
+        let (media_restored, media_missing) =
+            self.restore_bundled_media(&bundle.media, media_dir.as_ref())?;
+
+        Ok(StateBundleImport {
+            header: bundle.header,
+            config_toml: bundle.config_toml,
+            media_restored,
+            media_missing,
+        })
+    }
+
+    fn chat_media_attachments(&self) -> Result<Vec<super::ChatMediaAttachment>> {
+        let conn = self.lock_conn()?;
+        let mut stmt = conn.prepare(
+            "SELECT content FROM chat_messages WHERE content LIKE '%[media]%' ORDER BY created_at, id",
+        )?;
+        let contents = stmt
+            .query_map([], |row| row.get::<_, String>(0))?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(contents
+            .iter()
+            .flat_map(|content| parse_chat_media_blocks(content))
+            .collect())
+    }
+
+    fn restore_database_image(&self, encoded: &str, snapshot_path: &Path) -> Result<()> {
+        let bytes = base64::engine::general_purpose::STANDARD
+            .decode(encoded)
+            .context("Bundle database image is not valid base64")?;
+        fs::write(snapshot_path, bytes)?;
+        // Opening the copy applies any migrations it is missing, so its
+        // tables carry every column this build expects.
+        drop(AgentDatabase::new(snapshot_path).context("Bundle database image is unreadable")?);
+
+        let mut conn = self.lock_conn()?;
+        conn.execute(
+            "ATTACH DATABASE ?1 AS bundle",
+            [snapshot_path.to_string_lossy()],
+        )?;
+        let copied = copy_bundle_tables(&mut conn);
+        conn.execute("DETACH DATABASE bundle", [])?;
+        copied
+    }
+
+    fn restore_bundled_media(
+        &self,
+        media: &[BundledMedia],
+        media_dir: &Path,
+    ) -> Result<(usize, usize)> {
+        let mut rewrites = Vec::new();
+        let mut missing = 0;
+        for (index, item) in media.iter().enumerate() {
+            let Some(data) = item.data.as_deref() else {
+                missing += 1;
+                continue;
+            };
+            let bytes = base64::engine::general_purpose::STANDARD
+                .decode(data)
+                .with_context(|| {
+                    format!("Bundled media {} is not valid base64", item.original_path)
+                })?;
+            fs::create_dir_all(media_dir)?;
+            let file_name = Path::new(&item.original_path)
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_else(|| "media".to_string());
+            let target = media_dir.join(format!("{:04}_{}", index, file_name));
+            fs::write(&target, bytes)?;
+            rewrites.push((
+                item.original_path.clone(),
+                target.to_string_lossy().to_string(),
+            ));
+        }
+
+        if !rewrites.is_empty() {
+            let mut conn = self.lock_conn()?;
+            let tx = conn.transaction()?;
+            for (from, to) in &rewrites {
+                // [media] entries are JSON, so match the JSON-escaped path.
+                let from = json_string_body(from);
+                let to = json_string_body(to);
+                tx.execute(
+                    "UPDATE chat_messages SET content = REPLACE(content, ?1, ?2)
+                     WHERE content LIKE '%[media]%' AND INSTR(content, ?1) > 0",
+                    [&from, &to],
+                )?;
+            }
+            tx.commit()?;
+        }
+        Ok((rewrites.len(), missing))
+    }
+}
+
+/// Replaces each local table's rows with the attached bundle's, copying only
+/// the columns both sides have. FTS tables are skipped; their triggers
+/// repopulate them from the copied rows.
+fn copy_bundle_tables(conn: &mut Connection) -> Result<()> {
+    let tx = conn.transaction()?;
+    let virtual_tables = table_names(&tx, "main", true)?;
+    let bundle_tables: HashSet<String> = table_names(&tx, "bundle", false)?.into_iter().collect();
+    for table in table_names(&tx, "main", false)? {
+        let is_fts_shadow = virtual_tables
+            .iter()
+            .any(|virtual_table| table.starts_with(&format!("{}_", virtual_table)));
+        if BUNDLE_SKIPPED_TABLES.contains(&table.as_str())
+            || is_fts_shadow
+            || !bundle_tables.contains(&table)
+        {
+            continue;
+        }
+        let bundle_columns: HashSet<String> =
+            table_columns(&tx, "bundle", &table)?.into_iter().collect();
+        let columns = table_columns(&tx, "main", &table)?
+            .into_iter()
+            .filter(|column| bundle_columns.contains(column))
+            .map(|column| format!("\"{}\"", column))
+            .collect::<Vec<_>>()
+            .join(", ");
+        tx.execute(&format!("DELETE FROM main.\"{}\"", table), [])?;
+        tx.execute(
+            &format!(
+                "INSERT INTO main.\"{table}\" ({columns}) SELECT {columns} FROM bundle.\"{table}\""
+            ),
+            [],
+        )?;
+    }
+    tx.commit()?;
+    Ok(())
+}
+
+fn table_names(conn: &Connection, schema: &str, virtual_only: bool) -> Result<Vec<String>> {
+    let filter = if virtual_only {
+        "sql LIKE 'CREATE VIRTUAL%'"
+    } else {
+        "sql NOT LIKE 'CREATE VIRTUAL%'"
+    };
+    let mut stmt = conn.prepare(&format!(
+        "SELECT name FROM {schema}.sqlite_master
+         WHERE type = 'table' AND name NOT LIKE 'sqlite_%' AND {filter}"
+    ))?;
+    let names = stmt
+        .query_map([], |row| row.get::<_, String>(0))?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    Ok(names)
+}
+
+fn table_columns(conn: &Connection, schema: &str, table: &str) -> Result<Vec<String>> {
+    let mut stmt = conn.prepare(&format!("PRAGMA {schema}.table_info(\"{table}\")"))?;
+    let columns = stmt
+        .query_map([], |row| row.get::<_, String>(1))?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    Ok(columns)
+}
+
+fn json_string_body(value: &str) -> String {
+    let quoted = serde_json::to_string(value).unwrap_or_default();
+    quoted[1..quoted.len().saturating_sub(1).max(1)].to_string()
+}
+
+fn scratch_database_path(purpose: &str) -> PathBuf {
+    std::env::temp_dir().join(format!(
+        "ponderer_bundle_{}_{}.db",
+        purpose,
+        uuid::Uuid::new_v4()
+    ))
+}
@@ -1,14 +1,20 @@
 use anyhow::Result;
 use chrono::{DateTime, Utc};
-use rusqlite::params;
+use rusqlite::{params, Connection, Row};
 use serde::{Deserialize, Serialize};
 
-use super::helpers::{summarize_chat_message_for_context, truncate_for_db_digest};
+use super::helpers::{
+    extract_tagged_blocks, summarize_chat_message_for_context, truncate_for_db_digest,
+};
 use super::AgentDatabase;
 
 pub const DEFAULT_CHAT_SESSION_ID: &str = "default_session";
 pub const DEFAULT_CHAT_CONVERSATION_ID: &str = "default";
 pub const TELEGRAM_CONVERSATION_ID: &str = "telegram";
+/// Error recorded on turns that were still processing when the process died.
+pub const ORPHANED_TURN_ERROR: &str = "interrupted: backend exited before the turn completed";
+/// Background-subtask turns store `iteration` as this offset plus their turn number.
+pub const CHAT_BACKGROUND_ITERATION_OFFSET: i64 = 100;
 pub(super) const CHAT_TOOL_BLOCK_START: &str = "[tool_calls]";
 pub(super) const CHAT_TOOL_BLOCK_END: &str = "[/tool_calls]";
 pub(super) const CHAT_THINKING_BLOCK_START: &str = "[thinking]";
@@ -33,6 +39,52 @@ pub struct ChatMessage {
     pub turn_id: Option<String>,
 }
 
+impl ChatMessage {
+    /// Attachments listed in this message's `[media]` blocks.
+    pub fn media_attachments(&self) -> Vec<ChatMediaAttachment> {
+        parse_chat_media_blocks(&self.content)
+    }
+}
+
+/// One entry of a `[media]` block, as written by the agent's
+/// `format_chat_message_with_metadata`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChatMediaAttachment {
+    pub path: String,
+    /// `image`, `audio`, `video`, or `file`; anything else reads as `file`.
+    pub media_kind: String,
+    #[serde(default)]
+    pub mime_type: Option<String>,
+    /// Tool that produced the media.
+    #[serde(default)]
+    pub source: String,
+    #[serde(default)]
+    pub auto_play: bool,
+    /// Generation seed, when the producing tool reported one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+}
+
+/// Parses every `[media]` block in `content`. Malformed blocks and entries
+/// without a path are skipped rather than failing the whole message.
+pub fn parse_chat_media_blocks(content: &str) -> Vec<ChatMediaAttachment> {
+    let (_, blocks) = extract_tagged_blocks(content, CHAT_MEDIA_BLOCK_START, CHAT_MEDIA_BLOCK_END);
+    blocks
+        .iter()
+        .filter_map(|block| serde_json::from_str::<Vec<serde_json::Value>>(block.trim()).ok())
+        .flatten()
+        .filter_map(|entry| serde_json::from_value::<ChatMediaAttachment>(entry).ok())
+        .filter(|attachment| !attachment.path.trim().is_empty())
+        .map(|mut attachment| {
+            attachment.media_kind = match attachment.media_kind.trim().to_ascii_lowercase() {
+                kind if matches!(kind.as_str(), "image" | "audio" | "video") => kind,
+                _ => "file".to_string(),
+            };
+            attachment
+        })
+        .collect()
+}
+
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
 pub enum ChatTurnPhase {
@@ -84,6 +136,15 @@ pub struct ChatConversation {
     pub active_turn_id: Option<String>,
     pub message_count: usize,
     pub last_message_at: Option<DateTime<Utc>>,
+    /// Whether a compacted `ChatConversationSummary` exists for this thread.
+    #[serde(default)]
+    pub summary_present: bool,
+    /// Replaces the configured `system_prompt` for this thread's chat turns.
+    #[serde(default)]
+    pub system_prompt_override: Option<String>,
+    /// Pure conversation mode: chat turns in this thread are offered no tools.
+    #[serde(default)]
+    pub tools_disabled: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -115,6 +176,18 @@ pub struct ChatTurn {
     pub system_prompt_text: Option<String>,
 }
 
+/// Everything persisted about one turn: the prompts the model saw (on
+/// `turn`), its tool calls, OODA packet, decision, and final message.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatTurnExplanation {
+    pub turn: ChatTurn,
+    /// Turn ran inside a background subtask rather than the foreground chat loop.
+    pub background_subtask: bool,
+    pub ooda_packet: Option<OodaTurnPacketRecord>,
+    pub tool_calls: Vec<ChatTurnToolCall>,
+    pub final_message: Option<ChatMessage>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChatTurnToolCall {
     pub id: String,
@@ -124,6 +197,118 @@ pub struct ChatTurnToolCall {
     pub arguments_json: String,
     pub output_text: String,
     pub created_at: DateTime<Utc>,
+    /// Execution time; `None` for rows recorded before latency was tracked.
+    #[serde(default)]
+    pub duration_ms: Option<u64>,
+}
+
+/// Per-tool latency summary across every recorded chat-turn tool call.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ToolLatencyStats {
+    pub tool_name: String,
+    pub calls: usize,
+    pub mean_ms: f64,
+    pub p95_ms: u64,
+}
+
+pub(super) fn add_conversation_system_prompt_column(conn: &Connection) -> Result<()> {
+    if !AgentDatabase::table_has_column(conn, "chat_conversations", "system_prompt_override")? {
+        conn.execute(
+            "ALTER TABLE chat_conversations ADD COLUMN system_prompt_override TEXT",
+            [],
+        )?;
+    }
+    Ok(())
+}
+
+pub(super) fn add_conversation_tools_disabled_column(conn: &Connection) -> Result<()> {
+    if !AgentDatabase::table_has_column(conn, "chat_conversations", "tools_disabled")? {
+        conn.execute(
+            "ALTER TABLE chat_conversations ADD COLUMN tools_disabled INTEGER NOT NULL DEFAULT 0",
+            [],
+        )?;
+    }
+    Ok(())
+}
+
+pub(super) fn add_tool_call_duration_column(conn: &Connection) -> Result<()> {
+    if !AgentDatabase::table_has_column(conn, "chat_turn_tool_calls", "duration_ms")? {
+        conn.execute(
+            "ALTER TABLE chat_turn_tool_calls ADD COLUMN duration_ms INTEGER",
+            [],
+        )?;
+    }
+    Ok(())
+}
+
+const CHAT_TURN_COLUMNS: &str =
+    "id, session_id, conversation_id, iteration, phase_state, decision, status,
+    trigger_message_ids_json, operator_message, reason, error, tool_call_count,
+    started_at, completed_at, agent_message_id, prompt_text, system_prompt_text";
+
+fn parse_chat_turn_row(row: &Row<'_>) -> rusqlite::Result<ChatTurn> {
+    let started_at_str: String = row.get(12)?;
+    let completed_at_str: Option<String> = row.get(13)?;
+    let phase_state_raw: String = row.get(4)?;
+    let trigger_ids_raw: String = row.get(7)?;
+    let trigger_message_ids =
+        serde_json::from_str::<Vec<String>>(&trigger_ids_raw).unwrap_or_else(|_| Vec::new());
+
+    Ok(ChatTurn {
+        id: row.get(0)?,
+        session_id: row.get(1)?,
+        conversation_id: row.get(2)?,
+        iteration: row.get(3)?,
+        phase_state: ChatTurnPhase::from_db(&phase_state_raw),
+        decision: row.get(5)?,
+        status: row.get(6)?,
+        trigger_message_ids,
+        operator_message: row.get(8)?,
+        reason: row.get(9)?,
+        error: row.get(10)?,
+        tool_call_count: row.get::<_, i64>(11)? as usize,
+        started_at: started_at_str.parse().map_err(|e| {
+            rusqlite::Error::FromSqlConversionFailure(12, rusqlite::types::Type::Text, Box::new(e))
+        })?,
+        completed_at: match completed_at_str {
+            Some(v) => Some(v.parse().map_err(|e| {
+                rusqlite::Error::FromSqlConversionFailure(
+                    13,
+                    rusqlite::types::Type::Text,
+                    Box::new(e),
+                )
+            })?),
+            None => None,
+        },
+        agent_message_id: row.get(14)?,
+        prompt_text: row.get(15)?,
+        system_prompt_text: row.get(16)?,
+    })
+}
+
+fn parse_ooda_packet_row(row: &Row<'_>) -> rusqlite::Result<OodaTurnPacketRecord> {
+    let created_at_raw: String = row.get(7)?;
+    Ok(OodaTurnPacketRecord {
+        id: row.get(0)?,
+        conversation_id: row.get(1)?,
+        turn_id: row.get(2)?,
+        observe: row.get(3)?,
+        orient: row.get(4)?,
+        decide: row.get(5)?,
+        act: row.get(6)?,
+        created_at: created_at_raw.parse().map_err(|e| {
+            rusqlite::Error::FromSqlConversionFailure(7, rusqlite::types::Type::Text, Box::new(e))
+        })?,
+    })
+}
+
+/// Nearest-rank percentile over an ascending slice.
+fn nearest_rank_percentile(sorted: &[u64], percentile: f64) -> u64 {
+    if sorted.is_empty() {
+        return 0;
+    }
+    let rank = ((percentile / 100.0) * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -138,6 +323,183 @@ pub struct OodaTurnPacketRecord {
     pub created_at: DateTime<Utc>,
 }
 
+/// How the action digest chooses which turns to summarize.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ActionDigestStrategy {
+    /// The newest turns, in order.
+    #[default]
+    Recency,
+    /// Turns with tool calls, errors, or unfinished statuses first, drawn
+    /// from a wider window so notable early actions survive long sessions.
+    Salience,
+    /// Repeated similar turns folded into one line with a count.
+    Compressed,
+}
+
+impl ActionDigestStrategy {
+    pub fn from_config(raw: &str) -> Self {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "salience" => Self::Salience,
+            "compressed" => Self::Compressed,
+            _ => Self::Recency,
+        }
+    }
+}
+
+/// How many times `limit` turns the salience and compressed strategies scan.
+const ACTION_DIGEST_CANDIDATE_MULTIPLIER: usize = 4;
+
+#[derive(Debug, Clone)]
+struct ActionDigestRow {
+    conversation_id: String,
+    started_at: String,
+    phase_state: String,
+    decision: Option<String>,
+    status: Option<String>,
+    tool_count: usize,
+    reason: Option<String>,
+    error: Option<String>,
+    tool_preview: String,
+    agent_content: String,
+}
+
+impl ActionDigestRow {
+    fn error_text(&self) -> Option<&str> {
+        self.error
+            .as_deref()
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+    }
+
+    fn salience(&self) -> u32 {
+        let mut score = 0;
+        if self.error_text().is_some() {
+            score += 3;
+        }
+        if self.tool_count > 0 {
+            score += 2;
+        }
+        if self
+            .status
+            .as_deref()
+            .is_some_and(|status| !status.eq_ignore_ascii_case("done"))
+        {
+            score += 1;
+        }
+        score
+    }
+
+    /// Turns that did the same kind of thing (same outcome and tools) share a key.
+    fn cluster_key(&self) -> (&str, &str, &str, &str, &str, bool) {
+        (
+            &self.conversation_id,
+            &self.phase_state,
+            self.decision.as_deref().unwrap_or_default(),
+            self.status.as_deref().unwrap_or_default(),
+            self.tool_preview.trim(),
+            self.error_text().is_some(),
+        )
+    }
+
+    fn render(&self, include_conversation: bool) -> String {
+        let mut line = format!(
+            "- [{}] {}phase={} decision={} status={} tools={}{}",
+            self.started_at,
+            if include_conversation {
+                format!("conv={} ", self.conversation_id)
+            } else {
+                String::new()
+            },
+            self.phase_state,
+            self.decision.as_deref().unwrap_or("-"),
+            self.status.as_deref().unwrap_or("-"),
+            self.tool_count,
+            if self.tool_preview.trim().is_empty() {
+                String::new()
+            } else {
+                format!(" ({})", self.tool_preview)
+            }
+        );
+
+        let response_preview = summarize_chat_message_for_context(&self.agent_content);
+        if let Some(error_text) = self.error_text() {
+            line.push_str(&format!(
+                " error=\"{}\"",
+                truncate_for_db_digest(error_text, 140)
+            ));
+        } else if !response_preview.is_empty() {
+            line.push_str(&format!(
+                " reply=\"{}\"",
+                truncate_for_db_digest(&response_preview, 160)
+            ));
+        }
+
+        if let Some(reason_text) = self
+            .reason
+            .as_deref()
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+        {
+            line.push_str(&format!(
+                " reason=\"{}\"",
+                truncate_for_db_digest(reason_text, 120)
+            ));
+        }
+        line
+    }
+}
+
+/// Keeps the newest turn plus the most salient others (newer wins ties),
+/// returned newest-first like the recency digest.
+fn select_salient_rows(rows: &[ActionDigestRow], limit: usize) -> Vec<&ActionDigestRow> {
+    let mut ranked: Vec<usize> = (1..rows.len()).collect();
+    ranked.sort_by(|a, b| rows[*b].salience().cmp(&rows[*a].salience()).then(a.cmp(b)));
+    let mut chosen: Vec<usize> = rows.first().map(|_| 0).into_iter().collect();
+    chosen.extend(ranked.into_iter().take(limit.saturating_sub(1)));
+    chosen.sort_unstable();
+    chosen.into_iter().map(|index| &rows[index]).collect()
+}
+
+/// Similar turns folded together; `newest` is rendered and annotated.
+struct ActionDigestCluster<'a> {
+    newest: &'a ActionDigestRow,
+    oldest_started_at: &'a str,
+    count: usize,
+}
+
+impl ActionDigestCluster<'_> {
+    fn render(&self, include_conversation: bool) -> String {
+        let mut line = self.newest.render(include_conversation);
+        if self.count > 1 {
+            line.push_str(&format!(
+                " [x{} since {}]",
+                self.count, self.oldest_started_at
+            ));
+        }
+        line
+    }
+}
+
+/// Groups newest-first rows by `cluster_key`, ordering clusters by their newest member.
+fn cluster_digest_rows(rows: &[ActionDigestRow]) -> Vec<ActionDigestCluster<'_>> {
+    let mut clusters: Vec<ActionDigestCluster<'_>> = Vec::new();
+    let mut by_key: std::collections::HashMap<_, usize> = std::collections::HashMap::new();
+    for row in rows {
+        let index = *by_key.entry(row.cluster_key()).or_insert_with(|| {
+            clusters.push(ActionDigestCluster {
+                newest: row,
+                oldest_started_at: &row.started_at,
+                count: 0,
+            });
+            clusters.len() - 1
+        });
+        let cluster = &mut clusters[index];
+        cluster.count += 1;
+        cluster.oldest_started_at = &row.started_at;
+    }
+    clusters
+}
+
 impl AgentDatabase {
     /// Add a chat message
     pub fn add_chat_message(&self, role: &str, content: &str) -> Result<String> {
@@ -274,6 +636,9 @@ impl AgentDatabase {
             active_turn_id: None,
             message_count: 0,
             last_message_at: None,
+            summary_present: false,
+            system_prompt_override: None,
+            tools_disabled: false,
         })
     }
 
@@ -290,7 +655,13 @@ impl AgentDatabase {
                    c.runtime_state,
                    c.active_turn_id,
                    COUNT(m.id) as message_count,
-                   MAX(m.created_at) as last_message_at
+                   MAX(m.created_at) as last_message_at,
+                   EXISTS(
+                       SELECT 1 FROM chat_conversation_summaries s
+                       WHERE s.conversation_id = c.id
+                   ) as summary_present,
+                   c.system_prompt_override,
+                   c.tools_disabled
                FROM chat_conversations c
                LEFT JOIN chat_messages m ON m.conversation_id = c.id
                GROUP BY c.id
@@ -306,6 +677,9 @@ impl AgentDatabase {
                 let active_turn_id: Option<String> = row.get(6)?;
                 let message_count = row.get::<_, i64>(7)? as usize;
                 let last_message_at_str: Option<String> = row.get(8)?;
+                let summary_present: bool = row.get(9)?;
+                let system_prompt_override: Option<String> = row.get(10)?;
+                let tools_disabled = row.get::<_, i64>(11)? != 0;
 
                 Ok(ChatConversation {
                     id: row.get(0)?,
@@ -338,6 +712,9 @@ impl AgentDatabase {
                         })?),
                         None => None,
                     },
+                    summary_present,
+                    system_prompt_override,
+                    tools_disabled,
                 })
             })?
             .collect::<Result<Vec<_>, _>>()?;
@@ -358,7 +735,13 @@ impl AgentDatabase {
                    c.runtime_state,
                    c.active_turn_id,
                    COUNT(m.id) as message_count,
-                   MAX(m.created_at) as last_message_at
+                   MAX(m.created_at) as last_message_at,
+                   EXISTS(
+                       SELECT 1 FROM chat_conversation_summaries s
+                       WHERE s.conversation_id = c.id
+                   ) as summary_present,
+                   c.system_prompt_override,
+                   c.tools_disabled
                FROM chat_conversations c
                LEFT JOIN chat_messages m ON m.conversation_id = c.id
                WHERE c.id = ?1
@@ -377,6 +760,9 @@ impl AgentDatabase {
         let active_turn_id: Option<String> = row.get(6)?;
         let message_count = row.get::<_, i64>(7)? as usize;
         let last_message_at_str: Option<String> = row.get(8)?;
+        let summary_present: bool = row.get(9)?;
+        let system_prompt_override: Option<String> = row.get(10)?;
+        let tools_disabled = row.get::<_, i64>(11)? != 0;
 
         Ok(Some(ChatConversation {
             id: row.get(0)?,
@@ -409,37 +795,50 @@ impl AgentDatabase {
                 })?),
                 None => None,
             },
+            summary_present,
+            system_prompt_override,
+            tools_disabled,
         }))
     }
 
-    /// Delete a conversation and all its associated data.
+    /// Delete a conversation and all its associated data in one transaction.
     pub fn delete_chat_conversation(&self, conversation_id: &str) -> Result<()> {
-        let conn = self.lock_conn()?;
+        let mut conn = self.lock_conn()?;
+        let tx = conn.transaction()?;
         // Delete tool calls for all turns belonging to this conversation.
-        conn.execute(
+        tx.execute(
             "DELETE FROM chat_turn_tool_calls WHERE turn_id IN (SELECT id FROM chat_turns WHERE conversation_id = ?1)",
             params![conversation_id],
         )?;
-        conn.execute(
+        tx.execute(
             "DELETE FROM chat_turns WHERE conversation_id = ?1",
             params![conversation_id],
         )?;
-        conn.execute(
+        tx.execute(
+            "DELETE FROM chat_message_embeddings WHERE conversation_id = ?1",
+            params![conversation_id],
+        )?;
+        tx.execute(
             "DELETE FROM chat_messages WHERE conversation_id = ?1",
             params![conversation_id],
         )?;
-        conn.execute(
+        tx.execute(
             "DELETE FROM chat_conversation_summaries WHERE conversation_id = ?1",
             params![conversation_id],
         )?;
-        conn.execute(
+        tx.execute(
             "DELETE FROM ooda_turn_packets WHERE conversation_id = ?1",
             params![conversation_id],
         )?;
-        conn.execute(
+        tx.execute(
+            "DELETE FROM tool_approval_queue WHERE conversation_id = ?1",
+            params![conversation_id],
+        )?;
+        tx.execute(
             "DELETE FROM chat_conversations WHERE id = ?1",
             params![conversation_id],
         )?;
+        tx.commit()?;
         Ok(())
     }
 
@@ -453,6 +852,67 @@ impl AgentDatabase {
         Ok(())
     }
 
+    /// Set or clear (`None` or blank) a conversation's system-prompt override.
+    pub fn set_chat_conversation_system_prompt(
+        &self,
+        conversation_id: &str,
+        system_prompt: Option<&str>,
+    ) -> Result<()> {
+        let system_prompt = system_prompt.map(str::trim).filter(|s| !s.is_empty());
+        let conn = self.lock_conn()?;
+        conn.execute(
+            "UPDATE chat_conversations SET system_prompt_override = ?1, updated_at = ?2 WHERE id = ?3",
+            params![system_prompt, Utc::now().to_rfc3339(), conversation_id],
+        )?;
+        Ok(())
+    }
+
+    pub fn get_chat_conversation_system_prompt(
+        &self,
+        conversation_id: &str,
+    ) -> Result<Option<String>> {
+        let conn = self.lock_conn()?;
+        let result = conn.query_row(
+            "SELECT system_prompt_override FROM chat_conversations WHERE id = ?1",
+            [conversation_id],
+            |row| row.get::<_, Option<String>>(0),
+        );
+        match result {
+            Ok(system_prompt) => Ok(system_prompt),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Turn pure conversation mode on or off for a thread.
+    pub fn set_chat_conversation_tools_disabled(
+        &self,
+        conversation_id: &str,
+        tools_disabled: bool,
+    ) -> Result<()> {
+        let conn = self.lock_conn()?;
+        conn.execute(
+            "UPDATE chat_conversations SET tools_disabled = ?1, updated_at = ?2 WHERE id = ?3",
+            params![tools_disabled, Utc::now().to_rfc3339(), conversation_id],
+        )?;
+        Ok(())
+    }
+
+    /// Whether a thread is in pure conversation mode; `false` for unknown IDs.
+    pub fn get_chat_conversation_tools_disabled(&self, conversation_id: &str) -> Result<bool> {
+        let conn = self.lock_conn()?;
+        let result = conn.query_row(
+            "SELECT tools_disabled FROM chat_conversations WHERE id = ?1",
+            [conversation_id],
+            |row| row.get::<_, i64>(0),
+        );
+        match result {
+            Ok(tools_disabled) => Ok(tools_disabled != 0),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(false),
+            Err(e) => Err(e.into()),
+        }
+    }
+
     /// Start a new persisted turn for a conversation.
     pub fn begin_chat_turn(
         &self,
@@ -593,11 +1053,12 @@ impl AgentDatabase {
         tool_name: &str,
         arguments_json: &str,
         output_text: &str,
+        duration_ms: Option<u64>,
     ) -> Result<()> {
         let conn = self.lock_conn()?;
         conn.execute(
-            "INSERT INTO chat_turn_tool_calls (id, turn_id, call_index, tool_name, arguments_json, output_text, created_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            "INSERT INTO chat_turn_tool_calls (id, turn_id, call_index, tool_name, arguments_json, output_text, created_at, duration_ms)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
             params![
                 uuid::Uuid::new_v4().to_string(),
                 turn_id,
@@ -606,6 +1067,7 @@ impl AgentDatabase {
                 arguments_json,
                 output_text,
                 Utc::now().to_rfc3339(),
+                duration_ms.map(|ms| ms as i64),
             ],
         )?;
         Ok(())
@@ -690,6 +1152,43 @@ impl AgentDatabase {
         Ok(())
     }
 
+    /// Mark every turn still in `processing` as failed with `error`, and reset
+    /// its conversation's runtime state. Returns how many turns were closed.
+    pub fn fail_open_chat_turns(&self, error: &str) -> Result<usize> {
+        let now = Utc::now().to_rfc3339();
+        let mut conn = self.lock_conn()?;
+        let tx = conn.transaction()?;
+        tx.execute(
+            "UPDATE chat_conversations
+             SET runtime_state = ?1, active_turn_id = NULL, updated_at = ?2
+             WHERE id IN (SELECT conversation_id FROM chat_turns WHERE phase_state = ?3)",
+            params![
+                ChatTurnPhase::Failed.as_db_str(),
+                now.clone(),
+                ChatTurnPhase::Processing.as_db_str()
+            ],
+        )?;
+        let closed = tx.execute(
+            "UPDATE chat_turns
+             SET phase_state = ?1, error = ?2, completed_at = ?3
+             WHERE phase_state = ?4",
+            params![
+                ChatTurnPhase::Failed.as_db_str(),
+                error,
+                now,
+                ChatTurnPhase::Processing.as_db_str()
+            ],
+        )?;
+        tx.commit()?;
+        Ok(closed)
+    }
+
+    /// Close turns left in `processing` by a crash or hard kill. Run once at
+    /// startup, before the agent loop can open new turns.
+    pub fn reconcile_orphaned_turns(&self) -> Result<usize> {
+        self.fail_open_chat_turns(ORPHANED_TURN_ERROR)
+    }
+
     /// List recent turns for one conversation (newest first).
     pub fn list_chat_turns_for_conversation(
         &self,
@@ -697,71 +1196,102 @@ impl AgentDatabase {
         limit: usize,
     ) -> Result<Vec<ChatTurn>> {
         let conn = self.lock_conn()?;
-        let mut stmt = conn.prepare(
-            "SELECT
-                id, session_id, conversation_id, iteration, phase_state, decision, status,
-                trigger_message_ids_json, operator_message, reason, error, tool_call_count,
-                started_at, completed_at, agent_message_id, prompt_text, system_prompt_text
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {CHAT_TURN_COLUMNS}
              FROM chat_turns
              WHERE conversation_id = ?1
              ORDER BY started_at DESC
-             LIMIT ?2",
-        )?;
+             LIMIT ?2"
+        ))?;
 
         let turns = stmt
-            .query_map(params![conversation_id, limit], |row| {
-                let started_at_str: String = row.get(12)?;
-                let completed_at_str: Option<String> = row.get(13)?;
-                let phase_state_raw: String = row.get(4)?;
-                let trigger_ids_raw: String = row.get(7)?;
-                let trigger_message_ids = serde_json::from_str::<Vec<String>>(&trigger_ids_raw)
-                    .unwrap_or_else(|_| Vec::new());
-
-                Ok(ChatTurn {
-                    id: row.get(0)?,
-                    session_id: row.get(1)?,
-                    conversation_id: row.get(2)?,
-                    iteration: row.get(3)?,
-                    phase_state: ChatTurnPhase::from_db(&phase_state_raw),
-                    decision: row.get(5)?,
-                    status: row.get(6)?,
-                    trigger_message_ids,
-                    operator_message: row.get(8)?,
-                    reason: row.get(9)?,
-                    error: row.get(10)?,
-                    tool_call_count: row.get::<_, i64>(11)? as usize,
-                    started_at: started_at_str.parse().map_err(|e| {
-                        rusqlite::Error::FromSqlConversionFailure(
-                            12,
-                            rusqlite::types::Type::Text,
-                            Box::new(e),
-                        )
-                    })?,
-                    completed_at: match completed_at_str {
-                        Some(v) => Some(v.parse().map_err(|e| {
+            .query_map(params![conversation_id, limit], parse_chat_turn_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(turns)
+    }
+
+    /// Load a single turn by id.
+    pub fn get_chat_turn(&self, turn_id: &str) -> Result<Option<ChatTurn>> {
+        let conn = self.lock_conn()?;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {CHAT_TURN_COLUMNS} FROM chat_turns WHERE id = ?1 LIMIT 1"
+        ))?;
+        let mut rows = stmt.query([turn_id])?;
+        match rows.next()? {
+            Some(row) => Ok(Some(parse_chat_turn_row(row)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Assemble everything persisted about one turn so an operator can see
+    /// what context produced it. Works for foreground and background-subtask
+    /// turns alike, since both persist through the same tables.
+    pub fn get_turn_explanation(&self, turn_id: &str) -> Result<Option<ChatTurnExplanation>> {
+        let Some(turn) = self.get_chat_turn(turn_id)? else {
+            return Ok(None);
+        };
+        let tool_calls = self.list_chat_turn_tool_calls(turn_id)?;
+        let conn = self.lock_conn()?;
+        let ooda_packet = {
+            let mut stmt = conn.prepare(
+                "SELECT id, conversation_id, turn_id, observe, orient, decide, act, created_at
+                 FROM ooda_turn_packets
+                 WHERE turn_id = ?1
+                 ORDER BY created_at DESC
+                 LIMIT 1",
+            )?;
+            let mut rows = stmt.query([turn_id])?;
+            match rows.next()? {
+                Some(row) => Some(parse_ooda_packet_row(row)?),
+                None => None,
+            }
+        };
+        let final_message = match turn.agent_message_id.as_deref() {
+            Some(message_id) => {
+                let mut stmt = conn.prepare(
+                    "SELECT id, conversation_id, role, content, created_at, processed, turn_id
+                     FROM chat_messages
+                     WHERE id = ?1
+                     LIMIT 1",
+                )?;
+                let mut rows = stmt.query([message_id])?;
+                match rows.next()? {
+                    Some(row) => Some(ChatMessage {
+                        id: row.get(0)?,
+                        conversation_id: row.get(1)?,
+                        role: row.get(2)?,
+                        content: row.get(3)?,
+                        created_at: row.get::<_, String>(4)?.parse().map_err(|e| {
                             rusqlite::Error::FromSqlConversionFailure(
-                                13,
+                                4,
                                 rusqlite::types::Type::Text,
                                 Box::new(e),
                             )
-                        })?),
-                        None => None,
-                    },
-                    agent_message_id: row.get(14)?,
-                    prompt_text: row.get(15)?,
-                    system_prompt_text: row.get(16)?,
-                })
-            })?
-            .collect::<Result<Vec<_>, _>>()?;
+                        })?,
+                        processed: row.get::<_, i64>(5)? != 0,
+                        turn_id: row.get(6)?,
+                    }),
+                    None => None,
+                }
+            }
+            None => None,
+        };
 
-        Ok(turns)
+        Ok(Some(ChatTurnExplanation {
+            background_subtask: turn.iteration >= CHAT_BACKGROUND_ITERATION_OFFSET,
+            turn,
+            ooda_packet,
+            tool_calls,
+            final_message,
+        }))
     }
 
     /// List tool calls for a specific turn.
     pub fn list_chat_turn_tool_calls(&self, turn_id: &str) -> Result<Vec<ChatTurnToolCall>> {
         let conn = self.lock_conn()?;
         let mut stmt = conn.prepare(
-            "SELECT id, turn_id, call_index, tool_name, arguments_json, output_text, created_at
+            "SELECT id, turn_id, call_index, tool_name, arguments_json, output_text, created_at, duration_ms
              FROM chat_turn_tool_calls
              WHERE turn_id = ?1
              ORDER BY call_index ASC",
@@ -784,6 +1314,7 @@ impl AgentDatabase {
                             Box::new(e),
                         )
                     })?,
+                    duration_ms: row.get::<_, Option<i64>>(7)?.map(|ms| ms.max(0) as u64),
                 })
             })?
             .collect::<Result<Vec<_>, _>>()?;
@@ -791,6 +1322,58 @@ impl AgentDatabase {
         Ok(calls)
     }
 
+    /// Mean and p95 execution time per tool name, slowest mean first. Rows
+    /// without a recorded duration are ignored.
+    pub fn get_tool_latency_stats(&self) -> Result<Vec<ToolLatencyStats>> {
+        let conn = self.lock_conn()?;
+        let mut stmt = conn.prepare(
+            "SELECT tool_name, duration_ms
+             FROM chat_turn_tool_calls
+             WHERE duration_ms IS NOT NULL
+             ORDER BY tool_name ASC, duration_ms ASC",
+        )?;
+        let rows = stmt
+            .query_map([], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, i64>(1)?.max(0) as u64,
+                ))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut stats: Vec<ToolLatencyStats> = Vec::new();
+        let mut durations: Vec<u64> = Vec::new();
+        let mut flush = |tool_name: &str, durations: &mut Vec<u64>| {
+            if durations.is_empty() {
+                return;
+            }
+            let total: u64 = durations.iter().sum();
+            stats.push(ToolLatencyStats {
+                tool_name: tool_name.to_string(),
+                calls: durations.len(),
+                mean_ms: total as f64 / durations.len() as f64,
+                p95_ms: nearest_rank_percentile(durations, 95.0),
+            });
+            durations.clear();
+        };
+        let mut current: Option<String> = None;
+        for (tool_name, duration_ms) in rows {
+            if current.as_deref() != Some(tool_name.as_str()) {
+                if let Some(previous) = current.take() {
+                    flush(&previous, &mut durations);
+                }
+                current = Some(tool_name);
+            }
+            durations.push(duration_ms);
+        }
+        if let Some(previous) = current {
+            flush(&previous, &mut durations);
+        }
+
+        stats.sort_by(|a, b| b.mean_ms.total_cmp(&a.mean_ms));
+        Ok(stats)
+    }
+
     pub fn save_ooda_turn_packet(&self, packet: &OodaTurnPacketRecord) -> Result<()> {
         let conn = self.lock_conn()?;
         conn.execute(
@@ -827,23 +1410,7 @@ impl AgentDatabase {
         let Some(row) = rows.next()? else {
             return Ok(None);
         };
-        let created_at_raw: String = row.get(7)?;
-        Ok(Some(OodaTurnPacketRecord {
-            id: row.get(0)?,
-            conversation_id: row.get(1)?,
-            turn_id: row.get(2)?,
-            observe: row.get(3)?,
-            orient: row.get(4)?,
-            decide: row.get(5)?,
-            act: row.get(6)?,
-            created_at: created_at_raw.parse().map_err(|e| {
-                rusqlite::Error::FromSqlConversionFailure(
-                    7,
-                    rusqlite::types::Type::Text,
-                    Box::new(e),
-                )
-            })?,
-        }))
+        Ok(Some(parse_ooda_packet_row(row)?))
     }
 
     pub fn get_latest_ooda_turn_packet(&self) -> Result<Option<OodaTurnPacketRecord>> {
@@ -924,8 +1491,13 @@ impl AgentDatabase {
         Ok(rows.into_iter().rev().collect())
     }
 
-    pub fn get_recent_action_digest(&self, limit: usize, max_chars: usize) -> Result<String> {
-        self.get_recent_action_digest_inner(None, limit, max_chars)
+    pub fn get_recent_action_digest(
+        &self,
+        limit: usize,
+        max_chars: usize,
+        strategy: ActionDigestStrategy,
+    ) -> Result<String> {
+        self.get_recent_action_digest_inner(None, limit, max_chars, strategy)
     }
 
     pub fn get_recent_action_digest_for_conversation(
@@ -933,8 +1505,9 @@ impl AgentDatabase {
         conversation_id: &str,
         limit: usize,
         max_chars: usize,
+        strategy: ActionDigestStrategy,
     ) -> Result<String> {
-        self.get_recent_action_digest_inner(Some(conversation_id), limit, max_chars)
+        self.get_recent_action_digest_inner(Some(conversation_id), limit, max_chars, strategy)
     }
 
     fn get_recent_action_digest_inner(
@@ -942,11 +1515,59 @@ impl AgentDatabase {
         conversation_id: Option<&str>,
         limit: usize,
         max_chars: usize,
+        strategy: ActionDigestStrategy,
     ) -> Result<String> {
-        let conn = self.lock_conn()?;
-        let limit = limit.max(1) as i64;
-        let mut lines: Vec<String> = Vec::new();
+        let limit = limit.max(1);
+        let candidates = match strategy {
+            ActionDigestStrategy::Recency => limit,
+            ActionDigestStrategy::Salience | ActionDigestStrategy::Compressed => {
+                limit.saturating_mul(ACTION_DIGEST_CANDIDATE_MULTIPLIER)
+            }
+        };
+        let rows = self.load_action_digest_rows(conversation_id, candidates)?;
+        let include_conversation = conversation_id.is_none();
+        let lines: Vec<String> = match strategy {
+            ActionDigestStrategy::Recency => rows
+                .iter()
+                .take(limit)
+                .map(|row| row.render(include_conversation))
+                .collect(),
+            ActionDigestStrategy::Salience => select_salient_rows(&rows, limit)
+                .into_iter()
+                .map(|row| row.render(include_conversation))
+                .collect(),
+            ActionDigestStrategy::Compressed => cluster_digest_rows(&rows)
+                .into_iter()
+                .take(limit)
+                .map(|cluster| cluster.render(include_conversation))
+                .collect(),
+        };
+
+        if lines.is_empty() {
+            return Ok("None".to_string());
+        }
+
+        let mut output = String::new();
+        for line in lines {
+            if !output.is_empty() {
+                output.push('\n');
+            }
+            output.push_str(&line);
+            if output.chars().count() >= max_chars.max(120) {
+                output = truncate_for_db_digest(&output, max_chars.max(120));
+                break;
+            }
+        }
+        Ok(output)
+    }
 
+    /// Newest-first digest rows, at most `limit`.
+    fn load_action_digest_rows(
+        &self,
+        conversation_id: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<ActionDigestRow>> {
+        let conn = self.lock_conn()?;
         let mut stmt = conn.prepare(
             "SELECT
                 ct.conversation_id,
@@ -977,99 +1598,23 @@ impl AgentDatabase {
              ORDER BY ct.started_at DESC
              LIMIT ?2",
         )?;
-        let rows = stmt.query_map(params![conversation_id, limit], |row| {
-            Ok((
-                row.get::<_, String>(0)?,
-                row.get::<_, String>(1)?,
-                row.get::<_, String>(2)?,
-                row.get::<_, Option<String>>(3)?,
-                row.get::<_, Option<String>>(4)?,
-                row.get::<_, i64>(5)? as usize,
-                row.get::<_, Option<String>>(6)?,
-                row.get::<_, Option<String>>(7)?,
-                row.get::<_, String>(8)?,
-                row.get::<_, String>(9)?,
-            ))
-        })?;
-        for row in rows {
-            let (
-                convo,
-                started_at,
-                phase_state,
-                decision,
-                status,
-                tool_count,
-                reason,
-                error,
-                tool_preview,
-                agent_content,
-            ) = row?;
-            let mut line = format!(
-                "- [{}] {}phase={} decision={} status={} tools={}{}",
-                started_at,
-                if conversation_id.is_some() {
-                    String::new()
-                } else {
-                    format!("conv={} ", convo)
-                },
-                phase_state,
-                decision.unwrap_or_else(|| "-".to_string()),
-                status.unwrap_or_else(|| "-".to_string()),
-                tool_count,
-                if tool_preview.trim().is_empty() {
-                    String::new()
-                } else {
-                    format!(" ({})", tool_preview)
-                }
-            );
-
-            let response_preview = summarize_chat_message_for_context(&agent_content);
-            if let Some(error_text) = error
-                .as_deref()
-                .map(str::trim)
-                .filter(|value| !value.is_empty())
-            {
-                line.push_str(&format!(
-                    " error=\"{}\"",
-                    truncate_for_db_digest(error_text, 140)
-                ));
-            } else if !response_preview.is_empty() {
-                line.push_str(&format!(
-                    " reply=\"{}\"",
-                    truncate_for_db_digest(&response_preview, 160)
-                ));
-            }
-
-            if let Some(reason_text) = reason
-                .as_deref()
-                .map(str::trim)
-                .filter(|value| !value.is_empty())
-            {
-                line.push_str(&format!(
-                    " reason=\"{}\"",
-                    truncate_for_db_digest(reason_text, 120)
-                ));
-            }
-
-            lines.push(line);
-        }
-
-        if lines.is_empty() {
-            return Ok("None".to_string());
-        }
-
-        let mut output = String::new();
-        for line in lines {
-            if !output.is_empty() {
-                output.push('\n');
-            }
-            output.push_str(&line);
-            if output.chars().count() >= max_chars.max(120) {
-                output = truncate_for_db_digest(&output, max_chars.max(120));
-                break;
-            }
-        }
-        Ok(output)
+        let rows = stmt
+            .query_map(params![conversation_id, limit as i64], |row| {
+                Ok(ActionDigestRow {
+                    conversation_id: row.get(0)?,
+                    started_at: row.get(1)?,
+                    phase_state: row.get(2)?,
+                    decision: row.get(3)?,
+                    status: row.get(4)?,
+                    tool_count: row.get::<_, i64>(5)? as usize,
+                    reason: row.get(6)?,
+                    error: row.get(7)?,
+                    tool_preview: row.get(8)?,
+                    agent_content: row.get(9)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(rows)
     }
 
     /// Get unprocessed messages from the operator
@@ -1078,7 +1623,7 @@ impl AgentDatabase {
         let mut stmt = conn.prepare(
             "SELECT id, conversation_id, role, content, created_at, processed, turn_id FROM chat_messages
              WHERE role IN ('operator', 'scheduled') AND processed = 0
-             ORDER BY created_at ASC",
+             ORDER BY created_at ASC, rowid ASC",
         )?;
 
         let messages = stmt
@@ -1116,7 +1661,7 @@ impl AgentDatabase {
         let conn = self.lock_conn()?;
         let mut stmt = conn.prepare(
             "SELECT id, conversation_id, role, content, created_at, processed, turn_id FROM chat_messages
-             ORDER BY created_at DESC
+             ORDER BY created_at DESC, rowid DESC
              LIMIT ?1",
         )?;
 
@@ -1154,7 +1699,7 @@ impl AgentDatabase {
         let mut stmt = conn.prepare(
             "SELECT id, conversation_id, role, content, created_at, processed, turn_id FROM chat_messages
              WHERE conversation_id = ?1
-             ORDER BY created_at DESC
+             ORDER BY created_at DESC, rowid DESC
              LIMIT ?2",
         )?;
 
@@ -1201,7 +1746,7 @@ impl AgentDatabase {
         let mut stmt = conn.prepare(
             "SELECT id, conversation_id, role, content, created_at, processed, turn_id FROM chat_messages
              WHERE conversation_id = ?1
-             ORDER BY created_at DESC
+             ORDER BY created_at DESC, rowid DESC
              LIMIT ?2 OFFSET ?3",
         )?;
 
@@ -1293,6 +1838,43 @@ impl AgentDatabase {
         }))
     }
 
+    /// Summary snapshots refreshed with `range.start <= updated_at < range.end`,
+    /// oldest first.
+    pub fn get_chat_conversation_summaries_between(
+        &self,
+        range: std::ops::Range<DateTime<Utc>>,
+    ) -> Result<Vec<ChatConversationSummary>> {
+        let conn = self.lock_conn()?;
+        let mut stmt = conn.prepare(
+            "SELECT conversation_id, summary_text, summarized_message_count, updated_at
+             FROM chat_conversation_summaries
+             WHERE updated_at >= ?1 AND updated_at < ?2
+             ORDER BY updated_at ASC",
+        )?;
+        let summaries = stmt
+            .query_map(
+                params![range.start.to_rfc3339(), range.end.to_rfc3339()],
+                |row| {
+                    let updated_at_raw: String = row.get(3)?;
+                    let updated_at = updated_at_raw.parse().map_err(|e| {
+                        rusqlite::Error::FromSqlConversionFailure(
+                            3,
+                            rusqlite::types::Type::Text,
+                            Box::new(e),
+                        )
+                    })?;
+                    Ok(ChatConversationSummary {
+                        conversation_id: row.get(0)?,
+                        summary_text: row.get(1)?,
+                        summarized_message_count: row.get::<_, i64>(2)?.max(0) as usize,
+                        updated_at,
+                    })
+                },
+            )?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(summaries)
+    }
+
     /// Get chat history as formatted string for context
     pub fn get_chat_context(&self, limit: usize) -> Result<String> {
         let messages = self.get_chat_history(limit)?;
@@ -0,0 +1,156 @@
+use anyhow::Result;
+use chrono::Utc;
+use rusqlite::{params, Connection};
+
+use super::chat::ChatMessage;
+use super::helpers::{decode_embedding, encode_embedding, summarize_chat_message_for_context};
+use super::AgentDatabase;
+use crate::memory::candidate_backends::cosine_similarity;
+
+/// Characters of a message sent to the embeddings endpoint.
+const CHAT_EMBEDDING_TEXT_MAX_CHARS: usize = 2_000;
+/// Newest embedded messages of a conversation scored per retrieval, so a
+/// recall never decodes and compares an unbounded history.
+const CHAT_RECALL_SCAN_LIMIT: usize = 2_000;
+
+pub(super) fn create_chat_message_embeddings_table(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        r#"CREATE TABLE IF NOT EXISTS chat_message_embeddings (
+               message_id TEXT PRIMARY KEY,
+               conversation_id TEXT NOT NULL,
+               model TEXT NOT NULL,
+               embedding BLOB NOT NULL,
+               embedded_at TEXT NOT NULL
+           );
+           CREATE INDEX IF NOT EXISTS idx_chat_message_embeddings_conversation
+           ON chat_message_embeddings(conversation_id);"#,
+    )?;
+    Ok(())
+}
+
+/// Text embedded for a chat message: tool, thinking, and control blocks
+/// stripped, capped at `CHAT_EMBEDDING_TEXT_MAX_CHARS`. Empty when nothing
+/// readable is left.
+pub fn chat_message_embedding_text(message: &ChatMessage) -> String {
+    summarize_chat_message_for_context(&message.content)
+        .chars()
+        .take(CHAT_EMBEDDING_TEXT_MAX_CHARS)
+        .collect()
+}
+
+fn parse_chat_message_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<ChatMessage> {
+    Ok(ChatMessage {
+        id: row.get(0)?,
+        conversation_id: row.get(1)?,
+        role: row.get(2)?,
+        content: row.get(3)?,
+        created_at: row.get::<_, String>(4)?.parse().map_err(|e| {
+            rusqlite::Error::FromSqlConversionFailure(4, rusqlite::types::Type::Text, Box::new(e))
+        })?,
+        processed: row.get::<_, i64>(5)? != 0,
+        turn_id: row.get(6)?,
+    })
+}
+
+impl AgentDatabase {
+    /// Newest messages, across all conversations, with no embedding from `model`.
+    pub fn list_chat_messages_missing_embedding(
+        &self,
+        model: &str,
+        limit: usize,
+    ) -> Result<Vec<ChatMessage>> {
+        let conn = self.lock_conn()?;
+        let mut stmt = conn.prepare(
+            "SELECT m.id, m.conversation_id, m.role, m.content, m.created_at, m.processed, m.turn_id
+             FROM chat_messages m
+             LEFT JOIN chat_message_embeddings e ON e.message_id = m.id AND e.model = ?1
+             WHERE e.message_id IS NULL
+             ORDER BY m.created_at DESC, m.id DESC
+             LIMIT ?2",
+        )?;
+        let messages = stmt
+            .query_map(params![model, limit], parse_chat_message_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(messages)
+    }
+
+    /// Store (or replace) the embedding for one message. An empty vector marks
+    /// a message with no embeddable text so the backfill does not revisit it.
+    pub fn store_chat_message_embedding(
+        &self,
+        message_id: &str,
+        conversation_id: &str,
+        model: &str,
+        embedding: &[f32],
+    ) -> Result<()> {
+        let conn = self.lock_conn()?;
+        conn.execute(
+            "INSERT OR REPLACE INTO chat_message_embeddings
+                 (message_id, conversation_id, model, embedding, embedded_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                message_id,
+                conversation_id,
+                model,
+                encode_embedding(embedding),
+                Utc::now().to_rfc3339(),
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Up to `k` messages of one conversation ranked by cosine similarity to
+    /// `query_embedding`, best first. Only the newest `CHAT_RECALL_SCAN_LIMIT`
+    /// embeddings from `model` with the query's dimension and a positive
+    /// similarity are considered. Blocking; async callers should run it on a
+    /// blocking thread.
+    pub fn retrieve_relevant_messages(
+        &self,
+        conversation_id: &str,
+        model: &str,
+        query_embedding: &[f32],
+        k: usize,
+    ) -> Result<Vec<ChatMessage>> {
+        if k == 0 || query_embedding.is_empty() {
+            return Ok(Vec::new());
+        }
+        let conn = self.lock_conn()?;
+        let mut stmt = conn.prepare(
+            "SELECT m.id, m.conversation_id, m.role, m.content, m.created_at, m.processed, m.turn_id,
+                    e.embedding
+             FROM chat_message_embeddings e
+             JOIN chat_messages m ON m.id = e.message_id
+             WHERE e.conversation_id = ?1 AND e.model = ?2
+             ORDER BY m.created_at DESC, m.rowid DESC
+             LIMIT ?3",
+        )?;
+        let mut scored = stmt
+            .query_map(
+                params![conversation_id, model, CHAT_RECALL_SCAN_LIMIT],
+                |row| {
+                    Ok((
+                        parse_chat_message_row(row)?,
+                        decode_embedding(row.get_ref(7)?),
+                    ))
+                },
+            )?
+            .filter_map(|row| match row {
+                Ok((message, embedding)) => {
+                    let embedding = embedding?;
+                    if embedding.len() != query_embedding.len() {
+                        return None;
+                    }
+                    let similarity = cosine_similarity(query_embedding, &embedding);
+                    (similarity > 0.0).then_some(Ok((similarity, message)))
+                }
+                Err(error) => Some(Err(error)),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        scored.sort_by(|a, b| b.0.total_cmp(&a.0).then_with(|| a.1.id.cmp(&b.1.id)));
+        Ok(scored
+            .into_iter()
+            .take(k)
+            .map(|(_, message)| message)
+            .collect())
+    }
+}
@@ -1,11 +1,58 @@
 use anyhow::Result;
-use chrono::Utc;
-use rusqlite::params;
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Connection};
 
-use crate::agent::concerns::{Concern, ConcernContext, ConcernType, Salience};
+use crate::agent::concerns::{
+    Concern, ConcernContext, ConcernEdge, ConcernRelation, ConcernType, Salience,
+};
 
 use super::AgentDatabase;
 
+pub(super) fn add_concern_pinned_column(conn: &Connection) -> Result<()> {
+    if !AgentDatabase::table_has_column(conn, "concerns", "pinned")? {
+        conn.execute(
+            "ALTER TABLE concerns ADD COLUMN pinned INTEGER NOT NULL DEFAULT 0",
+            [],
+        )?;
+    }
+    Ok(())
+}
+
+pub(super) fn create_concern_edges_table(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        r#"CREATE TABLE IF NOT EXISTS concern_edges (
+               from_id TEXT NOT NULL,
+               to_id TEXT NOT NULL,
+               relation TEXT NOT NULL,
+               created_at TEXT NOT NULL,
+               PRIMARY KEY (from_id, to_id, relation)
+           );
+           CREATE INDEX IF NOT EXISTS idx_concern_edges_to ON concern_edges(to_id);"#,
+    )?;
+    Ok(())
+}
+
+pub(super) fn create_concern_notifications_table(conn: &Connection) -> Result<()> {
+    conn.execute(
+        r#"CREATE TABLE IF NOT EXISTS concern_notifications (
+               concern_id TEXT PRIMARY KEY,
+               last_notified_at TEXT NOT NULL
+           )"#,
+        [],
+    )?;
+    Ok(())
+}
+
+pub(super) fn add_concern_notification_trigger_column(conn: &Connection) -> Result<()> {
+    if !AgentDatabase::table_has_column(conn, "concern_notifications", "notified_trigger_time")? {
+        conn.execute(
+            "ALTER TABLE concern_notifications ADD COLUMN notified_trigger_time TEXT",
+            [],
+        )?;
+    }
+    Ok(())
+}
+
 impl AgentDatabase {
     pub fn save_concern(&self, concern: &Concern) -> Result<()> {
         let concern_type_json = serde_json::to_string(&concern.concern_type)
@@ -21,8 +68,8 @@ impl AgentDatabase {
         conn.execute(
             "INSERT OR REPLACE INTO concerns
              (id, created_at, last_touched, summary, concern_type, salience, my_thoughts,
-              related_memory_keys, context, updated_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+              related_memory_keys, context, updated_at, pinned)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
             params![
                 concern.id,
                 concern.created_at.to_rfc3339(),
@@ -34,6 +81,7 @@ impl AgentDatabase {
                 related_keys_json,
                 context_json,
                 Utc::now().to_rfc3339(),
+                concern.pinned,
             ],
         )?;
         Ok(())
@@ -43,7 +91,7 @@ impl AgentDatabase {
         let conn = self.lock_conn()?;
         let result = conn.query_row(
             "SELECT id, created_at, last_touched, summary, concern_type, salience, my_thoughts,
-                    related_memory_keys, context
+                    related_memory_keys, context, pinned
              FROM concerns
              WHERE id = ?1",
             [id],
@@ -110,6 +158,7 @@ impl AgentDatabase {
                     my_thoughts: row.get::<_, Option<String>>(6)?.unwrap_or_default(),
                     related_memory_keys,
                     context,
+                    pinned: row.get(9)?,
                 })
             },
         );
@@ -164,6 +213,91 @@ impl AgentDatabase {
         Ok(concerns)
     }
 
+    pub fn delete_concern(&self, id: &str) -> Result<bool> {
+        let mut conn = self.lock_conn()?;
+        let tx = conn.transaction()?;
+        tx.execute(
+            "DELETE FROM concern_edges WHERE from_id = ?1 OR to_id = ?1",
+            [id],
+        )?;
+        tx.execute(
+            "DELETE FROM concern_notifications WHERE concern_id = ?1",
+            [id],
+        )?;
+        let deleted = tx.execute("DELETE FROM concerns WHERE id = ?1", [id])?;
+        tx.commit()?;
+        Ok(deleted > 0)
+    }
+
+    pub fn save_concern_edge(
+        &self,
+        from_id: &str,
+        to_id: &str,
+        relation: ConcernRelation,
+    ) -> Result<()> {
+        let conn = self.lock_conn()?;
+        conn.execute(
+            "INSERT OR IGNORE INTO concern_edges (from_id, to_id, relation, created_at)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![
+                from_id,
+                to_id,
+                relation.as_db_str(),
+                Utc::now().to_rfc3339()
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn list_concern_edges(&self) -> Result<Vec<ConcernEdge>> {
+        let conn = self.lock_conn()?;
+        let mut stmt = conn.prepare(
+            "SELECT from_id, to_id, relation, created_at
+             FROM concern_edges
+             ORDER BY created_at ASC",
+        )?;
+        let edges = stmt
+            .query_map([], |row| {
+                let relation_raw: String = row.get(2)?;
+                let created_raw: String = row.get(3)?;
+                Ok(ConcernEdge {
+                    from_id: row.get(0)?,
+                    to_id: row.get(1)?,
+                    relation: ConcernRelation::from_db(&relation_raw),
+                    created_at: created_raw.parse().map_err(|e| {
+                        rusqlite::Error::FromSqlConversionFailure(
+                            3,
+                            rusqlite::types::Type::Text,
+                            Box::new(e),
+                        )
+                    })?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(edges)
+    }
+
+    /// Moves every edge touching `old_id` onto `new_id`, dropping edges that
+    /// would duplicate an existing one or become self-loops.
+    pub fn reassign_concern_edges(&self, old_id: &str, new_id: &str) -> Result<()> {
+        let mut conn = self.lock_conn()?;
+        let tx = conn.transaction()?;
+        tx.execute(
+            "UPDATE OR IGNORE concern_edges SET from_id = ?2 WHERE from_id = ?1",
+            params![old_id, new_id],
+        )?;
+        tx.execute(
+            "UPDATE OR IGNORE concern_edges SET to_id = ?2 WHERE to_id = ?1",
+            params![old_id, new_id],
+        )?;
+        tx.execute(
+            "DELETE FROM concern_edges WHERE from_id = ?1 OR to_id = ?1 OR from_id = to_id",
+            [old_id],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
     pub fn update_concern_salience(&self, id: &str, salience: Salience) -> Result<()> {
         let conn = self.lock_conn()?;
         conn.execute(
@@ -183,4 +317,51 @@ impl AgentDatabase {
         concern.context.last_update_reason = reason.to_string();
         self.save_concern(&concern)
     }
+
+    /// When the operator was last sent a chat notification for this concern.
+    pub fn get_concern_last_notified(&self, id: &str) -> Result<Option<DateTime<Utc>>> {
+        let conn = self.lock_conn()?;
+        let result = conn.query_row(
+            "SELECT last_notified_at FROM concern_notifications WHERE concern_id = ?1",
+            [id],
+            |row| row.get::<_, String>(0),
+        );
+        match result {
+            Ok(raw) => Ok(Some(raw.parse()?)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// The reminder `trigger_time` the last notification was sent for; a
+    /// reminder is not re-sent for the same due time.
+    pub fn get_concern_notified_trigger_time(&self, id: &str) -> Result<Option<DateTime<Utc>>> {
+        let conn = self.lock_conn()?;
+        let result = conn.query_row(
+            "SELECT notified_trigger_time FROM concern_notifications WHERE concern_id = ?1",
+            [id],
+            |row| row.get::<_, Option<String>>(0),
+        );
+        match result {
+            Ok(Some(raw)) => Ok(Some(raw.parse()?)),
+            Ok(None) | Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn mark_concern_notified(
+        &self,
+        id: &str,
+        at: DateTime<Utc>,
+        trigger_time: Option<DateTime<Utc>>,
+    ) -> Result<()> {
+        let conn = self.lock_conn()?;
+        conn.execute(
+            "INSERT OR REPLACE INTO concern_notifications
+                 (concern_id, last_notified_at, notified_trigger_time)
+             VALUES (?1, ?2, ?3)",
+            params![id, at.to_rfc3339(), trigger_time.map(|at| at.to_rfc3339())],
+        )?;
+        Ok(())
+    }
 }
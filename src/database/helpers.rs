@@ -387,3 +387,26 @@ pub(super) fn outcome_to_db(outcome: &PromotionOutcome) -> &'static str {
         PromotionOutcome::Hold => "hold",
     }
 }
+
+/// Packs an embedding as little-endian `f32`s for a BLOB column.
+pub(super) fn encode_embedding(embedding: &[f32]) -> Vec<u8> {
+    embedding
+        .iter()
+        .flat_map(|value| value.to_le_bytes())
+        .collect()
+}
+
+/// Reads an embedding column: a little-endian `f32` BLOB, or the JSON array
+/// text rows were stored as before. `None` when neither decodes.
+pub(super) fn decode_embedding(value: rusqlite::types::ValueRef<'_>) -> Option<Vec<f32>> {
+    match value {
+        rusqlite::types::ValueRef::Blob(bytes) if bytes.len() % 4 == 0 => Some(
+            bytes
+                .chunks_exact(4)
+                .map(|chunk| f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+                .collect(),
+        ),
+        rusqlite::types::ValueRef::Text(text) => serde_json::from_slice(text).ok(),
+        _ => None,
+    }
+}
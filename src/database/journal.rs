@@ -1,7 +1,12 @@
-use anyhow::Result;
-use rusqlite::params;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Connection, Row};
+use std::ops::Range;
+use std::path::Path;
 
-use crate::agent::journal::{JournalContext, JournalEntry, JournalEntryType, JournalMood};
+use crate::agent::journal::{
+    render_journal_markdown, JournalContext, JournalEntry, JournalEntryType, JournalMood,
+};
 
 use super::AgentDatabase;
 
@@ -43,52 +48,69 @@ impl AgentDatabase {
         )?;
 
         let entries = stmt
-            .query_map([limit.max(1)], |row| {
-                let timestamp_raw: String = row.get(1)?;
-                let related_raw: Option<String> = row.get(7)?;
-                let related_concerns = related_raw
-                    .as_deref()
-                    .map(serde_json::from_str::<Vec<String>>)
-                    .transpose()
-                    .map_err(|e| {
-                        rusqlite::Error::FromSqlConversionFailure(
-                            7,
-                            rusqlite::types::Type::Text,
-                            Box::new(e),
-                        )
-                    })?
-                    .unwrap_or_default();
-                let mood_valence: Option<f32> = row.get(8)?;
-                let mood_arousal: Option<f32> = row.get(9)?;
-
-                Ok(JournalEntry {
-                    id: row.get(0)?,
-                    timestamp: timestamp_raw.parse().map_err(|e| {
-                        rusqlite::Error::FromSqlConversionFailure(
-                            1,
-                            rusqlite::types::Type::Text,
-                            Box::new(e),
-                        )
-                    })?,
-                    entry_type: JournalEntryType::from_db(&row.get::<_, String>(2)?),
-                    content: row.get(3)?,
-                    context: JournalContext {
-                        trigger: row.get::<_, Option<String>>(4)?.unwrap_or_default(),
-                        user_state_at_time: row.get::<_, Option<String>>(5)?.unwrap_or_default(),
-                        time_of_day: row.get::<_, Option<String>>(6)?.unwrap_or_default(),
-                    },
-                    related_concerns,
-                    mood_at_time: match (mood_valence, mood_arousal) {
-                        (Some(valence), Some(arousal)) => Some(JournalMood { valence, arousal }),
-                        _ => None,
-                    },
-                })
-            })?
+            .query_map([limit.max(1)], journal_entry_from_row)?
             .collect::<Result<Vec<_>, _>>()?;
 
         Ok(entries)
     }
 
+    /// Entries with `range.start <= timestamp < range.end`, oldest first.
+    pub fn get_journal_between(&self, range: Range<DateTime<Utc>>) -> Result<Vec<JournalEntry>> {
+        let conn = self.lock_conn()?;
+        let mut stmt = conn.prepare(
+            "SELECT id, timestamp, entry_type, content, trigger, user_state_at_time, time_of_day,
+                    related_concerns, mood_valence, mood_arousal
+             FROM journal_entries
+             WHERE timestamp >= ?1 AND timestamp < ?2
+             ORDER BY timestamp ASC",
+        )?;
+
+        let entries = stmt
+            .query_map(
+                params![range.start.to_rfc3339(), range.end.to_rfc3339()],
+                journal_entry_from_row,
+            )?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(entries)
+    }
+
+    /// Number of entries with `range.start <= timestamp < range.end`.
+    pub fn count_journal_between(&self, range: Range<DateTime<Utc>>) -> Result<u32> {
+        let conn = self.lock_conn()?;
+        let count: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM journal_entries WHERE timestamp >= ?1 AND timestamp < ?2",
+            params![range.start.to_rfc3339(), range.end.to_rfc3339()],
+            |row| row.get(0),
+        )?;
+        Ok(count.max(0) as u32)
+    }
+
+    /// Writes the entries in `range` to `path` as Markdown (see
+    /// `render_journal_markdown`), replacing any existing file. Returns the
+    /// number of entries written; an empty range writes nothing.
+    pub fn export_journal_markdown(
+        &self,
+        path: &Path,
+        range: Range<DateTime<Utc>>,
+    ) -> Result<usize> {
+        let entries = self.get_journal_between(range)?;
+        if entries.is_empty() {
+            return Ok(0);
+        }
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).with_context(|| {
+                format!(
+                    "Failed to create journal export directory '{}'",
+                    parent.display()
+                )
+            })?;
+        }
+        std::fs::write(path, render_journal_markdown(&entries))
+            .with_context(|| format!("Failed to write journal export '{}'", path.display()))?;
+        Ok(entries.len())
+    }
+
     pub fn get_journal_for_context(&self, max_tokens: usize) -> Result<String> {
         if max_tokens == 0 {
             return Ok(String::new());
@@ -123,67 +145,112 @@ impl AgentDatabase {
         }
     }
 
+    /// Entries matching any word of `query`, best BM25 rank first. An empty
+    /// query returns the most recent entries.
     pub fn search_journal(&self, query: &str, limit: usize) -> Result<Vec<JournalEntry>> {
         let trimmed = query.trim();
         if trimmed.is_empty() {
             return self.get_recent_journal(limit.max(1));
         }
 
-        let like_pattern = format!("%{}%", trimmed.to_ascii_lowercase());
+        let Some(fts_query) = fts_match_query(trimmed) else {
+            return Ok(Vec::new());
+        };
         let conn = self.lock_conn()?;
         let mut stmt = conn.prepare(
-            "SELECT id, timestamp, entry_type, content, trigger, user_state_at_time, time_of_day,
-                    related_concerns, mood_valence, mood_arousal
-             FROM journal_entries
-             WHERE LOWER(content) LIKE ?1 OR LOWER(COALESCE(trigger, '')) LIKE ?1
-             ORDER BY timestamp DESC
+            "SELECT j.id, j.timestamp, j.entry_type, j.content, j.trigger, j.user_state_at_time,
+                    j.time_of_day, j.related_concerns, j.mood_valence, j.mood_arousal
+             FROM journal_entries_fts f
+             JOIN journal_entries j ON j.id = f.entry_id
+             WHERE journal_entries_fts MATCH ?1
+             ORDER BY bm25(journal_entries_fts), j.timestamp DESC
              LIMIT ?2",
         )?;
 
         let entries = stmt
-            .query_map(params![like_pattern, limit.max(1)], |row| {
-                let timestamp_raw: String = row.get(1)?;
-                let related_raw: Option<String> = row.get(7)?;
-                let related_concerns = related_raw
-                    .as_deref()
-                    .map(serde_json::from_str::<Vec<String>>)
-                    .transpose()
-                    .map_err(|e| {
-                        rusqlite::Error::FromSqlConversionFailure(
-                            7,
-                            rusqlite::types::Type::Text,
-                            Box::new(e),
-                        )
-                    })?
-                    .unwrap_or_default();
-                let mood_valence: Option<f32> = row.get(8)?;
-                let mood_arousal: Option<f32> = row.get(9)?;
-
-                Ok(JournalEntry {
-                    id: row.get(0)?,
-                    timestamp: timestamp_raw.parse().map_err(|e| {
-                        rusqlite::Error::FromSqlConversionFailure(
-                            1,
-                            rusqlite::types::Type::Text,
-                            Box::new(e),
-                        )
-                    })?,
-                    entry_type: JournalEntryType::from_db(&row.get::<_, String>(2)?),
-                    content: row.get(3)?,
-                    context: JournalContext {
-                        trigger: row.get::<_, Option<String>>(4)?.unwrap_or_default(),
-                        user_state_at_time: row.get::<_, Option<String>>(5)?.unwrap_or_default(),
-                        time_of_day: row.get::<_, Option<String>>(6)?.unwrap_or_default(),
-                    },
-                    related_concerns,
-                    mood_at_time: match (mood_valence, mood_arousal) {
-                        (Some(valence), Some(arousal)) => Some(JournalMood { valence, arousal }),
-                        _ => None,
-                    },
-                })
-            })?
+            .query_map(params![fts_query, limit.max(1)], journal_entry_from_row)?
             .collect::<Result<Vec<_>, _>>()?;
 
         Ok(entries)
     }
 }
+
+/// FTS5 index over journal `content` and `trigger`, kept current by triggers.
+///
+/// The index stores its own copy keyed by `entry_id` instead of using external
+/// content: `add_journal_entry` uses `INSERT OR REPLACE`, whose implicit delete
+/// does not fire delete triggers, so the insert trigger clears any stale row.
+pub(super) fn create_journal_fts(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        r#"CREATE VIRTUAL TABLE IF NOT EXISTS journal_entries_fts USING fts5(
+               entry_id UNINDEXED,
+               content,
+               trigger_text
+           );
+           CREATE TRIGGER IF NOT EXISTS journal_entries_fts_insert
+           AFTER INSERT ON journal_entries BEGIN
+               DELETE FROM journal_entries_fts WHERE entry_id = new.id;
+               INSERT INTO journal_entries_fts (entry_id, content, trigger_text)
+               VALUES (new.id, new.content, COALESCE(new.trigger, ''));
+           END;
+           CREATE TRIGGER IF NOT EXISTS journal_entries_fts_update
+           AFTER UPDATE ON journal_entries BEGIN
+               DELETE FROM journal_entries_fts WHERE entry_id = old.id;
+               INSERT INTO journal_entries_fts (entry_id, content, trigger_text)
+               VALUES (new.id, new.content, COALESCE(new.trigger, ''));
+           END;
+           CREATE TRIGGER IF NOT EXISTS journal_entries_fts_delete
+           AFTER DELETE ON journal_entries BEGIN
+               DELETE FROM journal_entries_fts WHERE entry_id = old.id;
+           END;
+           DELETE FROM journal_entries_fts;
+           INSERT INTO journal_entries_fts (entry_id, content, trigger_text)
+           SELECT id, content, COALESCE(trigger, '') FROM journal_entries;"#,
+    )?;
+    Ok(())
+}
+
+/// Quote each word so user text cannot inject FTS5 operators, and OR them so
+/// natural-language questions still match; BM25 ranks the fuller matches first.
+fn fts_match_query(query: &str) -> Option<String> {
+    let terms = query
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|term| !term.is_empty())
+        .map(|term| format!("\"{}\"", term))
+        .collect::<Vec<_>>();
+    (!terms.is_empty()).then(|| terms.join(" OR "))
+}
+
+fn journal_entry_from_row(row: &Row<'_>) -> rusqlite::Result<JournalEntry> {
+    let timestamp_raw: String = row.get(1)?;
+    let related_raw: Option<String> = row.get(7)?;
+    let related_concerns = related_raw
+        .as_deref()
+        .map(serde_json::from_str::<Vec<String>>)
+        .transpose()
+        .map_err(|e| {
+            rusqlite::Error::FromSqlConversionFailure(7, rusqlite::types::Type::Text, Box::new(e))
+        })?
+        .unwrap_or_default();
+    let mood_valence: Option<f32> = row.get(8)?;
+    let mood_arousal: Option<f32> = row.get(9)?;
+
+    Ok(JournalEntry {
+        id: row.get(0)?,
+        timestamp: timestamp_raw.parse().map_err(|e| {
+            rusqlite::Error::FromSqlConversionFailure(1, rusqlite::types::Type::Text, Box::new(e))
+        })?,
+        entry_type: JournalEntryType::from_db(&row.get::<_, String>(2)?),
+        content: row.get(3)?,
+        context: JournalContext {
+            trigger: row.get::<_, Option<String>>(4)?.unwrap_or_default(),
+            user_state_at_time: row.get::<_, Option<String>>(5)?.unwrap_or_default(),
+            time_of_day: row.get::<_, Option<String>>(6)?.unwrap_or_default(),
+        },
+        related_concerns,
+        mood_at_time: match (mood_valence, mood_arousal) {
+            (Some(valence), Some(arousal)) => Some(JournalMood { valence, arousal }),
+            _ => None,
+        },
+    })
+}
@@ -0,0 +1,67 @@
+use anyhow::Result;
+use chrono::Utc;
+use rusqlite::{params, Connection};
+use std::collections::HashMap;
+
+use super::helpers::{decode_embedding, encode_embedding};
+use super::AgentDatabase;
+
+pub(super) fn create_journal_entry_embeddings_table(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        r#"CREATE TABLE IF NOT EXISTS journal_entry_embeddings (
+               entry_id TEXT PRIMARY KEY,
+               model TEXT NOT NULL,
+               embedding BLOB NOT NULL,
+               embedded_at TEXT NOT NULL
+           );"#,
+    )?;
+    Ok(())
+}
+
+impl AgentDatabase {
+    /// Stored embeddings from `model` for the given journal entries, keyed by
+    /// entry id. Entries without one (or embedded by another model) are absent.
+    pub fn journal_entry_embeddings(
+        &self,
+        entry_ids: &[String],
+        model: &str,
+    ) -> Result<HashMap<String, Vec<f32>>> {
+        let conn = self.lock_conn()?;
+        let mut stmt = conn.prepare(
+            "SELECT embedding FROM journal_entry_embeddings
+             WHERE entry_id = ?1 AND model = ?2",
+        )?;
+        let mut embeddings = HashMap::new();
+        for entry_id in entry_ids {
+            let mut rows = stmt.query(params![entry_id, model])?;
+            if let Some(row) = rows.next()? {
+                if let Some(embedding) = decode_embedding(row.get_ref(0)?) {
+                    embeddings.insert(entry_id.clone(), embedding);
+                }
+            }
+        }
+        Ok(embeddings)
+    }
+
+    /// Store (or replace) the embedding for one journal entry.
+    pub fn store_journal_entry_embedding(
+        &self,
+        entry_id: &str,
+        model: &str,
+        embedding: &[f32],
+    ) -> Result<()> {
+        let conn = self.lock_conn()?;
+        conn.execute(
+            "INSERT OR REPLACE INTO journal_entry_embeddings
+                 (entry_id, model, embedding, embedded_at)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![
+                entry_id,
+                model,
+                encode_embedding(embedding),
+                Utc::now().to_rfc3339(),
+            ],
+        )?;
+        Ok(())
+    }
+}
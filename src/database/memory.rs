@@ -1,5 +1,8 @@
+use std::collections::{HashMap, HashSet};
+
 use anyhow::{Context, Result};
-use rusqlite::params;
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Connection};
 
 use crate::memory::archive::{
     evaluate_promotion_policy, MemoryDesignArchiveEntry, MemoryEvalRunRecord,
@@ -17,6 +20,64 @@ use super::helpers::{
 };
 use super::AgentDatabase;
 
+pub(super) fn add_working_memory_expiry_column(conn: &Connection) -> Result<()> {
+    if !AgentDatabase::table_has_column(conn, "working_memory", "expires_at")? {
+        conn.execute("ALTER TABLE working_memory ADD COLUMN expires_at TEXT", [])?;
+    }
+    Ok(())
+}
+
+pub(super) fn create_working_memory_tags_table(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        r#"CREATE TABLE IF NOT EXISTS working_memory_tags (
+               key TEXT NOT NULL,
+               tag TEXT NOT NULL,
+               PRIMARY KEY (key, tag)
+           );
+           CREATE INDEX IF NOT EXISTS idx_working_memory_tags_tag
+           ON working_memory_tags(tag);"#,
+    )?;
+    Ok(())
+}
+
+fn daily_activity_log_key(date: chrono::NaiveDate) -> String {
+    format!("activity-log-{}", date.format("%Y-%m-%d"))
+}
+
+/// Trims, lowercases, and dedups tags, dropping empty ones.
+pub fn normalize_memory_tags(tags: &[String]) -> Vec<String> {
+    let mut normalized: Vec<String> = tags
+        .iter()
+        .map(|tag| tag.trim().to_lowercase())
+        .filter(|tag| !tag.is_empty())
+        .collect();
+    normalized.sort();
+    normalized.dedup();
+    normalized
+}
+
+fn load_working_memory_tags(conn: &Connection) -> Result<HashMap<String, Vec<String>>> {
+    let mut stmt = conn.prepare("SELECT key, tag FROM working_memory_tags ORDER BY key, tag")?;
+    let mut tags: HashMap<String, Vec<String>> = HashMap::new();
+    for row in stmt.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get(1)?)))? {
+        let (key, tag) = row?;
+        tags.entry(key).or_default().push(tag);
+    }
+    Ok(tags)
+}
+
+/// Keys past their `expires_at`. They stay hidden until the dream-cycle
+/// sweep deletes them.
+fn expired_working_memory_keys(conn: &Connection, now: DateTime<Utc>) -> Result<HashSet<String>> {
+    let mut stmt = conn.prepare(
+        "SELECT key FROM working_memory WHERE expires_at IS NOT NULL AND expires_at <= ?1",
+    )?;
+    let keys = stmt
+        .query_map([now.to_rfc3339()], |row| row.get(0))?
+        .collect::<std::result::Result<HashSet<String>, _>>()?;
+    Ok(keys)
+}
+
 impl AgentDatabase {
     /// Get persisted memory design metadata.
     pub fn get_memory_design_version(&self) -> Result<Option<MemoryDesignVersion>> {
@@ -357,20 +418,111 @@ impl AgentDatabase {
 
     /// Set a working memory entry (creates or updates)
     pub fn set_working_memory(&self, key: &str, content: &str) -> Result<()> {
+        self.set_working_memory_with_expiry(key, content, None)
+    }
+
+    /// Set a working memory entry that is hidden once `expires_at` passes.
+    /// Every write replaces the previous expiry, so `None` makes the key
+    /// permanent again.
+    pub fn set_working_memory_with_expiry(
+        &self,
+        key: &str,
+        content: &str,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> Result<()> {
         let conn = self.lock_conn()?;
-        self.memory_backend.set_entry(&conn, key, content)
+        self.memory_backend.set_entry(&conn, key, content)?;
+        conn.execute(
+            "UPDATE working_memory SET expires_at = ?2 WHERE key = ?1",
+            params![key, expires_at.map(|at| at.to_rfc3339())],
+        )?;
+        Ok(())
     }
 
     /// Get a working memory entry by key
     pub fn get_working_memory(&self, key: &str) -> Result<Option<WorkingMemoryEntry>> {
         let conn = self.lock_conn()?;
+        if expired_working_memory_keys(&conn, Utc::now())?.contains(key) {
+            return Ok(None);
+        }
         self.memory_backend.get_entry(&conn, key)
     }
 
-    /// Get all working memory entries
+    /// Get all unexpired working memory entries
     pub fn get_all_working_memory(&self) -> Result<Vec<WorkingMemoryEntry>> {
         let conn = self.lock_conn()?;
-        self.memory_backend.list_entries(&conn)
+        let expired = expired_working_memory_keys(&conn, Utc::now())?;
+        let mut entries = self.memory_backend.list_entries(&conn)?;
+        entries.retain(|entry| !expired.contains(&entry.key));
+        Ok(entries)
+    }
+
+    /// Delete working memory entries whose expiry is at or before `now`,
+    /// returning how many were removed.
+    pub fn delete_expired_working_memory(&self, now: DateTime<Utc>) -> Result<usize> {
+        let mut conn = self.lock_conn()?;
+        let tx = conn.transaction()?;
+        let expired = expired_working_memory_keys(&tx, now)?;
+        for key in &expired {
+            self.memory_backend.delete_entry(&tx, key)?;
+            tx.execute("DELETE FROM working_memory_tags WHERE key = ?1", [key])?;
+            tx.execute(
+                "DELETE FROM working_memory_embeddings WHERE key = ?1",
+                [key],
+            )?;
+        }
+        tx.commit()?;
+        Ok(expired.len())
+    }
+
+    /// Replace the tags on a working memory key. Tags are normalized with
+    /// `normalize_memory_tags`; an empty list clears them.
+    pub fn set_working_memory_tags(&self, key: &str, tags: &[String]) -> Result<()> {
+        let mut conn = self.lock_conn()?;
+        let tx = conn.transaction()?;
+        tx.execute("DELETE FROM working_memory_tags WHERE key = ?1", [key])?;
+        for tag in normalize_memory_tags(tags) {
+            tx.execute(
+                "INSERT INTO working_memory_tags (key, tag) VALUES (?1, ?2)",
+                params![key, tag],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Tags for one working memory key, sorted.
+    pub fn get_working_memory_tags(&self, key: &str) -> Result<Vec<String>> {
+        let conn = self.lock_conn()?;
+        let mut stmt =
+            conn.prepare("SELECT tag FROM working_memory_tags WHERE key = ?1 ORDER BY tag")?;
+        let tags = stmt
+            .query_map([key], |row| row.get(0))?
+            .collect::<std::result::Result<Vec<String>, _>>()?;
+        Ok(tags)
+    }
+
+    /// Tags for every tagged working memory key.
+    pub fn get_working_memory_tag_map(&self) -> Result<HashMap<String, Vec<String>>> {
+        let conn = self.lock_conn()?;
+        load_working_memory_tags(&conn)
+    }
+
+    /// Unexpired entries carrying every tag in `tags`; an empty filter
+    /// returns all entries.
+    pub fn get_working_memory_with_tags(&self, tags: &[String]) -> Result<Vec<WorkingMemoryEntry>> {
+        let required = normalize_memory_tags(tags);
+        let mut entries = self.get_all_working_memory()?;
+        if required.is_empty() {
+            return Ok(entries);
+        }
+        let tag_map = self.get_working_memory_tag_map()?;
+        entries.retain(|entry| {
+            tag_map
+                .get(&entry.key)
+                .is_some_and(|entry_tags| required.iter().all(|tag| entry_tags.contains(tag)))
+        });
+        Ok(entries)
     }
 
     /// Search working memory entries using a simple relevance rank over key/content text.
@@ -378,10 +530,20 @@ impl AgentDatabase {
         &self,
         query: &str,
         limit: usize,
+    ) -> Result<Vec<WorkingMemoryEntry>> {
+        self.search_working_memory_with_tags(query, &[], limit)
+    }
+
+    /// `search_working_memory` restricted to entries carrying every tag in `tags`.
+    pub fn search_working_memory_with_tags(
+        &self,
+        query: &str,
+        tags: &[String],
+        limit: usize,
     ) -> Result<Vec<WorkingMemoryEntry>> {
         let max_results = limit.max(1);
         let trimmed_query = query.trim();
-        let mut entries = self.get_all_working_memory()?;
+        let mut entries = self.get_working_memory_with_tags(tags)?;
         if trimmed_query.is_empty() {
             entries.truncate(max_results);
             return Ok(entries);
@@ -440,7 +602,7 @@ impl AgentDatabase {
 
         use chrono::Utc;
         let now = Utc::now();
-        let day_key = format!("activity-log-{}", now.format("%Y-%m-%d"));
+        let day_key = daily_activity_log_key(now.date_naive());
         let line = format!("- [{} UTC] {}", now.format("%H:%M:%S"), trimmed);
         let existing = self
             .get_working_memory(&day_key)?
@@ -460,39 +622,80 @@ impl AgentDatabase {
         self.set_working_memory(&day_key, &merged)
     }
 
+    /// The accumulated activity log for one UTC day, if anything was logged.
+    pub fn get_daily_activity_log(&self, date: chrono::NaiveDate) -> Result<Option<String>> {
+        Ok(self
+            .get_working_memory(&daily_activity_log_key(date))?
+            .map(|item| item.content)
+            .filter(|content| !content.trim().is_empty()))
+    }
+
     /// Delete a working memory entry
     pub fn delete_working_memory(&self, key: &str) -> Result<()> {
         let conn = self.lock_conn()?;
-        self.memory_backend.delete_entry(&conn, key)
+        self.memory_backend.delete_entry(&conn, key)?;
+        conn.execute("DELETE FROM working_memory_tags WHERE key = ?1", [key])?;
+        conn.execute(
+            "DELETE FROM working_memory_embeddings WHERE key = ?1",
+            [key],
+        )?;
+        Ok(())
     }
 
     /// Get working memory as a formatted string for inclusion in context
     pub fn get_working_memory_context(&self) -> Result<String> {
+        self.get_working_memory_context_for_tags(&[])
+    }
+
+    /// `get_working_memory_context` scoped to `scope`: untagged entries and
+    /// entries sharing at least one scope tag are kept. An empty scope keeps
+    /// everything.
+    pub fn get_working_memory_context_for_tags(&self, scope: &[String]) -> Result<String> {
         let entries = self.get_all_working_memory()?;
         if entries.is_empty() {
             return Ok(String::new());
         }
+        let scope = normalize_memory_tags(scope);
+        let tag_map = if scope.is_empty() {
+            HashMap::new()
+        } else {
+            self.get_working_memory_tag_map()?
+        };
 
         let mut context = String::from("## Your Working Memory (Notes to Self)\n\n");
         for entry in entries {
             if is_session_handoff_entry(&entry.key) {
                 continue;
             }
+            if let Some(entry_tags) = tag_map.get(&entry.key) {
+                if !entry_tags.iter().any(|tag| scope.contains(tag)) {
+                    continue;
+                }
+            }
             context.push_str(&format!("### {}\n{}\n\n", entry.key, entry.content));
         }
         Ok(context)
     }
 
-    /// Get conversation-scoped working memory context to reduce cross-thread noise.
+    /// Get conversation-scoped working memory context to reduce cross-thread
+    /// noise. `scope` filters tagged entries as in
+    /// `get_working_memory_context_for_tags`.
     pub fn get_working_memory_context_for_conversation(
         &self,
         conversation_id: &str,
         max_chars: usize,
+        scope: &[String],
     ) -> Result<String> {
         let entries = self.get_all_working_memory()?;
         if entries.is_empty() {
             return Ok(String::new());
         }
+        let scope = normalize_memory_tags(scope);
+        let tag_map = if scope.is_empty() {
+            HashMap::new()
+        } else {
+            self.get_working_memory_tag_map()?
+        };
 
         let conversation_tag = short_conversation_tag(conversation_id);
         let mut context = String::from("## Your Working Memory (Notes to Self)\n\n");
@@ -505,6 +708,11 @@ impl AgentDatabase {
             if is_session_handoff_entry(&entry.key) {
                 continue;
             }
+            if let Some(entry_tags) = tag_map.get(&entry.key) {
+                if !entry_tags.iter().any(|tag| scope.contains(tag)) {
+                    continue;
+                }
+            }
 
             if entry.key.starts_with("activity-log-") {
                 if let Some(filtered) =
@@ -0,0 +1,77 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Connection};
+use std::collections::HashMap;
+
+use super::AgentDatabase;
+use crate::memory::WorkingMemoryEntry;
+
+pub(super) fn create_working_memory_embeddings_table(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        r#"CREATE TABLE IF NOT EXISTS working_memory_embeddings (
+               key TEXT PRIMARY KEY,
+               model TEXT NOT NULL,
+               entry_updated_at TEXT NOT NULL,
+               embedding TEXT NOT NULL,
+               embedded_at TEXT NOT NULL
+           );"#,
+    )?;
+    Ok(())
+}
+
+impl AgentDatabase {
+    /// Stored embeddings from `model` for the given entries, keyed by entry
+    /// key. An entry edited since it was embedded is absent, like one never
+    /// embedded.
+    pub fn working_memory_embeddings(
+        &self,
+        entries: &[WorkingMemoryEntry],
+        model: &str,
+    ) -> Result<HashMap<String, Vec<f32>>> {
+        let conn = self.lock_conn()?;
+        let mut stmt = conn.prepare(
+            "SELECT entry_updated_at, embedding FROM working_memory_embeddings
+             WHERE key = ?1 AND model = ?2",
+        )?;
+        let mut embeddings = HashMap::new();
+        for entry in entries {
+            let mut rows = stmt.query(params![entry.key, model])?;
+            if let Some(row) = rows.next()? {
+                let embedded_version: String = row.get(0)?;
+                let current = DateTime::parse_from_rfc3339(&embedded_version)
+                    .is_ok_and(|at| at.with_timezone(&Utc) == entry.updated_at);
+                if !current {
+                    continue;
+                }
+                let raw: String = row.get(1)?;
+                if let Ok(embedding) = serde_json::from_str::<Vec<f32>>(&raw) {
+                    embeddings.insert(entry.key.clone(), embedding);
+                }
+            }
+        }
+        Ok(embeddings)
+    }
+
+    /// Store (or replace) the embedding for one entry as of its `updated_at`.
+    pub fn store_working_memory_embedding(
+        &self,
+        entry: &WorkingMemoryEntry,
+        model: &str,
+        embedding: &[f32],
+    ) -> Result<()> {
+        let conn = self.lock_conn()?;
+        conn.execute(
+            "INSERT OR REPLACE INTO working_memory_embeddings
+                 (key, model, entry_updated_at, embedding, embedded_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                entry.key,
+                model,
+                entry.updated_at.to_rfc3339(),
+                serde_json::to_string(embedding)?,
+                Utc::now().to_rfc3339(),
+            ],
+        )?;
+        Ok(())
+    }
+}
@@ -0,0 +1,171 @@
+//! Ordered, versioned schema migrations applied by `AgentDatabase::new`.
+//!
+//! Each step runs in its own transaction with the `schema_version` row it
+//! produces, so an interrupted upgrade resumes at the first unapplied step.
+//! Append new steps; never reorder or edit shipped ones.
+
+use anyhow::{Context, Result};
+use chrono::Utc;
+use rusqlite::{params, Connection};
+
+use super::AgentDatabase;
+
+/// One schema step; its version is its 1-based position in `MIGRATIONS`.
+pub(super) struct Migration {
+    pub description: &'static str,
+    pub apply: fn(&Connection) -> Result<()>,
+}
+
+pub(super) const MIGRATIONS: &[Migration] = &[
+    Migration {
+        description: "base tables",
+        apply: AgentDatabase::create_base_tables,
+    },
+    Migration {
+        description: "chat message conversation and turn columns",
+        apply: AgentDatabase::ensure_chat_messages_conversation_column,
+    },
+    Migration {
+        description: "chat conversation session and runtime columns",
+        apply: AgentDatabase::ensure_chat_conversations_runtime_columns,
+    },
+    Migration {
+        description: "chat turn prompt columns",
+        apply: AgentDatabase::ensure_chat_turns_prompt_columns,
+    },
+    Migration {
+        description: "indexes",
+        apply: AgentDatabase::create_indexes,
+    },
+    Migration {
+        description: "journal full-text index",
+        apply: super::journal::create_journal_fts,
+    },
+    Migration {
+        description: "concern pinned column",
+        apply: super::concerns::add_concern_pinned_column,
+    },
+    Migration {
+        description: "reminders table",
+        apply: super::reminders::create_reminders_table,
+    },
+    Migration {
+        description: "chat turn tool call duration column",
+        apply: super::chat::add_tool_call_duration_column,
+    },
+    Migration {
+        description: "remembered tool approvals table",
+        apply: super::tool_approvals::create_tool_approvals_table,
+    },
+    Migration {
+        description: "concern edges table",
+        apply: super::concerns::create_concern_edges_table,
+    },
+    Migration {
+        description: "working memory expiry column",
+        apply: super::memory::add_working_memory_expiry_column,
+    },
+    Migration {
+        description: "working memory tags table",
+        apply: super::memory::create_working_memory_tags_table,
+    },
+    Migration {
+        description: "scheduled job capability profile column",
+        apply: super::scheduled_jobs::add_scheduled_job_capability_profile_column,
+    },
+    Migration {
+        description: "concern notifications table",
+        apply: super::concerns::create_concern_notifications_table,
+    },
+    Migration {
+        description: "chat conversation system prompt override column",
+        apply: super::chat::add_conversation_system_prompt_column,
+    },
+    Migration {
+        description: "surfaced anomalies table",
+        apply: super::orientation::create_surfaced_anomalies_table,
+    },
+    Migration {
+        description: "chat message embeddings table",
+        apply: super::chat_embeddings::create_chat_message_embeddings_table,
+    },
+    Migration {
+        description: "chat conversation tools disabled column",
+        apply: super::chat::add_conversation_tools_disabled_column,
+    },
+    Migration {
+        description: "tool approval queue table",
+        apply: super::approval_queue::create_tool_approval_queue_table,
+    },
+    Migration {
+        description: "journal entry embeddings table",
+        apply: super::journal_embeddings::create_journal_entry_embeddings_table,
+    },
+    Migration {
+        description: "working memory embeddings table",
+        apply: super::memory_embeddings::create_working_memory_embeddings_table,
+    },
+    Migration {
+        description: "concern notification trigger time column",
+        apply: super::concerns::add_concern_notification_trigger_column,
+    },
+    Migration {
+        description: "tool approval queue capability profile column",
+        apply: super::approval_queue::add_tool_approval_profile_column,
+    },
+];
+
+fn ensure_version_table(conn: &Connection) -> Result<()> {
+    conn.execute(
+        r#"CREATE TABLE IF NOT EXISTS schema_version (
+            version INTEGER PRIMARY KEY,
+            description TEXT NOT NULL,
+            applied_at TEXT NOT NULL
+        )"#,
+        [],
+    )?;
+    Ok(())
+}
+
+pub(super) fn current_version(conn: &Connection) -> Result<u32> {
+    ensure_version_table(conn)?;
+    let version: i64 = conn.query_row(
+        "SELECT COALESCE(MAX(version), 0) FROM schema_version",
+        [],
+        |row| row.get(0),
+    )?;
+    Ok(version as u32)
+}
+
+/// Apply every migration newer than the recorded version and return the
+/// resulting version. Databases from before versioning start at zero; the
+/// early steps are idempotent so they adopt existing tables in place.
+pub(super) fn apply_pending(conn: &mut Connection) -> Result<u32> {
+    let mut version = current_version(conn)?;
+    if version as usize > MIGRATIONS.len() {
+        tracing::warn!(
+            "Database schema version {} is newer than this build ({}); skipping migrations",
+            version,
+            MIGRATIONS.len()
+        );
+        return Ok(version);
+    }
+
+    for migration in &MIGRATIONS[version as usize..] {
+        let next = version + 1;
+        let tx = conn.transaction()?;
+        (migration.apply)(&tx).with_context(|| {
+            format!(
+                "Schema migration {} ({}) failed",
+                next, migration.description
+            )
+        })?;
+        tx.execute(
+            "INSERT INTO schema_version (version, description, applied_at) VALUES (?1, ?2, ?3)",
+            params![next, migration.description, Utc::now().to_rfc3339()],
+        )?;
+        tx.commit()?;
+        version = next;
+    }
+    Ok(version)
+}
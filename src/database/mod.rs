@@ -2,39 +2,75 @@ use anyhow::Result;
 use chrono::Utc;
 use rusqlite::params;
 use rusqlite::Connection;
+use std::collections::BTreeMap;
 use std::path::Path;
 use std::sync::Mutex;
 
 use crate::memory::{KvMemoryBackend, MemoryBackend, MemoryMigrationRegistry};
 
 mod helpers;
+mod migrations;
 
+pub mod approval_queue;
+pub mod bundle;
 pub mod chat;
+pub mod chat_embeddings;
 pub mod concerns;
 pub mod dream;
 pub mod intentions;
 pub mod journal;
+pub mod journal_embeddings;
 pub mod memory;
+pub mod memory_embeddings;
 pub mod orientation;
 pub mod persona;
 pub mod plugins;
 pub mod posts;
+pub mod reminders;
 pub mod scheduled_jobs;
+pub mod tool_approvals;
 
 // Re-export public types
+pub use approval_queue::{QueuedToolApproval, ToolApprovalStatus};
+pub use bundle::{
+    BundledMedia, StateBundleExport, StateBundleHeader, StateBundleImport, STATE_BUNDLE_FORMAT,
+    STATE_BUNDLE_VERSION,
+};
 pub use chat::{
-    ChatConversation, ChatConversationSummary, ChatMessage, ChatSession, ChatTurn, ChatTurnPhase,
-    ChatTurnToolCall, OodaTurnPacketRecord, DEFAULT_CHAT_CONVERSATION_ID, DEFAULT_CHAT_SESSION_ID,
-    TELEGRAM_CONVERSATION_ID,
+    parse_chat_media_blocks, ActionDigestStrategy, ChatConversation, ChatConversationSummary,
+    ChatMediaAttachment, ChatMessage, ChatSession, ChatTurn, ChatTurnExplanation, ChatTurnPhase,
+    ChatTurnToolCall, OodaTurnPacketRecord, ToolLatencyStats, CHAT_BACKGROUND_ITERATION_OFFSET,
+    DEFAULT_CHAT_CONVERSATION_ID, DEFAULT_CHAT_SESSION_ID, TELEGRAM_CONVERSATION_ID,
+};
+pub use chat_embeddings::chat_message_embedding_text;
+pub use orientation::{
+    MoodBucket, OrientationSnapshotRecord, PendingThoughtRecord, SurfacedAnomalyRecord,
+};
+pub use persona::{
+    CharacterCard, PersonaSnapshot, PersonaTimelineEntry, PersonaTraits, ReflectionRecord,
 };
-pub use orientation::{OrientationSnapshotRecord, PendingThoughtRecord};
-pub use persona::{CharacterCard, PersonaSnapshot, PersonaTraits, ReflectionRecord};
 pub use plugins::{
     NewPluginEvent, PluginEventCompactionReport, PluginEventCursor, PluginEventDeadLetter,
     PluginEventDeliveryBatch, PluginEventDeliveryReceipt, PluginEventPage, PluginEventRecord,
     PluginEventRetentionPolicy, PluginStateRecord,
 };
 pub use posts::ImportantPost;
+pub use reminders::Reminder;
+pub use tool_approvals::RememberedToolApproval;
+
+/// Tables whose sizes are reported by `table_row_counts`.
+const ROW_COUNT_TABLES: &[&str] = &[
+    "chat_conversations",
+    "chat_messages",
+    "chat_turns",
+    "concerns",
+    "journal_entries",
+    "orientation_snapshots",
+    "persona_history",
+    "reminders",
+    "scheduled_jobs",
+    "working_memory",
+];
 
 pub struct AgentDatabase {
     pub(super) conn: Mutex<Connection>,
@@ -78,7 +114,7 @@ impl AgentDatabase {
         Ok(columns.iter().any(|name| name == column))
     }
 
-    fn ensure_chat_messages_conversation_column(&self, conn: &Connection) -> Result<()> {
+    pub(super) fn ensure_chat_messages_conversation_column(conn: &Connection) -> Result<()> {
         if !Self::table_has_column(conn, "chat_messages", "conversation_id")? {
             conn.execute(
                 "ALTER TABLE chat_messages ADD COLUMN conversation_id TEXT NOT NULL DEFAULT 'default'",
@@ -98,7 +134,7 @@ impl AgentDatabase {
         Ok(())
     }
 
-    fn ensure_chat_conversations_runtime_columns(&self, conn: &Connection) -> Result<()> {
+    pub(super) fn ensure_chat_conversations_runtime_columns(conn: &Connection) -> Result<()> {
         if !Self::table_has_column(conn, "chat_conversations", "session_id")? {
             conn.execute(
                 "ALTER TABLE chat_conversations ADD COLUMN session_id TEXT NOT NULL DEFAULT 'default_session'",
@@ -134,7 +170,7 @@ impl AgentDatabase {
         Ok(())
     }
 
-    fn ensure_chat_turns_prompt_columns(&self, conn: &Connection) -> Result<()> {
+    pub(super) fn ensure_chat_turns_prompt_columns(conn: &Connection) -> Result<()> {
         if !Self::table_has_column(conn, "chat_turns", "prompt_text")? {
             conn.execute("ALTER TABLE chat_turns ADD COLUMN prompt_text TEXT", [])?;
         }
@@ -147,7 +183,7 @@ impl AgentDatabase {
         Ok(())
     }
 
-    fn ensure_default_chat_session(&self, conn: &Connection) -> Result<()> {
+    fn ensure_default_chat_session(conn: &Connection) -> Result<()> {
         let now = Utc::now().to_rfc3339();
         conn.execute(
             "INSERT OR IGNORE INTO chat_sessions (id, label, created_at, updated_at)
@@ -157,7 +193,7 @@ impl AgentDatabase {
         Ok(())
     }
 
-    fn ensure_default_chat_conversation(&self, conn: &Connection) -> Result<()> {
+    fn ensure_default_chat_conversation(conn: &Connection) -> Result<()> {
         let now = Utc::now().to_rfc3339();
         conn.execute(
             "INSERT OR IGNORE INTO chat_conversations (id, session_id, title, created_at, updated_at, runtime_state, active_turn_id)
@@ -174,10 +210,24 @@ impl AgentDatabase {
         Ok(())
     }
 
-    /// Create the database schema
+    /// Bring the schema up to date, then seed the default chat rows.
     fn ensure_schema(&self) -> Result<()> {
+        let mut conn = self.lock_conn()?;
+        migrations::apply_pending(&mut conn)?;
+        Self::ensure_default_chat_session(&conn)?;
+        Self::ensure_default_chat_conversation(&conn)?;
+        Ok(())
+    }
+
+    /// Schema version recorded by the last applied migration.
+    pub fn schema_version(&self) -> Result<u32> {
         let conn = self.lock_conn()?;
+        migrations::current_version(&conn)
+    }
 
+    /// Base tables. `IF NOT EXISTS` keeps this safe on databases created
+    /// before schema versioning.
+    pub(super) fn create_base_tables(conn: &Connection) -> Result<()> {
         conn.execute(
             r#"CREATE TABLE IF NOT EXISTS important_posts (
                 id TEXT PRIMARY KEY,
@@ -646,11 +696,10 @@ impl AgentDatabase {
             [],
         )?;
 
-        self.ensure_chat_messages_conversation_column(&conn)?;
-        self.ensure_chat_conversations_runtime_columns(&conn)?;
-        self.ensure_chat_turns_prompt_columns(&conn)?;
-        self.ensure_default_chat_session(&conn)?;
+        Ok(())
+    }
 
+    pub(super) fn create_indexes(conn: &Connection) -> Result<()> {
         conn.execute(
             "CREATE INDEX IF NOT EXISTS idx_chat_messages_created_at ON chat_messages(created_at ASC)",
             [],
@@ -756,8 +805,6 @@ impl AgentDatabase {
             [],
         )?;
 
-        self.ensure_default_chat_conversation(&conn)?;
-
         Ok(())
     }
 
@@ -786,6 +833,21 @@ impl AgentDatabase {
         )?;
         Ok(())
     }
+
+    /// Row counts for the main content tables, keyed by table name.
+    pub fn table_row_counts(&self) -> Result<BTreeMap<String, i64>> {
+        let conn = self.lock_conn()?;
+        ROW_COUNT_TABLES
+            .iter()
+            .map(|table| {
+                let count: i64 =
+                    conn.query_row(&format!("SELECT COUNT(*) FROM {table}"), [], |row| {
+                        row.get(0)
+                    })?;
+                Ok((table.to_string(), count))
+            })
+            .collect()
+    }
 }
 
 #[cfg(test)]
@@ -796,12 +858,94 @@ mod tests {
     use chrono::Duration as ChronoDuration;
     use std::path::PathBuf;
 
+    #[test]
+    fn media_blocks_parse_into_typed_attachments() {
+        let content = "Here you go.\n\n[media]\n[\
+            {\"path\":\"/tmp/speech.wav\",\"media_kind\":\"audio\",\"mime_type\":\"audio/wav\",\"source\":\"speak\",\"auto_play\":true},\
+            {\"path\":\"/tmp/clip.mp4\",\"media_kind\":\"Video\",\"source\":\"shell\"},\
+            {\"path\":\"/tmp/notes.pdf\",\"media_kind\":\"document\",\"source\":\"write_file\"},\
+            {\"path\":\"  \",\"media_kind\":\"image\",\"source\":\"x\"}\
+            ]\n[/media]\n\n[media]\nnot json\n[/media]";
+        let media = parse_chat_media_blocks(content);
+        let kinds: Vec<&str> = media.iter().map(|m| m.media_kind.as_str()).collect();
+        assert_eq!(kinds, vec!["audio", "video", "file"]);
+        assert!(media[0].auto_play);
+        assert_eq!(media[0].mime_type.as_deref(), Some("audio/wav"));
+        assert_eq!(media[1].mime_type, None);
+    }
+
     fn temp_db_path(name: &str) -> PathBuf {
         let mut path = std::env::temp_dir();
         path.push(format!("ponderer_{}_{}.db", name, uuid::Uuid::new_v4()));
         path
     }
 
+    #[test]
+    fn same_timestamp_messages_page_into_stable_disjoint_slices() {
+        let path = temp_db_path("chat_slice_ties");
+        let db = AgentDatabase::new(&path).expect("db init");
+        let conversation = db
+            .create_chat_conversation(Some("Ties"))
+            .expect("conversation");
+        for index in 0..6 {
+            db.add_chat_message_in_conversation(&conversation.id, "operator", &index.to_string())
+                .expect("message");
+        }
+        db.lock_conn()
+            .unwrap()
+            .execute(
+                "UPDATE chat_messages SET created_at = ?1 WHERE conversation_id = ?2",
+                params![Utc::now().to_rfc3339(), conversation.id],
+            )
+            .expect("collapse timestamps");
+
+        let ids = |messages: Vec<crate::database::ChatMessage>| -> Vec<String> {
+            messages.into_iter().map(|message| message.id).collect()
+        };
+        let recent = ids(db
+            .get_chat_history_for_conversation(&conversation.id, 3)
+            .unwrap());
+        let older = ids(db
+            .get_chat_history_slice_for_conversation(&conversation.id, 3, 3)
+            .unwrap());
+        assert_eq!(
+            recent,
+            ids(db
+                .get_chat_history_slice_for_conversation(&conversation.id, 0, 3)
+                .unwrap())
+        );
+        assert!(older.iter().all(|id| !recent.contains(id)));
+
+        let mut combined = [older.clone(), recent.clone()].concat();
+        let all = db
+            .get_chat_history_for_conversation(&conversation.id, 6)
+            .unwrap();
+        assert_eq!(
+            all.iter()
+                .map(|message| message.content.as_str())
+                .collect::<Vec<_>>(),
+            vec!["0", "1", "2", "3", "4", "5"]
+        );
+        assert_eq!(combined, ids(all));
+        combined.sort();
+        combined.dedup();
+        assert_eq!(combined.len(), 6);
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn table_row_counts_cover_each_reported_table() {
+        let path = temp_db_path("row_counts");
+        let db = AgentDatabase::new(&path).expect("db init");
+        db.add_chat_message("operator", "hello").expect("message");
+
+        let counts = db.table_row_counts().expect("row counts");
+        assert_eq!(counts.len(), ROW_COUNT_TABLES.len());
+        assert_eq!(counts["chat_messages"], 1);
+        assert_eq!(counts["journal_entries"], 0);
+        let _ = std::fs::remove_file(path);
+    }
+
     #[test]
     fn chat_turn_lifecycle_persists_state_and_tool_calls() {
         let path = temp_db_path("chat_turn_lifecycle");
@@ -830,6 +974,7 @@ mod tests {
             "list_directory",
             r#"{"path":"."}"#,
             "Found 3 entries",
+            Some(42),
         )
         .expect("record tool call");
 
@@ -885,6 +1030,24 @@ mod tests {
             .expect("list tool calls");
         assert_eq!(tool_calls.len(), 1);
         assert_eq!(tool_calls[0].tool_name, "list_directory");
+        assert_eq!(tool_calls[0].duration_ms, Some(42));
+
+        let explanation = db
+            .get_turn_explanation(&turn_id)
+            .expect("load explanation")
+            .expect("explanation exists");
+        assert!(!explanation.background_subtask);
+        assert_eq!(explanation.turn.decision.as_deref(), Some("yield"));
+        assert_eq!(explanation.tool_calls.len(), 1);
+        assert!(explanation.ooda_packet.is_none());
+        assert_eq!(
+            explanation.final_message.map(|message| message.content),
+            Some("Done.".to_string())
+        );
+        assert!(db
+            .get_turn_explanation("missing-turn")
+            .expect("lookup")
+            .is_none());
 
         let conversations = db.list_chat_conversations(50).expect("list conversations");
         let convo_state = conversations
@@ -897,6 +1060,81 @@ mod tests {
         let _ = std::fs::remove_file(&path);
     }
 
+    #[test]
+    fn reconcile_orphaned_turns_fails_only_processing_turns() {
+        let path = temp_db_path("orphaned_turns");
+        let db = AgentDatabase::new(&path).expect("db init");
+        let conversation = db
+            .create_chat_conversation(Some("Crash test"))
+            .expect("create conversation");
+        let message_id = db
+            .add_chat_message_in_conversation(&conversation.id, "operator", "Hello?")
+            .expect("insert operator message");
+        let orphaned = db
+            .begin_chat_turn(&conversation.id, std::slice::from_ref(&message_id), 1)
+            .expect("begin orphaned turn");
+        let finished = db
+            .begin_chat_turn(&conversation.id, std::slice::from_ref(&message_id), 2)
+            .expect("begin finished turn");
+        db.complete_chat_turn(
+            &finished,
+            ChatTurnPhase::Completed,
+            "yield",
+            "done",
+            "Hi.",
+            None,
+            0,
+            None,
+        )
+        .expect("complete turn");
+
+        assert_eq!(db.reconcile_orphaned_turns().expect("reconcile"), 1);
+        assert_eq!(db.reconcile_orphaned_turns().expect("reconcile again"), 0);
+
+        let turns = db
+            .list_chat_turns_for_conversation(&conversation.id, 10)
+            .expect("list turns");
+        let orphaned = turns.iter().find(|t| t.id == orphaned).expect("orphan");
+        assert_eq!(orphaned.phase_state, ChatTurnPhase::Failed);
+        assert_eq!(orphaned.error.as_deref(), Some(chat::ORPHANED_TURN_ERROR));
+        let finished = turns.iter().find(|t| t.id == finished).expect("finished");
+        assert_eq!(finished.phase_state, ChatTurnPhase::Completed);
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn tool_latency_stats_report_mean_and_p95_per_tool() {
+        let path = temp_db_path("tool_latency_stats");
+        let db = AgentDatabase::new(&path).expect("db init");
+        let turn_id = "turn-latency";
+        let mut index = 0;
+        let mut record = |tool_name: &str, duration_ms: Option<u64>| {
+            db.record_chat_turn_tool_call(turn_id, index, tool_name, "{}", "ok", duration_ms)
+                .expect("record tool call");
+            index += 1;
+        };
+        for ms in 1..=20 {
+            record("web_search", Some(ms * 100));
+        }
+        record("read_file", Some(4));
+        record("read_file", Some(6));
+        record("read_file", None);
+
+        let stats = db.get_tool_latency_stats().expect("latency stats");
+        assert_eq!(stats.len(), 2);
+        assert_eq!(stats[0].tool_name, "web_search");
+        assert_eq!(stats[0].calls, 20);
+        assert_eq!(stats[0].mean_ms, 1050.0);
+        assert_eq!(stats[0].p95_ms, 1900);
+        assert_eq!(stats[1].tool_name, "read_file");
+        assert_eq!(stats[1].calls, 2);
+        assert_eq!(stats[1].mean_ms, 5.0);
+        assert_eq!(stats[1].p95_ms, 6);
+
+        let _ = std::fs::remove_file(&path);
+    }
+
     #[test]
     fn failed_turn_keeps_operator_message_unprocessed_for_retry() {
         let path = temp_db_path("chat_turn_failure");
@@ -981,6 +1219,154 @@ mod tests {
         let _ = std::fs::remove_file(&path);
     }
 
+    #[test]
+    fn remembered_tool_approvals_round_trip_and_delete() {
+        let path = temp_db_path("tool_approvals");
+        let db = AgentDatabase::new(&path).expect("db init");
+        let approval = RememberedToolApproval {
+            id: "approval-1".to_string(),
+            tool_name: "read_file".to_string(),
+            provider: "builtin".to_string(),
+            arguments: serde_json::json!({"limit": 20}),
+            path_scopes: [("path".to_string(), "/srv/notes".to_string())]
+                .into_iter()
+                .collect(),
+            created_at: chrono::Utc::now(),
+        };
+        db.save_remembered_tool_approval(&approval)
+            .expect("save approval");
+
+        let loaded = db.list_remembered_tool_approvals().expect("list approvals");
+        assert_eq!(loaded, vec![approval.clone()]);
+
+        assert!(db
+            .delete_remembered_tool_approval(&approval.id)
+            .expect("delete approval"));
+        assert!(!db
+            .delete_remembered_tool_approval(&approval.id)
+            .expect("delete again"));
+        assert!(db
+            .list_remembered_tool_approvals()
+            .expect("list")
+            .is_empty());
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn tool_approval_queue_reuses_pending_calls_and_resolves_once() {
+        let path = temp_db_path("approval_queue");
+        let db = AgentDatabase::new(&path).expect("db init");
+        let params = serde_json::json!({"command": "rm -rf build", "cwd": "/srv"});
+
+        let (queued, created) = db
+            .enqueue_tool_approval("shell", &params, "needs approval", None, Some("heartbeat"))
+            .expect("enqueue");
+        assert!(created);
+        assert_eq!(queued.status, ToolApprovalStatus::Pending);
+        let (repeat, created) = db
+            .enqueue_tool_approval(
+                "shell",
+                &serde_json::json!({"cwd": "/srv", "command": "rm -rf build"}),
+                "needs approval",
+                None,
+                Some("heartbeat"),
+            )
+            .expect("enqueue repeat");
+        assert!(!created);
+        assert_eq!(repeat.id, queued.id);
+        assert_eq!(repeat.capability_profile.as_deref(), Some("heartbeat"));
+        let (scoped, created) = db
+            .enqueue_tool_approval(
+                "shell",
+                &params,
+                "needs approval",
+                Some("conv-1"),
+                Some("private_chat"),
+            )
+            .expect("enqueue scoped");
+        assert!(created);
+        assert_ne!(scoped.id, queued.id);
+        assert_eq!(db.list_pending_tool_approvals().expect("list").len(), 2);
+
+        let denied = db
+            .resolve_tool_approval(&queued.id, false)
+            .expect("resolve")
+            .expect("pending row");
+        assert_eq!(denied.status, ToolApprovalStatus::Denied);
+        assert_eq!(denied.capability_profile.as_deref(), Some("heartbeat"));
+        assert!(denied.resolved_at.is_some());
+        assert!(db
+            .resolve_tool_approval(&queued.id, true)
+            .expect("resolve again")
+            .is_none());
+        assert!(db
+            .resolve_tool_approval("missing", true)
+            .expect("resolve missing")
+            .is_none());
+        assert_eq!(
+            db.list_pending_tool_approvals().expect("list"),
+            vec![scoped.clone()]
+        );
+
+        let (fresh, created) = db
+            .enqueue_tool_approval("shell", &params, "needs approval", None, Some("heartbeat"))
+            .expect("enqueue after resolve");
+        assert!(created);
+        assert_ne!(fresh.id, queued.id);
+
+        let stale_at =
+            Utc::now() - ChronoDuration::hours(approval_queue::TOOL_APPROVAL_TTL_HOURS + 1);
+        db.lock_conn()
+            .unwrap()
+            .execute(
+                "UPDATE tool_approval_queue SET created_at = ?1 WHERE id = ?2",
+                rusqlite::params![stale_at.to_rfc3339(), fresh.id],
+            )
+            .unwrap();
+        assert_eq!(
+            db.list_pending_tool_approvals().expect("list"),
+            vec![scoped]
+        );
+        assert!(db
+            .resolve_tool_approval(&fresh.id, true)
+            .expect("resolve expired")
+            .is_none());
+
+        db.delete_chat_conversation("conv-1").expect("delete");
+        assert!(db.list_pending_tool_approvals().expect("list").is_empty());
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn reminders_surface_only_once_due_and_deliver_once() {
+        let path = temp_db_path("reminders_due");
+        let db = AgentDatabase::new(&path).expect("db init");
+        let now = chrono::Utc::now();
+        let due = db
+            .create_reminder("Stretch", now - ChronoDuration::minutes(1))
+            .expect("create due reminder");
+        let later = db
+            .create_reminder("Call back", now + ChronoDuration::minutes(25))
+            .expect("create future reminder");
+
+        let due_now = db.get_due_reminders(now, 8).expect("due reminders");
+        assert_eq!(due_now.len(), 1);
+        assert_eq!(due_now[0].id, due.id);
+        assert_eq!(db.list_pending_reminders(8).expect("pending").len(), 2);
+
+        db.mark_reminders_delivered(std::slice::from_ref(&due.id), now)
+            .expect("mark delivered");
+        assert!(db.get_due_reminders(now, 8).expect("due").is_empty());
+        assert!(!db.cancel_reminder(&due.id).expect("cancel delivered"));
+
+        assert!(db.cancel_reminder(&later.id).expect("cancel pending"));
+        assert!(db.list_pending_reminders(8).expect("pending").is_empty());
+
+        let _ = std::fs::remove_file(&path);
+    }
+
     #[test]
     fn next_scheduled_job_due_at_ignores_disabled_jobs() {
         let path = temp_db_path("scheduled_jobs_next_due");
@@ -1028,6 +1414,39 @@ mod tests {
         let _ = std::fs::remove_file(&path);
     }
 
+    #[test]
+    fn scheduled_job_capability_profile_round_trips_by_conversation() {
+        let path = temp_db_path("scheduled_jobs_profile");
+        let db = AgentDatabase::new(&path).expect("db init");
+        let job = db
+            .create_scheduled_job("Digest", "Summarize the news.", 60)
+            .expect("create job");
+        assert_eq!(job.capability_profile, None);
+
+        let updated = db
+            .set_scheduled_job_capability_profile(&job.id, Some(" research-only "))
+            .expect("set profile")
+            .expect("job exists");
+        assert_eq!(updated.capability_profile.as_deref(), Some("research-only"));
+        let found = db
+            .get_scheduled_job_by_conversation(&job.conversation_id)
+            .expect("lookup by conversation")
+            .expect("job exists");
+        assert_eq!(found.capability_profile.as_deref(), Some("research-only"));
+
+        let cleared = db
+            .set_scheduled_job_capability_profile(&job.id, Some(""))
+            .expect("clear profile")
+            .expect("job exists");
+        assert_eq!(cleared.capability_profile, None);
+        assert!(db
+            .set_scheduled_job_capability_profile("missing", Some("research-only"))
+            .expect("missing job")
+            .is_none());
+
+        let _ = std::fs::remove_file(&path);
+    }
+
     #[test]
     fn memory_search_returns_relevant_entries() {
         let path = temp_db_path("memory_search");
@@ -1047,6 +1466,154 @@ mod tests {
         let _ = std::fs::remove_file(&path);
     }
 
+    #[test]
+    fn tagged_working_memory_filters_search_and_scopes_context() {
+        let path = temp_db_path("working_memory_tags");
+        let db = AgentDatabase::new(&path).expect("db init");
+
+        db.set_working_memory("ponderer-plan", "ship tagged memory recall")
+            .expect("seed tagged memory");
+        db.set_working_memory_tags(
+            "ponderer-plan",
+            &[
+                " Project:Ponderer ".to_string(),
+                "plans".to_string(),
+                "plans".to_string(),
+            ],
+        )
+        .expect("tag memory");
+        db.set_working_memory("garden-plan", "ship tomato seedlings")
+            .expect("seed other memory");
+        db.set_working_memory_tags("garden-plan", &["home".to_string(), "plans".to_string()])
+            .expect("tag other memory");
+        db.set_working_memory("general-note", "ship small changes")
+            .expect("seed untagged memory");
+
+        assert_eq!(
+            db.get_working_memory_tags("ponderer-plan").expect("tags"),
+            vec!["plans", "project:ponderer"]
+        );
+
+        // Tags AND with each other and with the text query.
+        let keys = |tags: &[&str]| -> Vec<String> {
+            let tags: Vec<String> = tags.iter().map(|tag| tag.to_string()).collect();
+            db.search_working_memory_with_tags("ship", &tags, 10)
+                .expect("tagged search")
+                .into_iter()
+                .map(|entry| entry.key)
+                .collect()
+        };
+        assert_eq!(keys(&["plans", "project:ponderer"]), vec!["ponderer-plan"]);
+        assert_eq!(keys(&["PLANS"]).len(), 2);
+        assert!(keys(&["plans", "missing"]).is_empty());
+        assert_eq!(keys(&[]).len(), 3);
+
+        let scoped = db
+            .get_working_memory_context_for_tags(&["home".to_string()])
+            .expect("scoped context");
+        assert!(scoped.contains("garden-plan"));
+        assert!(scoped.contains("general-note"));
+        assert!(!scoped.contains("ponderer-plan"));
+        assert!(db
+            .get_working_memory_context()
+            .expect("unscoped context")
+            .contains("ponderer-plan"));
+        let chat_scoped = db
+            .get_working_memory_context_for_conversation(
+                "conv-tags",
+                4000,
+                &["Project:Ponderer".to_string()],
+            )
+            .expect("conversation scoped context");
+        assert!(chat_scoped.contains("ponderer-plan"));
+        assert!(chat_scoped.contains("general-note"));
+        assert!(!chat_scoped.contains("garden-plan"));
+
+        db.delete_working_memory("ponderer-plan")
+            .expect("delete memory");
+        assert!(db
+            .get_working_memory_tags("ponderer-plan")
+            .expect("tags after delete")
+            .is_empty());
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn expired_working_memory_is_hidden_then_swept() {
+        let path = temp_db_path("working_memory_expiry");
+        let db = AgentDatabase::new(&path).expect("db init");
+        let now = Utc::now();
+
+        db.set_working_memory("project-brief", "Keep turns bounded.")
+            .expect("seed permanent memory");
+        db.set_working_memory_with_expiry(
+            "dream-journal-2026-02-19",
+            "transient dream notes",
+            Some(now - ChronoDuration::minutes(1)),
+        )
+        .expect("seed expired memory");
+        db.set_working_memory_with_expiry(
+            "today-focus",
+            "finish the sweep",
+            Some(now + ChronoDuration::hours(1)),
+        )
+        .expect("seed unexpired memory");
+        for key in ["project-brief", "dream-journal-2026-02-19"] {
+            let entry = crate::memory::WorkingMemoryEntry {
+                key: key.to_string(),
+                content: String::new(),
+                updated_at: now,
+            };
+            db.store_working_memory_embedding(&entry, "embed-a", &[0.1, 0.9])
+                .expect("store embedding");
+        }
+        let embedding_rows = |key: &str| -> i64 {
+            db.lock_conn()
+                .expect("lock conn")
+                .query_row(
+                    "SELECT COUNT(*) FROM working_memory_embeddings WHERE key = ?1",
+                    [key],
+                    |row| row.get(0),
+                )
+                .expect("count embeddings")
+        };
+
+        let context = db.get_working_memory_context().expect("context");
+        assert!(context.contains("project-brief"));
+        assert!(context.contains("today-focus"));
+        assert!(!context.contains("transient dream notes"));
+        assert!(db
+            .get_working_memory("dream-journal-2026-02-19")
+            .expect("get memory")
+            .is_none());
+
+        assert_eq!(db.delete_expired_working_memory(now).expect("sweep"), 1);
+        assert_eq!(embedding_rows("dream-journal-2026-02-19"), 0);
+        assert_eq!(embedding_rows("project-brief"), 1);
+        assert_eq!(
+            db.delete_expired_working_memory(now + ChronoDuration::hours(2))
+                .expect("sweep later"),
+            1
+        );
+        // Rewriting without a TTL makes a key permanent again.
+        db.set_working_memory_with_expiry(
+            "project-brief",
+            "Keep turns bounded.",
+            Some(now - ChronoDuration::minutes(1)),
+        )
+        .expect("expire brief");
+        db.set_working_memory("project-brief", "Keep turns bounded.")
+            .expect("rewrite brief");
+        assert_eq!(
+            db.delete_expired_working_memory(now).expect("sweep again"),
+            0
+        );
+        assert_eq!(db.get_all_working_memory().expect("list").len(), 1);
+
+        let _ = std::fs::remove_file(&path);
+    }
+
     #[test]
     fn append_daily_activity_log_accumulates_lines() {
         let path = temp_db_path("daily_activity_log");
@@ -1064,6 +1631,24 @@ mod tests {
             .expect("daily log exists");
         assert!(item.content.contains("Ran memory search tool"));
         assert!(item.content.contains("Answered operator request"));
+        let today = chrono::Utc::now().date_naive();
+        assert_eq!(
+            db.get_daily_activity_log(today).expect("get log"),
+            Some(item.content)
+        );
+        assert_eq!(
+            db.get_daily_activity_log(today - ChronoDuration::days(1))
+                .expect("get empty log"),
+            None
+        );
+
+        db.upsert_chat_conversation_summary("default", "Talked about lunch.", 3)
+            .expect("upsert summary");
+        let summaries = db
+            .get_chat_conversation_summaries_between(crate::agent::daily_report::day_range(today))
+            .expect("summaries for today");
+        assert_eq!(summaries.len(), 1);
+        assert_eq!(summaries[0].summary_text, "Talked about lunch.");
 
         let _ = std::fs::remove_file(&path);
     }
@@ -1102,7 +1687,7 @@ mod tests {
         .expect("seed other-conversation handoff");
 
         let scoped = db
-            .get_working_memory_context_for_conversation(conversation_id, 4000)
+            .get_working_memory_context_for_conversation(conversation_id, 4000, &[])
             .expect("scoped context");
 
         assert!(scoped.contains(&conversation_tag));
@@ -1148,6 +1733,307 @@ mod tests {
         let _ = std::fs::remove_file(&path);
     }
 
+    #[test]
+    fn relevant_messages_rank_by_embedding_within_one_conversation() {
+        let path = temp_db_path("chat_semantic_recall");
+        let db = AgentDatabase::new(&path).expect("db init");
+        let conversation = db
+            .create_chat_conversation(Some("Recall"))
+            .expect("conversation");
+        let other = db.create_chat_conversation(Some("Other")).expect("other");
+        let garden = db
+            .add_chat_message_in_conversation(&conversation.id, "operator", "tomato blight again")
+            .expect("garden");
+        let taxes = db
+            .add_chat_message_in_conversation(&conversation.id, "operator", "quarterly taxes")
+            .expect("taxes");
+        let tools_only = db
+            .add_chat_message_in_conversation(
+                &conversation.id,
+                "agent",
+                "[thinking]\n[]\n[/thinking]",
+            )
+            .expect("tools only");
+        let elsewhere = db
+            .add_chat_message_in_conversation(&other.id, "operator", "tomatoes elsewhere")
+            .expect("elsewhere");
+
+        let missing = db
+            .list_chat_messages_missing_embedding("embed-a", 10)
+            .expect("missing");
+        assert_eq!(missing.len(), 4);
+
+        db.store_chat_message_embedding(&garden, &conversation.id, "embed-a", &[1.0, 0.1])
+            .unwrap();
+        db.store_chat_message_embedding(&taxes, &conversation.id, "embed-a", &[0.1, 1.0])
+            .unwrap();
+        db.store_chat_message_embedding(&tools_only, &conversation.id, "embed-a", &[])
+            .unwrap();
+        db.store_chat_message_embedding(&elsewhere, &other.id, "embed-a", &[1.0, 0.0])
+            .unwrap();
+        assert!(db
+            .list_chat_messages_missing_embedding("embed-a", 10)
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            db.list_chat_messages_missing_embedding("embed-b", 10)
+                .unwrap()
+                .len(),
+            4
+        );
+
+        let stored_type: String = db
+            .lock_conn()
+            .unwrap()
+            .query_row(
+                "SELECT typeof(embedding) FROM chat_message_embeddings WHERE message_id = ?1",
+                [&garden],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(stored_type, "blob");
+        // Rows from before BLOB storage hold JSON text and still rank.
+        db.lock_conn()
+            .unwrap()
+            .execute(
+                "UPDATE chat_message_embeddings SET embedding = '[0.1,1.0]' WHERE message_id = ?1",
+                [&taxes],
+            )
+            .unwrap();
+
+        let ranked: Vec<String> = db
+            .retrieve_relevant_messages(&conversation.id, "embed-a", &[1.0, 0.0], 5)
+            .expect("retrieve")
+            .into_iter()
+            .map(|message| message.id)
+            .collect();
+        assert_eq!(ranked, vec![garden.clone(), taxes.clone()]);
+        assert_eq!(
+            db.retrieve_relevant_messages(&conversation.id, "embed-a", &[1.0, 0.0], 1)
+                .unwrap()
+                .len(),
+            1
+        );
+        assert!(db
+            .retrieve_relevant_messages(&conversation.id, "embed-a", &[1.0, 0.0, 0.0], 5)
+            .unwrap()
+            .is_empty());
+
+        db.store_chat_message_embedding(&taxes, &conversation.id, "embed-b", &[1.0, 0.0])
+            .unwrap();
+        let by_model = |model: &str| -> Vec<String> {
+            db.retrieve_relevant_messages(&conversation.id, model, &[1.0, 0.0], 5)
+                .unwrap()
+                .into_iter()
+                .map(|message| message.id)
+                .collect()
+        };
+        assert_eq!(by_model("embed-a"), vec![garden.clone()]);
+        assert_eq!(by_model("embed-b"), vec![taxes]);
+
+        db.delete_chat_conversation(&conversation.id).unwrap();
+        assert!(db
+            .retrieve_relevant_messages(&conversation.id, "embed-a", &[1.0, 0.0], 5)
+            .unwrap()
+            .is_empty());
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn state_bundle_round_trips_into_a_fresh_database() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let source = AgentDatabase::new(dir.path().join("source.db")).expect("source db");
+        let now = Utc::now();
+
+        source
+            .save_persona_snapshot(&PersonaSnapshot {
+                id: "snap-1".to_string(),
+                captured_at: now,
+                traits: PersonaTraits::default(),
+                system_prompt: "prompt".to_string(),
+                trigger: "manual".to_string(),
+                self_description: "Curious.".to_string(),
+                inferred_trajectory: None,
+                formative_experiences: Vec::new(),
+            })
+            .expect("persona");
+        source
+            .save_concern(&Concern {
+                id: "concern-1".to_string(),
+                created_at: now,
+                last_touched: now,
+                summary: "Garden irrigation".to_string(),
+                concern_type: ConcernType::PersonalInterest {
+                    topic: "gardening".to_string(),
+                    curiosity_level: 0.5,
+                },
+                salience: Salience::Active,
+                my_thoughts: String::new(),
+                related_memory_keys: Vec::new(),
+                context: ConcernContext::default(),
+                pinned: true,
+            })
+            .expect("concern");
+        source
+            .add_journal_entry(&JournalEntry {
+                id: "journal-1".to_string(),
+                timestamp: now,
+                entry_type: JournalEntryType::Reflection,
+                content: "The tomatoes finally ripened.".to_string(),
+                context: JournalContext {
+                    trigger: "ambient".to_string(),
+                    user_state_at_time: "away".to_string(),
+                    time_of_day: "evening".to_string(),
+                },
+                related_concerns: Vec::new(),
+                mood_at_time: None,
+            })
+            .expect("journal");
+        let image_path = dir.path().join("tomato.png");
+        std::fs::write(&image_path, b"png-bytes").expect("media file");
+        let image_json = serde_json::to_string(&image_path.to_string_lossy()).unwrap();
+        source
+            .add_chat_message("operator", "Show me")
+            .expect("chat");
+        source
+            .add_chat_message(
+                "agent",
+                &format!(
+                    "Here.\n[media]\n[{{\"path\":{},\"media_kind\":\"image\"}}]\n[/media]",
+                    image_json
+                ),
+            )
+            .expect("chat media");
+
+        let bundle_path = dir.path().join("companion.bundle");
+        let export = source
+            .export_bundle(&bundle_path, Some("username = \"Wren\""))
+            .expect("export");
+        assert_eq!(export.header.version, STATE_BUNDLE_VERSION);
+        assert_eq!(export.media_included, 1);
+
+        let target = AgentDatabase::new(dir.path().join("target.db")).expect("target db");
+        let media_dir = dir.path().join("restored_media");
+        let import = target
+            .import_bundle(&bundle_path, &media_dir)
+            .expect("import");
+        assert_eq!(import.config_toml.as_deref(), Some("username = \"Wren\""));
+        assert_eq!(import.media_restored, 1);
+
+        assert_eq!(
+            target.table_row_counts().expect("target counts"),
+            source.table_row_counts().expect("source counts")
+        );
+        assert_eq!(target.get_persona_history(10).expect("persona").len(), 1);
+        assert!(
+            target
+                .get_concern("concern-1")
+                .expect("concern")
+                .unwrap()
+                .pinned
+        );
+        assert_eq!(
+            target
+                .search_journal("tomatoes", 5)
+                .expect("journal search")[0]
+                .id,
+            "journal-1"
+        );
+        let restored = target
+            .get_chat_history(10)
+            .expect("history")
+            .iter()
+            .flat_map(|message| message.media_attachments())
+            .collect::<Vec<_>>();
+        assert_eq!(restored.len(), 1);
+        assert!(restored[0].path.starts_with(&*media_dir.to_string_lossy()));
+        assert_eq!(
+            std::fs::read(&restored[0].path).expect("restored file"),
+            b"png-bytes"
+        );
+
+        let newer = std::fs::read_to_string(&bundle_path).unwrap().replacen(
+            &format!("\"version\":{}", STATE_BUNDLE_VERSION),
+            "\"version\":99",
+            1,
+        );
+        std::fs::write(&bundle_path, newer).unwrap();
+        assert!(target.import_bundle(&bundle_path, &media_dir).is_err());
+    }
+
+    #[test]
+    fn conversation_system_prompt_override_round_trips_and_clears() {
+        let path = temp_db_path("conversation_system_prompt");
+        let db = AgentDatabase::new(&path).expect("db init");
+        let conversation = db
+            .create_chat_conversation(Some("Coding"))
+            .expect("create conversation");
+        assert!(conversation.system_prompt_override.is_none());
+
+        db.set_chat_conversation_system_prompt(&conversation.id, Some("  Be terse.  "))
+            .expect("set override");
+        assert_eq!(
+            db.get_chat_conversation_system_prompt(&conversation.id)
+                .expect("get override")
+                .as_deref(),
+            Some("Be terse.")
+        );
+        let listed = db.list_chat_conversations(10).expect("list");
+        let listed = listed
+            .iter()
+            .find(|conv| conv.id == conversation.id)
+            .expect("listed");
+        assert_eq!(listed.system_prompt_override.as_deref(), Some("Be terse."));
+
+        db.set_chat_conversation_system_prompt(&conversation.id, Some(""))
+            .expect("clear override");
+        assert!(db
+            .get_chat_conversation(&conversation.id)
+            .expect("get conversation")
+            .expect("exists")
+            .system_prompt_override
+            .is_none());
+    }
+
+    #[test]
+    fn conversation_tools_disabled_flag_round_trips() {
+        let path = temp_db_path("conversation_tools_disabled");
+        let db = AgentDatabase::new(&path).expect("db init");
+        let conversation = db
+            .create_chat_conversation(Some("Just talk"))
+            .expect("create conversation");
+        assert!(!conversation.tools_disabled);
+        assert!(!db
+            .get_chat_conversation_tools_disabled(&conversation.id)
+            .expect("get flag"));
+
+        db.set_chat_conversation_tools_disabled(&conversation.id, true)
+            .expect("disable tools");
+        assert!(db
+            .get_chat_conversation_tools_disabled(&conversation.id)
+            .expect("get flag"));
+        assert!(
+            db.get_chat_conversation(&conversation.id)
+                .expect("get conversation")
+                .expect("exists")
+                .tools_disabled
+        );
+
+        db.set_chat_conversation_tools_disabled(&conversation.id, false)
+            .expect("enable tools");
+        assert!(
+            !db.list_chat_conversations(10)
+                .expect("list")
+                .iter()
+                .find(|conv| conv.id == conversation.id)
+                .expect("listed")
+                .tools_disabled
+        );
+        assert!(!db
+            .get_chat_conversation_tools_disabled("missing")
+            .expect("unknown conversation"));
+    }
+
     #[test]
     fn chat_conversation_summary_roundtrip_and_history_slice() {
         let path = temp_db_path("chat_summary_roundtrip");
@@ -1260,6 +2146,7 @@ mod tests {
                 key_events: vec!["initial planning".to_string()],
                 last_update_reason: "created".to_string(),
             },
+            pinned: false,
         };
 
         db.save_concern(&concern).expect("save concern");
@@ -1293,6 +2180,64 @@ mod tests {
         let _ = std::fs::remove_file(&path);
     }
 
+    #[test]
+    fn mood_timeseries_buckets_snapshots_by_window() {
+        let path = temp_db_path("mood_timeseries");
+        let db = AgentDatabase::new(&path).expect("db init");
+        let at = |time: &str| {
+            format!("2026-03-01T{time}:00Z")
+                .parse::<chrono::DateTime<chrono::Utc>>()
+                .expect("valid timestamp")
+        };
+        let samples = [
+            ("07:50", Some(0.9), Some(0.9)),
+            ("08:05", Some(0.2), Some(0.4)),
+            ("08:40", Some(0.4), Some(0.6)),
+            ("09:10", Some(-0.2), None),
+            ("09:20", None, None),
+            ("11:30", Some(0.6), Some(0.8)),
+        ];
+        for (time, valence, arousal) in samples {
+            db.save_orientation_snapshot(&OrientationSnapshotRecord {
+                id: uuid::Uuid::new_v4().to_string(),
+                timestamp: at(time),
+                user_state: serde_json::json!({"type":"idle"}),
+                disposition: "idle".to_string(),
+                synthesis: format!("snapshot at {time}"),
+                salience_map: serde_json::json!([]),
+                anomalies: serde_json::json!([]),
+                pending_thoughts: serde_json::json!([]),
+                mood_valence: valence,
+                mood_arousal: arousal,
+            })
+            .expect("save orientation snapshot");
+        }
+
+        let hourly = db
+            .get_mood_timeseries(at("08:00"), chrono::Duration::hours(1))
+            .expect("hourly mood");
+        let starts: Vec<_> = hourly.iter().map(|bucket| bucket.bucket_start).collect();
+        assert_eq!(starts, vec![at("08:00"), at("09:00"), at("11:00")]);
+        assert_eq!(hourly[0].samples, 2);
+        assert!((hourly[0].avg_valence.expect("valence") - 0.3).abs() < 1e-6);
+        assert!((hourly[0].avg_arousal.expect("arousal") - 0.5).abs() < 1e-6);
+        assert_eq!(hourly[1].samples, 1);
+        assert!((hourly[1].avg_valence.expect("valence") + 0.2).abs() < 1e-6);
+        assert_eq!(hourly[1].avg_arousal, None);
+        assert_eq!(hourly[2].samples, 1);
+
+        let two_hourly = db
+            .get_mood_timeseries(at("08:00"), chrono::Duration::hours(2))
+            .expect("two-hourly mood");
+        let summary: Vec<_> = two_hourly
+            .iter()
+            .map(|bucket| (bucket.bucket_start, bucket.samples))
+            .collect();
+        assert_eq!(summary, vec![(at("08:00"), 3), (at("10:00"), 1)]);
+
+        let _ = std::fs::remove_file(&path);
+    }
+
     #[test]
     fn orientation_snapshot_and_pending_thought_queue_roundtrip() {
         let path = temp_db_path("orientation_pending");
@@ -1317,6 +2262,12 @@ mod tests {
         assert_eq!(recent.len(), 1);
         assert_eq!(recent[0].id, snapshot.id);
         assert_eq!(recent[0].disposition, "observe");
+        assert_eq!(
+            db.get_latest_orientation_snapshot()
+                .expect("latest orientation")
+                .map(|latest| latest.id),
+            Some(snapshot.id.clone())
+        );
 
         let thought_a = PendingThoughtRecord {
             id: uuid::Uuid::new_v4().to_string(),
@@ -1377,6 +2328,7 @@ mod tests {
             "list_directory",
             "{\"path\":\".\"}",
             "Cargo.toml",
+            None,
         )
         .expect("record tool call");
         let agent_message_id = db
@@ -1411,6 +2363,24 @@ mod tests {
         };
         db.save_ooda_turn_packet(&packet).expect("save ooda packet");
 
+        let explanation = db
+            .get_turn_explanation(&turn_id)
+            .expect("load explanation")
+            .expect("explanation exists");
+        assert_eq!(
+            explanation.ooda_packet.map(|packet| packet.id),
+            Some(packet.id.clone())
+        );
+        let background_turn_id = db
+            .begin_chat_turn(&conversation.id, &[], CHAT_BACKGROUND_ITERATION_OFFSET + 1)
+            .expect("begin background turn");
+        assert!(
+            db.get_turn_explanation(&background_turn_id)
+                .expect("load background explanation")
+                .expect("background explanation exists")
+                .background_subtask
+        );
+
         let loaded = db
             .get_latest_ooda_turn_packet_for_conversation(&conversation.id)
             .expect("load conversation packet")
@@ -1449,7 +2419,12 @@ mod tests {
         assert_eq!(packets_before_latest[1].id, packet.id);
 
         let digest = db
-            .get_recent_action_digest_for_conversation(&conversation.id, 8, 1000)
+            .get_recent_action_digest_for_conversation(
+                &conversation.id,
+                8,
+                1000,
+                ActionDigestStrategy::Recency,
+            )
             .expect("digest");
         assert!(digest.contains("phase=completed"));
         assert!(digest.contains("decision=yield"));
@@ -1458,4 +2433,488 @@ mod tests {
 
         let _ = std::fs::remove_file(&path);
     }
+
+    #[test]
+    fn journal_export_writes_only_entries_in_range() {
+        let path = temp_db_path("journal_export");
+        let db = AgentDatabase::new(&path).expect("db init");
+        let at = |raw: &str| raw.parse::<chrono::DateTime<chrono::Utc>>().unwrap();
+        for (id, timestamp) in [
+            ("before", "2026-03-01T23:59:00Z"),
+            ("inside", "2026-03-02T09:30:00Z"),
+            ("after", "2026-03-03T00:00:00Z"),
+        ] {
+            db.add_journal_entry(&JournalEntry {
+                id: id.to_string(),
+                timestamp: at(timestamp),
+                entry_type: JournalEntryType::Observation,
+                content: format!("entry {}", id),
+                context: JournalContext {
+                    trigger: "test".to_string(),
+                    user_state_at_time: "idle".to_string(),
+                    time_of_day: "morning".to_string(),
+                },
+                related_concerns: Vec::new(),
+                mood_at_time: None,
+            })
+            .expect("save journal entry");
+        }
+
+        let dir = tempfile::tempdir().expect("tempdir");
+        let export = dir.path().join("journal").join("2026-03-02.md");
+        let day = at("2026-03-02T00:00:00Z")..at("2026-03-03T00:00:00Z");
+        let written = db
+            .export_journal_markdown(&export, day)
+            .expect("export journal");
+        assert_eq!(written, 1);
+        let markdown = std::fs::read_to_string(&export).expect("read export");
+        assert!(markdown.contains("## 2026-03-02"));
+        assert!(markdown.contains("entry inside"));
+        assert!(!markdown.contains("entry before"));
+        assert!(!markdown.contains("entry after"));
+
+        let empty = at("2026-04-01T00:00:00Z")..at("2026-04-02T00:00:00Z");
+        let missing = dir.path().join("journal").join("2026-04-01.md");
+        assert_eq!(db.export_journal_markdown(&missing, empty).unwrap(), 0);
+        assert!(!missing.exists());
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn journal_embeddings_are_scoped_to_their_model() {
+        let path = temp_db_path("journal_embeddings");
+        let db = AgentDatabase::new(&path).expect("db init");
+        let ids = vec!["entry-a".to_string(), "entry-b".to_string()];
+
+        db.store_journal_entry_embedding("entry-a", "embed-a", &[1.0, 0.5])
+            .expect("store");
+        db.store_journal_entry_embedding("entry-b", "embed-b", &[0.2, 0.4])
+            .expect("store other model");
+
+        let stored = db
+            .journal_entry_embeddings(&ids, "embed-a")
+            .expect("lookup");
+        assert_eq!(stored.len(), 1);
+        assert_eq!(stored["entry-a"], vec![1.0, 0.5]);
+        let stored_type: String = db
+            .lock_conn()
+            .unwrap()
+            .query_row(
+                "SELECT typeof(embedding) FROM journal_entry_embeddings WHERE entry_id = 'entry-a'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(stored_type, "blob");
+
+        db.store_journal_entry_embedding("entry-b", "embed-a", &[0.3, 0.1])
+            .expect("re-embed");
+        let stored = db
+            .journal_entry_embeddings(&ids, "embed-a")
+            .expect("lookup");
+        assert_eq!(stored.len(), 2);
+        assert!(db
+            .journal_entry_embeddings(&ids, "embed-b")
+            .expect("lookup")
+            .is_empty());
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn memory_embeddings_go_stale_when_the_entry_changes() {
+        let path = temp_db_path("memory_embeddings");
+        let db = AgentDatabase::new(&path).expect("db init");
+        db.set_working_memory("plants", "water the fern")
+            .expect("write");
+        let entry = db.get_working_memory("plants").unwrap().expect("entry");
+
+        db.store_working_memory_embedding(&entry, "embed-a", &[0.1, 0.9])
+            .expect("store");
+        let stored = db
+            .working_memory_embeddings(std::slice::from_ref(&entry), "embed-a")
+            .expect("lookup");
+        assert_eq!(stored["plants"], vec![0.1, 0.9]);
+        assert!(db
+            .working_memory_embeddings(std::slice::from_ref(&entry), "embed-b")
+            .expect("lookup")
+            .is_empty());
+
+        std::thread::sleep(std::time::Duration::from_millis(5));
+        db.set_working_memory("plants", "water the fern and the cactus")
+            .expect("rewrite");
+        let edited = db.get_working_memory("plants").unwrap().expect("entry");
+        assert!(db
+            .working_memory_embeddings(std::slice::from_ref(&edited), "embed-a")
+            .expect("lookup")
+            .is_empty());
+
+        db.store_working_memory_embedding(&edited, "embed-a", &[0.2, 0.8])
+            .expect("re-embed");
+        db.delete_working_memory("plants").expect("delete");
+        assert!(db
+            .working_memory_embeddings(&[edited], "embed-a")
+            .expect("lookup")
+            .is_empty());
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn journal_search_ranks_matches_and_tracks_replacements() {
+        let path = temp_db_path("journal_fts");
+        let db = AgentDatabase::new(&path).expect("db init");
+        let entry = |content: &str, minutes_ago: i64| JournalEntry {
+            id: uuid::Uuid::new_v4().to_string(),
+            timestamp: chrono::Utc::now() - ChronoDuration::minutes(minutes_ago),
+            entry_type: JournalEntryType::Reflection,
+            content: content.to_string(),
+            context: JournalContext {
+                trigger: "ambient_orientation".to_string(),
+                user_state_at_time: "idle".to_string(),
+                time_of_day: "evening".to_string(),
+            },
+            related_concerns: Vec::new(),
+            mood_at_time: None,
+        };
+        let project = entry("The thermal calibration project finally converged.", 30);
+        let passing = entry("Quick calibration check on the scale.", 5);
+        let unrelated = entry("Rain all afternoon.", 1);
+        for e in [&project, &passing, &unrelated] {
+            db.add_journal_entry(e).expect("save journal entry");
+        }
+
+        let found = db
+            .search_journal("what did I write about the thermal calibration project?", 5)
+            .expect("search journal");
+        let ids: Vec<_> = found.iter().map(|e| e.id.as_str()).collect();
+        assert_eq!(ids, vec![project.id.as_str(), passing.id.as_str()]);
+
+        let mut revised = project.clone();
+        revised.content = "Rewrote the kiln notes.".to_string();
+        db.add_journal_entry(&revised)
+            .expect("replace journal entry");
+        assert!(db.search_journal("thermal", 5).unwrap().is_empty());
+        let kiln = db.search_journal("kiln", 5).unwrap();
+        assert_eq!(kiln.len(), 1);
+        assert_eq!(kiln[0].id, project.id);
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn fresh_database_applies_every_migration_once() {
+        let path = temp_db_path("fresh_schema");
+        let db = AgentDatabase::new(&path).expect("db init");
+        assert_eq!(
+            db.schema_version().unwrap() as usize,
+            migrations::MIGRATIONS.len()
+        );
+        drop(db);
+
+        let db = AgentDatabase::new(&path).expect("reopen db");
+        let applied: i64 = db
+            .lock_conn()
+            .unwrap()
+            .query_row("SELECT COUNT(*) FROM schema_version", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(applied as usize, migrations::MIGRATIONS.len());
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn legacy_database_migrates_without_losing_rows() {
+        let path = temp_db_path("legacy_schema");
+        {
+            // Pre-versioning layout: chat tables without conversation,
+            // runtime, or prompt columns and no `schema_version` table.
+            let conn = Connection::open(&path).expect("open legacy db");
+            conn.execute_batch(
+                r#"CREATE TABLE chat_messages (
+                       id TEXT PRIMARY KEY,
+                       role TEXT NOT NULL,
+                       content TEXT NOT NULL,
+                       created_at TEXT NOT NULL,
+                       processed INTEGER NOT NULL DEFAULT 0
+                   );
+                   CREATE TABLE chat_conversations (
+                       id TEXT PRIMARY KEY,
+                       title TEXT NOT NULL,
+                       created_at TEXT NOT NULL,
+                       updated_at TEXT NOT NULL
+                   );
+                   CREATE TABLE chat_turns (
+                       id TEXT PRIMARY KEY,
+                       session_id TEXT NOT NULL,
+                       conversation_id TEXT NOT NULL,
+                       iteration INTEGER NOT NULL,
+                       phase_state TEXT NOT NULL,
+                       decision TEXT,
+                       status TEXT,
+                       trigger_message_ids_json TEXT NOT NULL,
+                       operator_message TEXT,
+                       reason TEXT,
+                       error TEXT,
+                       tool_call_count INTEGER NOT NULL DEFAULT 0,
+                       started_at TEXT NOT NULL,
+                       completed_at TEXT,
+                       agent_message_id TEXT
+                   );
+                   CREATE TABLE agent_state (key TEXT PRIMARY KEY, value TEXT NOT NULL);
+                   INSERT INTO agent_state (key, value) VALUES ('last_post', 'kept');
+                   INSERT INTO chat_conversations (id, title, created_at, updated_at)
+                       VALUES ('legacy-thread', 'Old thread',
+                               '2025-01-01T00:00:00+00:00', '2025-01-01T00:00:00+00:00');
+                   INSERT INTO chat_messages (id, role, content, created_at, processed)
+                       VALUES ('m1', 'operator', 'Remember the kiln.',
+                               '2025-01-01T00:00:00+00:00', 1);"#,
+            )
+            .expect("seed legacy schema");
+        }
+
+        let db = AgentDatabase::new(&path).expect("migrate legacy db");
+        assert_eq!(
+            db.schema_version().unwrap() as usize,
+            migrations::MIGRATIONS.len()
+        );
+        assert_eq!(db.get_state("last_post").unwrap().as_deref(), Some("kept"));
+
+        let history = db
+            .get_chat_history_for_conversation(DEFAULT_CHAT_CONVERSATION_ID, 10)
+            .expect("legacy messages move to the default conversation");
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].content, "Remember the kiln.");
+
+        let legacy = db
+            .get_chat_conversation("legacy-thread")
+            .unwrap()
+            .expect("legacy conversation kept");
+        assert_eq!(legacy.title, "Old thread");
+        assert_eq!(legacy.session_id, DEFAULT_CHAT_SESSION_ID);
+        assert_eq!(legacy.runtime_state, ChatTurnPhase::Idle);
+
+        let turn_id = db
+            .begin_chat_turn("legacy-thread", &[], 1)
+            .expect("turn after migration");
+        db.set_chat_turn_prompt(&turn_id, "prompt")
+            .expect("prompt column added");
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn delete_conversation_removes_only_the_targeted_thread() {
+        let path = temp_db_path("delete_conversation");
+        let db = AgentDatabase::new(&path).expect("db init");
+
+        let seed = |title: &str| {
+            let conversation = db
+                .create_chat_conversation(Some(title))
+                .expect("create conversation");
+            let message_id = db
+                .add_chat_message_in_conversation(&conversation.id, "operator", "Hello")
+                .expect("insert message");
+            let turn_id = db
+                .begin_chat_turn(&conversation.id, std::slice::from_ref(&message_id), 1)
+                .expect("begin turn");
+            db.record_chat_turn_tool_call(
+                &turn_id,
+                0,
+                "list_directory",
+                r#"{"path":"."}"#,
+                "a.txt",
+                None,
+            )
+            .expect("record tool call");
+            db.upsert_chat_conversation_summary(&conversation.id, "Greeting.", 1)
+                .expect("save summary");
+            db.save_ooda_turn_packet(&OodaTurnPacketRecord {
+                id: uuid::Uuid::new_v4().to_string(),
+                conversation_id: conversation.id.clone(),
+                turn_id: Some(turn_id.clone()),
+                observe: "Greeting".to_string(),
+                orient: "Small talk".to_string(),
+                decide: "Reply".to_string(),
+                act: "Replied".to_string(),
+                created_at: chrono::Utc::now(),
+            })
+            .expect("save ooda packet");
+            (conversation.id, turn_id)
+        };
+        let (stale_id, stale_turn) = seed("Stale");
+        let (kept_id, kept_turn) = seed("Kept");
+
+        let listed = db.list_chat_conversations(50).expect("list conversations");
+        let kept = listed
+            .iter()
+            .find(|c| c.id == kept_id)
+            .expect("kept listed");
+        assert_eq!(kept.message_count, 1);
+        assert!(kept.last_message_at.is_some());
+        assert!(kept.summary_present);
+
+        db.delete_chat_conversation(&stale_id)
+            .expect("delete conversation");
+
+        assert!(db.get_chat_conversation(&stale_id).unwrap().is_none());
+        assert_eq!(
+            db.count_chat_messages_for_conversation(&stale_id).unwrap(),
+            0
+        );
+        assert!(db
+            .list_chat_turns_for_conversation(&stale_id, 10)
+            .unwrap()
+            .is_empty());
+        assert!(db
+            .list_chat_turn_tool_calls(&stale_turn)
+            .unwrap()
+            .is_empty());
+        assert!(db
+            .get_chat_conversation_summary(&stale_id)
+            .unwrap()
+            .is_none());
+        assert!(db
+            .get_latest_ooda_turn_packet_for_conversation(&stale_id)
+            .unwrap()
+            .is_none());
+
+        let kept = db
+            .get_chat_conversation(&kept_id)
+            .unwrap()
+            .expect("kept conversation survives");
+        assert_eq!(kept.message_count, 1);
+        assert!(kept.summary_present);
+        assert_eq!(db.list_chat_turn_tool_calls(&kept_turn).unwrap().len(), 1);
+        assert!(db
+            .get_latest_ooda_turn_packet_for_conversation(&kept_id)
+            .unwrap()
+            .is_some());
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn persona_timeline_diffs_each_snapshot_against_its_predecessor() {
+        let path = temp_db_path("persona_timeline");
+        let db = AgentDatabase::new(&path).expect("db init");
+        let base = Utc::now() - ChronoDuration::hours(3);
+        for (index, curious) in [0.2, 0.5, 0.9].into_iter().enumerate() {
+            db.save_persona_snapshot(&PersonaSnapshot {
+                id: format!("snap-{}", index),
+                captured_at: base + ChronoDuration::hours(index as i64),
+                traits: PersonaTraits {
+                    dimensions: [("curious".to_string(), curious)].into_iter().collect(),
+                },
+                system_prompt: "prompt".to_string(),
+                trigger: "scheduled_reflection".to_string(),
+                self_description: format!("Snapshot {}.", index),
+                inferred_trajectory: None,
+                formative_experiences: Vec::new(),
+            })
+            .expect("save snapshot");
+        }
+
+        let timeline = db.get_persona_timeline(2).expect("timeline");
+        let ids: Vec<&str> = timeline
+            .iter()
+            .map(|entry| entry.snapshot.id.as_str())
+            .collect();
+        assert_eq!(ids, vec!["snap-1", "snap-2"]);
+        // The oldest shown entry is still diffed against the hidden snapshot before it.
+        let first = timeline[0].diff.as_ref().expect("diff against snap-0");
+        assert_eq!(first.from_id, "snap-0");
+        assert!((first.valence_drift - 0.3).abs() < 1e-9);
+        assert_eq!(timeline[1].diff.as_ref().unwrap().from_id, "snap-1");
+
+        let full = db.get_persona_timeline(10).expect("timeline");
+        assert!(full[0].diff.is_none());
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn action_digest_strategies_select_turns_differently() {
+        let path = temp_db_path("action_digest_strategies");
+        let db = AgentDatabase::new(&path).expect("db init");
+        let conversation = db
+            .create_chat_conversation(Some("Digest strategies"))
+            .expect("create conversation");
+        // Oldest first: one failing shell run, then five identical idle check-ins.
+        let turns: [(&str, &str, &[&str]); 6] = [
+            ("continue", "still_working", &["shell"]),
+            ("yield", "done", &[]),
+            ("yield", "done", &[]),
+            ("yield", "done", &[]),
+            ("yield", "done", &[]),
+            ("yield", "done", &[]),
+        ];
+        let base = chrono::Utc::now() - ChronoDuration::hours(1);
+        for (index, (decision, status, tools)) in turns.iter().enumerate() {
+            let turn_id = db
+                .begin_chat_turn(&conversation.id, &[], index as i64 + 1)
+                .expect("begin turn");
+            for (call_index, tool) in tools.iter().enumerate() {
+                db.record_chat_turn_tool_call(&turn_id, call_index, tool, "{}", "ok", None)
+                    .expect("record tool call");
+            }
+            db.complete_chat_turn(
+                &turn_id,
+                ChatTurnPhase::Completed,
+                decision,
+                status,
+                "",
+                None,
+                tools.len(),
+                None,
+            )
+            .expect("complete turn");
+            db.lock_conn()
+                .unwrap()
+                .execute(
+                    "UPDATE chat_turns SET started_at = ?2 WHERE id = ?1",
+                    rusqlite::params![
+                        turn_id,
+                        (base + ChronoDuration::minutes(index as i64)).to_rfc3339()
+                    ],
+                )
+                .expect("pin started_at");
+        }
+        let digest = |strategy| {
+            db.get_recent_action_digest_for_conversation(&conversation.id, 3, 4000, strategy)
+                .expect("digest")
+        };
+
+        let recency = digest(ActionDigestStrategy::Recency);
+        assert_eq!(recency.lines().count(), 3);
+        assert!(!recency.contains("(shell)"));
+
+        let salience = digest(ActionDigestStrategy::Salience);
+        let salience_lines: Vec<&str> = salience.lines().collect();
+        assert_eq!(salience_lines.len(), 3);
+        // The newest turn stays first; the early shell run outranks idle turns.
+        assert!(salience_lines[0].starts_with(recency.lines().next().unwrap()));
+        assert!(salience_lines[2].contains("status=still_working tools=1 (shell)"));
+
+        let compressed = digest(ActionDigestStrategy::Compressed);
+        let compressed_lines: Vec<&str> = compressed.lines().collect();
+        assert_eq!(compressed_lines.len(), 2);
+        assert!(compressed_lines[0].contains("decision=yield status=done"));
+        let oldest_idle = (base + ChronoDuration::minutes(1)).to_rfc3339();
+        assert!(compressed_lines[0].contains(&format!("[x5 since {}]", oldest_idle)));
+        assert!(compressed_lines[1].contains("(shell)"));
+        assert!(!compressed_lines[1].contains("[x"));
+
+        assert_eq!(
+            ActionDigestStrategy::from_config(" Salience "),
+            ActionDigestStrategy::Salience
+        );
+        assert_eq!(
+            ActionDigestStrategy::from_config("unknown"),
+            ActionDigestStrategy::Recency
+        );
+
+        let _ = std::fs::remove_file(&path);
+    }
 }
@@ -1,8 +1,10 @@
 use anyhow::{Context, Result};
 use chrono::Utc;
-use rusqlite::params;
+use rusqlite::{params, Connection};
 use serde::{Deserialize, Serialize};
 
+use crate::agent::orientation::AnomalySeverity;
+
 use super::AgentDatabase;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -31,6 +33,110 @@ pub struct PendingThoughtRecord {
     pub dismissed_at: Option<chrono::DateTime<Utc>>,
 }
 
+/// Average mood over one fixed-width time bucket. Averages skip snapshots
+/// that did not record that dimension; empty buckets are not returned.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MoodBucket {
+    pub bucket_start: chrono::DateTime<Utc>,
+    pub avg_valence: Option<f32>,
+    pub avg_arousal: Option<f32>,
+    pub samples: usize,
+}
+
+/// Running sums for one bucket while snapshots are folded in.
+#[derive(Default)]
+struct MoodAccumulator {
+    valence_sum: f64,
+    valence_count: usize,
+    arousal_sum: f64,
+    arousal_count: usize,
+    samples: usize,
+}
+
+impl MoodAccumulator {
+    fn add(&mut self, valence: Option<f32>, arousal: Option<f32>) {
+        if let Some(valence) = valence {
+            self.valence_sum += f64::from(valence);
+            self.valence_count += 1;
+        }
+        if let Some(arousal) = arousal {
+            self.arousal_sum += f64::from(arousal);
+            self.arousal_count += 1;
+        }
+        self.samples += 1;
+    }
+
+    fn finish(self, bucket_start: chrono::DateTime<Utc>) -> MoodBucket {
+        let average = |sum: f64, count: usize| (count > 0).then(|| (sum / count as f64) as f32);
+        MoodBucket {
+            bucket_start,
+            avg_valence: average(self.valence_sum, self.valence_count),
+            avg_arousal: average(self.arousal_sum, self.arousal_count),
+            samples: self.samples,
+        }
+    }
+}
+
+/// Start of the `bucket`-wide window containing `timestamp`, aligned to the
+/// Unix epoch so hourly buckets start on the hour.
+fn mood_bucket_start(
+    timestamp: chrono::DateTime<Utc>,
+    bucket_secs: i64,
+) -> Option<chrono::DateTime<Utc>> {
+    let secs = timestamp.timestamp();
+    chrono::DateTime::from_timestamp(secs - secs.rem_euclid(bucket_secs), 0)
+}
+
+/// Last time an anomaly fingerprint was surfaced to chat, and whether the
+/// operator has dismissed it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SurfacedAnomalyRecord {
+    pub fingerprint: String,
+    pub description: String,
+    pub severity: AnomalySeverity,
+    pub last_surfaced_at: chrono::DateTime<Utc>,
+    pub acknowledged_at: Option<chrono::DateTime<Utc>>,
+}
+
+pub(super) fn create_surfaced_anomalies_table(conn: &Connection) -> Result<()> {
+    conn.execute(
+        r#"CREATE TABLE IF NOT EXISTS surfaced_anomalies (
+               fingerprint TEXT PRIMARY KEY,
+               description TEXT NOT NULL,
+               severity TEXT NOT NULL,
+               last_surfaced_at TEXT NOT NULL,
+               acknowledged_at TEXT
+           )"#,
+        [],
+    )?;
+    Ok(())
+}
+
+fn parse_surfaced_anomaly_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<SurfacedAnomalyRecord> {
+    let severity_raw: String = row.get(2)?;
+    let surfaced_raw: String = row.get(3)?;
+    let acknowledged_raw: Option<String> = row.get(4)?;
+    let parse = |index: usize, raw: &str| {
+        raw.parse::<chrono::DateTime<Utc>>().map_err(|e| {
+            rusqlite::Error::FromSqlConversionFailure(
+                index,
+                rusqlite::types::Type::Text,
+                Box::new(e),
+            )
+        })
+    };
+    Ok(SurfacedAnomalyRecord {
+        fingerprint: row.get(0)?,
+        description: row.get(1)?,
+        severity: AnomalySeverity::from_db(&severity_raw),
+        last_surfaced_at: parse(3, &surfaced_raw)?,
+        acknowledged_at: acknowledged_raw
+            .as_deref()
+            .map(|raw| parse(4, raw))
+            .transpose()?,
+    })
+}
+
 impl AgentDatabase {
     pub fn save_orientation_snapshot(&self, orientation: &OrientationSnapshotRecord) -> Result<()> {
         let conn = self.lock_conn()?;
@@ -132,6 +238,74 @@ impl AgentDatabase {
         Ok(snapshots)
     }
 
+    /// Most recent orientation snapshot, used to rehydrate `last_orientation` after restart.
+    pub fn get_latest_orientation_snapshot(&self) -> Result<Option<OrientationSnapshotRecord>> {
+        Ok(self.get_recent_orientations(1)?.into_iter().next())
+    }
+
+    /// Average mood per `bucket` window for snapshots at or after `since`,
+    /// oldest bucket first. Snapshots without any mood reading are skipped.
+    /// Buckets narrower than one second are widened to one second.
+    pub fn get_mood_timeseries(
+        &self,
+        since: chrono::DateTime<Utc>,
+        bucket: chrono::Duration,
+    ) -> Result<Vec<MoodBucket>> {
+        let bucket_secs = bucket.num_seconds().max(1);
+        let conn = self.lock_conn()?;
+        let mut stmt = conn.prepare(
+            "SELECT timestamp, mood_valence, mood_arousal
+             FROM orientation_snapshots
+             WHERE timestamp >= ?1
+               AND (mood_valence IS NOT NULL OR mood_arousal IS NOT NULL)
+             ORDER BY timestamp ASC",
+        )?;
+        let rows = stmt
+            .query_map([since.to_rfc3339()], |row| {
+                let timestamp_raw: String = row.get(0)?;
+                let timestamp = timestamp_raw
+                    .parse::<chrono::DateTime<Utc>>()
+                    .map_err(|e| {
+                        rusqlite::Error::FromSqlConversionFailure(
+                            0,
+                            rusqlite::types::Type::Text,
+                            Box::new(e),
+                        )
+                    })?;
+                Ok((
+                    timestamp,
+                    row.get::<_, Option<f32>>(1)?,
+                    row.get::<_, Option<f32>>(2)?,
+                ))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut buckets = Vec::new();
+        let mut current: Option<(chrono::DateTime<Utc>, MoodAccumulator)> = None;
+        for (timestamp, valence, arousal) in rows {
+            let Some(start) = mood_bucket_start(timestamp, bucket_secs) else {
+                continue;
+            };
+            match current.as_mut() {
+                Some((current_start, accumulator)) if *current_start == start => {
+                    accumulator.add(valence, arousal);
+                }
+                _ => {
+                    if let Some((finished_start, accumulator)) = current.take() {
+                        buckets.push(accumulator.finish(finished_start));
+                    }
+                    let mut accumulator = MoodAccumulator::default();
+                    accumulator.add(valence, arousal);
+                    current = Some((start, accumulator));
+                }
+            }
+        }
+        if let Some((start, accumulator)) = current {
+            buckets.push(accumulator.finish(start));
+        }
+        Ok(buckets)
+    }
+
     // ========================================================================
     // Living Loop Foundation - Pending Thought Queue
     // ========================================================================
@@ -243,4 +417,70 @@ impl AgentDatabase {
         )?;
         Ok(())
     }
+
+    /// Records that an anomaly was surfaced at `at` with `severity`. A
+    /// re-surface clears any earlier acknowledgement.
+    pub fn record_anomaly_surfaced(
+        &self,
+        fingerprint: &str,
+        description: &str,
+        severity: AnomalySeverity,
+        at: chrono::DateTime<Utc>,
+    ) -> Result<()> {
+        let conn = self.lock_conn()?;
+        conn.execute(
+            "INSERT OR REPLACE INTO surfaced_anomalies
+             (fingerprint, description, severity, last_surfaced_at, acknowledged_at)
+             VALUES (?1, ?2, ?3, ?4, NULL)",
+            params![
+                fingerprint,
+                description,
+                severity.as_db_str(),
+                at.to_rfc3339()
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn get_surfaced_anomaly(&self, fingerprint: &str) -> Result<Option<SurfacedAnomalyRecord>> {
+        let conn = self.lock_conn()?;
+        let result = conn.query_row(
+            "SELECT fingerprint, description, severity, last_surfaced_at, acknowledged_at
+             FROM surfaced_anomalies
+             WHERE fingerprint = ?1",
+            [fingerprint],
+            parse_surfaced_anomaly_row,
+        );
+        match result {
+            Ok(record) => Ok(Some(record)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Most recently surfaced anomalies first.
+    pub fn list_surfaced_anomalies(&self, limit: usize) -> Result<Vec<SurfacedAnomalyRecord>> {
+        let conn = self.lock_conn()?;
+        let mut stmt = conn.prepare(
+            "SELECT fingerprint, description, severity, last_surfaced_at, acknowledged_at
+             FROM surfaced_anomalies
+             ORDER BY last_surfaced_at DESC
+             LIMIT ?1",
+        )?;
+        let records = stmt
+            .query_map([limit as i64], parse_surfaced_anomaly_row)?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(records)
+    }
+
+    /// Operator dismissal: the anomaly stays quiet until it escalates.
+    /// Returns `false` when the fingerprint was never surfaced.
+    pub fn acknowledge_anomaly(&self, fingerprint: &str) -> Result<bool> {
+        let conn = self.lock_conn()?;
+        let rows = conn.execute(
+            "UPDATE surfaced_anomalies SET acknowledged_at = ?1 WHERE fingerprint = ?2",
+            params![Utc::now().to_rfc3339(), fingerprint],
+        )?;
+        Ok(rows > 0)
+    }
 }
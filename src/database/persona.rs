@@ -5,6 +5,7 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 use super::AgentDatabase;
+use crate::agent::trajectory::{diff_snapshots, PersonaDiff};
 
 /// A snapshot of the agent's persona at a point in time.
 /// This is the core data structure for "Ludonarrative Assonantic Tracing" -
@@ -52,6 +53,14 @@ pub struct PersonaTraits {
     pub dimensions: HashMap<String, f64>,
 }
 
+/// A persona snapshot paired with what changed since the snapshot before it.
+/// `diff` is `None` only for the oldest snapshot ever captured.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PersonaTimelineEntry {
+    pub snapshot: PersonaSnapshot,
+    pub diff: Option<PersonaDiff>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ReflectionRecord {
     pub id: String,
@@ -351,6 +360,25 @@ impl AgentDatabase {
         Ok(snapshots)
     }
 
+    /// Get the most recent `limit` snapshots oldest-first, each diffed against
+    /// its predecessor (fetched even when it falls outside `limit`).
+    pub fn get_persona_timeline(&self, limit: usize) -> Result<Vec<PersonaTimelineEntry>> {
+        let mut history = self.get_persona_history(limit.saturating_add(1))?;
+        history.reverse();
+        let skip = history.len().saturating_sub(limit);
+        Ok(history
+            .iter()
+            .enumerate()
+            .skip(skip)
+            .map(|(index, snapshot)| PersonaTimelineEntry {
+                snapshot: snapshot.clone(),
+                diff: index
+                    .checked_sub(1)
+                    .map(|previous| diff_snapshots(&history[previous], snapshot)),
+            })
+            .collect())
+    }
+
     /// Get the most recent persona snapshot
     pub fn get_latest_persona(&self) -> Result<Option<PersonaSnapshot>> {
         let snapshots = self.get_persona_history(1)?;
@@ -0,0 +1,132 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+
+use super::AgentDatabase;
+
+/// A one-shot, time-based reminder surfaced by the heartbeat once due.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Reminder {
+    pub id: String,
+    pub content: String,
+    pub due_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+    /// Set once a heartbeat has handed the reminder to the tool loop.
+    pub delivered_at: Option<DateTime<Utc>>,
+}
+
+pub(super) fn create_reminders_table(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        r#"CREATE TABLE IF NOT EXISTS reminders (
+               id TEXT PRIMARY KEY,
+               content TEXT NOT NULL,
+               due_at TEXT NOT NULL,
+               created_at TEXT NOT NULL,
+               delivered_at TEXT
+           );
+           CREATE INDEX IF NOT EXISTS idx_reminders_pending
+           ON reminders(delivered_at, due_at ASC);"#,
+    )?;
+    Ok(())
+}
+
+fn parse_timestamp(idx: usize, raw: &str) -> rusqlite::Result<DateTime<Utc>> {
+    raw.parse().map_err(|error| {
+        rusqlite::Error::FromSqlConversionFailure(idx, rusqlite::types::Type::Text, Box::new(error))
+    })
+}
+
+fn parse_reminder_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<Reminder> {
+    let due_at: String = row.get(2)?;
+    let created_at: String = row.get(3)?;
+    let delivered_at: Option<String> = row.get(4)?;
+    Ok(Reminder {
+        id: row.get(0)?,
+        content: row.get(1)?,
+        due_at: parse_timestamp(2, &due_at)?,
+        created_at: parse_timestamp(3, &created_at)?,
+        delivered_at: delivered_at
+            .map(|raw| parse_timestamp(4, &raw))
+            .transpose()?,
+    })
+}
+
+impl AgentDatabase {
+    pub fn create_reminder(&self, content: &str, due_at: DateTime<Utc>) -> Result<Reminder> {
+        let reminder = Reminder {
+            id: uuid::Uuid::new_v4().to_string(),
+            content: content.trim().to_string(),
+            due_at,
+            created_at: Utc::now(),
+            delivered_at: None,
+        };
+        let conn = self.lock_conn()?;
+        conn.execute(
+            "INSERT INTO reminders (id, content, due_at, created_at, delivered_at)
+             VALUES (?1, ?2, ?3, ?4, NULL)",
+            params![
+                &reminder.id,
+                &reminder.content,
+                reminder.due_at.to_rfc3339(),
+                reminder.created_at.to_rfc3339(),
+            ],
+        )?;
+        Ok(reminder)
+    }
+
+    /// Undelivered reminders ordered by due time.
+    pub fn list_pending_reminders(&self, limit: usize) -> Result<Vec<Reminder>> {
+        let conn = self.lock_conn()?;
+        let mut stmt = conn.prepare(
+            "SELECT id, content, due_at, created_at, delivered_at
+             FROM reminders
+             WHERE delivered_at IS NULL
+             ORDER BY due_at ASC
+             LIMIT ?1",
+        )?;
+        let reminders = stmt
+            .query_map([limit as i64], parse_reminder_row)?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(reminders)
+    }
+
+    /// Undelivered reminders whose `due_at` is at or before `now`.
+    pub fn get_due_reminders(&self, now: DateTime<Utc>, limit: usize) -> Result<Vec<Reminder>> {
+        let conn = self.lock_conn()?;
+        let mut stmt = conn.prepare(
+            "SELECT id, content, due_at, created_at, delivered_at
+             FROM reminders
+             WHERE delivered_at IS NULL AND due_at <= ?1
+             ORDER BY due_at ASC
+             LIMIT ?2",
+        )?;
+        let reminders = stmt
+            .query_map(params![now.to_rfc3339(), limit as i64], parse_reminder_row)?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(reminders)
+    }
+
+    pub fn mark_reminders_delivered(&self, ids: &[String], at: DateTime<Utc>) -> Result<()> {
+        let mut conn = self.lock_conn()?;
+        let tx = conn.transaction()?;
+        for id in ids {
+            tx.execute(
+                "UPDATE reminders SET delivered_at = ?1 WHERE id = ?2 AND delivered_at IS NULL",
+                params![at.to_rfc3339(), id],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Removes an undelivered reminder; delivered ones are kept as history.
+    pub fn cancel_reminder(&self, id: &str) -> Result<bool> {
+        let conn = self.lock_conn()?;
+        let rows = conn.execute(
+            "DELETE FROM reminders WHERE id = ?1 AND delivered_at IS NULL",
+            [id],
+        )?;
+        Ok(rows > 0)
+    }
+}
@@ -1,12 +1,24 @@
 use anyhow::Result;
 use chrono::{DateTime, Utc};
-use rusqlite::params;
+use rusqlite::{params, Connection};
 
 use crate::scheduled_jobs::ScheduledJob;
 
 use super::chat::{ChatTurnPhase, DEFAULT_CHAT_SESSION_ID};
 use super::AgentDatabase;
 
+const SCHEDULED_JOB_COLUMNS: &str = "id, name, prompt, interval_minutes, conversation_id, enabled, last_run_at, next_run_at, created_at, updated_at, capability_profile";
+
+pub(super) fn add_scheduled_job_capability_profile_column(conn: &Connection) -> Result<()> {
+    if !AgentDatabase::table_has_column(conn, "scheduled_jobs", "capability_profile")? {
+        conn.execute(
+            "ALTER TABLE scheduled_jobs ADD COLUMN capability_profile TEXT",
+            [],
+        )?;
+    }
+    Ok(())
+}
+
 impl AgentDatabase {
     pub(super) fn parse_scheduled_job_row(
         row: &rusqlite::Row<'_>,
@@ -54,6 +66,7 @@ impl AgentDatabase {
                     Box::new(error),
                 )
             })?,
+            capability_profile: row.get(10)?,
         })
     }
 
@@ -89,6 +102,7 @@ impl AgentDatabase {
             next_run_at: ScheduledJob::next_run_after(now, interval_minutes),
             created_at: now,
             updated_at: now,
+            capability_profile: None,
         };
         let now_str = now.to_rfc3339();
         let conn = self.lock_conn()?;
@@ -135,12 +149,12 @@ impl AgentDatabase {
 
     pub fn list_scheduled_jobs(&self, limit: usize) -> Result<Vec<ScheduledJob>> {
         let conn = self.lock_conn()?;
-        let mut stmt = conn.prepare(
-            "SELECT id, name, prompt, interval_minutes, conversation_id, enabled, last_run_at, next_run_at, created_at, updated_at
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {SCHEDULED_JOB_COLUMNS}
              FROM scheduled_jobs
              ORDER BY enabled DESC, next_run_at ASC
-             LIMIT ?1",
-        )?;
+             LIMIT ?1"
+        ))?;
         let jobs = stmt
             .query_map([limit as i64], Self::parse_scheduled_job_row)?
             .collect::<std::result::Result<Vec<_>, _>>()?;
@@ -149,11 +163,11 @@ impl AgentDatabase {
 
     pub fn get_scheduled_job(&self, job_id: &str) -> Result<Option<ScheduledJob>> {
         let conn = self.lock_conn()?;
-        let mut stmt = conn.prepare(
-            "SELECT id, name, prompt, interval_minutes, conversation_id, enabled, last_run_at, next_run_at, created_at, updated_at
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {SCHEDULED_JOB_COLUMNS}
              FROM scheduled_jobs
-             WHERE id = ?1",
-        )?;
+             WHERE id = ?1"
+        ))?;
         let mut rows = stmt.query([job_id])?;
         let Some(row) = rows.next()? else {
             return Ok(None);
@@ -161,6 +175,49 @@ impl AgentDatabase {
         Ok(Some(Self::parse_scheduled_job_row(row)?))
     }
 
+    /// The job whose dedicated conversation is `conversation_id`, if any.
+    pub fn get_scheduled_job_by_conversation(
+        &self,
+        conversation_id: &str,
+    ) -> Result<Option<ScheduledJob>> {
+        let conn = self.lock_conn()?;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {SCHEDULED_JOB_COLUMNS}
+             FROM scheduled_jobs
+             WHERE conversation_id = ?1"
+        ))?;
+        let mut rows = stmt.query([conversation_id])?;
+        let Some(row) = rows.next()? else {
+            return Ok(None);
+        };
+        Ok(Some(Self::parse_scheduled_job_row(row)?))
+    }
+
+    /// Select the named capability profile the job runs under; `None` or a
+    /// blank name restores the built-in `scheduled` profile.
+    pub fn set_scheduled_job_capability_profile(
+        &self,
+        job_id: &str,
+        profile: Option<&str>,
+    ) -> Result<Option<ScheduledJob>> {
+        let profile = profile
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(str::to_string);
+        let now = Utc::now();
+        {
+            let conn = self.lock_conn()?;
+            let rows = conn.execute(
+                "UPDATE scheduled_jobs SET capability_profile = ?1, updated_at = ?2 WHERE id = ?3",
+                params![profile, now.to_rfc3339(), job_id],
+            )?;
+            if rows == 0 {
+                return Ok(None);
+            }
+        }
+        self.get_scheduled_job(job_id)
+    }
+
     pub fn update_scheduled_job(
         &self,
         job_id: &str,
@@ -255,13 +312,13 @@ impl AgentDatabase {
     ) -> Result<Vec<ScheduledJob>> {
         let mut conn = self.lock_conn()?;
         let tx = conn.transaction()?;
-        let mut stmt = tx.prepare(
-            "SELECT id, name, prompt, interval_minutes, conversation_id, enabled, last_run_at, next_run_at, created_at, updated_at
+        let mut stmt = tx.prepare(&format!(
+            "SELECT {SCHEDULED_JOB_COLUMNS}
              FROM scheduled_jobs
              WHERE enabled = 1 AND next_run_at <= ?1
              ORDER BY next_run_at ASC
-             LIMIT ?2",
-        )?;
+             LIMIT ?2"
+        ))?;
         let mut jobs = stmt
             .query_map(
                 params![now.to_rfc3339(), limit as i64],
@@ -0,0 +1,102 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use super::AgentDatabase;
+
+/// An operator-approved tool call signature that later matching calls reuse
+/// instead of returning `NeedsApproval`. Matching lives in `tools::approval`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RememberedToolApproval {
+    pub id: String,
+    pub tool_name: String,
+    /// `Tool::authorization_provider` of the registration that was approved.
+    pub provider: String,
+    /// Non-path arguments, matched exactly.
+    pub arguments: serde_json::Value,
+    /// Path argument name -> normalized directory prefix it may fall under.
+    pub path_scopes: BTreeMap<String, String>,
+    pub created_at: DateTime<Utc>,
+}
+
+pub(super) fn create_tool_approvals_table(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        r#"CREATE TABLE IF NOT EXISTS remembered_tool_approvals (
+               id TEXT PRIMARY KEY,
+               tool_name TEXT NOT NULL,
+               provider TEXT NOT NULL,
+               arguments TEXT NOT NULL,
+               path_scopes TEXT NOT NULL,
+               created_at TEXT NOT NULL
+           );"#,
+    )?;
+    Ok(())
+}
+
+fn json_column<T: serde::de::DeserializeOwned>(idx: usize, raw: &str) -> rusqlite::Result<T> {
+    serde_json::from_str(raw).map_err(|error| {
+        rusqlite::Error::FromSqlConversionFailure(idx, rusqlite::types::Type::Text, Box::new(error))
+    })
+}
+
+fn parse_approval_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<RememberedToolApproval> {
+    let arguments: String = row.get(3)?;
+    let path_scopes: String = row.get(4)?;
+    let created_at: String = row.get(5)?;
+    Ok(RememberedToolApproval {
+        id: row.get(0)?,
+        tool_name: row.get(1)?,
+        provider: row.get(2)?,
+        arguments: json_column(3, &arguments)?,
+        path_scopes: json_column(4, &path_scopes)?,
+        created_at: created_at.parse().map_err(|error| {
+            rusqlite::Error::FromSqlConversionFailure(
+                5,
+                rusqlite::types::Type::Text,
+                Box::new(error),
+            )
+        })?,
+    })
+}
+
+impl AgentDatabase {
+    pub fn save_remembered_tool_approval(&self, approval: &RememberedToolApproval) -> Result<()> {
+        let conn = self.lock_conn()?;
+        conn.execute(
+            "INSERT OR REPLACE INTO remembered_tool_approvals
+                 (id, tool_name, provider, arguments, path_scopes, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                &approval.id,
+                &approval.tool_name,
+                &approval.provider,
+                serde_json::to_string(&approval.arguments)?,
+                serde_json::to_string(&approval.path_scopes)?,
+                approval.created_at.to_rfc3339(),
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Every remembered approval, oldest first.
+    pub fn list_remembered_tool_approvals(&self) -> Result<Vec<RememberedToolApproval>> {
+        let conn = self.lock_conn()?;
+        let mut stmt = conn.prepare(
+            "SELECT id, tool_name, provider, arguments, path_scopes, created_at
+             FROM remembered_tool_approvals
+             ORDER BY created_at ASC",
+        )?;
+        let approvals = stmt
+            .query_map([], parse_approval_row)?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(approvals)
+    }
+
+    pub fn delete_remembered_tool_approval(&self, id: &str) -> Result<bool> {
+        let conn = self.lock_conn()?;
+        let rows = conn.execute("DELETE FROM remembered_tool_approvals WHERE id = ?1", [id])?;
+        Ok(rows > 0)
+    }
+}
@@ -0,0 +1,294 @@
+//! Outbound webhook that forwards agent events to an operator endpoint.
+//!
+//! When `event_webhook_url` is set, a tokio task subscribes to the same
+//! `ApiEventEnvelope` broadcast that feeds `/v1/ws/events` and POSTs each
+//! envelope as JSON. The broadcast receiver is the bounded queue: a slow
+//! endpoint lags and drops the oldest envelopes instead of stalling the agent.
+//!
+//! No new dependencies — uses existing `reqwest` for HTTP.
+
+use std::time::Duration;
+
+use tokio::sync::{broadcast, Mutex};
+use tokio::task::JoinHandle;
+
+use crate::server::ApiEventEnvelope;
+
+/// Delivery attempts per envelope, including the first.
+const WEBHOOK_MAX_ATTEMPTS: u32 = 3;
+/// Delay before the first retry; doubles on each further retry.
+const WEBHOOK_RETRY_BASE_DELAY: Duration = Duration::from_millis(500);
+const WEBHOOK_REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Which event types (`ApiEventEnvelope::event_type`) are forwarded.
+/// An empty list forwards everything.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct EventFilter {
+    event_types: Vec<String>,
+}
+
+impl EventFilter {
+    fn new(event_types: &[String]) -> Self {
+        let mut event_types: Vec<String> = event_types
+            .iter()
+            .map(|name| name.trim().to_ascii_lowercase())
+            .filter(|name| !name.is_empty())
+            .collect();
+        event_types.sort();
+        event_types.dedup();
+        Self { event_types }
+    }
+
+    fn allows(&self, event_type: &str) -> bool {
+        self.event_types.is_empty()
+            || self
+                .event_types
+                .iter()
+                .any(|name| name.eq_ignore_ascii_case(event_type))
+    }
+}
+
+// ─── Public entry point ───────────────────────────────────────────────────────
+
+pub struct EventWebhookManager {
+    task: Mutex<Option<EventWebhookTask>>,
+}
+
+struct EventWebhookTask {
+    url: String,
+    filter: EventFilter,
+    join: JoinHandle<()>,
+}
+
+impl EventWebhookManager {
+    pub fn new() -> Self {
+        Self {
+            task: Mutex::new(None),
+        }
+    }
+
+    /// Start, stop, or restart the forwarder to match the latest config.
+    pub async fn reconfigure(
+        &self,
+        events: &broadcast::Sender<ApiEventEnvelope>,
+        url: Option<String>,
+        event_types: &[String],
+    ) {
+        let url = url.unwrap_or_default().trim().to_string();
+        let filter = EventFilter::new(event_types);
+        let mut guard = self.task.lock().await;
+
+        if let Some(current) = guard.as_ref() {
+            if current.url == url && current.filter == filter {
+                return;
+            }
+        }
+
+        if let Some(existing) = guard.take() {
+            existing.join.abort();
+            tracing::info!("Event webhook stopped for reconfiguration");
+        }
+
+        if url.is_empty() {
+            return;
+        }
+
+        let receiver = events.subscribe();
+        let task_url = url.clone();
+        let task_filter = filter.clone();
+        let join = tokio::spawn(async move {
+            tracing::info!(
+                "Event webhook active (events: {})",
+                if task_filter.event_types.is_empty() {
+                    "all".to_string()
+                } else {
+                    task_filter.event_types.join(",")
+                }
+            );
+            run_forwarder(receiver, task_url, task_filter, WEBHOOK_RETRY_BASE_DELAY).await;
+        });
+
+        *guard = Some(EventWebhookTask { url, filter, join });
+    }
+}
+
+impl Default for EventWebhookManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// ─── Forwarder loop ───────────────────────────────────────────────────────────
+
+async fn run_forwarder(
+    mut receiver: broadcast::Receiver<ApiEventEnvelope>,
+    url: String,
+    filter: EventFilter,
+    retry_base_delay: Duration,
+) {
+    let client = crate::http_client::build_http_client_with_timeout(Some(WEBHOOK_REQUEST_TIMEOUT));
+    loop {
+        let envelope = match receiver.recv().await {
+            Ok(envelope) => envelope,
+            Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                tracing::warn!("Event webhook fell behind; dropped {} events", skipped);
+                continue;
+            }
+            Err(broadcast::error::RecvError::Closed) => return,
+        };
+        if !filter.allows(&envelope.event_type) {
+            continue;
+        }
+        deliver(&client, &url, &envelope, retry_base_delay).await;
+    }
+}
+
+/// POSTs one envelope, retrying transport errors, 429, and 5xx responses.
+/// Returns whether the endpoint accepted it.
+async fn deliver(
+    client: &reqwest::Client,
+    url: &str,
+    envelope: &ApiEventEnvelope,
+    retry_base_delay: Duration,
+) -> bool {
+    let mut delay = retry_base_delay;
+    for attempt in 1..=WEBHOOK_MAX_ATTEMPTS {
+        let (retryable, detail) = match client.post(url).json(envelope).send().await {
+            Ok(response) if response.status().is_success() => return true,
+            Ok(response) => {
+                let status = response.status();
+                (
+                    status.is_server_error() || status.as_u16() == 429,
+                    format!("HTTP {}", status),
+                )
+            }
+            Err(error) => (true, error.to_string()),
+        };
+        if !retryable || attempt == WEBHOOK_MAX_ATTEMPTS {
+            tracing::warn!(
+                "Event webhook dropped '{}' event after {} attempt(s): {}",
+                envelope.event_type,
+                attempt,
+                detail
+            );
+            return false;
+        }
+        tokio::time::sleep(delay).await;
+        delay *= 2;
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{http::StatusCode, routing::post, Json, Router};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex as StdMutex};
+
+    fn envelope(event_type: &str) -> ApiEventEnvelope {
+        ApiEventEnvelope {
+            event_type: event_type.to_string(),
+            emitted_at: chrono::Utc::now(),
+            payload: serde_json::json!({ "text": event_type }),
+        }
+    }
+
+    /// Records received event types; the first `failures` requests get a 503.
+    async fn recording_endpoint(failures: usize) -> (String, Arc<StdMutex<Vec<String>>>) {
+        let received = Arc::new(StdMutex::new(Vec::new()));
+        let hits = Arc::new(AtomicUsize::new(0));
+        let sink = Arc::clone(&received);
+        let app = Router::new().route(
+            "/hook",
+            post(move |Json(body): Json<serde_json::Value>| {
+                let sink = Arc::clone(&sink);
+                let hits = Arc::clone(&hits);
+                async move {
+                    if hits.fetch_add(1, Ordering::SeqCst) < failures {
+                        return StatusCode::SERVICE_UNAVAILABLE;
+                    }
+                    sink.lock()
+                        .unwrap()
+                        .push(body["event_type"].as_str().unwrap_or_default().to_string());
+                    StatusCode::OK
+                }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind webhook endpoint");
+        let address = listener.local_addr().expect("webhook address");
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.expect("serve webhook");
+        });
+        (format!("http://{address}/hook"), received)
+    }
+
+    #[test]
+    fn filter_matches_event_types_case_insensitively() {
+        let all = EventFilter::new(&[]);
+        assert!(all.allows("observation"));
+
+        let some = EventFilter::new(&[" Chat_Reply".to_string(), String::new()]);
+        assert!(some.allows("chat_reply"));
+        assert!(!some.allows("observation"));
+    }
+
+    #[tokio::test]
+    async fn forwarder_posts_filtered_events_and_retries_server_errors() {
+        let (url, received) = recording_endpoint(1).await;
+        let (sender, _) = broadcast::channel(16);
+        let receiver = sender.subscribe();
+        let filter = EventFilter::new(&["chat_reply".to_string(), "error".to_string()]);
+        let forwarder = tokio::spawn(run_forwarder(
+            receiver,
+            url,
+            filter,
+            Duration::from_millis(1),
+        ));
+
+        for event_type in ["observation", "chat_reply", "error"] {
+            sender.send(envelope(event_type)).expect("send envelope");
+        }
+        drop(sender);
+        tokio::time::timeout(Duration::from_secs(5), forwarder)
+            .await
+            .expect("forwarder exits when the channel closes")
+            .expect("forwarder task");
+
+        // The first chat_reply POST hit a 503 and was retried.
+        assert_eq!(*received.lock().unwrap(), vec!["chat_reply", "error"]);
+    }
+
+    #[tokio::test]
+    async fn client_errors_are_not_retried() {
+        let hits = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&hits);
+        let app = Router::new().route(
+            "/hook",
+            post(move || {
+                counter.fetch_add(1, Ordering::SeqCst);
+                async { StatusCode::BAD_REQUEST }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind webhook endpoint");
+        let address = listener.local_addr().expect("webhook address");
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.expect("serve webhook");
+        });
+
+        let client = reqwest::Client::new();
+        let delivered = deliver(
+            &client,
+            &format!("http://{address}/hook"),
+            &envelope("error"),
+            Duration::from_millis(1),
+        )
+        .await;
+        assert!(!delivered);
+        assert_eq!(hits.load(Ordering::SeqCst), 1);
+    }
+}
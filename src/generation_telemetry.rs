@@ -23,6 +23,7 @@ pub enum GenerationSource {
     PersonaSnapshot,
     ConversationSummary,
     ConversationTitle,
+    DailyReport,
     Vision,
 }
 
@@ -1,3 +1,4 @@
+use std::net::IpAddr;
 use std::time::Duration;
 
 /// Default deadline for ordinary backend HTTP requests, including LLM calls.
@@ -8,12 +9,47 @@ pub fn build_http_client() -> reqwest::Client {
 }
 
 pub fn build_http_client_with_timeout(timeout: Option<Duration>) -> reqwest::Client {
+    build_with(timeout, true)
+}
+
+/// Like `build_http_client_with_timeout`, but returns 3xx responses to the
+/// caller instead of following them, so each hop can be validated.
+pub fn build_http_client_without_redirects(timeout: Option<Duration>) -> reqwest::Client {
+    build_with(timeout, false)
+}
+
+/// Loopback, private, link-local, and other non-routable addresses that
+/// must never be treated as a public destination.
+pub fn is_private_or_local_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => {
+            v4.is_private()
+                || v4.is_loopback()
+                || v4.is_link_local()
+                || v4.is_broadcast()
+                || v4.is_documentation()
+                || v4.is_unspecified()
+                || v4.is_multicast()
+        }
+        IpAddr::V6(v6) => {
+            v6.is_loopback()
+                || v6.is_unspecified()
+                || v6.is_multicast()
+                || v6.is_unique_local()
+                || v6.is_unicast_link_local()
+        }
+    }
+}
+
+fn build_with(timeout: Option<Duration>, follow_redirects: bool) -> reqwest::Client {
     let allow_system_proxy = std::env::var("PONDERER_ENABLE_SYSTEM_PROXY")
         .map(|value| value == "1" || value.eq_ignore_ascii_case("true"))
         .unwrap_or(false);
 
     if allow_system_proxy {
-        if let Ok(Ok(client)) = std::panic::catch_unwind(|| attempt_build(timeout, false)) {
+        if let Ok(Ok(client)) =
+            std::panic::catch_unwind(|| attempt_build(timeout, false, follow_redirects))
+        {
             return client;
         }
 
@@ -22,7 +58,7 @@ pub fn build_http_client_with_timeout(timeout: Option<Duration>) -> reqwest::Cli
         );
     }
 
-    match std::panic::catch_unwind(|| attempt_build(timeout, true)) {
+    match std::panic::catch_unwind(|| attempt_build(timeout, true, follow_redirects)) {
         Ok(Ok(client)) => client,
         Ok(Err(error)) => {
             panic!(
@@ -39,8 +75,12 @@ pub fn build_http_client_with_timeout(timeout: Option<Duration>) -> reqwest::Cli
 fn attempt_build(
     timeout: Option<Duration>,
     no_proxy: bool,
+    follow_redirects: bool,
 ) -> Result<reqwest::Client, reqwest::Error> {
     let mut builder = reqwest::Client::builder();
+    if !follow_redirects {
+        builder = builder.redirect(reqwest::redirect::Policy::none());
+    }
     if let Some(timeout) = timeout {
         builder = builder.timeout(timeout);
     }
@@ -2,15 +2,18 @@ pub mod agent;
 pub mod character_card;
 pub mod config;
 pub mod database;
+pub mod event_webhook;
 pub mod generation_telemetry;
 pub mod http_client;
 pub mod intentions;
 pub mod llm_client;
+pub mod llm_provider;
 pub mod memory;
 pub mod plugin;
 pub mod plugin_contract;
 pub mod plugin_event_ledger;
 pub(crate) mod plugin_lifecycle;
+pub(crate) mod plugin_poll_backoff;
 pub(crate) mod plugin_restart_policy;
 pub mod plugin_workbench;
 pub mod presence;
@@ -21,5 +24,6 @@ pub mod runtime_process_plugin;
 pub mod scheduled_jobs;
 pub mod server;
 pub mod skills;
+pub mod status_server;
 pub mod telegram;
 pub mod tools;
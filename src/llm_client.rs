@@ -4,25 +4,134 @@ use image::codecs::jpeg::JpegEncoder;
 use image::imageops::FilterType;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::config::AgentConfig;
 
 use crate::generation_telemetry::{
     GenerationObserver, GenerationOutcome, GenerationSession, TokenNoveltyTracker,
 };
 use crate::http_client::build_http_client;
+use crate::llm_provider::{
+    anthropic_messages_url, anthropic_response_to_openai, build_anthropic_request, LlmProvider,
+};
 
 const VISION_MAX_DIMENSION: u32 = 1280;
 const VISION_MAX_BYTES_MULTIMODAL: usize = 512 * 1024;
 const VISION_MAX_BYTES_INLINE_FALLBACK: usize = 64 * 1024;
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(10);
+/// How often a backoff sleep re-checks the stop counter.
+const RETRY_CANCEL_POLL: Duration = Duration::from_millis(50);
+/// Inputs sent per `/v1/embeddings` request unless overridden.
+pub const DEFAULT_EMBEDDING_BATCH_SIZE: usize = 64;
 
 #[derive(Clone)]
 pub struct LlmClient {
     api_url: String,
     api_key: String,
     model: String,
+    embedding_api_url: Option<String>,
+    embedding_api_key: Option<String>,
+    embedding_batch_size: usize,
+    provider: LlmProvider,
+    retry_policy: LlmRetryPolicy,
+    /// Stop counter and the value captured when it was attached; a change
+    /// abandons pending retries.
+    cancel_generation: Option<(Arc<AtomicU64>, u64)>,
     client: reqwest::Client,
     generation_observer: Option<GenerationObserver>,
 }
 
+/// Retry schedule for transient chat-request failures.
+///
+/// Transport errors and 5xx responses are retried with exponential backoff
+/// (`base_delay`, doubled per attempt and capped at `MAX_RETRY_DELAY`); 4xx
+/// responses are returned immediately.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LlmRetryPolicy {
+    /// Total attempts including the first; `1` disables retries.
+    pub max_attempts: u32,
+    pub base_delay: Duration,
+}
+
+impl Default for LlmRetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(500),
+        }
+    }
+}
+
+impl LlmRetryPolicy {
+    pub fn from_config(config: &AgentConfig) -> Self {
+        Self {
+            max_attempts: config.llm_retry_max_attempts.max(1),
+            base_delay: Duration::from_millis(config.llm_retry_base_delay_ms),
+        }
+    }
+
+    fn delay_before_retry(&self, failed_attempt: u32) -> Duration {
+        let factor = 1u32 << failed_attempt.saturating_sub(1).min(16);
+        self.base_delay.saturating_mul(factor).min(MAX_RETRY_DELAY)
+    }
+}
+
+/// Send a request built by `build`, retrying transport errors and 5xx
+/// responses per `policy`. The last response or error is returned once
+/// attempts are exhausted, the status is not retryable, or `is_cancelled`
+/// reports a stop (checked between attempts and during backoff).
+pub(crate) async fn send_with_retry(
+    policy: &LlmRetryPolicy,
+    build: impl Fn() -> reqwest::RequestBuilder,
+    is_cancelled: impl Fn() -> bool,
+) -> reqwest::Result<reqwest::Response> {
+    let max_attempts = policy.max_attempts.max(1);
+    let mut attempt = 1;
+    loop {
+        let result = build().send().await;
+        let retryable = match &result {
+            Ok(response) => response.status().is_server_error(),
+            Err(error) => !error.is_builder(),
+        };
+        if !retryable || attempt >= max_attempts || is_cancelled() {
+            return result;
+        }
+
+        let delay = policy.delay_before_retry(attempt);
+        match &result {
+            Ok(response) => tracing::warn!(
+                "LLM request returned {} (attempt {}/{}); retrying in {:?}",
+                response.status(),
+                attempt,
+                max_attempts,
+                delay
+            ),
+            Err(error) => tracing::warn!(
+                "LLM request failed (attempt {}/{}): {}; retrying in {:?}",
+                attempt,
+                max_attempts,
+                error,
+                delay
+            ),
+        }
+        let deadline = tokio::time::Instant::now() + delay;
+        while tokio::time::Instant::now() < deadline {
+            if is_cancelled() {
+                return result;
+            }
+            tokio::time::sleep_until(deadline.min(tokio::time::Instant::now() + RETRY_CANCEL_POLL))
+                .await;
+        }
+        if is_cancelled() {
+            return result;
+        }
+        attempt += 1;
+    }
+}
+
 #[derive(Debug, Deserialize)]
 pub struct DecisionResponse {
     pub should_respond: bool,
@@ -48,6 +157,53 @@ pub struct Message {
 #[derive(Debug, Deserialize)]
 struct ChatCompletionResponse {
     choices: Vec<Choice>,
+    #[serde(default)]
+    usage: Option<TokenUsage>,
+}
+
+/// Prompt/completion token counts for one or more model requests.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TokenUsage {
+    #[serde(default)]
+    pub prompt_tokens: u64,
+    #[serde(default)]
+    pub completion_tokens: u64,
+    /// Set when any counted request lacked provider `usage` and was estimated.
+    #[serde(default)]
+    pub estimated: bool,
+}
+
+impl TokenUsage {
+    /// Reads an OpenAI-shaped `usage` object from a response or stream chunk.
+    pub fn from_response(value: &Value) -> Option<Self> {
+        let usage = value.get("usage").filter(|usage| usage.is_object())?;
+        Some(Self {
+            prompt_tokens: usage["prompt_tokens"].as_u64().unwrap_or(0),
+            completion_tokens: usage["completion_tokens"].as_u64().unwrap_or(0),
+            estimated: false,
+        })
+    }
+
+    /// Rough count for providers that report no usage (about four characters
+    /// per token for English text and code).
+    pub fn estimate(prompt_text: &str, completion_text: &str) -> Self {
+        let tokens = |text: &str| (text.chars().count() as u64).div_ceil(4);
+        Self {
+            prompt_tokens: tokens(prompt_text),
+            completion_tokens: tokens(completion_text),
+            estimated: true,
+        }
+    }
+
+    pub fn add(&mut self, other: TokenUsage) {
+        self.prompt_tokens += other.prompt_tokens;
+        self.completion_tokens += other.completion_tokens;
+        self.estimated |= other.estimated;
+    }
+
+    pub fn total_tokens(&self) -> u64 {
+        self.prompt_tokens + self.completion_tokens
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -55,12 +211,30 @@ struct Choice {
     message: Message,
 }
 
+#[derive(Debug, Deserialize)]
+struct EmbeddingsResponse {
+    data: Vec<EmbeddingItem>,
+}
+
+#[derive(Debug, Deserialize)]
+struct EmbeddingItem {
+    #[serde(default)]
+    index: usize,
+    embedding: Vec<f32>,
+}
+
 impl LlmClient {
     pub fn new(api_url: String, api_key: String, model: String) -> Self {
         Self {
             api_url,
             api_key,
             model,
+            embedding_api_url: None,
+            embedding_api_key: None,
+            embedding_batch_size: DEFAULT_EMBEDDING_BATCH_SIZE,
+            provider: LlmProvider::default(),
+            retry_policy: LlmRetryPolicy::default(),
+            cancel_generation: None,
             client: build_http_client(),
             generation_observer: None,
         }
@@ -71,6 +245,48 @@ impl LlmClient {
         self
     }
 
+    /// Send embedding requests to a separate base URL instead of `api_url`.
+    pub fn with_embedding_endpoint(mut self, url: Option<String>) -> Self {
+        self.embedding_api_url = url
+            .map(|url| url.trim().to_string())
+            .filter(|url| !url.is_empty());
+        self
+    }
+
+    /// Bearer token for the embeddings endpoint. Without one, `api_key` is
+    /// sent only when the embeddings endpoint shares `api_url`'s host.
+    pub fn with_embedding_api_key(mut self, api_key: Option<String>) -> Self {
+        self.embedding_api_key = api_key
+            .map(|key| key.trim().to_string())
+            .filter(|key| !key.is_empty());
+        self
+    }
+
+    /// Cap the inputs per embeddings request; larger calls to `embed` are
+    /// split into several requests. Zero is treated as one.
+    pub fn with_embedding_batch_size(mut self, batch_size: usize) -> Self {
+        self.embedding_batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Select the chat wire format; defaults to OpenAI-compatible.
+    pub fn with_provider(mut self, provider: LlmProvider) -> Self {
+        self.provider = provider;
+        self
+    }
+
+    pub fn with_retry_policy(mut self, policy: LlmRetryPolicy) -> Self {
+        self.retry_policy = policy;
+        self
+    }
+
+    /// Stop retrying once `counter` moves past its current value.
+    pub fn with_cancel_generation(mut self, counter: Arc<AtomicU64>) -> Self {
+        let start = counter.load(Ordering::SeqCst);
+        self.cancel_generation = Some((counter, start));
+        self
+    }
+
     /// Generate a completion using the OpenAI API format
     pub async fn generate(&self, messages: Vec<Message>) -> Result<String> {
         self.generate_with_model(messages, &self.model).await
@@ -78,7 +294,34 @@ impl LlmClient {
 
     /// Generate a completion with a specific model
     pub async fn generate_with_model(&self, messages: Vec<Message>, model: &str) -> Result<String> {
+        self.generate_with_model_and_usage(messages, model)
+            .await
+            .map(|(content, _)| content)
+    }
+
+    /// Generate a completion and report its token usage, estimated when the
+    /// provider response has no `usage` object.
+    pub async fn generate_with_model_and_usage(
+        &self,
+        messages: Vec<Message>,
+        model: &str,
+    ) -> Result<(String, TokenUsage)> {
         let session = self.begin_generation();
+        let prompt_text = messages
+            .iter()
+            .map(|message| message.content.as_str())
+            .collect::<Vec<_>>()
+            .join("\n");
+        if self.provider == LlmProvider::Anthropic {
+            let messages = messages
+                .iter()
+                .map(serde_json::to_value)
+                .collect::<serde_json::Result<Vec<_>>>()?;
+            let (content, usage) = self.generate_anthropic(&messages, model, 0.7, 2000).await?;
+            Self::complete_generation(session, &content);
+            let usage = usage.unwrap_or_else(|| TokenUsage::estimate(&prompt_text, &content));
+            return Ok((content, usage));
+        }
         let url = chat_completions_url(&self.api_url);
 
         let request = ChatCompletionRequest {
@@ -88,14 +331,11 @@ impl LlmClient {
             max_tokens: Some(2000),
         };
 
-        let mut req = self.client.post(&url).json(&request);
-
-        // Add API key header if provided (not needed for local models)
-        if !self.api_key.is_empty() {
-            req = req.header("Authorization", format!("Bearer {}", self.api_key));
-        }
-
-        let response = req.send().await.context("Failed to send LLM request")?;
+        // The API key header is only sent when set (not needed for local models)
+        let response = self
+            .send_chat(&url, &request)
+            .await
+            .context("Failed to send LLM request")?;
 
         // Check for HTTP errors and include response body for debugging
         if !response.status().is_success() {
@@ -120,7 +360,10 @@ impl LlmClient {
 
         Self::complete_generation(session, &content);
 
-        Ok(content)
+        let usage = completion
+            .usage
+            .unwrap_or_else(|| TokenUsage::estimate(&prompt_text, &content));
+        Ok((content, usage))
     }
 
     /// Ask the LLM to decide whether to respond to a post
@@ -252,6 +495,14 @@ impl LlmClient {
             }));
         }
 
+        if self.provider == LlmProvider::Anthropic {
+            let (content, _) = self
+                .generate_anthropic(&request_messages, &self.model, 0.2, 1000)
+                .await?;
+            Self::complete_generation(session, &content);
+            return Ok(content);
+        }
+
         let request = json!({
             "model": self.model,
             "messages": request_messages,
@@ -259,13 +510,10 @@ impl LlmClient {
             "max_tokens": 1000
         });
 
-        let mut req = self.client.post(&url).json(&request);
-
-        if !self.api_key.is_empty() {
-            req = req.header("Authorization", format!("Bearer {}", self.api_key));
-        }
-
-        let response = req.send().await.context("Failed to send vision request")?;
+        let response = self
+            .send_chat(&url, &request)
+            .await
+            .context("Failed to send vision request")?;
 
         if !response.status().is_success() {
             let status = response.status();
@@ -311,6 +559,18 @@ impl LlmClient {
             last_msg.content = format!("[IMAGE_BASE64: {}]\n\n{}", image_base64, last_msg.content);
         }
 
+        if self.provider == LlmProvider::Anthropic {
+            let messages = messages
+                .iter()
+                .map(serde_json::to_value)
+                .collect::<serde_json::Result<Vec<_>>>()?;
+            let (content, _) = self
+                .generate_anthropic(&messages, &self.model, 0.2, 1000)
+                .await?;
+            Self::complete_generation(session, &content);
+            return Ok(content);
+        }
+
         let url = chat_completions_url(&self.api_url);
         let request = ChatCompletionRequest {
             model: self.model.clone(),
@@ -319,13 +579,8 @@ impl LlmClient {
             max_tokens: Some(1000),
         };
 
-        let mut req = self.client.post(&url).json(&request);
-        if !self.api_key.is_empty() {
-            req = req.header("Authorization", format!("Bearer {}", self.api_key));
-        }
-
-        let response = req
-            .send()
+        let response = self
+            .send_chat(&url, &request)
             .await
             .context("Failed to send inline fallback vision request")?;
         if !response.status().is_success() {
@@ -348,6 +603,146 @@ impl LlmClient {
         Ok(content)
     }
 
+    /// POST a chat request with provider auth, retrying transient failures.
+    async fn send_chat<T: Serialize + ?Sized>(
+        &self,
+        url: &str,
+        body: &T,
+    ) -> reqwest::Result<reqwest::Response> {
+        let cancel = self.cancel_generation.clone();
+        send_with_retry(
+            &self.retry_policy,
+            || {
+                self.provider
+                    .authorize(self.client.post(url).json(body), Some(&self.api_key))
+            },
+            || {
+                cancel
+                    .as_ref()
+                    .is_some_and(|(counter, start)| counter.load(Ordering::SeqCst) != *start)
+            },
+        )
+        .await
+    }
+
+    /// Send OpenAI-shaped messages to the Anthropic Messages API and return the
+    /// assistant text with any reported usage.
+    async fn generate_anthropic(
+        &self,
+        messages: &[Value],
+        model: &str,
+        temperature: f32,
+        max_tokens: u32,
+    ) -> Result<(String, Option<TokenUsage>)> {
+        let url = anthropic_messages_url(&self.api_url);
+        let request =
+            build_anthropic_request(model, messages, None, Some(temperature), max_tokens, false);
+        let response = self
+            .send_chat(&url, &request)
+            .await
+            .context("Failed to send LLM request")?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unable to read body".to_string());
+            anyhow::bail!("LLM API returned error {}: {}", status, body);
+        }
+
+        let body: Value = response
+            .json()
+            .await
+            .context("Failed to parse LLM response")?;
+        let completion = anthropic_response_to_openai(&body)?;
+        let content = extract_message_content(completion["choices"][0]["message"].get("content"))
+            .ok_or_else(|| anyhow::anyhow!("No response from LLM"))?;
+        Ok((content, TokenUsage::from_response(&completion)))
+    }
+
+    /// Embed each input with an OpenAI-compatible `/v1/embeddings` endpoint.
+    ///
+    /// Inputs are sent in batches of at most `embedding_batch_size`. Vectors
+    /// are returned in input order regardless of the order the provider lists
+    /// them in. `model` is the embedding model, independent of the chat model.
+    pub async fn embed(&self, inputs: &[String], model: &str) -> Result<Vec<Vec<f32>>> {
+        let url = embeddings_url(self.embedding_api_url.as_deref().unwrap_or(&self.api_url));
+        let mut vectors = Vec::with_capacity(inputs.len());
+        for batch in inputs.chunks(self.embedding_batch_size) {
+            vectors.extend(self.embed_batch(&url, batch, model).await?);
+        }
+        Ok(vectors)
+    }
+
+    async fn embed_batch(
+        &self,
+        url: &str,
+        inputs: &[String],
+        model: &str,
+    ) -> Result<Vec<Vec<f32>>> {
+        let request = json!({
+            "model": model,
+            "input": inputs,
+        });
+
+        let mut req = self.client.post(url).json(&request);
+        if let Some(api_key) = self.embedding_bearer() {
+            req = req.header("Authorization", format!("Bearer {}", api_key));
+        }
+
+        let response = req
+            .send()
+            .await
+            .context("Failed to send embeddings request")?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unable to read body".to_string());
+            anyhow::bail!("Embeddings API returned error {}: {}", status, body);
+        }
+
+        let body: EmbeddingsResponse = response
+            .json()
+            .await
+            .context("Failed to parse embeddings response")?;
+        let mut data = body.data;
+        data.sort_by_key(|item| item.index);
+        if data.len() != inputs.len() {
+            anyhow::bail!(
+                "Embeddings API returned {} vectors for {} inputs",
+                data.len(),
+                inputs.len()
+            );
+        }
+        Ok(data.into_iter().map(|item| item.embedding).collect())
+    }
+
+    /// The dedicated embedding key, else the chat key when embeddings go to
+    /// the chat provider's host. Never the chat key for a foreign host.
+    fn embedding_bearer(&self) -> Option<&str> {
+        if let Some(api_key) = self.embedding_api_key.as_deref() {
+            return Some(api_key);
+        }
+        if self.api_key.is_empty() {
+            return None;
+        }
+        let same_host = match self.embedding_api_url.as_deref() {
+            None => true,
+            Some(embedding_url) => {
+                let host = |url: &str| {
+                    reqwest::Url::parse(url.trim())
+                        .ok()
+                        .and_then(|url| url.host_str().map(str::to_ascii_lowercase))
+                };
+                host(embedding_url).is_some() && host(embedding_url) == host(&self.api_url)
+            }
+        };
+        same_host.then_some(self.api_key.as_str())
+    }
+
     fn begin_generation(&self) -> Option<GenerationSession> {
         self.generation_observer
             .as_ref()
@@ -521,6 +916,17 @@ fn chat_completions_url(base_url: &str) -> String {
     }
 }
 
+fn embeddings_url(base_url: &str) -> String {
+    let trimmed = base_url.trim().trim_end_matches('/');
+    if trimmed.ends_with("/embeddings") {
+        trimmed.to_string()
+    } else if trimmed.ends_with("/v1") {
+        format!("{}/embeddings", trimmed)
+    } else {
+        format!("{}/v1/embeddings", trimmed)
+    }
+}
+
 fn preprocess_image_for_vision(
     image_bytes: &[u8],
     max_bytes: usize,
@@ -606,9 +1012,218 @@ pub struct ImageEvaluation {
 
 #[cfg(test)]
 mod tests {
-    use super::{chat_completions_url, extract_message_content, parse_json_response};
+    use super::{
+        chat_completions_url, embeddings_url, extract_message_content, parse_json_response,
+        LlmClient, LlmRetryPolicy, Message, TokenUsage,
+    };
     use serde::Deserialize;
     use serde_json::json;
+    use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    /// Serves `/v1/chat/completions`, answering with `statuses` in order and
+    /// then 200 with a fixed completion; returns the base URL and hit counter.
+    async fn flaky_provider(statuses: Vec<u16>) -> (String, Arc<AtomicUsize>) {
+        use axum::http::StatusCode;
+        use axum::response::IntoResponse;
+        use axum::routing::post;
+        use axum::{Json, Router};
+
+        let hits = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&hits);
+        let app = Router::new().route(
+            "/v1/chat/completions",
+            post(move || {
+                let counter = Arc::clone(&counter);
+                let statuses = statuses.clone();
+                async move {
+                    let hit = counter.fetch_add(1, Ordering::SeqCst);
+                    match statuses.get(hit) {
+                        Some(&status) => StatusCode::from_u16(status).unwrap().into_response(),
+                        None => Json(json!({
+                            "choices": [{"message": {"role": "assistant", "content": "recovered"}}]
+                        }))
+                        .into_response(),
+                    }
+                }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind mock provider");
+        let address = listener.local_addr().expect("mock provider address");
+        tokio::spawn(async move {
+            axum::serve(listener, app)
+                .await
+                .expect("serve mock provider");
+        });
+        (format!("http://{address}"), hits)
+    }
+
+    fn user_message() -> Vec<Message> {
+        vec![Message {
+            role: "user".to_string(),
+            content: "hello".to_string(),
+        }]
+    }
+
+    fn fast_retries(max_attempts: u32) -> LlmRetryPolicy {
+        LlmRetryPolicy {
+            max_attempts,
+            base_delay: Duration::from_millis(1),
+        }
+    }
+
+    #[tokio::test]
+    async fn generate_retries_server_errors_then_succeeds() {
+        let (url, hits) = flaky_provider(vec![503, 502]).await;
+        let client = LlmClient::new(url, String::new(), "model".to_string())
+            .with_retry_policy(fast_retries(3));
+
+        let content = client
+            .generate(user_message())
+            .await
+            .expect("third attempt");
+        assert_eq!(content, "recovered");
+        assert_eq!(hits.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn generate_does_not_retry_client_errors_or_exceed_attempts() {
+        let (url, hits) = flaky_provider(vec![400]).await;
+        let client = LlmClient::new(url, String::new(), "model".to_string())
+            .with_retry_policy(fast_retries(3));
+        let error = client.generate(user_message()).await.unwrap_err();
+        assert!(error.to_string().contains("400"));
+        assert_eq!(hits.load(Ordering::SeqCst), 1);
+
+        let (url, hits) = flaky_provider(vec![500, 500, 500]).await;
+        let client = LlmClient::new(url, String::new(), "model".to_string())
+            .with_retry_policy(fast_retries(2));
+        assert!(client.generate(user_message()).await.is_err());
+        assert_eq!(hits.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn stop_counter_abandons_pending_retries() {
+        let (url, hits) = flaky_provider(vec![503, 503]).await;
+        let stop = Arc::new(AtomicU64::new(0));
+        let client = LlmClient::new(url, String::new(), "model".to_string())
+            .with_retry_policy(LlmRetryPolicy {
+                max_attempts: 3,
+                base_delay: Duration::from_secs(5),
+            })
+            .with_cancel_generation(Arc::clone(&stop));
+        let stopper = tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            stop.fetch_add(1, Ordering::SeqCst);
+        });
+
+        let started = std::time::Instant::now();
+        assert!(client.generate(user_message()).await.is_err());
+        assert!(started.elapsed() < Duration::from_secs(2));
+        assert_eq!(hits.load(Ordering::SeqCst), 1);
+        stopper.await.unwrap();
+    }
+
+    #[test]
+    fn retry_delay_doubles_and_caps() {
+        let policy = LlmRetryPolicy {
+            max_attempts: 10,
+            base_delay: Duration::from_millis(500),
+        };
+        assert_eq!(policy.delay_before_retry(1), Duration::from_millis(500));
+        assert_eq!(policy.delay_before_retry(2), Duration::from_millis(1000));
+        assert_eq!(policy.delay_before_retry(9), Duration::from_secs(10));
+    }
+
+    #[test]
+    fn chat_key_is_only_sent_to_embeddings_on_the_chat_host() {
+        let client = || {
+            LlmClient::new(
+                "https://api.example.com/v1".to_string(),
+                "sk-chat".to_string(),
+                "chat-model".to_string(),
+            )
+        };
+        assert_eq!(client().embedding_bearer(), Some("sk-chat"));
+        let same_host = client().with_embedding_endpoint(Some("https://api.example.com/v2".into()));
+        assert_eq!(same_host.embedding_bearer(), Some("sk-chat"));
+        let foreign = client().with_embedding_endpoint(Some("https://embed.other.net".into()));
+        assert_eq!(foreign.embedding_bearer(), None);
+        let dedicated = foreign.with_embedding_api_key(Some("sk-embed".to_string()));
+        assert_eq!(dedicated.embedding_bearer(), Some("sk-embed"));
+    }
+
+    #[tokio::test]
+    async fn embed_batches_inputs_and_restores_input_order() {
+        use axum::routing::post;
+        use axum::{Json, Router};
+        use std::sync::Mutex;
+
+        let requests = Arc::new(Mutex::new(Vec::<serde_json::Value>::new()));
+        let seen = Arc::clone(&requests);
+        let app = Router::new().route(
+            "/v1/embeddings",
+            post(move |Json(body): Json<serde_json::Value>| {
+                let seen = Arc::clone(&seen);
+                async move {
+                    // Reply in reverse order with explicit indices, as some
+                    // providers do, and encode each input's length as the vector.
+                    let data: Vec<_> = body["input"]
+                        .as_array()
+                        .unwrap()
+                        .iter()
+                        .enumerate()
+                        .rev()
+                        .map(|(index, text)| {
+                            let len = text.as_str().unwrap().len() as f32;
+                            json!({"index": index, "embedding": [len, 1.0]})
+                        })
+                        .collect();
+                    seen.lock().unwrap().push(body);
+                    Json(json!({"object": "list", "data": data}))
+                }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind mock provider");
+        let address = listener.local_addr().expect("mock provider address");
+        tokio::spawn(async move {
+            axum::serve(listener, app)
+                .await
+                .expect("serve mock provider");
+        });
+
+        let client = LlmClient::new(
+            "http://unused.invalid".to_string(),
+            "secret".to_string(),
+            "chat-model".to_string(),
+        )
+        .with_embedding_endpoint(Some(format!("http://{address}")))
+        .with_embedding_batch_size(2);
+        let inputs: Vec<String> = ["a", "bb", "ccc", "dddd", "eeeee"]
+            .iter()
+            .map(|text| text.to_string())
+            .collect();
+
+        let vectors = client.embed(&inputs, "embed-model").await.expect("embed");
+        assert_eq!(
+            vectors,
+            (1..=5).map(|len| vec![len as f32, 1.0]).collect::<Vec<_>>()
+        );
+
+        assert!(client.embed(&[], "embed-model").await.unwrap().is_empty());
+        let requests = requests.lock().unwrap();
+        let batch_sizes: Vec<usize> = requests
+            .iter()
+            .map(|body| body["input"].as_array().unwrap().len())
+            .collect();
+        assert_eq!(batch_sizes, vec![2, 2, 1]);
+        assert!(requests.iter().all(|body| body["model"] == "embed-model"));
+    }
 
     #[derive(Debug, Deserialize, PartialEq)]
     struct JsonProbe {
@@ -636,6 +1251,22 @@ mod tests {
         );
     }
 
+    #[test]
+    fn normalizes_openai_embeddings_url() {
+        assert_eq!(
+            embeddings_url("http://localhost:11434"),
+            "http://localhost:11434/v1/embeddings"
+        );
+        assert_eq!(
+            embeddings_url("http://localhost:11434/v1/"),
+            "http://localhost:11434/v1/embeddings"
+        );
+        assert_eq!(
+            embeddings_url("http://localhost:11434/v1/embeddings"),
+            "http://localhost:11434/v1/embeddings"
+        );
+    }
+
     #[test]
     fn extracts_message_content_from_multimodal_array() {
         let content = json!([
@@ -687,4 +1318,22 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn usage_is_read_from_response_or_estimated() {
+        let reported = TokenUsage::from_response(&json!({
+            "choices": [],
+            "usage": {"prompt_tokens": 120, "completion_tokens": 30, "total_tokens": 150}
+        }))
+        .expect("usage object");
+        assert_eq!(reported.total_tokens(), 150);
+        assert!(!reported.estimated);
+        assert!(TokenUsage::from_response(&json!({"choices": []})).is_none());
+
+        let mut total = reported;
+        total.add(TokenUsage::estimate("12345678", "abc"));
+        assert_eq!(total.prompt_tokens, 122);
+        assert_eq!(total.completion_tokens, 31);
+        assert!(total.estimated);
+    }
 }
@@ -0,0 +1,513 @@
+//! Wire-format selection for chat model backends.
+//!
+//! Callers build requests and parse responses in the OpenAI chat-completions
+//! shape. For the Anthropic Messages API this module translates at the HTTP
+//! boundary: OpenAI-shaped messages/tools become a Messages request, and
+//! Messages responses (and stream events) become OpenAI-shaped values that the
+//! existing parsing code already understands.
+
+use anyhow::{Context, Result};
+use serde_json::{json, Map, Value};
+
+pub const LLM_PROVIDER_OPENAI: &str = "openai";
+pub const LLM_PROVIDER_ANTHROPIC: &str = "anthropic";
+
+const ANTHROPIC_VERSION: &str = "2023-06-01";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LlmProvider {
+    /// OpenAI-compatible `/v1/chat/completions` (Ollama, LM Studio, vLLM, OpenAI).
+    #[default]
+    OpenAiCompatible,
+    /// Anthropic Messages API (`/v1/messages`).
+    Anthropic,
+}
+
+impl LlmProvider {
+    /// Unknown values fall back to OpenAI-compatible so existing configs keep working.
+    pub fn from_config(raw: &str) -> Self {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            LLM_PROVIDER_ANTHROPIC => Self::Anthropic,
+            _ => Self::OpenAiCompatible,
+        }
+    }
+
+    /// Attach provider-specific authentication. Empty keys send no auth header.
+    pub fn authorize(
+        self,
+        request: reqwest::RequestBuilder,
+        api_key: Option<&str>,
+    ) -> reqwest::RequestBuilder {
+        let key = api_key.map(str::trim).filter(|key| !key.is_empty());
+        match self {
+            Self::OpenAiCompatible => match key {
+                Some(key) => request.header("Authorization", format!("Bearer {}", key)),
+                None => request,
+            },
+            Self::Anthropic => {
+                let request = request.header("anthropic-version", ANTHROPIC_VERSION);
+                match key {
+                    Some(key) => request.header("x-api-key", key),
+                    None => request,
+                }
+            }
+        }
+    }
+}
+
+/// Normalize a base URL into the Messages endpoint, mirroring the
+/// chat-completions normalization in `llm_client.rs`.
+pub fn anthropic_messages_url(base_url: &str) -> String {
+    let trimmed = base_url.trim_end_matches('/');
+    if trimmed.ends_with("/messages") {
+        trimmed.to_string()
+    } else if trimmed.ends_with("/v1") {
+        format!("{}/messages", trimmed)
+    } else {
+        format!("{}/v1/messages", trimmed)
+    }
+}
+
+/// Build a Messages request from OpenAI-shaped `messages` and `tools` values.
+///
+/// System messages are hoisted into `system`, assistant `tool_calls` become
+/// `tool_use` blocks, `tool` messages become `tool_result` blocks, and
+/// consecutive same-role turns are merged because the API requires
+/// alternating roles.
+pub fn build_anthropic_request(
+    model: &str,
+    messages: &[Value],
+    tools: Option<&Value>,
+    temperature: Option<f32>,
+    max_tokens: u32,
+    stream: bool,
+) -> Value {
+    let mut system_parts = Vec::new();
+    let mut turns: Vec<(String, Vec<Value>)> = Vec::new();
+
+    for message in messages {
+        let role = message["role"].as_str().unwrap_or("user");
+        let (role, blocks) = match role {
+            "system" => {
+                if let Some(text) = content_text(&message["content"]) {
+                    system_parts.push(text);
+                }
+                continue;
+            }
+            "assistant" => {
+                let mut blocks = content_blocks(&message["content"]);
+                if let Some(calls) = message["tool_calls"].as_array() {
+                    for call in calls {
+                        let arguments = call["function"]["arguments"].as_str().unwrap_or("{}");
+                        let input = serde_json::from_str::<Value>(arguments)
+                            .ok()
+                            .filter(Value::is_object)
+                            .unwrap_or_else(|| json!({}));
+                        blocks.push(json!({
+                            "type": "tool_use",
+                            "id": call["id"].as_str().unwrap_or_default(),
+                            "name": call["function"]["name"].as_str().unwrap_or_default(),
+                            "input": input,
+                        }));
+                    }
+                }
+                ("assistant", blocks)
+            }
+            "tool" => (
+                "user",
+                vec![json!({
+                    "type": "tool_result",
+                    "tool_use_id": message["tool_call_id"].as_str().unwrap_or_default(),
+                    "content": content_text(&message["content"]).unwrap_or_default(),
+                })],
+            ),
+            _ => ("user", content_blocks(&message["content"])),
+        };
+        if blocks.is_empty() {
+            continue;
+        }
+        match turns.last_mut() {
+            Some((last_role, last_blocks)) if last_role == role => last_blocks.extend(blocks),
+            _ => turns.push((role.to_string(), blocks)),
+        }
+    }
+
+    let mut body = json!({
+        "model": model,
+        "max_tokens": max_tokens,
+        "messages": turns
+            .into_iter()
+            .map(|(role, content)| json!({ "role": role, "content": content }))
+            .collect::<Vec<_>>(),
+    });
+    if !system_parts.is_empty() {
+        body["system"] = json!(system_parts.join("\n\n"));
+    }
+    if let Some(temperature) = temperature {
+        body["temperature"] = json!(temperature);
+    }
+    if stream {
+        body["stream"] = json!(true);
+    }
+    if let Some(tools) = tools.and_then(Value::as_array).filter(|t| !t.is_empty()) {
+        body["tools"] = Value::Array(
+            tools
+                .iter()
+                .map(|tool| {
+                    let function = &tool["function"];
+                    json!({
+                        "name": function["name"],
+                        "description": function["description"],
+                        "input_schema": function["parameters"],
+                    })
+                })
+                .collect(),
+        );
+    }
+    body
+}
+
+/// Translate a Messages response into a one-choice chat-completions value.
+pub fn anthropic_response_to_openai(response: &Value) -> Result<Value> {
+    let blocks = response["content"]
+        .as_array()
+        .context("Anthropic response has no content array")?;
+    let mut text = String::new();
+    let mut tool_calls = Vec::new();
+    for block in blocks {
+        match block["type"].as_str() {
+            Some("text") => text.push_str(block["text"].as_str().unwrap_or_default()),
+            Some("tool_use") => tool_calls.push(json!({
+                "id": block["id"],
+                "type": "function",
+                "function": {
+                    "name": block["name"],
+                    "arguments": block["input"].to_string(),
+                },
+            })),
+            _ => {}
+        }
+    }
+
+    let mut message = Map::new();
+    message.insert("role".to_string(), json!("assistant"));
+    let content = if text.is_empty() {
+        Value::Null
+    } else {
+        json!(text)
+    };
+    message.insert("content".to_string(), content);
+    if !tool_calls.is_empty() {
+        message.insert("tool_calls".to_string(), Value::Array(tool_calls));
+    }
+    let mut completion = json!({
+        "choices": [{
+            "message": message,
+            "finish_reason": finish_reason(response["stop_reason"].as_str()),
+        }]
+    });
+    if response["usage"].is_object() {
+        completion["usage"] = json!({
+            "prompt_tokens": response["usage"]["input_tokens"].as_u64().unwrap_or_default(),
+            "completion_tokens": response["usage"]["output_tokens"].as_u64().unwrap_or_default(),
+        });
+    }
+    Ok(completion)
+}
+
+/// Map an Anthropic `stop_reason` to the chat-completions `finish_reason`.
+pub fn finish_reason(stop_reason: Option<&str>) -> Option<&'static str> {
+    match stop_reason? {
+        "tool_use" => Some("tool_calls"),
+        "max_tokens" => Some("length"),
+        "end_turn" | "stop_sequence" => Some("stop"),
+        _ => None,
+    }
+}
+
+/// Converts Messages stream events into chat-completions stream chunks.
+#[derive(Debug, Default)]
+pub struct AnthropicStreamTranslator {
+    /// Content-block index → tool-call index for open `tool_use` blocks.
+    tool_indices: Vec<(u64, usize)>,
+    next_tool_index: usize,
+    /// `input_tokens` from `message_start`, reported once output is known.
+    input_tokens: u64,
+    finished: bool,
+}
+
+impl AnthropicStreamTranslator {
+    /// Translate one SSE `data:` payload. `Ok(None)` means the event carries
+    /// nothing for the caller (pings, block stops). Usage arrives as a chunk
+    /// with an OpenAI-shaped `usage` object once `message_delta` reports output.
+    pub fn translate(&mut self, event: &Value) -> Result<Option<Value>> {
+        let delta = match event["type"].as_str().unwrap_or_default() {
+            "content_block_start" if event["content_block"]["type"] == "tool_use" => {
+                let block = &event["content_block"];
+                let index = self.next_tool_index;
+                self.next_tool_index += 1;
+                self.tool_indices
+                    .push((event["index"].as_u64().unwrap_or_default(), index));
+                json!({ "tool_calls": [{
+                    "index": index,
+                    "id": block["id"],
+                    "type": "function",
+                    "function": { "name": block["name"], "arguments": "" },
+                }]})
+            }
+            "content_block_delta" => match event["delta"]["type"].as_str() {
+                Some("text_delta") => json!({ "content": event["delta"]["text"] }),
+                Some("input_json_delta") => {
+                    let block = event["index"].as_u64().unwrap_or_default();
+                    let Some(&(_, index)) = self.tool_indices.iter().find(|(b, _)| *b == block)
+                    else {
+                        return Ok(None);
+                    };
+                    json!({ "tool_calls": [{
+                        "index": index,
+                        "function": { "arguments": event["delta"]["partial_json"] },
+                    }]})
+                }
+                _ => return Ok(None),
+            },
+            "message_start" => {
+                self.input_tokens = event["message"]["usage"]["input_tokens"]
+                    .as_u64()
+                    .unwrap_or_default();
+                return Ok(None);
+            }
+            "message_delta" => {
+                let reason = finish_reason(event["delta"]["stop_reason"].as_str());
+                let output_tokens = event["usage"]["output_tokens"].as_u64();
+                if reason.is_none() && output_tokens.is_none() {
+                    return Ok(None);
+                }
+                let choices = match reason {
+                    Some(reason) => json!([{ "delta": {}, "finish_reason": reason }]),
+                    None => json!([]),
+                };
+                let mut chunk = json!({ "choices": choices });
+                if let Some(output_tokens) = output_tokens {
+                    chunk["usage"] = json!({
+                        "prompt_tokens": self.input_tokens,
+                        "completion_tokens": output_tokens,
+                    });
+                }
+                return Ok(Some(chunk));
+            }
+            "message_stop" => {
+                self.finished = true;
+                return Ok(None);
+            }
+            "error" => anyhow::bail!("Anthropic stream error: {}", event["error"]),
+            _ => return Ok(None),
+        };
+        Ok(Some(json!({ "choices": [{ "delta": delta }] })))
+    }
+
+    /// Whether `message_stop` has been seen.
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+}
+
+fn content_text(content: &Value) -> Option<String> {
+    match content {
+        Value::String(text) => Some(text.clone()),
+        Value::Array(parts) => {
+            let text = parts
+                .iter()
+                .filter_map(|part| part["text"].as_str())
+                .collect::<Vec<_>>()
+                .join("\n");
+            Some(text)
+        }
+        _ => None,
+    }
+}
+
+/// OpenAI content (string or multimodal parts) as Messages content blocks.
+/// `data:` image URLs become base64 image blocks; remote image URLs are
+/// passed as URL sources.
+fn content_blocks(content: &Value) -> Vec<Value> {
+    match content {
+        Value::String(text) if !text.is_empty() => vec![json!({ "type": "text", "text": text })],
+        Value::Array(parts) => parts
+            .iter()
+            .filter_map(|part| match part["type"].as_str() {
+                Some("text") => Some(json!({ "type": "text", "text": part["text"] })),
+                Some("image_url") => {
+                    let url = part["image_url"]["url"].as_str()?;
+                    Some(match parse_data_url(url) {
+                        Some((media_type, data)) => json!({
+                            "type": "image",
+                            "source": { "type": "base64", "media_type": media_type, "data": data },
+                        }),
+                        None => json!({
+                            "type": "image",
+                            "source": { "type": "url", "url": url },
+                        }),
+                    })
+                }
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn parse_data_url(url: &str) -> Option<(&str, &str)> {
+    let rest = url.strip_prefix("data:")?;
+    let (media_type, data) = rest.split_once(";base64,")?;
+    Some((media_type, data))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn provider_defaults_to_openai_compatible() {
+        assert_eq!(
+            LlmProvider::from_config("anthropic"),
+            LlmProvider::Anthropic
+        );
+        assert_eq!(
+            LlmProvider::from_config(" Anthropic "),
+            LlmProvider::Anthropic
+        );
+        assert_eq!(
+            LlmProvider::from_config("ollama"),
+            LlmProvider::OpenAiCompatible
+        );
+        assert_eq!(LlmProvider::default(), LlmProvider::OpenAiCompatible);
+    }
+
+    #[test]
+    fn normalizes_anthropic_messages_url() {
+        for base in [
+            "https://api.anthropic.com",
+            "https://api.anthropic.com/",
+            "https://api.anthropic.com/v1",
+            "https://api.anthropic.com/v1/messages",
+        ] {
+            assert_eq!(
+                anthropic_messages_url(base),
+                "https://api.anthropic.com/v1/messages"
+            );
+        }
+    }
+
+    #[test]
+    fn request_hoists_system_and_maps_tool_turns() {
+        let messages = vec![
+            json!({"role": "system", "content": "Be brief."}),
+            json!({"role": "user", "content": "List files"}),
+            json!({"role": "assistant", "tool_calls": [{
+                "id": "call_1",
+                "type": "function",
+                "function": {"name": "list_directory", "arguments": "{\"path\":\".\"}"}
+            }]}),
+            json!({"role": "tool", "tool_call_id": "call_1", "content": "a.txt"}),
+            json!({"role": "user", "content": "Thanks"}),
+        ];
+        let tools = json!([{
+            "type": "function",
+            "function": {"name": "list_directory", "description": "List", "parameters": {"type": "object"}}
+        }]);
+        let body =
+            build_anthropic_request("claude", &messages, Some(&tools), Some(0.5), 1024, true);
+
+        assert_eq!(body["system"], "Be brief.");
+        assert_eq!(body["max_tokens"], 1024);
+        assert_eq!(body["stream"], true);
+        assert_eq!(body["tools"][0]["input_schema"]["type"], "object");
+        let turns = body["messages"].as_array().unwrap();
+        assert_eq!(turns.len(), 3);
+        assert_eq!(turns[1]["content"][0]["type"], "tool_use");
+        assert_eq!(turns[1]["content"][0]["input"]["path"], ".");
+        // Tool result and the following user text share one user turn.
+        assert_eq!(turns[2]["role"], "user");
+        assert_eq!(turns[2]["content"][0]["type"], "tool_result");
+        assert_eq!(turns[2]["content"][0]["tool_use_id"], "call_1");
+        assert_eq!(turns[2]["content"][1]["text"], "Thanks");
+    }
+
+    #[test]
+    fn response_maps_tool_use_and_stop_reason() {
+        let response = json!({
+            "content": [
+                {"type": "text", "text": "Checking."},
+                {"type": "tool_use", "id": "toolu_1", "name": "read_file", "input": {"path": "a.txt"}}
+            ],
+            "stop_reason": "tool_use",
+            "usage": {"input_tokens": 12, "output_tokens": 5}
+        });
+        let openai = anthropic_response_to_openai(&response).unwrap();
+        assert_eq!(openai["usage"]["prompt_tokens"], 12);
+        assert_eq!(openai["usage"]["completion_tokens"], 5);
+        let choice = &openai["choices"][0];
+        assert_eq!(choice["finish_reason"], "tool_calls");
+        assert_eq!(choice["message"]["content"], "Checking.");
+        assert_eq!(choice["message"]["tool_calls"][0]["id"], "toolu_1");
+        assert_eq!(
+            choice["message"]["tool_calls"][0]["function"]["arguments"],
+            "{\"path\":\"a.txt\"}"
+        );
+    }
+
+    #[test]
+    fn stream_events_become_chat_completion_deltas() {
+        let mut translator = AnthropicStreamTranslator::default();
+        let text = translator
+            .translate(&json!({"type": "content_block_delta", "index": 0, "delta": {"type": "text_delta", "text": "Hi"}}))
+            .unwrap()
+            .unwrap();
+        assert_eq!(text["choices"][0]["delta"]["content"], "Hi");
+
+        let start = translator
+            .translate(&json!({"type": "content_block_start", "index": 1, "content_block": {"type": "tool_use", "id": "toolu_1", "name": "read_file", "input": {}}}))
+            .unwrap()
+            .unwrap();
+        assert_eq!(start["choices"][0]["delta"]["tool_calls"][0]["index"], 0);
+        assert_eq!(
+            start["choices"][0]["delta"]["tool_calls"][0]["function"]["name"],
+            "read_file"
+        );
+        let args = translator
+            .translate(&json!({"type": "content_block_delta", "index": 1, "delta": {"type": "input_json_delta", "partial_json": "{\"path\""}}))
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            args["choices"][0]["delta"]["tool_calls"][0]["function"]["arguments"],
+            "{\"path\""
+        );
+
+        assert!(translator
+            .translate(
+                &json!({"type": "message_start", "message": {"usage": {"input_tokens": 42}}})
+            )
+            .unwrap()
+            .is_none());
+        let end = translator
+            .translate(&json!({"type": "message_delta", "delta": {"stop_reason": "end_turn"}, "usage": {"output_tokens": 7}}))
+            .unwrap()
+            .unwrap();
+        assert_eq!(end["choices"][0]["finish_reason"], "stop");
+        assert_eq!(end["usage"]["prompt_tokens"], 42);
+        assert_eq!(end["usage"]["completion_tokens"], 7);
+
+        assert!(translator
+            .translate(&json!({"type": "ping"}))
+            .unwrap()
+            .is_none());
+        assert!(!translator.is_finished());
+        translator
+            .translate(&json!({"type": "message_stop"}))
+            .unwrap();
+        assert!(translator.is_finished());
+        assert!(translator
+            .translate(&json!({"type": "error", "error": {"type": "overloaded_error"}}))
+            .is_err());
+    }
+}
@@ -1,6 +1,8 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
 use rusqlite::{params, Connection};
+use std::collections::HashMap;
+use std::sync::Arc;
 use uuid::Uuid;
 
 use super::{MemoryBackend, MemoryDesignVersion, WorkingMemoryEntry};
@@ -204,6 +206,270 @@ impl MemoryBackend for EpisodicMemoryBackendV3 {
     }
 }
 
+/// Produces embedding vectors for the vector memory backend.
+///
+/// The trait is synchronous because `MemoryBackend` runs under the database
+/// lock; remote embeddings are computed by callers (see `tools/memory.rs`) and
+/// ranked with `rank_by_embedding`.
+pub trait TextEmbedder: Send + Sync {
+    fn embed(&self, text: &str) -> Result<Vec<f32>>;
+}
+
+/// Deterministic, offline embedder using feature hashing over lowercase tokens
+/// and their character trigrams, so related word forms share dimensions.
+pub struct HashedTextEmbedder {
+    dimensions: usize,
+}
+
+pub const HASHED_EMBEDDING_DIMENSIONS: usize = 256;
+
+impl HashedTextEmbedder {
+    pub fn new(dimensions: usize) -> Self {
+        Self {
+            dimensions: dimensions.max(1),
+        }
+    }
+}
+
+impl Default for HashedTextEmbedder {
+    fn default() -> Self {
+        Self::new(HASHED_EMBEDDING_DIMENSIONS)
+    }
+}
+
+impl TextEmbedder for HashedTextEmbedder {
+    fn embed(&self, text: &str) -> Result<Vec<f32>> {
+        let mut vector = vec![0.0f32; self.dimensions];
+        for token in text
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|t| !t.is_empty())
+            .map(str::to_lowercase)
+        {
+            vector[fnv1a(token.as_bytes()) as usize % self.dimensions] += 2.0;
+            let padded: Vec<char> = format!("^{}$", token).chars().collect();
+            for window in padded.windows(3) {
+                let gram: String = window.iter().collect();
+                vector[fnv1a(gram.as_bytes()) as usize % self.dimensions] += 1.0;
+            }
+        }
+        normalize(&mut vector);
+        Ok(vector)
+    }
+}
+
+/// Embedder serving vectors computed ahead of time, typically by the
+/// configured remote embeddings model, so replay evaluation can score the
+/// embedder the agent actually uses. Texts it was not given are an error.
+pub struct PrecomputedTextEmbedder {
+    vectors: HashMap<String, Vec<f32>>,
+}
+
+impl PrecomputedTextEmbedder {
+    pub fn new(vectors: HashMap<String, Vec<f32>>) -> Self {
+        Self { vectors }
+    }
+}
+
+impl TextEmbedder for PrecomputedTextEmbedder {
+    fn embed(&self, text: &str) -> Result<Vec<f32>> {
+        self.vectors
+            .get(text)
+            .cloned()
+            .with_context(|| format!("No precomputed embedding for '{}'", text))
+    }
+}
+
+/// Candidate backend that stores an embedding per entry and ranks by cosine similarity.
+pub struct VectorMemoryBackendV4 {
+    embedder: Arc<dyn TextEmbedder>,
+}
+
+impl VectorMemoryBackendV4 {
+    pub fn new() -> Self {
+        Self::with_embedder(Arc::new(HashedTextEmbedder::default()))
+    }
+
+    pub fn with_embedder(embedder: Arc<dyn TextEmbedder>) -> Self {
+        Self { embedder }
+    }
+}
+
+impl Default for VectorMemoryBackendV4 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MemoryBackend for VectorMemoryBackendV4 {
+    fn design_version(&self) -> MemoryDesignVersion {
+        MemoryDesignVersion {
+            design_id: "vector_v4".to_string(),
+            schema_version: 4,
+        }
+    }
+
+    fn set_entry(&self, conn: &Connection, key: &str, content: &str) -> Result<()> {
+        ensure_vector_tables(conn)?;
+        let embedding = self
+            .embedder
+            .embed(&embedding_text(key, content))
+            .with_context(|| format!("Failed to embed memory entry '{}'", key))?;
+        conn.execute(
+            "INSERT OR REPLACE INTO working_memory_vectors (key, content, updated_at, embedding)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![
+                key,
+                content,
+                Utc::now().to_rfc3339(),
+                serde_json::to_string(&embedding)?
+            ],
+        )?;
+        Ok(())
+    }
+
+    fn get_entry(&self, conn: &Connection, key: &str) -> Result<Option<WorkingMemoryEntry>> {
+        ensure_vector_tables(conn)?;
+        let result = conn.query_row(
+            "SELECT key, content, updated_at
+             FROM working_memory_vectors
+             WHERE key = ?1",
+            [key],
+            |row| {
+                Ok(WorkingMemoryEntry {
+                    key: row.get(0)?,
+                    content: row.get(1)?,
+                    updated_at: parse_rfc3339(row.get::<_, String>(2)?, 2)?,
+                })
+            },
+        );
+
+        match result {
+            Ok(entry) => Ok(Some(entry)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn list_entries(&self, conn: &Connection) -> Result<Vec<WorkingMemoryEntry>> {
+        Ok(load_vector_rows(conn)?
+            .into_iter()
+            .map(|(entry, _)| entry)
+            .collect())
+    }
+
+    fn delete_entry(&self, conn: &Connection, key: &str) -> Result<()> {
+        ensure_vector_tables(conn)?;
+        conn.execute("DELETE FROM working_memory_vectors WHERE key = ?1", [key])?;
+        Ok(())
+    }
+
+    fn search_entries(
+        &self,
+        conn: &Connection,
+        query: &str,
+        top_k: usize,
+    ) -> Result<Option<Vec<WorkingMemoryEntry>>> {
+        let query_embedding = self.embedder.embed(query)?;
+        let rows = load_vector_rows(conn)?;
+        Ok(Some(rank_by_embedding(&query_embedding, rows, top_k)))
+    }
+}
+
+/// Text embedded for a memory entry; shared so stored and ad-hoc vectors agree.
+pub fn embedding_text(key: &str, content: &str) -> String {
+    format!("{}: {}", key, content)
+}
+
+/// Rank entries by cosine similarity to `query`, best first, ties broken by key.
+pub fn rank_by_embedding(
+    query: &[f32],
+    rows: Vec<(WorkingMemoryEntry, Vec<f32>)>,
+    top_k: usize,
+) -> Vec<WorkingMemoryEntry> {
+    rank_by_embedding_above(query, rows, top_k, f32::NEG_INFINITY)
+}
+
+/// `rank_by_embedding`, dropping entries whose similarity is below
+/// `min_similarity` so an unrelated query does not return the nearest noise.
+pub fn rank_by_embedding_above(
+    query: &[f32],
+    rows: Vec<(WorkingMemoryEntry, Vec<f32>)>,
+    top_k: usize,
+    min_similarity: f32,
+) -> Vec<WorkingMemoryEntry> {
+    let mut scored: Vec<(f32, WorkingMemoryEntry)> = rows
+        .into_iter()
+        .map(|(entry, embedding)| (cosine_similarity(query, &embedding), entry))
+        .filter(|(similarity, _)| *similarity >= min_similarity)
+        .collect();
+    scored.sort_by(|a, b| b.0.total_cmp(&a.0).then_with(|| a.1.key.cmp(&b.1.key)));
+    scored
+        .into_iter()
+        .take(top_k.max(1))
+        .map(|(_, entry)| entry)
+        .collect()
+}
+
+/// Cosine similarity over the shared prefix; zero when either side is empty or zero-length.
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    let len = a.len().min(b.len());
+    let (mut dot, mut norm_a, mut norm_b) = (0.0f32, 0.0f32, 0.0f32);
+    for i in 0..len {
+        dot += a[i] * b[i];
+        norm_a += a[i] * a[i];
+        norm_b += b[i] * b[i];
+    }
+    if norm_a <= f32::EPSILON || norm_b <= f32::EPSILON {
+        return 0.0;
+    }
+    dot / (norm_a.sqrt() * norm_b.sqrt())
+}
+
+fn load_vector_rows(conn: &Connection) -> Result<Vec<(WorkingMemoryEntry, Vec<f32>)>> {
+    ensure_vector_tables(conn)?;
+    let mut stmt = conn.prepare(
+        "SELECT key, content, updated_at, embedding
+         FROM working_memory_vectors
+         ORDER BY updated_at DESC",
+    )?;
+
+    let rows = stmt
+        .query_map([], |row| {
+            Ok((
+                WorkingMemoryEntry {
+                    key: row.get(0)?,
+                    content: row.get(1)?,
+                    updated_at: parse_rfc3339(row.get::<_, String>(2)?, 2)?,
+                },
+                row.get::<_, String>(3)?,
+            ))
+        })?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+
+    rows.into_iter()
+        .map(|(entry, raw)| {
+            let embedding: Vec<f32> = serde_json::from_str(&raw)
+                .with_context(|| format!("Corrupt embedding for memory entry '{}'", entry.key))?;
+            Ok((entry, embedding))
+        })
+        .collect()
+}
+
+fn normalize(vector: &mut [f32]) {
+    let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+    if norm > f32::EPSILON {
+        for v in vector.iter_mut() {
+            *v /= norm;
+        }
+    }
+}
+
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325u64, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
 fn ensure_fts_tables(conn: &Connection) -> Result<()> {
     conn.execute(
         r#"CREATE TABLE IF NOT EXISTS working_memory_fts_docs (
@@ -246,6 +512,19 @@ fn ensure_episodic_tables(conn: &Connection) -> Result<()> {
     Ok(())
 }
 
+fn ensure_vector_tables(conn: &Connection) -> Result<()> {
+    conn.execute(
+        r#"CREATE TABLE IF NOT EXISTS working_memory_vectors (
+            key TEXT PRIMARY KEY,
+            content TEXT NOT NULL,
+            updated_at TEXT NOT NULL,
+            embedding TEXT NOT NULL
+        )"#,
+        [],
+    )?;
+    Ok(())
+}
+
 fn parse_rfc3339(
     value: String,
     column: usize,
@@ -325,4 +604,44 @@ mod tests {
         assert_eq!(total_rows, 3);
         assert_eq!(active_rows, 1);
     }
+
+    #[test]
+    fn vector_backend_roundtrip_core_api() {
+        let conn = setup_conn();
+        let backend = VectorMemoryBackendV4::new();
+
+        backend.set_entry(&conn, "focus", "alpha").unwrap();
+        backend.set_entry(&conn, "focus", "beta").unwrap();
+        let got = backend.get_entry(&conn, "focus").unwrap().unwrap();
+        assert_eq!(got.content, "beta");
+        assert_eq!(backend.list_entries(&conn).unwrap().len(), 1);
+
+        backend.delete_entry(&conn, "focus").unwrap();
+        assert!(backend.get_entry(&conn, "focus").unwrap().is_none());
+    }
+
+    #[test]
+    fn vector_backend_ranks_related_word_forms_first() {
+        let conn = setup_conn();
+        let backend = VectorMemoryBackendV4::new();
+
+        backend
+            .set_entry(&conn, "release", "migrating the memory schema")
+            .unwrap();
+        backend
+            .set_entry(&conn, "garden", "water the tomatoes weekly")
+            .unwrap();
+
+        let ranked = backend
+            .search_entries(&conn, "schema migration", 2)
+            .unwrap()
+            .expect("vector backend ranks natively");
+        assert_eq!(ranked[0].key, "release");
+    }
+
+    #[test]
+    fn cosine_similarity_handles_zero_vectors() {
+        assert_eq!(cosine_similarity(&[0.0, 0.0], &[1.0, 0.0]), 0.0);
+        assert!((cosine_similarity(&[1.0, 0.0], &[2.0, 0.0]) - 1.0).abs() < 1e-6);
+    }
 }
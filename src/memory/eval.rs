@@ -4,9 +4,13 @@ use rusqlite::Connection;
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
 use std::path::Path;
+use std::sync::Arc;
 use std::time::Instant;
 
-use super::candidate_backends::{EpisodicMemoryBackendV3, FtsMemoryBackendV2};
+use super::candidate_backends::{
+    embedding_text, EpisodicMemoryBackendV3, FtsMemoryBackendV2, TextEmbedder,
+    VectorMemoryBackendV4,
+};
 use super::{KvMemoryBackend, MemoryBackend, MemoryDesignVersion, WorkingMemoryEntry};
 
 const DEFAULT_QUERY_TOP_K: usize = 3;
@@ -51,6 +55,7 @@ pub enum EvalBackendKind {
     KvV1,
     FtsV2,
     EpisodicV3,
+    VectorV4,
     NullV0,
 }
 
@@ -60,15 +65,29 @@ impl EvalBackendKind {
             EvalBackendKind::KvV1 => "kv_v1",
             EvalBackendKind::FtsV2 => "fts_v2",
             EvalBackendKind::EpisodicV3 => "episodic_v3",
+            EvalBackendKind::VectorV4 => "vector_v4",
             EvalBackendKind::NullV0 => "null_v0",
         }
     }
 
     pub fn build_backend(&self) -> Box<dyn MemoryBackend> {
+        self.build_backend_with_embedder(None)
+    }
+
+    /// `build_backend`, with `embedder` replacing the offline hashed embedder
+    /// for `vector_v4`. Other backends ignore it.
+    pub fn build_backend_with_embedder(
+        &self,
+        embedder: Option<Arc<dyn TextEmbedder>>,
+    ) -> Box<dyn MemoryBackend> {
         match self {
             EvalBackendKind::KvV1 => Box::new(KvMemoryBackend::new()),
             EvalBackendKind::FtsV2 => Box::new(FtsMemoryBackendV2::new()),
             EvalBackendKind::EpisodicV3 => Box::new(EpisodicMemoryBackendV3::new()),
+            EvalBackendKind::VectorV4 => Box::new(match embedder {
+                Some(embedder) => VectorMemoryBackendV4::with_embedder(embedder),
+                None => VectorMemoryBackendV4::new(),
+            }),
             EvalBackendKind::NullV0 => Box::new(NullMemoryBackend),
         }
     }
@@ -84,6 +103,10 @@ impl EvalBackendKind {
                 design_id: "episodic_v3".to_string(),
                 schema_version: 3,
             },
+            EvalBackendKind::VectorV4 => MemoryDesignVersion {
+                design_id: "vector_v4".to_string(),
+                schema_version: 4,
+            },
             EvalBackendKind::NullV0 => MemoryDesignVersion {
                 design_id: "null_v0".to_string(),
                 schema_version: 0,
@@ -214,6 +237,39 @@ pub fn write_report_json(report: &MemoryEvalReport, path: &Path) -> Result<()> {
 pub fn evaluate_trace_set(
     traces: &MemoryEvalTraceSet,
     candidates: &[EvalBackendKind],
+) -> Result<MemoryEvalReport> {
+    evaluate_trace_set_with_embedder(traces, candidates, None)
+}
+
+/// Every text `vector_v4` embeds while replaying `traces`: written entries
+/// (as `embedding_text`) and query strings, without duplicates.
+pub fn trace_set_embedding_texts(traces: &MemoryEvalTraceSet) -> Vec<String> {
+    let mut seen = HashSet::new();
+    let mut texts = Vec::new();
+    for trace in &traces.traces {
+        let writes = trace.steps.iter().filter_map(|step| match step {
+            MemoryEvalStep::Write { key, content } => Some(embedding_text(key, content)),
+            MemoryEvalStep::Delete { .. } => None,
+        });
+        let queries = trace.checks.iter().filter_map(|check| match check {
+            MemoryEvalCheck::Query { query, .. } => Some(query.clone()),
+            MemoryEvalCheck::Get { .. } => None,
+        });
+        for text in writes.chain(queries) {
+            if seen.insert(text.clone()) {
+                texts.push(text);
+            }
+        }
+    }
+    texts
+}
+
+/// `evaluate_trace_set` with `vector_v4` using `embedder` (for example a
+/// `PrecomputedTextEmbedder` filled from `trace_set_embedding_texts`).
+pub fn evaluate_trace_set_with_embedder(
+    traces: &MemoryEvalTraceSet,
+    candidates: &[EvalBackendKind],
+    embedder: Option<Arc<dyn TextEmbedder>>,
 ) -> Result<MemoryEvalReport> {
     if candidates.is_empty() {
         anyhow::bail!("No memory eval backend candidates provided");
@@ -221,7 +277,7 @@ pub fn evaluate_trace_set(
 
     let mut reports = Vec::with_capacity(candidates.len());
     for candidate in candidates {
-        reports.push(evaluate_candidate(traces, candidate)?);
+        reports.push(evaluate_candidate(traces, candidate, embedder.clone())?);
     }
 
     let winner = reports
@@ -324,8 +380,9 @@ pub fn evaluate_shadow_against_kv(
 fn evaluate_candidate(
     traces: &MemoryEvalTraceSet,
     candidate: &EvalBackendKind,
+    embedder: Option<Arc<dyn TextEmbedder>>,
 ) -> Result<MemoryEvalCandidateReport> {
-    let backend = candidate.build_backend();
+    let backend = candidate.build_backend_with_embedder(embedder);
     let design_version = candidate.design_version();
 
     let mut steps_total = 0usize;
@@ -467,8 +524,18 @@ fn evaluate_query_check(
         return Ok((1.0, 1.0));
     }
 
-    let entries = backend.list_entries(conn)?;
-    let ranked = rank_entries(query, &entries, top_k.max(1));
+    // Backends with native ranking (e.g. vector_v4) are scored on their own
+    // retrieval; the rest share the lexical ranker.
+    let ranked = match backend.search_entries(conn, query, top_k.max(1))? {
+        Some(found) => found,
+        None => {
+            let entries = backend.list_entries(conn)?;
+            rank_entries(query, &entries, top_k.max(1))
+                .into_iter()
+                .cloned()
+                .collect()
+        }
+    };
     let expected: HashSet<&str> = expected_keys.iter().map(|s| s.as_str()).collect();
 
     let top1_hit = ranked
@@ -680,16 +747,48 @@ mod tests {
                 EvalBackendKind::KvV1,
                 EvalBackendKind::FtsV2,
                 EvalBackendKind::EpisodicV3,
+                EvalBackendKind::VectorV4,
             ],
         )
         .unwrap();
 
-        assert_eq!(report.candidates.len(), 3);
+        assert_eq!(report.candidates.len(), 4);
         assert!(report.candidates.iter().any(|c| c.backend_id == "fts_v2"));
         assert!(report
             .candidates
             .iter()
             .any(|c| c.backend_id == "episodic_v3"));
+        let vector = report
+            .candidates
+            .iter()
+            .find(|c| c.backend_id == "vector_v4")
+            .expect("vector_v4 participates");
+        assert_eq!(vector.metrics.get_checks, vector.metrics.get_passed);
+    }
+
+    #[test]
+    fn vector_eval_runs_on_precomputed_embeddings() {
+        use crate::memory::candidate_backends::{HashedTextEmbedder, PrecomputedTextEmbedder};
+
+        let traces = sample_trace_set();
+        let hashed = HashedTextEmbedder::default();
+        let vectors = trace_set_embedding_texts(&traces)
+            .into_iter()
+            .map(|text| {
+                let vector = hashed.embed(&text).unwrap();
+                (text, vector)
+            })
+            .collect();
+        let embedder: Arc<dyn TextEmbedder> = Arc::new(PrecomputedTextEmbedder::new(vectors));
+
+        let precomputed =
+            evaluate_trace_set_with_embedder(&traces, &[EvalBackendKind::VectorV4], Some(embedder))
+                .expect("every embedded text was precomputed");
+        let offline = evaluate_trace_set(&traces, &[EvalBackendKind::VectorV4]).unwrap();
+        assert_eq!(
+            precomputed.candidates[0].metrics.recall_at_k,
+            offline.candidates[0].metrics.recall_at_k
+        );
     }
 
     #[test]
@@ -51,6 +51,17 @@ pub trait MemoryBackend: Send + Sync {
     fn get_entry(&self, conn: &Connection, key: &str) -> Result<Option<WorkingMemoryEntry>>;
     fn list_entries(&self, conn: &Connection) -> Result<Vec<WorkingMemoryEntry>>;
     fn delete_entry(&self, conn: &Connection, key: &str) -> Result<()>;
+
+    /// Backend-native ranked retrieval. `None` means the backend has no ranking
+    /// of its own and callers should fall back to lexical scoring.
+    fn search_entries(
+        &self,
+        _conn: &Connection,
+        _query: &str,
+        _top_k: usize,
+    ) -> Result<Option<Vec<WorkingMemoryEntry>>> {
+        Ok(None)
+    }
 }
 
 /// Baseline KV memory backend that preserves current behavior.
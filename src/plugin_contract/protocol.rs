@@ -24,6 +24,14 @@ pub struct RuntimePluginRpcError {
     pub message: String,
 }
 
+impl std::fmt::Display for RuntimePluginRpcError {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(formatter, "{}: {}", self.code, self.message)
+    }
+}
+
+impl std::error::Error for RuntimePluginRpcError {}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct RuntimePluginRpcResponse {
     #[serde(default = "default_plugin_protocol_version")]
@@ -0,0 +1,109 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+const DEFAULT_INITIAL_DELAY: Duration = Duration::from_secs(30);
+const DEFAULT_MAX_DELAY: Duration = Duration::from_secs(15 * 60);
+
+/// Per-plugin poll throttle for plugins whose `plugin.poll_events` keeps
+/// reporting errors, typically because the upstream API is rate limiting.
+/// Each consecutive failure doubles the wait before the next poll; a
+/// successful poll clears the streak.
+#[derive(Debug)]
+pub(crate) struct PluginPollBackoff {
+    initial_delay: Duration,
+    max_delay: Duration,
+    streaks: HashMap<String, PollFailureStreak>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct PollFailureStreak {
+    consecutive_failures: u32,
+    next_poll_at: Instant,
+}
+
+impl PluginPollBackoff {
+    pub(crate) fn new(initial_delay: Duration, max_delay: Duration) -> Self {
+        Self {
+            initial_delay,
+            max_delay: max_delay.max(initial_delay),
+            streaks: HashMap::new(),
+        }
+    }
+
+    /// Whether `plugin_id` may be polled at `now`.
+    pub(crate) fn is_due(&self, plugin_id: &str, now: Instant) -> bool {
+        self.streaks
+            .get(plugin_id)
+            .is_none_or(|streak| now >= streak.next_poll_at)
+    }
+
+    /// Record a failed poll and return how long the plugin now sits out.
+    pub(crate) fn record_failure(&mut self, plugin_id: &str, now: Instant) -> Duration {
+        let consecutive_failures = self
+            .streaks
+            .get(plugin_id)
+            .map_or(1, |streak| streak.consecutive_failures.saturating_add(1));
+        let delay = self.delay_for(consecutive_failures);
+        self.streaks.insert(
+            plugin_id.to_string(),
+            PollFailureStreak {
+                consecutive_failures,
+                next_poll_at: now + delay,
+            },
+        );
+        delay
+    }
+
+    /// Clear the streak after a successful poll or a process restart.
+    pub(crate) fn reset(&mut self, plugin_id: &str) {
+        self.streaks.remove(plugin_id);
+    }
+
+    fn delay_for(&self, consecutive_failures: u32) -> Duration {
+        let exponent = consecutive_failures.saturating_sub(1).min(31);
+        self.initial_delay
+            .saturating_mul(1_u32 << exponent)
+            .min(self.max_delay)
+    }
+}
+
+impl Default for PluginPollBackoff {
+    fn default() -> Self {
+        Self::new(DEFAULT_INITIAL_DELAY, DEFAULT_MAX_DELAY)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn consecutive_failures_grow_the_poll_interval_until_success() {
+        let mut backoff = PluginPollBackoff::new(Duration::from_secs(5), Duration::from_secs(30));
+        let start = Instant::now();
+
+        let delays = (0..5)
+            .map(|_| backoff.record_failure("graphchan", start))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            delays,
+            vec![
+                Duration::from_secs(5),
+                Duration::from_secs(10),
+                Duration::from_secs(20),
+                Duration::from_secs(30),
+                Duration::from_secs(30),
+            ]
+        );
+        assert!(!backoff.is_due("graphchan", start + Duration::from_secs(29)));
+        assert!(backoff.is_due("graphchan", start + Duration::from_secs(30)));
+        assert!(backoff.is_due("other", start));
+
+        backoff.reset("graphchan");
+        assert!(backoff.is_due("graphchan", start));
+        assert_eq!(
+            backoff.record_failure("graphchan", start),
+            Duration::from_secs(5)
+        );
+    }
+}
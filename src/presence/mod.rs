@@ -3,10 +3,23 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::process::Command;
 use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::RwLock;
 use std::time::{Duration, Instant};
 
 static LOCAL_TIME_FALLBACK_WARNED: AtomicBool = AtomicBool::new(false);
 
+/// Operator keyword patterns consulted before the built-in heuristics.
+/// Process-wide because `FocusedWindow::category` has no monitor handle.
+static CUSTOM_PROCESS_CATEGORIES: RwLock<Vec<(ProcessCategory, Vec<String>)>> =
+    RwLock::new(Vec::new());
+
+/// Interval between the two CPU counter reads when no previous sample exists.
+const CPU_SAMPLE_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Process names that only run while the screen is being shared: Zoom's
+/// share host, macOS Screen Sharing, and KDE's desktop sharing server.
+const SCREEN_SHARE_PROCESS_MARKERS: &[&str] = &["cpthost", "screensharingagent", "krfb"];
+
 /// Foundation-only presence monitor.
 ///
 /// ll.1 intentionally keeps this as a lightweight stub so schema/types can land
@@ -15,6 +28,51 @@ pub struct PresenceMonitor {
     session_start: Instant,
     last_interaction: Option<Instant>,
     process_cache: HashMap<u32, ProcessCategory>,
+    /// Aggregate CPU counters from the previous sample; later samples report
+    /// load over the interval since then.
+    last_cpu_times: Option<CpuTimes>,
+    /// Configured Linux idle probe order; empty means auto-detect from the
+    /// session type.
+    #[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+    idle_probes: Vec<IdleProbe>,
+    /// Whether the last process scan saw a screen-sharing helper.
+    screen_sharing: bool,
+}
+
+/// A source of user idle time on Linux desktops.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IdleProbe {
+    /// `xprintidle`, which only answers under X11 (or XWayland).
+    Xprintidle,
+    /// GNOME's `org.gnome.Mutter.IdleMonitor` over the session bus.
+    MutterIdleMonitor,
+    /// `org.freedesktop.ScreenSaver.GetSessionIdleTime` over the session bus
+    /// (KDE Plasma and other implementers).
+    FreedesktopScreenSaver,
+}
+
+impl IdleProbe {
+    pub fn from_config_key(key: &str) -> Option<Self> {
+        match key.trim().to_ascii_lowercase().as_str() {
+            "xprintidle" | "x11" => Some(Self::Xprintidle),
+            "mutter" | "gnome" => Some(Self::MutterIdleMonitor),
+            "screensaver" | "freedesktop" => Some(Self::FreedesktopScreenSaver),
+            _ => None,
+        }
+    }
+
+    /// Parses configured probe keys, skipping unknown entries and duplicates.
+    pub fn parse_order(keys: &[String]) -> Vec<Self> {
+        let mut probes = Vec::new();
+        for key in keys {
+            match Self::from_config_key(key) {
+                Some(probe) if !probes.contains(&probe) => probes.push(probe),
+                Some(_) => {}
+                None => tracing::warn!("Ignoring unknown idle detection probe '{}'", key),
+            }
+        }
+        probes
+    }
 }
 
 impl PresenceMonitor {
@@ -23,6 +81,27 @@ impl PresenceMonitor {
             session_start: Instant::now(),
             last_interaction: None,
             process_cache: HashMap::new(),
+            last_cpu_times: None,
+            idle_probes: Vec::new(),
+            screen_sharing: false,
+        }
+    }
+
+    /// Sets the Linux idle probe order. An empty list restores auto-detection.
+    pub fn set_idle_probes(&mut self, probes: Vec<IdleProbe>) {
+        self.idle_probes = probes;
+    }
+
+    /// Installs operator keyword patterns from
+    /// `ProcessCategory::parse_keyword_map`. Cached per-pid categories were
+    /// computed under the old patterns, so a change clears the cache.
+    pub fn set_process_categories(&mut self, categories: Vec<(ProcessCategory, Vec<String>)>) {
+        let mut current = CUSTOM_PROCESS_CATEGORIES
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if *current != categories {
+            *current = categories;
+            self.process_cache.clear();
         }
     }
 
@@ -30,6 +109,11 @@ impl PresenceMonitor {
         self.last_interaction = Some(Instant::now());
     }
 
+    /// Screen-sharing state as of the last `sample`.
+    pub fn is_screen_sharing(&self) -> bool {
+        self.screen_sharing
+    }
+
     pub fn sample(&mut self) -> PresenceState {
         let now = Instant::now();
         let user_idle_seconds = self.get_user_idle_seconds().unwrap_or_else(|| {
@@ -39,7 +123,9 @@ impl PresenceMonitor {
         });
         let time_since_interaction = Duration::from_secs(user_idle_seconds);
         let active_processes = self.get_interesting_processes();
-        let system_load = self.get_system_load();
+        let mut system_load = self.get_system_load();
+        system_load.screen_sharing = self.screen_sharing;
+        let power = sample_power_state();
 
         PresenceState {
             user_idle_seconds,
@@ -47,6 +133,7 @@ impl PresenceMonitor {
             session_duration: now.saturating_duration_since(self.session_start),
             time_context: TimeContext::now(),
             system_load,
+            power,
             active_processes,
         }
     }
@@ -73,19 +160,49 @@ impl PresenceMonitor {
 
     #[cfg(target_os = "linux")]
     fn get_user_idle_seconds(&self) -> Option<u64> {
-        let output = Command::new("xprintidle").output().ok()?;
-        if !output.status.success() {
+        let wayland = std::env::var_os("WAYLAND_DISPLAY").is_some_and(|value| !value.is_empty());
+        resolve_idle_probe_order(&self.idle_probes, wayland)
+            .into_iter()
+            .find_map(|probe| {
+                let ms = match probe {
+                    IdleProbe::Xprintidle => query_xprintidle_ms(),
+                    IdleProbe::MutterIdleMonitor => query_dbus_idle_ms(&[
+                        "--dest=org.gnome.Mutter.IdleMonitor",
+                        "/org/gnome/Mutter/IdleMonitor/Core",
+                        "org.gnome.Mutter.IdleMonitor.GetIdletime",
+                    ]),
+                    IdleProbe::FreedesktopScreenSaver => query_dbus_idle_ms(&[
+                        "--dest=org.freedesktop.ScreenSaver",
+                        "/org/freedesktop/ScreenSaver",
+                        "org.freedesktop.ScreenSaver.GetSessionIdleTime",
+                    ]),
+                };
+                ms.map(|ms| ms / 1000)
+            })
+    }
+
+    #[cfg(target_os = "windows")]
+    fn get_user_idle_seconds(&self) -> Option<u64> {
+        use windows_sys::Win32::System::SystemInformation::GetTickCount;
+        use windows_sys::Win32::UI::Input::KeyboardAndMouse::{GetLastInputInfo, LASTINPUTINFO};
+
+        let mut info = LASTINPUTINFO {
+            cbSize: std::mem::size_of::<LASTINPUTINFO>() as u32,
+            dwTime: 0,
+        };
+        // SAFETY: `info` is a properly sized, initialized LASTINPUTINFO that
+        // outlives the call; the API only writes `dwTime`.
+        if unsafe { GetLastInputInfo(&mut info) } == 0 {
             return None;
         }
-        let ms = String::from_utf8(output.stdout)
-            .ok()?
-            .trim()
-            .parse::<u64>()
-            .ok()?;
-        Some(ms / 1000)
+        // Both values are 32-bit millisecond tick counts that wrap after ~49.7
+        // days, so the wrapping difference is still the elapsed idle time.
+        // SAFETY: GetTickCount has no preconditions.
+        let now = unsafe { GetTickCount() };
+        Some(u64::from(now.wrapping_sub(info.dwTime)) / 1000)
     }
 
-    #[cfg(not(any(target_os = "macos", target_os = "linux")))]
+    #[cfg(not(any(target_os = "macos", target_os = "linux", target_os = "windows")))]
     fn get_user_idle_seconds(&self) -> Option<u64> {
         None
     }
@@ -105,6 +222,7 @@ impl PresenceMonitor {
         };
 
         let mut processes = Vec::new();
+        let mut screen_sharing = false;
         for line in text.lines() {
             let mut parts = line.trim().split_whitespace();
             let pid = match parts.next().and_then(|v| v.parse::<u32>().ok()) {
@@ -115,10 +233,12 @@ impl PresenceMonitor {
                 Some(v) => v,
                 None => continue,
             };
+            let comm = parts.next().unwrap_or_default();
+            // Share helpers can sit near 0% CPU, so check before the CPU filter.
+            screen_sharing |= is_screen_share_process(comm);
             if cpu <= 0.25 {
                 continue;
             }
-            let comm = parts.next().unwrap_or_default();
             let args = parts.collect::<Vec<_>>().join(" ");
             let descriptor = if args.is_empty() {
                 comm.to_string()
@@ -138,12 +258,13 @@ impl PresenceMonitor {
             });
         }
 
+        self.screen_sharing = screen_sharing;
         processes.sort_by(|a, b| b.cpu_percent.total_cmp(&a.cpu_percent));
         processes.truncate(8);
         processes
     }
 
-    fn get_system_load(&self) -> SystemLoad {
+    fn get_system_load(&mut self) -> SystemLoad {
         let cpu_percent = self.sample_cpu_percent().unwrap_or(0.0);
         let memory_percent = self.sample_memory_percent().unwrap_or(0.0);
         let (gpu_temp_celsius, gpu_util_percent) = self.sample_gpu();
@@ -153,10 +274,50 @@ impl PresenceMonitor {
             memory_percent,
             gpu_temp_celsius,
             gpu_util_percent,
+            screen_sharing: false,
         }
     }
 
-    fn sample_cpu_percent(&self) -> Option<f32> {
+    #[cfg(target_os = "linux")]
+    fn sample_cpu_percent(&mut self) -> Option<f32> {
+        let read = || {
+            std::fs::read_to_string("/proc/stat")
+                .ok()
+                .and_then(|text| parse_proc_stat_cpu(&text))
+        };
+        let previous = match self.last_cpu_times {
+            Some(times) => times,
+            None => {
+                let first = read()?;
+                std::thread::sleep(CPU_SAMPLE_INTERVAL);
+                first
+            }
+        };
+        let current = read()?;
+        self.last_cpu_times = Some(current);
+        cpu_percent_between(previous, current)
+    }
+
+    #[cfg(target_os = "macos")]
+    fn sample_cpu_percent(&mut self) -> Option<f32> {
+        // The first `top` sample is the boot-time average; the second covers
+        // the one-second interval that `-s 1` waits between them.
+        let output = Command::new("top")
+            .args(["-l", "2", "-n", "0", "-s", "1"])
+            .output()
+            .ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        let text = String::from_utf8(output.stdout).ok()?;
+        let idle = parse_top_cpu_idle_percent(&text)?;
+        Some((100.0 - idle).clamp(0.0, 100.0))
+    }
+
+    /// Fallback for platforms without delta counters. `pcpu` is a lifetime
+    /// average per process, so this only approximates current load.
+    #[cfg(not(any(target_os = "linux", target_os = "macos")))]
+    fn sample_cpu_percent(&mut self) -> Option<f32> {
         let output = Command::new("ps")
             .args(["-A", "-o", "pcpu="])
             .output()
@@ -189,6 +350,9 @@ impl PresenceMonitor {
         Some(total.clamp(0.0, 100.0))
     }
 
+    /// Normalizes the summed per-process `pcpu` fallback; the Linux/macOS
+    /// delta samplers are already expressed as a share of all cores.
+    #[cfg_attr(any(target_os = "linux", target_os = "macos"), allow(dead_code))]
     fn logical_core_count(&self) -> Option<u32> {
         #[cfg(target_os = "macos")]
         {
@@ -270,6 +434,9 @@ pub struct PresenceState {
     pub session_duration: Duration,
     pub time_context: TimeContext,
     pub system_load: SystemLoad,
+    /// Defaults to mains power when absent so older persisted snapshots load.
+    #[serde(default)]
+    pub power: PowerState,
     pub active_processes: Vec<InterestingProcess>,
 }
 
@@ -306,6 +473,17 @@ impl TimeContext {
         Self::from_components(hour, minute, weekday)
     }
 
+    /// Whether the local hour falls in `[start, end)`, wrapping past midnight
+    /// when `start > end` (e.g. 23 to 6). Equal bounds describe an empty window.
+    pub fn in_quiet_hours(&self, start: u8, end: u8) -> bool {
+        let hour = self.local_hour;
+        if start <= end {
+            (start..end).contains(&hour)
+        } else {
+            !(end..start).contains(&hour)
+        }
+    }
+
     fn from_components(hour: u8, minute: u8, weekday: Weekday) -> Self {
         let is_weekend = matches!(weekday, Weekday::Sat | Weekday::Sun);
         Self {
@@ -326,6 +504,39 @@ pub struct SystemLoad {
     pub memory_percent: f32,
     pub gpu_temp_celsius: Option<f32>,
     pub gpu_util_percent: Option<f32>,
+    /// A known screen-sharing helper process is running.
+    #[serde(default)]
+    pub screen_sharing: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PowerState {
+    pub on_ac: bool,
+    pub battery_percent: Option<f32>,
+    pub charging: bool,
+}
+
+impl Default for PowerState {
+    /// Desktop assumption used when no battery can be read.
+    fn default() -> Self {
+        Self {
+            on_ac: true,
+            battery_percent: None,
+            charging: false,
+        }
+    }
+}
+
+impl PowerState {
+    /// Whether heavy background work may start: always on mains power, and on
+    /// battery only at or above `min_battery_percent` (an unreadable charge
+    /// level does not block).
+    pub fn allows_heavy_work(&self, min_battery_percent: f32) -> bool {
+        self.on_ac
+            || self
+                .battery_percent
+                .is_none_or(|percent| percent >= min_battery_percent)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -335,7 +546,7 @@ pub struct InterestingProcess {
     pub cpu_percent: f32,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum ProcessCategory {
     Development,
@@ -343,12 +554,513 @@ pub enum ProcessCategory {
     Research,
     Communication,
     Media,
+    /// Operator-defined deep-work apps; has no built-in keywords.
+    Focus,
     System,
 }
 
+impl ProcessCategory {
+    pub fn from_config_key(key: &str) -> Option<Self> {
+        match key.trim().to_ascii_lowercase().as_str() {
+            "development" | "dev" => Some(Self::Development),
+            "creative" => Some(Self::Creative),
+            "research" => Some(Self::Research),
+            "communication" | "chat" => Some(Self::Communication),
+            "media" => Some(Self::Media),
+            "focus" => Some(Self::Focus),
+            "system" => Some(Self::System),
+            _ => None,
+        }
+    }
+
+    /// Parses the `process_categories` config map into lowercase keyword
+    /// lists in category order, skipping unknown categories and blank keywords.
+    pub fn parse_keyword_map(map: &HashMap<String, Vec<String>>) -> Vec<(Self, Vec<String>)> {
+        let mut merged: Vec<(Self, Vec<String>)> = Vec::new();
+        for (key, keywords) in map {
+            let Some(category) = Self::from_config_key(key) else {
+                tracing::warn!("Ignoring unknown process category '{}'", key);
+                continue;
+            };
+            let keywords = keywords
+                .iter()
+                .map(|keyword| keyword.trim().to_ascii_lowercase())
+                .filter(|keyword| !keyword.is_empty());
+            match merged
+                .iter_mut()
+                .find(|(existing, _)| *existing == category)
+            {
+                Some((_, list)) => list.extend(keywords),
+                None => merged.push((category, keywords.collect())),
+            }
+        }
+        merged.sort_by_key(|(category, _)| *category);
+        for (_, keywords) in &mut merged {
+            keywords.sort();
+            keywords.dedup();
+        }
+        merged.retain(|(_, keywords)| !keywords.is_empty());
+        merged
+    }
+}
+
+/// The application and window title that currently has input focus.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FocusedWindow {
+    pub app: String,
+    pub title: String,
+}
+
+impl FocusedWindow {
+    /// Keyword category of the focused app, matched against both the app
+    /// name and the title (browser tabs often name the real activity).
+    pub fn category(&self) -> ProcessCategory {
+        match categorize_process(&self.app) {
+            ProcessCategory::System => categorize_process(&self.title),
+            category => category,
+        }
+    }
+}
+
+/// Samples the focused window through platform window-manager APIs.
+///
+/// Unlike screenshots this needs no screen-recording permission. Returns
+/// `None` when no probe is available or nothing is focused.
+pub fn sample_focused_window() -> Option<FocusedWindow> {
+    sample_focused_window_impl()
+}
+
+#[cfg(target_os = "macos")]
+fn sample_focused_window_impl() -> Option<FocusedWindow> {
+    const SCRIPT: &str = r#"tell application "System Events"
+    set frontApp to first application process whose frontmost is true
+    set appName to name of frontApp
+    set winTitle to ""
+    try
+        set winTitle to name of front window of frontApp
+    end try
+end tell
+return appName & linefeed & winTitle"#;
+    let output = Command::new("osascript")
+        .args(["-e", SCRIPT])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    parse_app_title_lines(&String::from_utf8_lossy(&output.stdout))
+}
+
+#[cfg(target_os = "linux")]
+fn sample_focused_window_impl() -> Option<FocusedWindow> {
+    let session_var = |key: &str| std::env::var_os(key).is_some_and(|value| !value.is_empty());
+    if session_var("HYPRLAND_INSTANCE_SIGNATURE") {
+        if let Some(window) = Command::new("hyprctl")
+            .args(["activewindow", "-j"])
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+            .and_then(|output| {
+                parse_hyprctl_active_window(&String::from_utf8_lossy(&output.stdout))
+            })
+        {
+            return Some(window);
+        }
+    }
+    if session_var("DISPLAY") {
+        // `getactivewindow` pushes the window onto xdotool's stack, so the
+        // chained commands print its class then its title on separate lines.
+        let output = Command::new("xdotool")
+            .args(["getactivewindow", "getwindowclassname", "getwindowname"])
+            .output()
+            .ok()?;
+        if output.status.success() {
+            return parse_app_title_lines(&String::from_utf8_lossy(&output.stdout));
+        }
+    }
+    None
+}
+
+#[cfg(target_os = "windows")]
+fn sample_focused_window_impl() -> Option<FocusedWindow> {
+    use windows_sys::Win32::Foundation::CloseHandle;
+    use windows_sys::Win32::System::Threading::{
+        OpenProcess, QueryFullProcessImageNameW, PROCESS_NAME_WIN32,
+        PROCESS_QUERY_LIMITED_INFORMATION,
+    };
+    use windows_sys::Win32::UI::WindowsAndMessaging::{
+        GetForegroundWindow, GetWindowTextW, GetWindowThreadProcessId,
+    };
+
+    // SAFETY: GetForegroundWindow has no preconditions.
+    let hwnd = unsafe { GetForegroundWindow() };
+    if hwnd == 0 {
+        return None;
+    }
+
+    let mut title_buf = [0u16; 512];
+    // SAFETY: `title_buf` is writable for the advertised length and `hwnd`
+    // came from GetForegroundWindow; a stale handle only yields 0.
+    let title_len = unsafe { GetWindowTextW(hwnd, title_buf.as_mut_ptr(), title_buf.len() as i32) };
+    let title = String::from_utf16_lossy(&title_buf[..title_len.max(0) as usize]);
+
+    let mut pid = 0u32;
+    // SAFETY: `pid` outlives the call and the API only writes through it.
+    unsafe { GetWindowThreadProcessId(hwnd, &mut pid) };
+    let mut app = String::new();
+    if pid != 0 {
+        // SAFETY: limited-information access is requested for a process id
+        // obtained above; a failed open returns 0 and is skipped.
+        let process = unsafe { OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, pid) };
+        if process != 0 {
+            let mut path_buf = [0u16; 1024];
+            let mut path_len = path_buf.len() as u32;
+            // SAFETY: `path_buf`/`path_len` describe a writable buffer and
+            // `process` is an open handle closed immediately afterwards.
+            let ok = unsafe {
+                QueryFullProcessImageNameW(
+                    process,
+                    PROCESS_NAME_WIN32,
+                    path_buf.as_mut_ptr(),
+                    &mut path_len,
+                )
+            };
+            // SAFETY: `process` was opened above and is not used again.
+            unsafe { CloseHandle(process) };
+            if ok != 0 {
+                let path = String::from_utf16_lossy(&path_buf[..path_len as usize]);
+                app = std::path::Path::new(&path)
+                    .file_stem()
+                    .map(|stem| stem.to_string_lossy().into_owned())
+                    .unwrap_or(path);
+            }
+        }
+    }
+
+    focused_window_from_parts(&app, &title)
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux", target_os = "windows")))]
+fn sample_focused_window_impl() -> Option<FocusedWindow> {
+    None
+}
+
+/// Screen rectangle of a window in the desktop's own coordinates, which may
+/// be logical points rather than screenshot pixels on scaled displays.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WindowBounds {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Bounds of the focused window where the platform exposes them (macOS via
+/// `osascript`, Hyprland, X11 via `xdotool`, Windows).
+pub fn sample_focused_window_bounds() -> Option<WindowBounds> {
+    sample_focused_window_bounds_impl()
+}
+
+#[cfg(target_os = "macos")]
+fn sample_focused_window_bounds_impl() -> Option<WindowBounds> {
+    const SCRIPT: &str = r#"tell application "System Events"
+    set frontApp to first application process whose frontmost is true
+    set {winX, winY} to position of front window of frontApp
+    set {winW, winH} to size of front window of frontApp
+end tell
+return (winX as text) & "," & (winY as text) & "," & (winW as text) & "," & (winH as text)"#;
+    let output = Command::new("osascript")
+        .args(["-e", SCRIPT])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    parse_comma_bounds(&String::from_utf8_lossy(&output.stdout))
+}
+
+#[cfg(target_os = "linux")]
+fn sample_focused_window_bounds_impl() -> Option<WindowBounds> {
+    let session_var = |key: &str| std::env::var_os(key).is_some_and(|value| !value.is_empty());
+    if session_var("HYPRLAND_INSTANCE_SIGNATURE") {
+        if let Some(bounds) = Command::new("hyprctl")
+            .args(["activewindow", "-j"])
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+            .and_then(|output| {
+                parse_hyprctl_window_bounds(&String::from_utf8_lossy(&output.stdout))
+            })
+        {
+            return Some(bounds);
+        }
+    }
+    if session_var("DISPLAY") {
+        let output = Command::new("xdotool")
+            .args(["getactivewindow", "getwindowgeometry", "--shell"])
+            .output()
+            .ok()?;
+        if output.status.success() {
+            return parse_xdotool_geometry(&String::from_utf8_lossy(&output.stdout));
+        }
+    }
+    None
+}
+
+#[cfg(target_os = "windows")]
+fn sample_focused_window_bounds_impl() -> Option<WindowBounds> {
+    use windows_sys::Win32::Foundation::RECT;
+    use windows_sys::Win32::UI::WindowsAndMessaging::{GetForegroundWindow, GetWindowRect};
+
+    // SAFETY: GetForegroundWindow has no preconditions.
+    let hwnd = unsafe { GetForegroundWindow() };
+    if hwnd == 0 {
+        return None;
+    }
+    let mut rect = RECT {
+        left: 0,
+        top: 0,
+        right: 0,
+        bottom: 0,
+    };
+    // SAFETY: `rect` outlives the call and the API only writes through it; a
+    // stale handle returns 0.
+    if unsafe { GetWindowRect(hwnd, &mut rect) } == 0 {
+        return None;
+    }
+    bounds_from_parts(
+        rect.left as i64,
+        rect.top as i64,
+        (rect.right - rect.left) as i64,
+        (rect.bottom - rect.top) as i64,
+    )
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux", target_os = "windows")))]
+fn sample_focused_window_bounds_impl() -> Option<WindowBounds> {
+    None
+}
+
+/// Size of the whole desktop in the same coordinates `WindowBounds` uses, so
+/// a window rectangle can be scaled onto a screenshot's pixels.
+pub fn sample_desktop_size() -> Option<(u32, u32)> {
+    sample_desktop_size_impl()
+}
+
+#[cfg(target_os = "macos")]
+fn sample_desktop_size_impl() -> Option<(u32, u32)> {
+    let output = Command::new("osascript")
+        .args([
+            "-e",
+            r#"tell application "Finder" to get bounds of window of desktop"#,
+        ])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    parse_corner_bounds_size(&String::from_utf8_lossy(&output.stdout))
+}
+
+#[cfg(target_os = "linux")]
+fn sample_desktop_size_impl() -> Option<(u32, u32)> {
+    let session_var = |key: &str| std::env::var_os(key).is_some_and(|value| !value.is_empty());
+    let stdout = |program: &str, args: &[&str]| {
+        Command::new(program)
+            .args(args)
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+            .map(|output| String::from_utf8_lossy(&output.stdout).to_string())
+    };
+    if session_var("HYPRLAND_INSTANCE_SIGNATURE") {
+        if let Some(size) =
+            stdout("hyprctl", &["monitors", "-j"]).and_then(|text| parse_hyprctl_layout_size(&text))
+        {
+            return Some(size);
+        }
+    }
+    if session_var("DISPLAY") {
+        return stdout("xdotool", &["getdisplaygeometry"])
+            .and_then(|text| parse_display_geometry(&text));
+    }
+    None
+}
+
+#[cfg(target_os = "windows")]
+fn sample_desktop_size_impl() -> Option<(u32, u32)> {
+    use windows_sys::Win32::UI::WindowsAndMessaging::{
+        GetSystemMetrics, SM_CXVIRTUALSCREEN, SM_CYVIRTUALSCREEN,
+    };
+
+    // SAFETY: GetSystemMetrics has no preconditions.
+    let (width, height) = unsafe {
+        (
+            GetSystemMetrics(SM_CXVIRTUALSCREEN),
+            GetSystemMetrics(SM_CYVIRTUALSCREEN),
+        )
+    };
+    Some((u32::try_from(width).ok()?, u32::try_from(height).ok()?))
+        .filter(|(w, h)| *w > 0 && *h > 0)
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux", target_os = "windows")))]
+fn sample_desktop_size_impl() -> Option<(u32, u32)> {
+    None
+}
+
+/// Reads Finder's `left, top, right, bottom` desktop bounds.
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn parse_corner_bounds_size(text: &str) -> Option<(u32, u32)> {
+    let corners = parse_comma_bounds(text)?;
+    let width = i64::from(corners.width) - i64::from(corners.x);
+    let height = i64::from(corners.height) - i64::from(corners.y);
+    Some((u32::try_from(width).ok()?, u32::try_from(height).ok()?))
+        .filter(|(w, h)| *w > 0 && *h > 0)
+}
+
+/// Logical extent of the Hyprland layout: each monitor's pixel size divided
+/// by its scale, offset by its layout position.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_hyprctl_layout_size(text: &str) -> Option<(u32, u32)> {
+    let value: serde_json::Value = serde_json::from_str(text.trim()).ok()?;
+    let (mut width, mut height) = (0.0_f64, 0.0_f64);
+    for monitor in value.as_array()? {
+        let number = |key: &str| monitor.get(key).and_then(serde_json::Value::as_f64);
+        let scale = number("scale").filter(|scale| *scale > 0.0).unwrap_or(1.0);
+        width = width.max(number("x")? + number("width")? / scale);
+        height = height.max(number("y")? + number("height")? / scale);
+    }
+    Some((width.round() as u32, height.round() as u32)).filter(|(w, h)| *w > 0 && *h > 0)
+}
+
+/// Reads `xdotool getdisplaygeometry`'s `width height`.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_display_geometry(text: &str) -> Option<(u32, u32)> {
+    let mut parts = text.split_whitespace().map(|part| part.parse::<u32>().ok());
+    let size = (parts.next()??, parts.next()??);
+    Some(size).filter(|(w, h)| *w > 0 && *h > 0)
+}
+
+fn bounds_from_parts(x: i64, y: i64, width: i64, height: i64) -> Option<WindowBounds> {
+    if width <= 0 || height <= 0 {
+        return None;
+    }
+    Some(WindowBounds {
+        x: i32::try_from(x).ok()?,
+        y: i32::try_from(y).ok()?,
+        width: u32::try_from(width).ok()?,
+        height: u32::try_from(height).ok()?,
+    })
+}
+
+/// Reads `x,y,width,height` from the macOS `osascript` probe.
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn parse_comma_bounds(text: &str) -> Option<WindowBounds> {
+    let parts: Vec<i64> = text
+        .trim()
+        .split(',')
+        .map(|part| part.trim().parse().ok())
+        .collect::<Option<_>>()?;
+    match parts.as_slice() {
+        [x, y, width, height] => bounds_from_parts(*x, *y, *width, *height),
+        _ => None,
+    }
+}
+
+/// Reads the `at` and `size` pairs from `hyprctl activewindow -j`.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_hyprctl_window_bounds(text: &str) -> Option<WindowBounds> {
+    let value: serde_json::Value = serde_json::from_str(text.trim()).ok()?;
+    let pair = |key: &str| -> Option<(i64, i64)> {
+        let items = value.get(key)?.as_array()?;
+        Some((items.first()?.as_i64()?, items.get(1)?.as_i64()?))
+    };
+    let (x, y) = pair("at")?;
+    let (width, height) = pair("size")?;
+    bounds_from_parts(x, y, width, height)
+}
+
+/// Reads `X=`, `Y=`, `WIDTH=`, `HEIGHT=` lines from
+/// `xdotool getwindowgeometry --shell`.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_xdotool_geometry(text: &str) -> Option<WindowBounds> {
+    let field = |key: &str| -> Option<i64> {
+        text.lines()
+            .find_map(|line| line.trim().strip_prefix(key)?.strip_prefix('='))
+            .and_then(|value| value.trim().parse().ok())
+    };
+    bounds_from_parts(field("X")?, field("Y")?, field("WIDTH")?, field("HEIGHT")?)
+}
+
+/// Builds a `FocusedWindow`, falling back to the title when the app name is
+/// unknown; returns `None` when both are empty.
+fn focused_window_from_parts(app: &str, title: &str) -> Option<FocusedWindow> {
+    let app = app.trim();
+    let title = title.trim();
+    if app.is_empty() && title.is_empty() {
+        return None;
+    }
+    Some(FocusedWindow {
+        app: if app.is_empty() { title } else { app }.to_string(),
+        title: title.to_string(),
+    })
+}
+
+/// Reads `app\ntitle` output from `osascript` or chained `xdotool` commands.
+#[cfg_attr(not(any(target_os = "macos", target_os = "linux")), allow(dead_code))]
+fn parse_app_title_lines(text: &str) -> Option<FocusedWindow> {
+    let mut lines = text.lines();
+    let app = lines.next().unwrap_or_default();
+    let title = lines.next().unwrap_or_default();
+    focused_window_from_parts(app, title)
+}
+
+/// Reads `hyprctl activewindow -j`, which prints `{}` or `Invalid` when no
+/// window is focused.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_hyprctl_active_window(text: &str) -> Option<FocusedWindow> {
+    let value: serde_json::Value = serde_json::from_str(text.trim()).ok()?;
+    let field = |key: &str| {
+        value
+            .get(key)
+            .and_then(serde_json::Value::as_str)
+            .unwrap_or("")
+    };
+    let app = match field("class") {
+        "" => field("initialClass"),
+        class => class,
+    };
+    focused_window_from_parts(app, field("title"))
+}
+
+fn is_screen_share_process(comm: &str) -> bool {
+    let name = comm.rsplit('/').next().unwrap_or(comm).to_ascii_lowercase();
+    SCREEN_SHARE_PROCESS_MARKERS
+        .iter()
+        .any(|marker| name.contains(marker))
+}
+
 fn categorize_process(descriptor: &str) -> ProcessCategory {
+    let custom = CUSTOM_PROCESS_CATEGORIES
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    categorize_with(descriptor, &custom)
+}
+
+/// Operator keywords win over the built-in lists, so a custom entry can
+/// reclassify an app the defaults already match.
+fn categorize_with(descriptor: &str, custom: &[(ProcessCategory, Vec<String>)]) -> ProcessCategory {
     let value = descriptor.to_ascii_lowercase();
 
+    if let Some((category, _)) = custom.iter().find(|(_, keywords)| {
+        keywords
+            .iter()
+            .any(|keyword| value.contains(keyword.as_str()))
+    }) {
+        return *category;
+    }
+
     if contains_any(
         &value,
         &[
@@ -408,6 +1120,257 @@ fn categorize_process(descriptor: &str) -> ProcessCategory {
     ProcessCategory::System
 }
 
+/// Aggregate CPU time counters (jiffies on Linux) across all logical cores.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct CpuTimes {
+    busy: u64,
+    total: u64,
+}
+
+/// Parse the aggregate `cpu` line of `/proc/stat`. `iowait` counts as idle;
+/// `guest` time is already included in `user`, so only the first eight
+/// columns are summed.
+fn parse_proc_stat_cpu(text: &str) -> Option<CpuTimes> {
+    let line = text
+        .lines()
+        .find(|line| line.split_whitespace().next() == Some("cpu"))?;
+    let values = line
+        .split_whitespace()
+        .skip(1)
+        .take(8)
+        .map(|v| v.parse::<u64>().ok())
+        .collect::<Option<Vec<_>>>()?;
+    if values.len() < 4 {
+        return None;
+    }
+    let total = values.iter().sum::<u64>();
+    let idle = values[3] + values.get(4).copied().unwrap_or(0);
+    Some(CpuTimes {
+        busy: total.saturating_sub(idle),
+        total,
+    })
+}
+
+/// Busy share of the interval between two counter snapshots. The aggregate
+/// counters already sum every logical core, so the ratio is normalized to
+/// total capacity (0-100) just like dividing per-process load by core count.
+fn cpu_percent_between(previous: CpuTimes, current: CpuTimes) -> Option<f32> {
+    let total = current.total.checked_sub(previous.total)?;
+    if total == 0 {
+        return None;
+    }
+    let busy = current.busy.saturating_sub(previous.busy).min(total);
+    Some((busy as f64 / total as f64 * 100.0) as f32)
+}
+
+/// Idle percentage from the last `CPU usage: ... % idle` line of `top -l N`.
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn parse_top_cpu_idle_percent(text: &str) -> Option<f32> {
+    let line = text
+        .lines()
+        .rev()
+        .find(|line| line.trim_start().starts_with("CPU usage:"))?;
+    line.split(',').find_map(|part| {
+        let part = part.trim();
+        part.strip_suffix("idle")
+            .map(str::trim)
+            .and_then(|value| value.trim_end_matches('%').trim().parse::<f32>().ok())
+    })
+}
+
+#[cfg(target_os = "linux")]
+fn sample_power_state() -> PowerState {
+    let Ok(entries) = std::fs::read_dir("/sys/class/power_supply") else {
+        return PowerState::default();
+    };
+    let read = |dir: &std::path::Path, name: &str| {
+        std::fs::read_to_string(dir.join(name))
+            .ok()
+            .map(|value| value.trim().to_string())
+    };
+    let supplies: Vec<PowerSupplyReading> = entries
+        .flatten()
+        .map(|entry| {
+            let dir = entry.path();
+            PowerSupplyReading {
+                kind: read(&dir, "type").unwrap_or_default(),
+                online: read(&dir, "online"),
+                capacity: read(&dir, "capacity"),
+                status: read(&dir, "status"),
+            }
+        })
+        .collect();
+    power_state_from_supplies(&supplies)
+}
+
+#[cfg(target_os = "macos")]
+fn sample_power_state() -> PowerState {
+    match Command::new("pmset").args(["-g", "batt"]).output() {
+        Ok(out) if out.status.success() => String::from_utf8(out.stdout)
+            .ok()
+            .and_then(|text| parse_pmset_batt(&text))
+            .unwrap_or_default(),
+        _ => PowerState::default(),
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux")))]
+fn sample_power_state() -> PowerState {
+    PowerState::default()
+}
+
+/// One `/sys/class/power_supply/<name>` entry.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+struct PowerSupplyReading {
+    kind: String,
+    online: Option<String>,
+    capacity: Option<String>,
+    status: Option<String>,
+}
+
+/// Combines sysfs supplies: any online `Mains` supply means AC; without a
+/// `Mains` entry, a battery that is not discharging is treated as on AC.
+/// Multiple batteries report their mean capacity.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn power_state_from_supplies(supplies: &[PowerSupplyReading]) -> PowerState {
+    let mains: Vec<&PowerSupplyReading> = supplies
+        .iter()
+        .filter(|supply| supply.kind.eq_ignore_ascii_case("Mains"))
+        .collect();
+    let batteries: Vec<&PowerSupplyReading> = supplies
+        .iter()
+        .filter(|supply| supply.kind.eq_ignore_ascii_case("Battery"))
+        .collect();
+    if batteries.is_empty() {
+        return PowerState::default();
+    }
+
+    let status_is = |wanted: &str| {
+        batteries.iter().any(|battery| {
+            battery
+                .status
+                .as_deref()
+                .is_some_and(|status| status.eq_ignore_ascii_case(wanted))
+        })
+    };
+    let charging = status_is("Charging");
+    let on_ac = if mains.is_empty() {
+        !status_is("Discharging")
+    } else {
+        mains
+            .iter()
+            .any(|supply| supply.online.as_deref() == Some("1"))
+    };
+    let capacities: Vec<f32> = batteries
+        .iter()
+        .filter_map(|battery| battery.capacity.as_deref()?.parse::<f32>().ok())
+        .collect();
+    let battery_percent = if capacities.is_empty() {
+        None
+    } else {
+        Some(capacities.iter().sum::<f32>() / capacities.len() as f32)
+    };
+
+    PowerState {
+        on_ac,
+        battery_percent,
+        charging,
+    }
+}
+
+/// Parses `pmset -g batt`, e.g. `Now drawing from 'Battery Power'` followed by
+/// ` -InternalBattery-0 (id=123)\t85%; discharging; 4:20 remaining`. Returns
+/// `None` when no battery line is present (desktop Macs).
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn parse_pmset_batt(text: &str) -> Option<PowerState> {
+    let on_ac = text.contains("'AC Power'");
+    let battery_line = text.lines().find(|line| line.contains('%'))?;
+    let (before_percent, after_percent) = battery_line.split_once('%')?;
+    let digits: String = before_percent
+        .chars()
+        .rev()
+        .take_while(|ch| ch.is_ascii_digit())
+        .collect::<Vec<_>>()
+        .into_iter()
+        .rev()
+        .collect();
+    let status = after_percent
+        .trim_start_matches(';')
+        .split(';')
+        .next()
+        .unwrap_or("")
+        .trim()
+        .to_ascii_lowercase();
+
+    Some(PowerState {
+        on_ac,
+        battery_percent: digits.parse::<f32>().ok(),
+        charging: status == "charging" || status == "finishing charge",
+    })
+}
+
+/// Probe order to try: the configured order when set, otherwise D-Bus first
+/// under Wayland (where `xprintidle` sees no input) and `xprintidle` first
+/// elsewhere.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn resolve_idle_probe_order(configured: &[IdleProbe], wayland: bool) -> Vec<IdleProbe> {
+    if !configured.is_empty() {
+        return configured.to_vec();
+    }
+    if wayland {
+        vec![
+            IdleProbe::MutterIdleMonitor,
+            IdleProbe::FreedesktopScreenSaver,
+            IdleProbe::Xprintidle,
+        ]
+    } else {
+        vec![
+            IdleProbe::Xprintidle,
+            IdleProbe::MutterIdleMonitor,
+            IdleProbe::FreedesktopScreenSaver,
+        ]
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn query_xprintidle_ms() -> Option<u64> {
+    let output = Command::new("xprintidle").output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    String::from_utf8(output.stdout)
+        .ok()?
+        .trim()
+        .parse::<u64>()
+        .ok()
+}
+
+#[cfg(target_os = "linux")]
+fn query_dbus_idle_ms(target: &[&str]) -> Option<u64> {
+    let output = Command::new("dbus-send")
+        .args(["--session", "--print-reply"])
+        .args(target)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    parse_dbus_uint_reply(&String::from_utf8(output.stdout).ok()?)
+}
+
+/// Reads the value of the first `uint32`/`uint64` line in `dbus-send
+/// --print-reply` output.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_dbus_uint_reply(text: &str) -> Option<u64> {
+    text.lines().find_map(|line| {
+        let mut parts = line.split_whitespace();
+        match parts.next()? {
+            "uint32" | "uint64" => parts.next()?.parse::<u64>().ok(),
+            _ => None,
+        }
+    })
+}
+
 fn contains_any(value: &str, needles: &[&str]) -> bool {
     needles.iter().any(|needle| value.contains(needle))
 }
@@ -464,6 +1427,95 @@ mod tests {
         assert!(state.time_context.local_hour <= 23);
     }
 
+    #[test]
+    fn quiet_hours_wrap_past_midnight() {
+        let at = |hour| TimeContext::from_components(hour, 0, Weekday::Tue);
+        assert!(at(23).in_quiet_hours(23, 6));
+        assert!(at(0).in_quiet_hours(23, 6));
+        assert!(at(5).in_quiet_hours(23, 6));
+        assert!(!at(6).in_quiet_hours(23, 6));
+        assert!(!at(12).in_quiet_hours(23, 6));
+        assert!(!at(22).in_quiet_hours(23, 6));
+
+        assert!(at(13).in_quiet_hours(12, 14));
+        assert!(!at(14).in_quiet_hours(12, 14));
+        assert!(!at(3).in_quiet_hours(3, 3));
+    }
+
+    #[test]
+    fn focused_window_probe_output_parses() {
+        assert_eq!(
+            parse_app_title_lines("Code\nmain.rs - ponderer\n"),
+            Some(FocusedWindow {
+                app: "Code".to_string(),
+                title: "main.rs - ponderer".to_string(),
+            })
+        );
+        assert_eq!(parse_app_title_lines("\n\n"), None);
+        assert_eq!(
+            parse_hyprctl_active_window(r#"{"class": "kitty", "title": "cargo test"}"#),
+            Some(FocusedWindow {
+                app: "kitty".to_string(),
+                title: "cargo test".to_string(),
+            })
+        );
+        assert_eq!(parse_hyprctl_active_window("{}"), None);
+        assert_eq!(parse_hyprctl_active_window("Invalid"), None);
+    }
+
+    #[test]
+    fn focused_window_bounds_probe_output_parses() {
+        let expected = Some(WindowBounds {
+            x: 10,
+            y: 20,
+            width: 800,
+            height: 600,
+        });
+        assert_eq!(parse_comma_bounds("10, 20, 800, 600\n"), expected);
+        assert_eq!(
+            parse_hyprctl_window_bounds(r#"{"at": [10, 20], "size": [800, 600]}"#),
+            expected
+        );
+        assert_eq!(
+            parse_xdotool_geometry("WINDOW=123\nX=10\nY=20\nWIDTH=800\nHEIGHT=600\nSCREEN=0\n"),
+            expected
+        );
+        assert_eq!(parse_comma_bounds("10,20,0,600"), None);
+        assert_eq!(parse_hyprctl_window_bounds("{}"), None);
+    }
+
+    #[test]
+    fn desktop_size_probe_output_parses() {
+        assert_eq!(
+            parse_corner_bounds_size("0, 0, 1512, 982\n"),
+            Some((1512, 982))
+        );
+        assert_eq!(
+            parse_hyprctl_layout_size(
+                r#"[{"x": 0, "y": 0, "width": 2880, "height": 1800, "scale": 2.0},
+                    {"x": 1440, "y": 0, "width": 1920, "height": 1080, "scale": 1.0}]"#
+            ),
+            Some((3360, 1080))
+        );
+        assert_eq!(parse_display_geometry("1920 1080\n"), Some((1920, 1080)));
+        assert_eq!(parse_display_geometry("garbage"), None);
+        assert_eq!(parse_hyprctl_layout_size("[]"), None);
+    }
+
+    #[test]
+    fn focused_window_category_falls_back_to_title() {
+        let terminal = FocusedWindow {
+            app: "kitty".to_string(),
+            title: "cargo build".to_string(),
+        };
+        assert_eq!(terminal.category(), ProcessCategory::Development);
+        let unknown = FocusedWindow {
+            app: "Finder".to_string(),
+            title: "Downloads".to_string(),
+        };
+        assert_eq!(unknown.category(), ProcessCategory::System);
+    }
+
     #[test]
     fn process_categorization_heuristics() {
         assert_eq!(
@@ -479,4 +1531,161 @@ mod tests {
             ProcessCategory::Media
         );
     }
+
+    #[test]
+    fn custom_process_keywords_extend_and_override_defaults() {
+        let map = HashMap::from([
+            ("creative".to_string(), vec!["Bitwig".to_string()]),
+            (
+                "focus".to_string(),
+                vec!["obsidian".to_string(), " ".to_string()],
+            ),
+            ("nonsense".to_string(), vec!["ignored".to_string()]),
+        ]);
+        let custom = ProcessCategory::parse_keyword_map(&map);
+        assert_eq!(
+            custom,
+            vec![
+                (ProcessCategory::Creative, vec!["bitwig".to_string()]),
+                (ProcessCategory::Focus, vec!["obsidian".to_string()]),
+            ]
+        );
+
+        assert_eq!(
+            categorize_with("Bitwig Studio", &custom),
+            ProcessCategory::Creative
+        );
+        // Obsidian is Research by default; the operator keyword wins.
+        assert_eq!(categorize_with("Obsidian", &[]), ProcessCategory::Research);
+        assert_eq!(categorize_with("Obsidian", &custom), ProcessCategory::Focus);
+        assert_eq!(
+            categorize_with("Slack helper", &custom),
+            ProcessCategory::Communication
+        );
+    }
+
+    #[test]
+    fn screen_share_helpers_are_detected_by_process_name() {
+        assert!(is_screen_share_process(
+            "/Applications/zoom.us.app/Contents/Frameworks/CptHost.app/Contents/MacOS/CptHost"
+        ));
+        assert!(is_screen_share_process("krfb"));
+        assert!(!is_screen_share_process("zoom.us"));
+    }
+
+    #[test]
+    fn cpu_percent_uses_delta_between_jiffy_snapshots() {
+        let first = parse_proc_stat_cpu(
+            "cpu  100 0 100 700 100 0 0 0 0 0\ncpu0 50 0 50 350 50 0 0 0 0 0\n",
+        )
+        .unwrap();
+        let second = parse_proc_stat_cpu(
+            "cpu  250 0 150 900 100 0 0 0 0 0\ncpu0 125 0 75 450 50 0 0 0 0 0\n",
+        )
+        .unwrap();
+        assert_eq!(
+            first,
+            CpuTimes {
+                busy: 200,
+                total: 1000
+            }
+        );
+        // 200 busy jiffies out of 400 elapsed across all cores.
+        let percent = cpu_percent_between(first, second).unwrap();
+        assert!((percent - 50.0).abs() < 0.01);
+        assert_eq!(cpu_percent_between(second, second), None);
+    }
+
+    #[test]
+    fn top_cpu_usage_reads_last_sample() {
+        let text = "Processes: 500 total\n\
+             CPU usage: 10.0% user, 5.0% sys, 85.0% idle\n\
+             Processes: 501 total\n\
+             CPU usage: 30.5% user, 9.5% sys, 60.0% idle\n";
+        assert_eq!(parse_top_cpu_idle_percent(text), Some(60.0));
+    }
+
+    #[test]
+    fn dbus_idle_reply_reads_uint_value() {
+        let mutter = "method return time=1712.3 sender=:1.23 -> destination=:1.99 serial=1234 reply_serial=2\n   uint64 45210\n";
+        assert_eq!(parse_dbus_uint_reply(mutter), Some(45_210));
+        let screensaver = "method return time=1712.3 sender=:1.5 -> destination=:1.80 serial=77 reply_serial=2\n   uint32 9000\n";
+        assert_eq!(parse_dbus_uint_reply(screensaver), Some(9_000));
+        assert_eq!(
+            parse_dbus_uint_reply("Error org.freedesktop.DBus.Error.ServiceUnknown"),
+            None
+        );
+    }
+
+    #[test]
+    fn idle_probe_order_prefers_dbus_under_wayland_unless_configured() {
+        assert_eq!(
+            resolve_idle_probe_order(&[], false).first(),
+            Some(&IdleProbe::Xprintidle)
+        );
+        assert_eq!(
+            resolve_idle_probe_order(&[], true).first(),
+            Some(&IdleProbe::MutterIdleMonitor)
+        );
+        let configured = IdleProbe::parse_order(&[
+            "xprintidle".to_string(),
+            "bogus".to_string(),
+            "X11".to_string(),
+        ]);
+        assert_eq!(configured, vec![IdleProbe::Xprintidle]);
+        assert_eq!(
+            resolve_idle_probe_order(&configured, true),
+            vec![IdleProbe::Xprintidle]
+        );
+    }
+
+    #[test]
+    fn sysfs_power_supplies_combine_into_power_state() {
+        let supply =
+            |kind: &str, online: Option<&str>, capacity: Option<&str>, status: Option<&str>| {
+                PowerSupplyReading {
+                    kind: kind.to_string(),
+                    online: online.map(str::to_string),
+                    capacity: capacity.map(str::to_string),
+                    status: status.map(str::to_string),
+                }
+            };
+        let on_battery = power_state_from_supplies(&[
+            supply("Mains", Some("0"), None, None),
+            supply("Battery", None, Some("42"), Some("Discharging")),
+        ]);
+        assert_eq!(
+            on_battery,
+            PowerState {
+                on_ac: false,
+                battery_percent: Some(42.0),
+                charging: false,
+            }
+        );
+        assert!(!on_battery.allows_heavy_work(50.0));
+        assert!(on_battery.allows_heavy_work(20.0));
+
+        let charging =
+            power_state_from_supplies(&[supply("Battery", None, Some("80"), Some("Charging"))]);
+        assert!(charging.on_ac && charging.charging);
+        assert_eq!(
+            power_state_from_supplies(&[supply("USB", Some("1"), None, None)]),
+            PowerState::default()
+        );
+    }
+
+    #[test]
+    fn pmset_battery_output_parses() {
+        let on_battery = "Now drawing from 'Battery Power'\n -InternalBattery-0 (id=4653155)\t5%; discharging; 0:20 remaining present: true\n";
+        let state = parse_pmset_batt(on_battery).expect("battery line");
+        assert!(!state.on_ac);
+        assert_eq!(state.battery_percent, Some(5.0));
+        assert!(!state.charging);
+        assert!(!state.allows_heavy_work(20.0));
+
+        let charging = "Now drawing from 'AC Power'\n -InternalBattery-0 (id=4653155)\t63%; charging; 1:02 remaining present: true\n";
+        let state = parse_pmset_batt(charging).expect("battery line");
+        assert!(state.on_ac && state.charging);
+        assert_eq!(parse_pmset_batt("Now drawing from 'AC Power'\n"), None);
+    }
 }
@@ -10,8 +10,9 @@ use flume::Sender;
 use futures_util::FutureExt;
 use serde::Serialize;
 
+use crate::agent::capability_profiles::validate_custom_profiles;
 use crate::agent::{Agent, AgentEvent};
-use crate::config::AgentConfig;
+use crate::config::{AgentConfig, REQUIRE_LOCAL_LLM_BLOCKED_TOOLS};
 use crate::database::AgentDatabase;
 
 use crate::plugin_contract::{
@@ -97,11 +98,21 @@ impl BackendRuntimeBuilder {
 
     pub fn build(self) -> Result<BackendRuntime> {
         let config = self.config;
+        config.check_local_llm_guard()?;
         let tool_registry = Arc::new(ToolRegistry::new());
         let process_registry = Arc::new(ProcessRegistry::new());
         let runtime_process_plugins = Arc::new(RuntimeProcessPluginCatalog::discover()?);
         let ui_database = match AgentDatabase::new(&config.database_path) {
-            Ok(db) => Some(Arc::new(db)),
+            Ok(db) => {
+                // Nothing can be mid-turn before the agent loop starts, so any
+                // turn still processing was orphaned by a crash or hard kill.
+                match db.reconcile_orphaned_turns() {
+                    Ok(0) => {}
+                    Ok(closed) => tracing::warn!("Closed {} orphaned chat turn(s)", closed),
+                    Err(e) => tracing::warn!("Failed to reconcile orphaned chat turns: {}", e),
+                }
+                Some(Arc::new(db))
+            }
             Err(e) => {
                 tracing::warn!("Failed to create shared runtime database: {}", e);
                 None
@@ -117,11 +128,32 @@ impl BackendRuntimeBuilder {
             tool_registry.clone(),
             process_registry.clone(),
             self.event_tx.clone(),
+            config.require_local_llm,
+            config.enable_tts,
         ))?;
+        if let Some(db) = ui_database.as_ref() {
+            match db.list_remembered_tool_approvals() {
+                Ok(approvals) => {
+                    init_rt.block_on(tool_registry.load_remembered_approvals(approvals))
+                }
+                Err(e) => tracing::warn!("Failed to load remembered tool approvals: {}", e),
+            }
+        }
 
         let mut manifests = vec![builtin_core_manifest()];
         manifests.extend(runtime_process_plugins.manifests());
 
+        // Enabled plugins register later, so their manifest tools count as known.
+        let mut known_tools = init_rt.block_on(tool_registry.list_names());
+        known_tools.extend(
+            manifests
+                .iter()
+                .flat_map(|manifest| manifest.provided_tools.iter().cloned()),
+        );
+        for problem in validate_custom_profiles(&config.custom_capability_profiles, &known_tools) {
+            tracing::warn!("{}", problem);
+        }
+
         let agent = Arc::new(Agent::new(
             tool_registry.clone(),
             runtime_plugin_host.clone(),
@@ -209,6 +241,10 @@ async fn supervise_agent_loop(
                 tracing::warn!("Runtime plugin control task exited abnormally: {error}");
             }
         }
+        if agent.is_shutting_down() {
+            supervisor.mark_generation_exited("shutdown".to_string());
+            return;
+        }
         let error = describe_agent_loop_exit(outcome);
         supervisor.mark_generation_exited(error.clone());
 
@@ -304,21 +340,33 @@ fn builtin_core_manifest() -> PluginManifest {
             "write_file".to_string(),
             "list_directory".to_string(),
             "patch_file".to_string(),
+            "search_files".to_string(),
+            "move_file".to_string(),
+            "delete_file".to_string(),
             "evaluate_local_image".to_string(),
             "publish_media_to_chat".to_string(),
+            "transcribe_audio".to_string(),
+            "speak".to_string(),
             "capture_screen".to_string(),
             "capture_camera_snapshot".to_string(),
             "search_memory".to_string(),
+            "search_journal".to_string(),
             "write_memory".to_string(),
+            "pin_concern".to_string(),
             "write_session_handoff".to_string(),
             "private_chat_mode".to_string(),
             "scratch_note".to_string(),
             "http_fetch".to_string(),
+            "http_get".to_string(),
+            "web_search".to_string(),
+            "clipboard".to_string(),
             "flag_uncertainty".to_string(),
             "list_scheduled_jobs".to_string(),
             "create_scheduled_job".to_string(),
             "update_scheduled_job".to_string(),
             "delete_scheduled_job".to_string(),
+            "schedule_reminder".to_string(),
+            "focus_session".to_string(),
             "plugin_workbench".to_string(),
         ],
         tools: Vec::new(),
@@ -335,15 +383,24 @@ async fn register_builtin_core_tools(
     tool_registry: Arc<ToolRegistry>,
     process_registry: Arc<ProcessRegistry>,
     event_tx: Sender<AgentEvent>,
+    require_local_llm: bool,
+    enable_tts: bool,
 ) -> Result<()> {
     use crate::tools::{
-        files::{ListDirectoryTool, PatchFileTool, ReadFileTool, WriteFileTool},
-        http::HttpFetchTool,
+        audio::{SpeakTool, TranscribeAudioTool},
+        clipboard::ClipboardTool,
+        files::{
+            DeleteFileTool, ListDirectoryTool, MoveFileTool, PatchFileTool, ReadFileTool,
+            SearchFilesTool, WriteFileTool,
+        },
+        focus::FocusSessionTool,
+        http::{HttpFetchTool, HttpGetTool},
         memory::{
-            FlagUncertaintyTool, MemorySearchTool, MemoryWriteTool, PrivateChatModeTool,
-            ScratchNoteTool, WriteSessionHandoffTool,
+            FlagUncertaintyTool, JournalSearchTool, MemorySearchTool, MemoryWriteTool,
+            PinConcernTool, PrivateChatModeTool, ScratchNoteTool, WriteSessionHandoffTool,
         },
         plugin_workbench::PluginWorkbenchTool,
+        reminders::ScheduleReminderTool,
         scheduled_jobs::{
             CreateScheduledJobTool, DeleteScheduledJobTool, ListScheduledJobsTool,
             UpdateScheduledJobTool,
@@ -353,6 +410,7 @@ async fn register_builtin_core_tools(
             CaptureCameraSnapshotTool, CaptureScreenTool, EvaluateLocalImageTool,
             PublishMediaToChatTool,
         },
+        web_search::WebSearchTool,
     };
 
     tool_registry
@@ -364,12 +422,25 @@ async fn register_builtin_core_tools(
         .register(Arc::new(ListDirectoryTool::new()))
         .await;
     tool_registry.register(Arc::new(PatchFileTool::new())).await;
+    tool_registry
+        .register(Arc::new(SearchFilesTool::new()))
+        .await;
+    tool_registry.register(Arc::new(MoveFileTool::new())).await;
+    tool_registry
+        .register(Arc::new(DeleteFileTool::new()))
+        .await;
     tool_registry
         .register(Arc::new(EvaluateLocalImageTool::new()))
         .await;
     tool_registry
         .register(Arc::new(PublishMediaToChatTool::new()))
         .await;
+    tool_registry
+        .register(Arc::new(TranscribeAudioTool::new()))
+        .await;
+    if enable_tts {
+        tool_registry.register(Arc::new(SpeakTool::new())).await;
+    }
     tool_registry
         .register(Arc::new(CaptureScreenTool::new()))
         .await;
@@ -379,9 +450,15 @@ async fn register_builtin_core_tools(
     tool_registry
         .register(Arc::new(MemorySearchTool::new()))
         .await;
+    tool_registry
+        .register(Arc::new(JournalSearchTool::new()))
+        .await;
     tool_registry
         .register(Arc::new(MemoryWriteTool::new()))
         .await;
+    tool_registry
+        .register(Arc::new(PinConcernTool::new()))
+        .await;
     tool_registry
         .register(Arc::new(WriteSessionHandoffTool::new()))
         .await;
@@ -391,7 +468,17 @@ async fn register_builtin_core_tools(
     tool_registry
         .register(Arc::new(ScratchNoteTool::new()))
         .await;
-    tool_registry.register(Arc::new(HttpFetchTool::new())).await;
+    if require_local_llm {
+        tracing::info!(
+            "require_local_llm is set; not registering {}",
+            REQUIRE_LOCAL_LLM_BLOCKED_TOOLS.join(", ")
+        );
+    } else {
+        tool_registry.register(Arc::new(HttpFetchTool::new())).await;
+        tool_registry.register(Arc::new(HttpGetTool::new())).await;
+        tool_registry.register(Arc::new(WebSearchTool::new())).await;
+    }
+    tool_registry.register(Arc::new(ClipboardTool::new())).await;
     tool_registry
         .register(Arc::new(FlagUncertaintyTool::new(event_tx)))
         .await;
@@ -407,6 +494,12 @@ async fn register_builtin_core_tools(
     tool_registry
         .register(Arc::new(DeleteScheduledJobTool::new()))
         .await;
+    tool_registry
+        .register(Arc::new(ScheduleReminderTool::new()))
+        .await;
+    tool_registry
+        .register(Arc::new(FocusSessionTool::new()))
+        .await;
     tool_registry
         .register(Arc::new(PluginWorkbenchTool::new(
             crate::plugin_workbench::PluginWorkbench::from_environment(),
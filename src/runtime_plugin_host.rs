@@ -2,7 +2,7 @@ use std::collections::{HashMap, HashSet};
 use std::process::Stdio;
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use anyhow::{Context, Result};
 use chrono::Utc;
@@ -26,6 +26,7 @@ use crate::plugin_lifecycle::{
     PluginDesiredState, PluginLifecycleAction, PluginLifecycleMachine, PluginLifecycleSnapshot,
     PluginOperationalState,
 };
+use crate::plugin_poll_backoff::PluginPollBackoff;
 use crate::plugin_restart_policy::PluginRestartPolicy;
 use crate::runtime_process_plugin::{
     RuntimeProcessCatalogRefresh, RuntimeProcessPluginBundle, SharedRuntimeProcessPluginCatalog,
@@ -122,6 +123,7 @@ pub struct RuntimePluginHost {
     last_settings: RwLock<HashMap<String, Value>>,
     last_tool_registry: RwLock<Option<Arc<ToolRegistry>>>,
     restart_policy: PluginRestartPolicy,
+    poll_backoff: Mutex<PluginPollBackoff>,
     reconcile_lock: Mutex<()>,
     last_event_compaction: RwLock<Option<chrono::DateTime<Utc>>>,
     request_counter: AtomicU64,
@@ -193,6 +195,7 @@ impl RuntimePluginHost {
             last_settings: RwLock::new(HashMap::new()),
             last_tool_registry: RwLock::new(None),
             restart_policy,
+            poll_backoff: Mutex::new(PluginPollBackoff::default()),
             reconcile_lock: Mutex::new(()),
             last_event_compaction: RwLock::new(None),
             request_counter: AtomicU64::new(1),
@@ -1114,6 +1117,15 @@ impl RuntimePluginHost {
             if !plugin.handshake.capabilities.skill_polling {
                 continue;
             }
+            let plugin_id = plugin.bundle.id();
+            if !self
+                .poll_backoff
+                .lock()
+                .await
+                .is_due(plugin_id, Instant::now())
+            {
+                continue;
+            }
             let call_result = {
                 let mut client = plugin.client.lock().await;
                 self.call_plugin::<RuntimePluginPollResponse>(
@@ -1125,6 +1137,7 @@ impl RuntimePluginHost {
             };
             match call_result {
                 Ok(response) => {
+                    self.poll_backoff.lock().await.reset(plugin_id);
                     if let Err(error) = self.accept_poll_response(
                         plugin.bundle.id(),
                         response,
@@ -1142,12 +1155,29 @@ impl RuntimePluginHost {
                             .await;
                     }
                 }
+                Err(error) if error.downcast_ref::<RuntimePluginRpcError>().is_some() => {
+                    // The plugin answered but reported a failure (usually its
+                    // upstream API): the process is healthy, so back off
+                    // polling instead of restarting it.
+                    let delay = self
+                        .poll_backoff
+                        .lock()
+                        .await
+                        .record_failure(plugin_id, Instant::now());
+                    tracing::warn!(
+                        "Runtime plugin '{}' poll_events reported an error; next poll in {}s: {}",
+                        plugin_id,
+                        delay.as_secs(),
+                        error
+                    );
+                }
                 Err(error) => {
                     tracing::warn!(
                         "Runtime plugin '{}' poll_events failed: {}",
                         plugin.bundle.id(),
                         error
                     );
+                    self.poll_backoff.lock().await.reset(plugin_id);
                     self.deactivate_failed_plugin(plugin.bundle.id(), &error)
                         .await;
                 }
@@ -1491,6 +1521,7 @@ impl RuntimePluginHost {
         let Some(plugin) = removed else {
             return None;
         };
+        self.poll_backoff.lock().await.reset(plugin_id);
 
         let registered_tools = plugin.registered_tools.read().await.clone();
         for tool_name in registered_tools {
@@ -1691,7 +1722,7 @@ impl RuntimePluginHost {
                 code: "plugin_error".to_string(),
                 message: "runtime plugin call failed".to_string(),
             });
-            anyhow::bail!("{}: {}", error.code, error.message);
+            return Err(error.into());
         }
 
         Ok(response.result.unwrap_or(Value::Null))
@@ -16,6 +16,9 @@ pub struct ScheduledJob {
     pub next_run_at: DateTime<Utc>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    /// Named capability profile the job runs under; `None` uses `scheduled`.
+    #[serde(default)]
+    pub capability_profile: Option<String>,
 }
 
 impl ScheduledJob {
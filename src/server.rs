@@ -1,5 +1,6 @@
 use std::net::SocketAddr;
 use std::sync::Arc;
+use std::time::Duration;
 
 use anyhow::{anyhow, Context, Result};
 use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
@@ -7,18 +8,24 @@ use axum::extract::{Path, Query, State};
 use axum::http::{header, HeaderMap, StatusCode};
 use axum::middleware::{self, Next};
 use axum::response::{IntoResponse, Response};
-use axum::routing::{get, post, put};
+use axum::routing::{delete, get, post, put};
 use axum::{Json, Router};
 use chrono::{DateTime, Utc};
 use futures_util::StreamExt;
 use serde::{Deserialize, Serialize};
 use tokio::sync::broadcast;
 
-use crate::agent::{AgentEvent, AgentRuntimeStatus};
+use crate::agent::capability_profiles::is_known_profile_name;
+use crate::agent::concerns::{ConcernGraph, ConcernsManager};
+use crate::agent::daily_report::DailyReport;
+use crate::agent::trajectory::TrajectoryAnalysis;
+use crate::agent::{AgentEvent, AgentRuntimeStatus, ConversationCompaction, ResolvedToolApproval};
 use crate::config::{normalize_private_chat_mode, AgentConfig};
 use crate::database::{
-    AgentDatabase, ChatConversation, ChatConversationSummary, ChatMessage, ChatTurn,
-    ChatTurnToolCall, DEFAULT_CHAT_CONVERSATION_ID,
+    AgentDatabase, ChatConversation, ChatConversationSummary, ChatMediaAttachment, ChatMessage,
+    ChatTurn, ChatTurnExplanation, ChatTurnToolCall, MoodBucket, PersonaTimelineEntry,
+    QueuedToolApproval, RememberedToolApproval, SurfacedAnomalyRecord,
+    DEFAULT_CHAT_CONVERSATION_ID,
 };
 use crate::generation_telemetry::GenerationEvent;
 use crate::plugin_contract::{PluginKind, PluginManifest, PluginRuntimeStatus};
@@ -28,6 +35,9 @@ use crate::runtime_plugin_host::RuntimePluginHost;
 use crate::scheduled_jobs::ScheduledJob;
 use crate::tools::memory::PRIVATE_CHAT_MODE_STATE_KEY;
 
+/// How long shutdown waits for background subtasks before aborting them.
+const AGENT_SHUTDOWN_GRACE: Duration = Duration::from_secs(10);
+
 #[derive(Clone)]
 pub struct ServerState {
     pub agent: Arc<crate::agent::Agent>,
@@ -40,6 +50,7 @@ pub struct ServerState {
     pub runtime_plugin_host: Arc<RuntimePluginHost>,
     pub ws_events: broadcast::Sender<ApiEventEnvelope>,
     pub telegram_bot: Arc<crate::telegram::TelegramBotManager>,
+    pub event_webhook: Arc<crate::event_webhook::EventWebhookManager>,
 }
 
 #[derive(Debug, Clone)]
@@ -88,6 +99,22 @@ struct ListScheduledJobsQuery {
     limit: Option<usize>,
 }
 
+#[derive(Debug, Deserialize)]
+struct PersonaTimelineQuery {
+    limit: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MoodTimeseriesQuery {
+    hours: Option<usize>,
+    bucket_minutes: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ListAnomaliesQuery {
+    limit: Option<usize>,
+}
+
 #[derive(Debug, Deserialize)]
 struct CreateConversationRequest {
     title: Option<String>,
@@ -103,6 +130,11 @@ struct SetPauseRequest {
     paused: bool,
 }
 
+#[derive(Debug, Deserialize)]
+struct SetAmbientRequest {
+    enabled: bool,
+}
+
 #[derive(Debug, Deserialize)]
 struct SetLooseModeRequest {
     enabled: bool,
@@ -118,6 +150,8 @@ struct CreateScheduledJobRequest {
     name: String,
     prompt: String,
     interval_minutes: u64,
+    #[serde(default)]
+    capability_profile: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -126,6 +160,9 @@ struct UpdateScheduledJobRequest {
     prompt: Option<String>,
     interval_minutes: Option<u64>,
     enabled: Option<bool>,
+    /// An empty string clears the job's profile.
+    #[serde(default)]
+    capability_profile: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -139,6 +176,11 @@ struct PauseStateResponse {
     paused: bool,
 }
 
+#[derive(Debug, Serialize)]
+struct AmbientStateResponse {
+    enabled: bool,
+}
+
 #[derive(Debug, Serialize)]
 struct LooseModeResponse {
     enabled: bool,
@@ -154,6 +196,11 @@ struct StopResponse {
     stopped: bool,
 }
 
+#[derive(Debug, Serialize)]
+struct ResumeBackgroundResponse {
+    resumed: bool,
+}
+
 #[derive(Debug, Serialize)]
 struct ChatTurnPromptResponse {
     turn_id: String,
@@ -178,6 +225,7 @@ pub async fn serve_backend(
         .ok_or_else(|| anyhow!("Backend database unavailable"))?;
     let (ws_events, _) = broadcast::channel(512);
     let telegram_bot = Arc::new(crate::telegram::TelegramBotManager::new());
+    let event_webhook = Arc::new(crate::event_webhook::EventWebhookManager::new());
 
     let state = Arc::new(ServerState {
         agent: runtime.agent.clone(),
@@ -195,9 +243,20 @@ pub async fn serve_backend(
         runtime_plugin_host: runtime.runtime_plugin_host.clone(),
         ws_events: ws_events.clone(),
         telegram_bot: telegram_bot.clone(),
+        event_webhook: event_webhook.clone(),
     });
 
+    event_webhook
+        .reconfigure(
+            &ws_events,
+            runtime.config.event_webhook_url.clone(),
+            &runtime.config.event_webhook_events,
+        )
+        .await;
     spawn_event_bridge(event_rx, ws_events);
+    if let Some(raw_addr) = runtime.config.status_server_addr.as_deref() {
+        crate::status_server::spawn_status_server(runtime.agent.clone(), raw_addr);
+    }
     telegram_bot
         .reconfigure(
             state.clone(),
@@ -227,13 +286,28 @@ pub async fn serve_backend(
                 .patch(update_conversation),
         )
         .route("/conversations/:id/summary", get(get_conversation_summary))
+        .route("/conversations/:id/compact", post(compact_conversation))
         .route(
             "/conversations/:id/messages",
             get(list_messages).post(send_operator_message),
         )
         .route("/conversations/:id/turns", get(list_turns))
+        .route("/conversations/:id/stop", post(stop_conversation_turn))
+        .route(
+            "/conversations/:id/background/resume",
+            post(resume_background_subtask),
+        )
         .route("/turns/:id/tool-calls", get(list_turn_tool_calls))
         .route("/turns/:id/prompt", get(get_turn_prompt))
+        .route("/turns/:id/explanation", get(get_turn_explanation))
+        .route("/concerns/graph", get(get_concern_graph))
+        .route("/anomalies", get(list_surfaced_anomalies))
+        .route(
+            "/anomalies/:fingerprint/acknowledge",
+            post(acknowledge_anomaly),
+        )
+        .route("/persona/timeline", get(get_persona_timeline))
+        .route("/orientation/mood", get(get_mood_timeseries))
         .route(
             "/scheduled-jobs",
             get(list_scheduled_jobs).post(create_scheduled_job),
@@ -253,10 +327,20 @@ pub async fn serve_backend(
             get(get_private_chat_mode).put(set_private_chat_mode),
         )
         .route("/agent/pause", put(set_pause))
+        .route("/agent/ambient", put(set_ambient))
         .route("/agent/loose-mode", put(set_loose_mode))
         .route("/agent/toggle-pause", post(toggle_pause))
         .route("/agent/stop", post(stop_agent_turn))
+        .route("/agent/daily-report", post(generate_daily_report))
+        .route("/agent/reflect", post(trigger_reflection))
         .route("/agent/tools/:tool_name/approve", post(approve_tool))
+        .route(
+            "/agent/tools/approvals",
+            get(list_remembered_tool_approvals),
+        )
+        .route("/agent/tools/approvals/:id", delete(forget_tool_approval))
+        .route("/agent/approvals", get(list_pending_approvals))
+        .route("/agent/approvals/:id/resolve", post(resolve_approval))
         .route("/ws/events", get(ws_events_route))
         .with_state(state.clone())
         .layer(middleware::from_fn_with_state(
@@ -270,12 +354,49 @@ pub async fn serve_backend(
         .await
         .with_context(|| format!("Failed to bind backend server to {}", bind_addr))?;
     tracing::info!("Ponderer backend listening on http://{}", bind_addr);
-    axum::serve(listener, app)
-        .await
-        .context("Backend server failed")?;
+    // Long-lived WebSocket connections would stall axum's graceful drain, so
+    // stop serving outright once a signal arrives and the agent has settled.
+    tokio::select! {
+        served = axum::serve(listener, app) => {
+            served.context("Backend server failed")?;
+        }
+        _ = shutdown_signal() => {
+            tracing::info!("Shutdown signal received; stopping agent");
+            runtime.agent.shutdown(AGENT_SHUTDOWN_GRACE).await;
+        }
+    }
     Ok(())
 }
 
+/// Resolves on Ctrl-C, or on SIGTERM where the platform has it.
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        if let Err(error) = tokio::signal::ctrl_c().await {
+            tracing::warn!("Failed to listen for Ctrl-C: {}", error);
+            std::future::pending::<()>().await;
+        }
+    };
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(error) => {
+                tracing::warn!("Failed to listen for SIGTERM: {}", error);
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {}
+        _ = terminate => {}
+    }
+}
+
 fn spawn_event_bridge(
     event_rx: flume::Receiver<AgentEvent>,
     ws_events: broadcast::Sender<ApiEventEnvelope>,
@@ -388,9 +509,22 @@ fn map_agent_event(event: AgentEvent) -> ApiEventEnvelope {
             serde_json::json!({ "id": id, "summary": summary }),
         ),
         AgentEvent::Error(error) => envelope("error", serde_json::json!({ "error": error })),
-        AgentEvent::ApprovalRequest { tool_name, reason } => envelope(
+        AgentEvent::ApprovalRequested {
+            id,
+            tool,
+            params,
+            reason,
+        } => envelope(
+            // Keeps the pre-queue event name and `tool_name` field so existing
+            // clients and webhook filters still match.
             "approval_request",
-            serde_json::json!({ "tool_name": tool_name, "reason": reason }),
+            serde_json::json!({
+                "id": id,
+                "tool": tool,
+                "tool_name": tool,
+                "params": params,
+                "reason": reason
+            }),
         ),
         AgentEvent::UncertaintyFlagged {
             question,
@@ -412,6 +546,18 @@ fn map_agent_event(event: AgentEvent) -> ApiEventEnvelope {
                 "content": content
             }),
         ),
+        AgentEvent::UsageReport {
+            conversation_id,
+            prompt_tokens,
+            completion_tokens,
+        } => envelope(
+            "usage_report",
+            serde_json::json!({
+                "conversation_id": conversation_id,
+                "prompt_tokens": prompt_tokens,
+                "completion_tokens": completion_tokens
+            }),
+        ),
         AgentEvent::CycleStart { label } => {
             envelope("cycle_start", serde_json::json!({ "label": label }))
         }
@@ -560,6 +706,11 @@ async fn update_config(
         new_config.enable_ambient_loop = true;
     }
     let previous_loose_mode = state.config.read().await.loose_mode;
+    let candidate = new_config.clone();
+    tokio::task::spawn_blocking(move || candidate.check_local_llm_guard())
+        .await
+        .map_err(|error| internal_error(error.into()))?
+        .map_err(|error| (StatusCode::BAD_REQUEST, error.to_string()))?;
     if let Err(error) = new_config.save() {
         return Err((
             StatusCode::INTERNAL_SERVER_ERROR,
@@ -586,6 +737,14 @@ async fn update_config(
             new_config.telegram_chat_id,
         )
         .await;
+    state
+        .event_webhook
+        .reconfigure(
+            &state.ws_events,
+            new_config.event_webhook_url.clone(),
+            &new_config.event_webhook_events,
+        )
+        .await;
     Ok(Json(new_config))
 }
 
@@ -633,6 +792,10 @@ async fn delete_conversation(
 #[derive(serde::Deserialize)]
 struct UpdateConversationRequest {
     title: Option<String>,
+    /// An empty string clears the override.
+    system_prompt: Option<String>,
+    /// `true` puts the thread in pure conversation mode (no tools).
+    tools_disabled: Option<bool>,
 }
 
 async fn update_conversation(
@@ -650,6 +813,18 @@ async fn update_conversation(
                 .map_err(internal_error)?;
         }
     }
+    if let Some(system_prompt) = body.system_prompt.as_deref() {
+        state
+            .db
+            .set_chat_conversation_system_prompt(&conversation_id, Some(system_prompt))
+            .map_err(internal_error)?;
+    }
+    if let Some(tools_disabled) = body.tools_disabled {
+        state
+            .db
+            .set_chat_conversation_tools_disabled(&conversation_id, tools_disabled)
+            .map_err(internal_error)?;
+    }
     match state
         .db
         .get_chat_conversation(&conversation_id)
@@ -686,18 +861,47 @@ async fn get_conversation_summary(
         .map_err(internal_error)
 }
 
+async fn compact_conversation(
+    State(state): State<Arc<ServerState>>,
+    Path(conversation_id): Path<String>,
+) -> Result<Json<ConversationCompaction>, (StatusCode, String)> {
+    require_conversation(&state, &conversation_id)?;
+    state
+        .agent
+        .compact_conversation(&conversation_id)
+        .await
+        .map(Json)
+        .map_err(internal_error)
+}
+
+/// A stored message plus its `[media]` block parsed for rendering.
+#[derive(Debug, Serialize)]
+struct ChatMessageResponse {
+    #[serde(flatten)]
+    message: ChatMessage,
+    media: Vec<ChatMediaAttachment>,
+}
+
 async fn list_messages(
     State(state): State<Arc<ServerState>>,
     Path(conversation_id): Path<String>,
     Query(query): Query<ListMessagesQuery>,
-) -> Result<Json<Vec<ChatMessage>>, (StatusCode, String)> {
+) -> Result<Json<Vec<ChatMessageResponse>>, (StatusCode, String)> {
     require_conversation(&state, &conversation_id)?;
     let limit = clamp_limit(query.limit, 200, 1, 2000);
-    state
+    let messages = state
         .db
         .get_chat_history_for_conversation(&conversation_id, limit)
-        .map(Json)
-        .map_err(internal_error)
+        .map_err(internal_error)?;
+    Ok(Json(
+        messages
+            .into_iter()
+            .map(|message| ChatMessageResponse {
+                media: message.media_attachments(),
+                message,
+            })
+            .collect(),
+    ))
 }
 
 async fn send_operator_message(
@@ -769,6 +973,79 @@ async fn get_turn_prompt(
     }))
 }
 
+async fn get_turn_explanation(
+    State(state): State<Arc<ServerState>>,
+    Path(turn_id): Path<String>,
+) -> Result<Json<ChatTurnExplanation>, (StatusCode, String)> {
+    state
+        .db
+        .get_turn_explanation(&turn_id)
+        .map_err(internal_error)?
+        .map(Json)
+        .ok_or_else(|| (StatusCode::NOT_FOUND, format!("turn {} not found", turn_id)))
+}
+
+async fn get_concern_graph(
+    State(state): State<Arc<ServerState>>,
+) -> Result<Json<ConcernGraph>, (StatusCode, String)> {
+    ConcernsManager::get_concern_graph(&state.db)
+        .map(Json)
+        .map_err(internal_error)
+}
+
+async fn list_surfaced_anomalies(
+    State(state): State<Arc<ServerState>>,
+    Query(query): Query<ListAnomaliesQuery>,
+) -> Result<Json<Vec<SurfacedAnomalyRecord>>, (StatusCode, String)> {
+    let limit = clamp_limit(query.limit, 50, 1, 500);
+    state
+        .db
+        .list_surfaced_anomalies(limit)
+        .map(Json)
+        .map_err(internal_error)
+}
+
+async fn acknowledge_anomaly(
+    State(state): State<Arc<ServerState>>,
+    Path(fingerprint): Path<String>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    if state
+        .db
+        .acknowledge_anomaly(&fingerprint)
+        .map_err(internal_error)?
+    {
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err(not_found(format!("anomaly '{}' not found", fingerprint)))
+    }
+}
+
+async fn get_persona_timeline(
+    State(state): State<Arc<ServerState>>,
+    Query(query): Query<PersonaTimelineQuery>,
+) -> Result<Json<Vec<PersonaTimelineEntry>>, (StatusCode, String)> {
+    let limit = clamp_limit(query.limit, 50, 1, 500);
+    state
+        .db
+        .get_persona_timeline(limit)
+        .map(Json)
+        .map_err(internal_error)
+}
+
+async fn get_mood_timeseries(
+    State(state): State<Arc<ServerState>>,
+    Query(query): Query<MoodTimeseriesQuery>,
+) -> Result<Json<Vec<MoodBucket>>, (StatusCode, String)> {
+    let hours = clamp_limit(query.hours, 24, 1, 24 * 30);
+    let bucket_minutes = clamp_limit(query.bucket_minutes, 60, 1, 24 * 60);
+    let since = Utc::now() - chrono::Duration::hours(hours as i64);
+    state
+        .db
+        .get_mood_timeseries(since, chrono::Duration::minutes(bucket_minutes as i64))
+        .map(Json)
+        .map_err(internal_error)
+}
+
 async fn list_scheduled_jobs(
     State(state): State<Arc<ServerState>>,
     Query(query): Query<ListScheduledJobsQuery>,
@@ -811,10 +1088,21 @@ async fn create_scheduled_job(
         ));
     }
 
-    let job = state
+    validate_scheduled_job_profile(&state, body.capability_profile.as_deref()).await?;
+
+    let mut job = state
         .db
         .create_scheduled_job(name, prompt, body.interval_minutes)
         .map_err(internal_error)?;
+    if body.capability_profile.is_some() {
+        if let Some(updated) = state
+            .db
+            .set_scheduled_job_capability_profile(&job.id, body.capability_profile.as_deref())
+            .map_err(internal_error)?
+        {
+            job = updated;
+        }
+    }
     state
         .agent
         .notify_operator_message_queued(&job.conversation_id);
@@ -837,11 +1125,20 @@ async fn update_scheduled_job(
         .map(str::trim)
         .filter(|value| !value.is_empty());
 
-    match state
+    validate_scheduled_job_profile(&state, body.capability_profile.as_deref()).await?;
+
+    let updated = state
         .db
         .update_scheduled_job(&job_id, name, prompt, body.interval_minutes, body.enabled)
-        .map_err(internal_error)?
-    {
+        .map_err(internal_error)?;
+    let updated = match (updated, body.capability_profile.as_deref()) {
+        (Some(_), Some(profile)) => state
+            .db
+            .set_scheduled_job_capability_profile(&job_id, Some(profile))
+            .map_err(internal_error)?,
+        (updated, _) => updated,
+    };
+    match updated {
         Some(job) => {
             state
                 .agent
@@ -852,6 +1149,25 @@ async fn update_scheduled_job(
     }
 }
 
+/// Rejects names that are neither a built-in nor a configured custom profile.
+async fn validate_scheduled_job_profile(
+    state: &ServerState,
+    profile: Option<&str>,
+) -> Result<(), (StatusCode, String)> {
+    let Some(profile) = profile.map(str::trim).filter(|value| !value.is_empty()) else {
+        return Ok(());
+    };
+    let config = state.config.read().await;
+    if is_known_profile_name(&config, profile) {
+        Ok(())
+    } else {
+        Err((
+            StatusCode::BAD_REQUEST,
+            format!("unknown capability profile '{}'", profile),
+        ))
+    }
+}
+
 async fn delete_scheduled_job(
     State(state): State<Arc<ServerState>>,
     Path(job_id): Path<String>,
@@ -961,6 +1277,14 @@ async fn set_pause(
     Ok(Json(PauseStateResponse { paused }))
 }
 
+async fn set_ambient(
+    State(state): State<Arc<ServerState>>,
+    Json(body): Json<SetAmbientRequest>,
+) -> Result<Json<AmbientStateResponse>, (StatusCode, String)> {
+    let enabled = state.agent.set_ambient_enabled(body.enabled).await;
+    Ok(Json(AmbientStateResponse { enabled }))
+}
+
 async fn set_loose_mode(
     State(state): State<Arc<ServerState>>,
     Json(body): Json<SetLooseModeRequest>,
@@ -1006,14 +1330,184 @@ async fn stop_agent_turn(
     Ok(Json(StopResponse { stopped: true }))
 }
 
+async fn stop_conversation_turn(
+    State(state): State<Arc<ServerState>>,
+    Path(conversation_id): Path<String>,
+) -> Result<Json<StopResponse>, (StatusCode, String)> {
+    require_conversation(&state, &conversation_id)?;
+    state
+        .agent
+        .request_stop_for_conversation(&conversation_id)
+        .await;
+    Ok(Json(StopResponse { stopped: true }))
+}
+
+async fn resume_background_subtask(
+    State(state): State<Arc<ServerState>>,
+    Path(conversation_id): Path<String>,
+) -> Result<Json<ResumeBackgroundResponse>, (StatusCode, String)> {
+    require_conversation(&state, &conversation_id)?;
+    let resumed = state
+        .agent
+        .resume_background_subtask(&conversation_id)
+        .await;
+    Ok(Json(ResumeBackgroundResponse { resumed }))
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct DailyReportRequest {
+    /// UTC day to summarize; defaults to today.
+    #[serde(default)]
+    date: Option<chrono::NaiveDate>,
+    /// Defaults to `daily_report_post_to_chat`.
+    #[serde(default)]
+    post_to_chat: Option<bool>,
+}
+
+async fn generate_daily_report(
+    State(state): State<Arc<ServerState>>,
+    body: Option<Json<DailyReportRequest>>,
+) -> Result<Json<DailyReport>, (StatusCode, String)> {
+    let body = body.map(|Json(body)| body).unwrap_or_default();
+    let post_to_chat = match body.post_to_chat {
+        Some(post_to_chat) => post_to_chat,
+        None => state.config.read().await.daily_report_post_to_chat,
+    };
+    let date = body.date.unwrap_or_else(|| Utc::now().date_naive());
+    state
+        .agent
+        .generate_daily_report(date, post_to_chat)
+        .await
+        .map(Json)
+        .map_err(internal_error)
+}
+
+async fn trigger_reflection(
+    State(state): State<Arc<ServerState>>,
+) -> Result<Json<TrajectoryAnalysis>, (StatusCode, String)> {
+    if !state.config.read().await.enable_self_reflection {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "self-reflection is disabled".to_string(),
+        ));
+    }
+    match state
+        .agent
+        .trigger_reflection()
+        .await
+        .map_err(internal_error)?
+    {
+        Some(analysis) => Ok(Json(analysis)),
+        None => Err((
+            StatusCode::CONFLICT,
+            "a reflection is already running".to_string(),
+        )),
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ApproveToolRequest {
+    /// Persist an approval for this call's signature instead of granting the
+    /// whole tool for the session.
+    #[serde(default)]
+    remember: bool,
+    /// The pending call's arguments, as carried by `NeedsApproval`.
+    #[serde(default)]
+    params: Option<serde_json::Value>,
+    /// Optional directory that remembered path arguments may fall under.
+    #[serde(default)]
+    scope: Option<String>,
+}
+
 async fn approve_tool(
     State(state): State<Arc<ServerState>>,
     Path(tool_name): Path<String>,
+    body: Option<Json<ApproveToolRequest>>,
 ) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
-    state.agent.grant_session_tool_approval(&tool_name).await;
-    Ok(Json(
-        serde_json::json!({ "approved": true, "tool": tool_name }),
-    ))
+    let body = body.map(|Json(body)| body).unwrap_or_default();
+    if !body.remember {
+        state.agent.grant_session_tool_approval(&tool_name).await;
+        return Ok(Json(
+            serde_json::json!({ "approved": true, "tool": tool_name }),
+        ));
+    }
+    let Some(params) = body.params else {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "remember requires the call's params".to_string(),
+        ));
+    };
+    let approval = state
+        .agent
+        .remember_tool_approval(&tool_name, &params, body.scope.as_deref())
+        .await
+        .map_err(internal_error)?
+        .ok_or_else(|| not_found(format!("tool '{}' not found", tool_name)))?;
+    Ok(Json(serde_json::json!({
+        "approved": true,
+        "tool": tool_name,
+        "remembered": approval,
+    })))
+}
+
+async fn list_remembered_tool_approvals(
+    State(state): State<Arc<ServerState>>,
+) -> Json<Vec<RememberedToolApproval>> {
+    Json(state.agent.remembered_tool_approvals().await)
+}
+
+async fn forget_tool_approval(
+    State(state): State<Arc<ServerState>>,
+    Path(approval_id): Path<String>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    if state
+        .agent
+        .forget_tool_approval(&approval_id)
+        .await
+        .map_err(internal_error)?
+    {
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err(not_found(format!(
+            "remembered approval '{}' not found",
+            approval_id
+        )))
+    }
+}
+
+async fn list_pending_approvals(
+    State(state): State<Arc<ServerState>>,
+) -> Result<Json<Vec<QueuedToolApproval>>, (StatusCode, String)> {
+    state
+        .agent
+        .pending_tool_approvals()
+        .await
+        .map(Json)
+        .map_err(internal_error)
+}
+
+#[derive(Debug, Deserialize)]
+struct ResolveApprovalRequest {
+    approved: bool,
+}
+
+async fn resolve_approval(
+    State(state): State<Arc<ServerState>>,
+    Path(approval_id): Path<String>,
+    Json(body): Json<ResolveApprovalRequest>,
+) -> Result<Json<ResolvedToolApproval>, (StatusCode, String)> {
+    state
+        .agent
+        .resolve_approval(&approval_id, body.approved)
+        .await
+        .map_err(internal_error)?
+        .map(Json)
+        .ok_or_else(|| {
+            not_found(format!(
+                "pending approval '{}' not found or already resolved",
+                approval_id
+            ))
+        })
 }
 
 async fn ws_events_route(
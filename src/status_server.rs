@@ -0,0 +1,83 @@
+//! Optional read-only monitoring server.
+//!
+//! When `status_server_addr` is set, a separate axum listener serves
+//! `GET /status` with `Agent::status_report` as JSON. It carries no auth and
+//! no mutating routes, so it binds loopback unless an explicit host is given.
+
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use axum::{extract::State, routing::get, Json, Router};
+
+use crate::agent::{Agent, AgentStatusReport};
+
+/// Parses `status_server_addr`: a bare port binds 127.0.0.1, anything else
+/// must be a full `host:port` socket address.
+pub fn resolve_status_bind_addr(raw: &str) -> Result<SocketAddr> {
+    let raw = raw.trim();
+    if let Ok(port) = raw.parse::<u16>() {
+        return Ok(SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), port));
+    }
+    raw.parse::<SocketAddr>()
+        .with_context(|| format!("Invalid status_server_addr '{raw}' (expected port or host:port)"))
+}
+
+pub fn status_router(agent: Arc<Agent>) -> Router {
+    Router::new()
+        .route("/status", get(status))
+        .with_state(agent)
+}
+
+/// Binds and serves in a background task; failures are logged rather than
+/// taking the main backend down.
+pub fn spawn_status_server(agent: Arc<Agent>, raw_addr: &str) {
+    let addr = match resolve_status_bind_addr(raw_addr) {
+        Ok(addr) => addr,
+        Err(error) => {
+            tracing::warn!("Status server disabled: {}", error);
+            return;
+        }
+    };
+    if !addr.ip().is_loopback() {
+        tracing::warn!(
+            "Status server binding non-loopback address {}; /status is unauthenticated",
+            addr
+        );
+    }
+    tokio::spawn(async move {
+        let listener = match tokio::net::TcpListener::bind(addr).await {
+            Ok(listener) => listener,
+            Err(error) => {
+                tracing::warn!("Failed to bind status server to {}: {}", addr, error);
+                return;
+            }
+        };
+        tracing::info!("Status server listening on http://{}/status", addr);
+        if let Err(error) = axum::serve(listener, status_router(agent)).await {
+            tracing::warn!("Status server stopped: {}", error);
+        }
+    });
+}
+
+async fn status(State(agent): State<Arc<Agent>>) -> Json<AgentStatusReport> {
+    Json(agent.status_report().await)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bare_port_binds_loopback_and_full_addresses_pass_through() {
+        assert_eq!(
+            resolve_status_bind_addr(" 9464 ").unwrap(),
+            "127.0.0.1:9464".parse::<SocketAddr>().unwrap()
+        );
+        assert_eq!(
+            resolve_status_bind_addr("0.0.0.0:9464").unwrap(),
+            "0.0.0.0:9464".parse::<SocketAddr>().unwrap()
+        );
+        assert!(resolve_status_bind_addr("localhost").is_err());
+    }
+}
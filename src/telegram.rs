@@ -17,6 +17,10 @@ use tokio::task::JoinHandle;
 use crate::database::TELEGRAM_CONVERSATION_ID;
 use crate::server::{ApiEventEnvelope, ServerState};
 
+/// Agent-state key prefix holding the next `getUpdates` offset across
+/// restarts; `offset_state_key` appends the bot id.
+const TELEGRAM_OFFSET_STATE_KEY: &str = "telegram.update_offset";
+
 // ─── Telegram API types ──────────────────────────────────────────────────────
 
 #[derive(Deserialize)]
@@ -117,7 +121,8 @@ impl TelegramBotManager {
 async fn run_bot(state: Arc<ServerState>, token: String, allowed_chat_id: Option<i64>) {
     let api_base = format!("https://api.telegram.org/bot{}", token);
     let client = reqwest::Client::new();
-    let mut offset: i64 = 0;
+    let offset_key = offset_state_key(&token);
+    let mut offset = load_offset(&state, &offset_key);
 
     // Subscribe before processing any messages so we never miss a quick reply.
     let mut event_rx = state.ws_events.subscribe();
@@ -129,7 +134,10 @@ async fn run_bot(state: Arc<ServerState>, token: String, allowed_chat_id: Option
         };
 
         for update in updates {
-            offset = update.update_id + 1;
+            // Persist before routing so a restart mid-reply never re-delivers
+            // a message that was already stored in the conversation.
+            offset = next_offset(offset, update.update_id);
+            store_offset(&state, &offset_key, offset);
 
             let msg = match update.message {
                 Some(m) => m,
@@ -182,6 +190,40 @@ async fn run_bot(state: Arc<ServerState>, token: String, allowed_chat_id: Option
 
 // ─── Helpers ─────────────────────────────────────────────────────────────────
 
+/// Offsets are per bot: update ids from one bot mean nothing to another, so
+/// a new bot's token must not start from the old bot's offset. The numeric
+/// prefix of `<bot_id>:<secret>` survives token rotation for the same bot.
+fn offset_state_key(token: &str) -> String {
+    let bot_id = token.split(':').next().unwrap_or_default().trim();
+    if bot_id.is_empty() || !bot_id.bytes().all(|byte| byte.is_ascii_digit()) {
+        return TELEGRAM_OFFSET_STATE_KEY.to_string();
+    }
+    format!("{}.{}", TELEGRAM_OFFSET_STATE_KEY, bot_id)
+}
+
+fn load_offset(state: &ServerState, key: &str) -> i64 {
+    match state.db.get_state(key) {
+        Ok(Some(raw)) => raw.trim().parse().unwrap_or(0),
+        Ok(None) => 0,
+        Err(e) => {
+            tracing::warn!("Telegram: failed to load update offset: {}", e);
+            0
+        }
+    }
+}
+
+fn store_offset(state: &ServerState, key: &str, offset: i64) {
+    if let Err(e) = state.db.set_state(key, &offset.to_string()) {
+        tracing::warn!("Telegram: failed to persist update offset: {}", e);
+    }
+}
+
+/// Telegram confirms every update below the requested offset, so the offset
+/// only ever moves forward.
+fn next_offset(current: i64, update_id: i64) -> i64 {
+    current.max(update_id + 1)
+}
+
 async fn poll_updates(
     client: &reqwest::Client,
     api_base: &str,
@@ -360,6 +402,29 @@ mod tests {
         assert_eq!(truncated.chars().count(), 4096);
     }
 
+    #[test]
+    fn next_offset_never_moves_backwards() {
+        assert_eq!(next_offset(0, 41), 42);
+        assert_eq!(next_offset(42, 10), 42);
+    }
+
+    #[test]
+    fn offset_state_key_is_per_bot_and_survives_rotation() {
+        assert_eq!(
+            offset_state_key("123456:AAE-first-secret"),
+            "telegram.update_offset.123456"
+        );
+        assert_eq!(
+            offset_state_key("123456:AAE-rotated-secret"),
+            offset_state_key("123456:AAE-first-secret")
+        );
+        assert_ne!(
+            offset_state_key("987654:AAE-other-bot"),
+            offset_state_key("123456:AAE-first-secret")
+        );
+        assert_eq!(offset_state_key("not-a-token"), "telegram.update_offset");
+    }
+
     #[test]
     fn truncate_log_text_adds_suffix_when_trimmed() {
         let truncated = truncate_log_text("abcdef", 3);
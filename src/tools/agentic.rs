@@ -9,58 +9,116 @@
 
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
-use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 use std::sync::Arc;
 
+use crate::config::SamplingScheduleStep;
 use crate::generation_telemetry::{
     GenerationMetricSample, GenerationObserver, GenerationOutcome, ProviderToken,
     TokenNoveltyTracker,
 };
 use crate::http_client::build_http_client;
+use crate::llm_client::{send_with_retry, LlmRetryPolicy, TokenUsage};
+use crate::llm_provider::{
+    anthropic_messages_url, anthropic_response_to_openai, build_anthropic_request,
+    AnthropicStreamTranslator, LlmProvider,
+};
 
 use super::safety;
-use super::{ToolCall, ToolContext, ToolDef, ToolOutput, ToolRegistry};
+use super::{ToolCall, ToolCallResult, ToolContext, ToolDef, ToolOutput, ToolRegistry};
+
+/// Calls to unregistered tools tolerated in one run before tools are withheld.
+pub const MAX_UNKNOWN_TOOL_CALLS_PER_RUN: usize = 3;
 
 /// Configuration for the agentic loop
 #[derive(Debug, Clone)]
 pub struct AgenticConfig {
     /// Maximum iterations before stopping. `None` means unlimited.
     pub max_iterations: Option<usize>,
+    /// Tool calls executed per run, across iterations. `None` means unlimited.
+    pub max_tool_calls_per_turn: Option<usize>,
     /// LLM API URL
     pub api_url: String,
     /// LLM model name
     pub model: String,
+    /// Models tried in order when `model` is missing or unreachable
+    pub fallback_models: Vec<String>,
     /// Optional API key
     pub api_key: Option<String>,
+    /// Chat wire format for `api_url`
+    pub provider: LlmProvider,
+    /// Backoff for transient request failures; pending retries stop on cancel
+    pub retry: LlmRetryPolicy,
     /// Temperature for LLM calls
     pub temperature: f32,
     /// Max tokens per LLM response
     pub max_tokens: u32,
+    /// Overrides for later autonomous turns; see `sampling_for_turn`.
+    pub sampling_schedule: Vec<SamplingScheduleStep>,
     /// Shared generation counter used to cancel in-flight loops.
     /// If current value differs from `start_generation`, loop exits early.
     pub cancel_generation: Option<Arc<AtomicU64>>,
     /// Generation snapshot captured at loop start.
     pub start_generation: u64,
+    /// Optional per-conversation counter; bumping it cancels only loops
+    /// started for that conversation while `cancel_generation` stops everything.
+    pub conversation_cancel_generation: Option<Arc<AtomicU64>>,
+    /// Per-conversation generation snapshot captured at loop start.
+    pub conversation_start_generation: u64,
     /// Observability lane for each model request made by this loop.
     pub generation_observer: Option<GenerationObserver>,
+    /// Simulate tool calls instead of executing them so the operator can
+    /// review the model's plan before anything runs.
+    pub plan_only: bool,
 }
 
 impl Default for AgenticConfig {
     fn default() -> Self {
         Self {
             max_iterations: Some(10),
+            max_tool_calls_per_turn: None,
             api_url: "http://localhost:11434/v1".to_string(),
             model: "llama3.2".to_string(),
+            fallback_models: Vec::new(),
             api_key: None,
+            provider: LlmProvider::OpenAiCompatible,
+            retry: LlmRetryPolicy::default(),
             temperature: 0.7,
             max_tokens: 4096,
+            sampling_schedule: Vec::new(),
             cancel_generation: None,
             start_generation: 0,
+            conversation_cancel_generation: None,
+            conversation_start_generation: 0,
             generation_observer: None,
+            plan_only: false,
         }
     }
 }
 
+impl AgenticConfig {
+    /// Temperature and token budget for the 1-based autonomous `turn`: the
+    /// schedule step with the highest `after_continuations` at or below
+    /// `turn - 1` overrides the base values it sets validly.
+    pub fn sampling_for_turn(&self, turn: usize) -> (f32, u32) {
+        let continuations = turn.saturating_sub(1);
+        let step = self
+            .sampling_schedule
+            .iter()
+            .filter(|step| step.after_continuations <= continuations)
+            .max_by_key(|step| step.after_continuations);
+        let temperature = step
+            .and_then(|step| step.temperature)
+            .filter(|temperature| (0.0..=2.0).contains(temperature))
+            .unwrap_or(self.temperature);
+        let max_tokens = step
+            .and_then(|step| step.max_tokens)
+            .filter(|max_tokens| *max_tokens > 0)
+            .unwrap_or(self.max_tokens);
+        (temperature, max_tokens)
+    }
+}
+
 /// A message in the conversation
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Message {
@@ -97,6 +155,12 @@ pub enum AgenticTermination {
     Cancelled,
     /// The configured tool-calling iteration budget was exhausted.
     IterationLimit,
+    /// The per-turn tool-call budget was spent and the model still asked for
+    /// tools after being told to summarize.
+    ToolBudget,
+    /// The model kept calling tools that do not exist, even after tools were
+    /// withheld.
+    UnknownToolLimit,
 }
 
 /// The outcome of running the agentic loop
@@ -116,6 +180,10 @@ pub struct AgenticResult {
     ///
     /// Retained for compatibility; prefer matching on `termination`.
     pub hit_limit: bool,
+    /// Tokens spent across every LLM request in the loop.
+    pub usage: TokenUsage,
+    /// One note per switch to a fallback model, in the order they happened.
+    pub model_fallbacks: Vec<String>,
 }
 
 /// Record of a tool call made during the loop
@@ -124,6 +192,9 @@ pub struct ToolCallRecord {
     pub tool_name: String,
     pub arguments: serde_json::Value,
     pub output: ToolOutput,
+    /// Wall-clock time spent in `ToolRegistry::execute_call`; zero for calls
+    /// rejected before execution.
+    pub duration_ms: u64,
 }
 
 /// Per-token-ish metrics emitted while streaming assistant text.
@@ -253,11 +324,22 @@ fn emit_message_as_stream_update(callback: Option<&dyn Fn(&StreamingUpdate)>, me
     });
 }
 
+/// Tool definitions for one LLM request. With `calls_allowed` false the
+/// definitions are still sent (so earlier tool turns stay valid) but the
+/// request sets `tool_choice` to none.
+#[derive(Debug, Clone, Copy)]
+struct OfferedTools<'a> {
+    defs: &'a [ToolDef],
+    calls_allowed: bool,
+}
+
 /// The agentic loop executor
 pub struct AgenticLoop {
     config: AgenticConfig,
     registry: Arc<ToolRegistry>,
     client: reqwest::Client,
+    /// Autonomous turn the next run belongs to; selects `sampling_schedule` steps.
+    autonomous_turn: AtomicUsize,
 }
 
 impl AgenticLoop {
@@ -266,21 +348,36 @@ impl AgenticLoop {
             config,
             registry,
             client: build_http_client(),
+            autonomous_turn: AtomicUsize::new(1),
         }
     }
 
+    /// Tell the loop which autonomous turn (1-based) it is about to run, so
+    /// later turns pick up the configured sampling schedule.
+    pub fn set_autonomous_turn(&self, turn: usize) {
+        self.autonomous_turn.store(turn.max(1), Ordering::SeqCst);
+    }
+
     fn is_cancelled(&self) -> bool {
-        self.config
-            .cancel_generation
-            .as_ref()
-            .map(|generation| generation.load(Ordering::SeqCst) != self.config.start_generation)
-            .unwrap_or(false)
+        let moved = |generation: &Option<Arc<AtomicU64>>, start: u64| {
+            generation
+                .as_ref()
+                .map(|generation| generation.load(Ordering::SeqCst) != start)
+                .unwrap_or(false)
+        };
+        moved(&self.config.cancel_generation, self.config.start_generation)
+            || moved(
+                &self.config.conversation_cancel_generation,
+                self.config.conversation_start_generation,
+            )
     }
 
     fn cancelled_result(
         &self,
         iterations: usize,
         tool_calls_made: Vec<ToolCallRecord>,
+        usage: TokenUsage,
+        model_fallbacks: Vec<String>,
     ) -> AgenticResult {
         AgenticResult {
             response: Some("Stopped current turn at operator request.".to_string()),
@@ -289,6 +386,8 @@ impl AgenticLoop {
             iterations,
             termination: AgenticTermination::Cancelled,
             hit_limit: false,
+            usage,
+            model_fallbacks,
         }
     }
 
@@ -401,11 +500,26 @@ impl AgenticLoop {
 
         let mut tool_calls_made = Vec::new();
         let mut iterations = 0;
+        let mut usage = TokenUsage::default();
+        // Once a fallback answers, later iterations stay on it for this run.
+        let models = self.model_chain();
+        let mut active_model = 0;
+        let mut model_fallbacks = Vec::new();
+        // Set once the tool budget is spent; later calls get no tools.
+        let mut tool_budget_spent = false;
+        // Likewise once the model has called too many unregistered tools.
+        let mut unknown_tool_calls = 0;
+        let mut unknown_tool_limit_hit = false;
 
         loop {
             if self.is_cancelled() {
                 tracing::info!("Agentic loop cancelled by operator request");
-                return Ok(self.cancelled_result(iterations, tool_calls_made));
+                return Ok(self.cancelled_result(
+                    iterations,
+                    tool_calls_made,
+                    usage,
+                    model_fallbacks,
+                ));
             }
             iterations += 1;
 
@@ -422,6 +536,8 @@ impl AgenticLoop {
                         iterations: iterations - 1,
                         termination: AgenticTermination::IterationLimit,
                         hit_limit: true,
+                        usage,
+                        model_fallbacks,
                     });
                 }
             }
@@ -435,22 +551,78 @@ impl AgenticLoop {
                     token_metrics: Vec::new(),
                 });
             }
-            let llm_response = self
-                .call_llm(&messages, &tool_defs, on_text_stream)
-                .await
-                .context("LLM call failed in agentic loop")?;
+            let llm_response = loop {
+                let model = models[active_model];
+                // The definitions stay on the request even once calls are
+                // closed: providers reject tool_use history without them.
+                let offered_tools = OfferedTools {
+                    defs: &tool_defs,
+                    calls_allowed: !(tool_budget_spent || unknown_tool_limit_hit),
+                };
+                match self
+                    .call_llm(&messages, offered_tools, model, on_text_stream, &mut usage)
+                    .await
+                {
+                    Ok(message) => break message,
+                    Err(error) if active_model + 1 < models.len() && model_unavailable(&error) => {
+                        active_model += 1;
+                        let note = format!(
+                            "Model '{}' unavailable ({}); falling back to '{}'",
+                            model, error, models[active_model]
+                        );
+                        tracing::warn!("{}", note);
+                        model_fallbacks.push(note);
+                    }
+                    Err(error) => return Err(error).context("LLM call failed in agentic loop"),
+                }
+            };
             // Streaming/non-streaming request helpers use a synthetic assistant
             // message to unwind promptly on cancellation. Re-check the generation
             // here so that message cannot be classified as normal completion.
             if self.is_cancelled() {
                 tracing::info!("Agentic loop cancelled during LLM execution");
-                return Ok(self.cancelled_result(iterations, tool_calls_made));
+                return Ok(self.cancelled_result(
+                    iterations,
+                    tool_calls_made,
+                    usage,
+                    model_fallbacks,
+                ));
             }
 
             // Check if LLM returned tool calls
             if let Some(ref tool_calls) = llm_response.tool_calls {
                 if !tool_calls.is_empty() {
                     tracing::debug!("LLM requested {} tool call(s)", tool_calls.len());
+                    if tool_budget_spent {
+                        return Ok(AgenticResult {
+                            response: Some(format!(
+                                "[Reached the budget of {} tool calls for this turn]",
+                                self.config.max_tool_calls_per_turn.unwrap_or_default()
+                            )),
+                            thinking_blocks: Vec::new(),
+                            tool_calls_made,
+                            iterations,
+                            termination: AgenticTermination::ToolBudget,
+                            hit_limit: false,
+                            usage,
+                            model_fallbacks,
+                        });
+                    }
+                    if unknown_tool_limit_hit {
+                        return Ok(AgenticResult {
+                            response: Some(format!(
+                                "[Stopped after {} calls to tools that do not exist]",
+                                unknown_tool_calls
+                            )),
+                            thinking_blocks: Vec::new(),
+                            tool_calls_made,
+                            iterations,
+                            termination: AgenticTermination::UnknownToolLimit,
+                            hit_limit: false,
+                            usage,
+                            model_fallbacks,
+                        });
+                    }
 
                     // Add assistant message with tool calls to history
                     messages.push(llm_response.clone());
@@ -459,7 +631,28 @@ impl AgenticLoop {
                     for tc in tool_calls {
                         if self.is_cancelled() {
                             tracing::info!("Agentic loop cancelled before tool execution");
-                            return Ok(self.cancelled_result(iterations, tool_calls_made));
+                            return Ok(self.cancelled_result(
+                                iterations,
+                                tool_calls_made,
+                                usage,
+                                model_fallbacks,
+                            ));
+                        }
+                        if self
+                            .config
+                            .max_tool_calls_per_turn
+                            .is_some_and(|budget| tool_calls_made.len() >= budget)
+                        {
+                            // Every tool_call_id still needs an answer.
+                            messages.push(Message {
+                                role: "tool".to_string(),
+                                content: Some(
+                                    "[Skipped: tool budget for this turn exhausted]".to_string(),
+                                ),
+                                tool_calls: None,
+                                tool_call_id: Some(tc.id.clone()),
+                            });
+                            continue;
                         }
                         let arguments: serde_json::Value =
                             serde_json::from_str(&tc.function.arguments).unwrap_or_else(|e| {
@@ -478,6 +671,7 @@ impl AgenticLoop {
                                     tool_name: tc.function.name.clone(),
                                     arguments: arguments.clone(),
                                     output: output.clone(),
+                                    duration_ms: 0,
                                 });
                                 messages.push(Message {
                                     role: "tool".to_string(),
@@ -503,7 +697,13 @@ impl AgenticLoop {
                             arguments: arguments.clone(),
                         };
 
-                        let result = self.registry.execute_call(&call, tool_ctx).await;
+                        let (result, duration_ms) = if self.config.plan_only {
+                            (dry_run_result(&call), 0)
+                        } else {
+                            let started = std::time::Instant::now();
+                            let result = self.registry.execute_call(&call, tool_ctx).await;
+                            (result, started.elapsed().as_millis() as u64)
+                        };
 
                         // Run output through safety pipeline
                         let safe_output = match &result.output {
@@ -526,10 +726,14 @@ impl AgenticLoop {
                             other => other.to_llm_string(),
                         };
 
+                        if result.output.is_unknown_tool_error() {
+                            unknown_tool_calls += 1;
+                        }
                         let record = ToolCallRecord {
                             tool_name: tc.function.name.clone(),
                             arguments,
                             output: result.output,
+                            duration_ms,
                         };
                         if let Some(callback) = on_tool_event {
                             callback(&record);
@@ -545,6 +749,46 @@ impl AgenticLoop {
                         });
                     }
 
+                    if let Some(budget) = self.config.max_tool_calls_per_turn {
+                        if tool_calls_made.len() >= budget {
+                            tracing::warn!("Agentic loop hit tool budget ({} calls)", budget);
+                            tool_budget_spent = true;
+                            messages.push(Message {
+                                role: "user".to_string(),
+                                content: Some(format!(
+                                    "Tool budget exhausted: {} tool calls already ran this turn. \
+                                     Do not request more tools. Summarize what you did and found, \
+                                     and what is left to do.",
+                                    budget
+                                )),
+                                tool_calls: None,
+                                tool_call_id: None,
+                            });
+                        }
+                    }
+
+                    if !tool_budget_spent
+                        && !unknown_tool_limit_hit
+                        && unknown_tool_calls >= MAX_UNKNOWN_TOOL_CALLS_PER_RUN
+                    {
+                        tracing::warn!(
+                            "Agentic loop withholding tools after {} unknown tool calls",
+                            unknown_tool_calls
+                        );
+                        unknown_tool_limit_hit = true;
+                        messages.push(Message {
+                            role: "user".to_string(),
+                            content: Some(format!(
+                                "You have called {} tools that do not exist. Tools are now \
+                                 withheld for this turn. Answer with what you have, and say \
+                                 what you could not do.",
+                                unknown_tool_calls
+                            )),
+                            tool_calls: None,
+                            tool_call_id: None,
+                        });
+                    }
+
                     // Continue loop — LLM will see tool results
                     if let Some(callback) = on_text_stream {
                         callback(&StreamingUpdate {
@@ -573,20 +817,25 @@ impl AgenticLoop {
                 iterations,
                 termination: AgenticTermination::Completed,
                 hit_limit: false,
+                usage,
+                model_fallbacks,
             });
         }
     }
 
-    /// Call the LLM with the current messages and tool definitions.
+    /// Call the LLM with the current messages and tool definitions, adding
+    /// the tokens spent to `usage`.
     async fn call_llm(
         &self,
         messages: &[Message],
-        tool_defs: &[ToolDef],
+        tool_defs: OfferedTools<'_>,
+        model: &str,
         on_text_stream: Option<&dyn Fn(&StreamingUpdate)>,
+        usage: &mut TokenUsage,
     ) -> Result<Message> {
         if on_text_stream.is_some() {
             match self
-                .call_llm_streaming(messages, tool_defs, on_text_stream)
+                .call_llm_streaming(messages, tool_defs, model, on_text_stream, usage)
                 .await
             {
                 Ok(message) => {
@@ -605,7 +854,9 @@ impl AgenticLoop {
                         "Streaming returned no visible text or tool calls; \
                          retrying non-streaming to recover the response"
                     );
-                    let ns_message = self.call_llm_non_streaming(messages, tool_defs).await?;
+                    let ns_message = self
+                        .call_llm_non_streaming(messages, tool_defs, model, usage)
+                        .await?;
                     let recovered = select_verified_response(ns_message)?;
                     emit_message_as_stream_update(on_text_stream, &recovered);
                     return Ok(recovered);
@@ -619,7 +870,9 @@ impl AgenticLoop {
             }
         }
 
-        let message = self.call_llm_non_streaming(messages, tool_defs).await?;
+        let message = self
+            .call_llm_non_streaming(messages, tool_defs, model, usage)
+            .await?;
         emit_message_as_stream_update(on_text_stream, &message);
         Ok(message)
     }
@@ -627,7 +880,9 @@ impl AgenticLoop {
     async fn call_llm_non_streaming(
         &self,
         messages: &[Message],
-        tool_defs: &[ToolDef],
+        tool_defs: OfferedTools<'_>,
+        model: &str,
+        usage: &mut TokenUsage,
     ) -> Result<Message> {
         if self.is_cancelled() {
             return Ok(self.cancelled_message());
@@ -637,33 +892,15 @@ impl AgenticLoop {
             .generation_observer
             .as_ref()
             .map(GenerationObserver::start);
-        let url = format!("{}/chat/completions", self.config.api_url);
-
-        let mut body = serde_json::json!({
-            "model": self.config.model,
-            "messages": messages,
-            "temperature": self.config.temperature,
-            "max_tokens": self.config.max_tokens,
-        });
-
-        // Only include tools if we have any
-        if !tool_defs.is_empty() {
-            body["tools"] = serde_json::to_value(tool_defs)?;
-        }
-
-        let mut req = self.client.post(&url).json(&body);
-
-        if let Some(ref key) = self.config.api_key {
-            req = req.header("Authorization", format!("Bearer {}", key));
-        }
-
-        let response = req.send().await.context("Failed to send LLM request")?;
+        let (url, body) = self.request_url_and_body(messages, tool_defs, model, false)?;
+        let response = self.send_with_retry(&url, &body).await;
         if self.is_cancelled() {
             if let Some(session) = telemetry.as_mut() {
                 session.finish(GenerationOutcome::Cancelled);
             }
             return Ok(self.cancelled_message());
         }
+        let response = response.context("Failed to send LLM request")?;
 
         if !response.status().is_success() {
             let status = response.status();
@@ -671,10 +908,13 @@ impl AgenticLoop {
             anyhow::bail!("LLM API error {}: {}", status, body);
         }
 
-        let response_json: serde_json::Value = response
+        let mut response_json: serde_json::Value = response
             .json()
             .await
             .context("Failed to parse LLM response")?;
+        if self.config.provider == LlmProvider::Anthropic {
+            response_json = anthropic_response_to_openai(&response_json)?;
+        }
 
         // Extract the assistant message from the response
         let choice = response_json["choices"]
@@ -701,19 +941,26 @@ impl AgenticLoop {
             .get("tool_calls")
             .and_then(|tc| serde_json::from_value(tc.clone()).ok());
 
-        Ok(Message {
+        let reply = Message {
             role: "assistant".to_string(),
             content,
             tool_calls,
             tool_call_id: None,
-        })
+        };
+        usage.add(
+            TokenUsage::from_response(&response_json)
+                .unwrap_or_else(|| estimate_usage(messages, &reply)),
+        );
+        Ok(reply)
     }
 
     async fn call_llm_streaming(
         &self,
         messages: &[Message],
-        tool_defs: &[ToolDef],
+        tool_defs: OfferedTools<'_>,
+        model: &str,
         on_text_stream: Option<&dyn Fn(&StreamingUpdate)>,
+        usage: &mut TokenUsage,
     ) -> Result<Message> {
         #[derive(Debug, Clone, Default)]
         struct ToolCallAccumulator {
@@ -731,34 +978,28 @@ impl AgenticLoop {
             .generation_observer
             .as_ref()
             .map(GenerationObserver::start);
-        let url = format!("{}/chat/completions", self.config.api_url);
-
-        let mut body = serde_json::json!({
-            "model": self.config.model,
-            "messages": messages,
-            "temperature": self.config.temperature,
-            "max_tokens": self.config.max_tokens,
-            "stream": true,
-        });
-
-        if !tool_defs.is_empty() {
-            body["tools"] = serde_json::to_value(tool_defs)?;
-        }
-
-        let mut body_with_metrics = body.clone();
-        body_with_metrics["logprobs"] = serde_json::json!(true);
-        body_with_metrics["top_logprobs"] = serde_json::json!(5);
-
-        let mut response = match self.send_streaming_request(&url, &body_with_metrics).await {
-            Ok(response) => response,
-            Err(error) if logprob_request_unsupported(&error.to_string()) => {
-                tracing::debug!(
-                    "Streaming provider rejected logprob request; retrying without token logprobs: {}",
-                    error
-                );
-                self.send_streaming_request(&url, &body).await?
+        let (url, body) = self.request_url_and_body(messages, tool_defs, model, true)?;
+
+        // The Messages API has no token logprobs, so only OpenAI-compatible
+        // providers get the metrics-first attempt.
+        let mut response = if self.config.provider == LlmProvider::Anthropic {
+            self.send_streaming_request(&url, &body).await?
+        } else {
+            let mut body_with_metrics = body.clone();
+            body_with_metrics["logprobs"] = serde_json::json!(true);
+            body_with_metrics["top_logprobs"] = serde_json::json!(5);
+
+            match self.send_streaming_request(&url, &body_with_metrics).await {
+                Ok(response) => response,
+                Err(error) if logprob_request_unsupported(&error.to_string()) => {
+                    tracing::debug!(
+                        "Streaming provider rejected logprob request; retrying without token logprobs: {}",
+                        error
+                    );
+                    self.send_streaming_request(&url, &body).await?
+                }
+                Err(error) => return Err(error),
             }
-            Err(error) => return Err(error),
         };
         if self.is_cancelled() {
             if let Some(session) = telemetry.as_mut() {
@@ -772,6 +1013,9 @@ impl AgenticLoop {
         let mut line_buffer = String::new();
         let mut saw_done = false;
         let mut novelty_tracker = TokenNoveltyTracker::default();
+        let mut reported_usage: Option<TokenUsage> = None;
+        let mut anthropic_stream = (self.config.provider == LlmProvider::Anthropic)
+            .then(AnthropicStreamTranslator::default);
 
         while let Some(chunk) = response
             .chunk()
@@ -810,9 +1054,22 @@ impl AgenticLoop {
                     break;
                 }
 
-                let chunk_json: serde_json::Value = serde_json::from_str(payload)
+                let mut chunk_json: serde_json::Value = serde_json::from_str(payload)
                     .with_context(|| format!("Failed to parse stream payload: {}", payload))?;
+                if let Some(translator) = anthropic_stream.as_mut() {
+                    match translator.translate(&chunk_json)? {
+                        Some(translated) => chunk_json = translated,
+                        None if translator.is_finished() => {
+                            saw_done = true;
+                            break;
+                        }
+                        None => continue,
+                    }
+                }
 
+                if let Some(chunk_usage) = TokenUsage::from_response(&chunk_json) {
+                    reported_usage = Some(chunk_usage);
+                }
                 let Some(choice) = chunk_json["choices"].as_array().and_then(|arr| arr.first())
                 else {
                     continue;
@@ -938,7 +1195,7 @@ impl AgenticLoop {
             })
             .collect::<Vec<_>>();
 
-        Ok(Message {
+        let reply = Message {
             role: "assistant".to_string(),
             content: if content.is_empty() {
                 None
@@ -951,7 +1208,98 @@ impl AgenticLoop {
                 Some(parsed_tool_calls)
             },
             tool_call_id: None,
-        })
+        };
+        // OpenAI-compatible streams only report usage when asked via
+        // `stream_options`, which not every backend accepts.
+        usage.add(reported_usage.unwrap_or_else(|| estimate_usage(messages, &reply)));
+        Ok(reply)
+    }
+
+    /// The primary model followed by distinct, non-empty fallbacks.
+    fn model_chain(&self) -> Vec<&str> {
+        let mut models = vec![self.config.model.as_str()];
+        for model in self.config.fallback_models.iter().map(|m| m.trim()) {
+            if !model.is_empty() && !models.contains(&model) {
+                models.push(model);
+            }
+        }
+        models
+    }
+
+    /// Endpoint and request body in the configured provider's wire format.
+    fn request_url_and_body(
+        &self,
+        messages: &[Message],
+        tool_defs: OfferedTools<'_>,
+        model: &str,
+        stream: bool,
+    ) -> Result<(String, serde_json::Value)> {
+        let tools = if tool_defs.defs.is_empty() {
+            None
+        } else {
+            Some(serde_json::to_value(tool_defs.defs)?)
+        };
+        let refuse_calls = tools.is_some() && !tool_defs.calls_allowed;
+        let (temperature, max_tokens) = self
+            .config
+            .sampling_for_turn(self.autonomous_turn.load(Ordering::SeqCst));
+
+        if self.config.provider == LlmProvider::Anthropic {
+            let messages = messages
+                .iter()
+                .map(serde_json::to_value)
+                .collect::<serde_json::Result<Vec<_>>>()?;
+            let mut body = build_anthropic_request(
+                model,
+                &messages,
+                tools.as_ref(),
+                Some(temperature),
+                max_tokens,
+                stream,
+            );
+            if refuse_calls {
+                body["tool_choice"] = serde_json::json!({ "type": "none" });
+            }
+            return Ok((anthropic_messages_url(&self.config.api_url), body));
+        }
+
+        let mut body = serde_json::json!({
+            "model": model,
+            "messages": messages,
+            "temperature": temperature,
+            "max_tokens": max_tokens,
+        });
+        if stream {
+            body["stream"] = serde_json::json!(true);
+        }
+        // Only include tools if we have any
+        if let Some(tools) = tools {
+            body["tools"] = tools;
+        }
+        if refuse_calls {
+            body["tool_choice"] = serde_json::json!("none");
+        }
+        Ok((format!("{}/chat/completions", self.config.api_url), body))
+    }
+
+    /// POST with provider auth, retrying transient failures until the loop
+    /// is cancelled.
+    async fn send_with_retry(
+        &self,
+        url: &str,
+        body: &serde_json::Value,
+    ) -> reqwest::Result<reqwest::Response> {
+        send_with_retry(
+            &self.config.retry,
+            || {
+                self.config.provider.authorize(
+                    self.client.post(url).json(body),
+                    self.config.api_key.as_deref(),
+                )
+            },
+            || self.is_cancelled(),
+        )
+        .await
     }
 
     async fn send_streaming_request(
@@ -959,13 +1307,8 @@ impl AgenticLoop {
         url: &str,
         body: &serde_json::Value,
     ) -> Result<reqwest::Response> {
-        let mut req = self.client.post(url).json(body);
-        if let Some(ref key) = self.config.api_key {
-            req = req.header("Authorization", format!("Bearer {}", key));
-        }
-
-        let response = req
-            .send()
+        let response = self
+            .send_with_retry(url, body)
             .await
             .context("Failed to send streaming LLM request")?;
         if response.status().is_success() {
@@ -978,6 +1321,39 @@ impl AgenticLoop {
     }
 }
 
+/// Whether an LLM call failed because the model is missing or the endpoint
+/// is unreachable, rather than for a reason another model would share.
+fn model_unavailable(error: &anyhow::Error) -> bool {
+    error.chain().any(|cause| {
+        if let Some(error) = cause.downcast_ref::<reqwest::Error>() {
+            return error.is_connect() || error.is_timeout();
+        }
+        let text = cause.to_string().to_ascii_lowercase();
+        text.contains("api error 404")
+            || (text.contains("model")
+                && (text.contains("not found") || text.contains("does not exist")))
+    })
+}
+
+/// Simulated result fed back to the model when the loop runs in plan-only mode.
+fn dry_run_result(call: &ToolCall) -> ToolCallResult {
+    ToolCallResult {
+        name: call.name.clone(),
+        output: ToolOutput::Text(format!(
+            "[DRY RUN] would execute {} with {}. Plan-only mode is on, so nothing ran; \
+             continue planning or give the operator your final plan.",
+            call.name, call.arguments
+        )),
+    }
+}
+
+/// Character-based usage estimate for a request whose provider reported none.
+fn estimate_usage(prompt: &[Message], reply: &Message) -> TokenUsage {
+    let prompt_text = serde_json::to_string(prompt).unwrap_or_default();
+    let reply_text = serde_json::to_string(reply).unwrap_or_default();
+    TokenUsage::estimate(&prompt_text, &reply_text)
+}
+
 fn split_visible_and_thinking(input: &str) -> (String, Vec<String>) {
     fn extract_tag(text: String, open_tag: &str, close_tag: &str) -> (String, Vec<String>) {
         let mut rest = text;
@@ -1124,7 +1500,8 @@ mod tests {
                         "content": "Hi!",
                         "tool_calls": []
                     }
-                }]
+                }],
+                "usage": {"prompt_tokens": 12, "completion_tokens": 2}
             }))
             .into_response()
         }
@@ -1154,6 +1531,7 @@ mod tests {
         let callback = move |update: &StreamingUpdate| {
             captured.lock().unwrap().push(update.clone());
         };
+        let mut usage = TokenUsage::default();
         let response = loop_runner
             .call_llm(
                 &[Message {
@@ -1162,13 +1540,22 @@ mod tests {
                     tool_calls: None,
                     tool_call_id: None,
                 }],
-                &[],
+                OfferedTools {
+                    defs: &[],
+                    calls_allowed: true,
+                },
+                "llama3.2",
                 Some(&callback),
+                &mut usage,
             )
             .await
             .expect("recover verified response");
 
         server.abort();
+        // The empty stream reported nothing, so its share is estimated.
+        assert!(usage.estimated);
+        assert!(usage.prompt_tokens > 12);
+        assert!(usage.completion_tokens > 2);
         assert_eq!(response.content.as_deref(), Some("Hi!"));
         assert!(updates
             .lock()
@@ -1177,6 +1564,569 @@ mod tests {
             .any(|update| update.done && update.content == "Hi!"));
     }
 
+    #[tokio::test]
+    async fn anthropic_stream_translates_text_and_tool_use() {
+        use axum::http::HeaderMap;
+        use axum::response::IntoResponse;
+        use axum::routing::post;
+        use axum::{Json, Router};
+
+        async fn messages(
+            headers: HeaderMap,
+            Json(body): Json<serde_json::Value>,
+        ) -> axum::response::Response {
+            assert_eq!(headers["x-api-key"], "sk-test");
+            assert!(headers.contains_key("anthropic-version"));
+            assert_eq!(body["system"], "Be brief.");
+            assert_eq!(body["tools"][0]["name"], "read_file");
+            assert!(body.get("logprobs").is_none());
+            let events = [
+                r#"{"type":"message_start","message":{"id":"msg_1","usage":{"input_tokens":31}}}"#,
+                r#"{"type":"content_block_start","index":0,"content_block":{"type":"text","text":""}}"#,
+                r#"{"type":"content_block_delta","index":0,"delta":{"type":"text_delta","text":"Reading."}}"#,
+                r#"{"type":"content_block_start","index":1,"content_block":{"type":"tool_use","id":"toolu_1","name":"read_file","input":{}}}"#,
+                r#"{"type":"content_block_delta","index":1,"delta":{"type":"input_json_delta","partial_json":"{\"path\":"}}"#,
+                r#"{"type":"content_block_delta","index":1,"delta":{"type":"input_json_delta","partial_json":"\"a.txt\"}"}}"#,
+                r#"{"type":"message_delta","delta":{"stop_reason":"tool_use"},"usage":{"output_tokens":9}}"#,
+                r#"{"type":"message_stop"}"#,
+            ];
+            let stream: String = events
+                .iter()
+                .map(|event| format!("event: x\ndata: {}\n\n", event))
+                .collect();
+            ([("content-type", "text/event-stream")], stream).into_response()
+        }
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind mock provider");
+        let address = listener.local_addr().expect("mock provider address");
+        let server = tokio::spawn(async move {
+            axum::serve(
+                listener,
+                Router::new().route("/v1/messages", post(messages)),
+            )
+            .await
+            .expect("serve mock provider");
+        });
+
+        let loop_runner = AgenticLoop::new(
+            AgenticConfig {
+                api_url: format!("http://{address}/v1"),
+                api_key: Some("sk-test".to_string()),
+                provider: LlmProvider::Anthropic,
+                ..AgenticConfig::default()
+            },
+            Arc::new(ToolRegistry::new()),
+        );
+        let tool_defs = vec![ToolDef {
+            tool_type: "function".to_string(),
+            function: crate::tools::FunctionDef {
+                name: "read_file".to_string(),
+                description: "Read".to_string(),
+                parameters: serde_json::json!({"type": "object"}),
+            },
+        }];
+        let callback = |_: &StreamingUpdate| {};
+        let mut usage = TokenUsage::default();
+        let response = loop_runner
+            .call_llm(
+                &[
+                    Message {
+                        role: "system".to_string(),
+                        content: Some("Be brief.".to_string()),
+                        tool_calls: None,
+                        tool_call_id: None,
+                    },
+                    Message {
+                        role: "user".to_string(),
+                        content: Some("Open a.txt".to_string()),
+                        tool_calls: None,
+                        tool_call_id: None,
+                    },
+                ],
+                OfferedTools {
+                    defs: &tool_defs,
+                    calls_allowed: true,
+                },
+                "claude",
+                Some(&callback),
+                &mut usage,
+            )
+            .await
+            .expect("translated streaming response");
+
+        server.abort();
+        assert_eq!(
+            usage,
+            TokenUsage {
+                prompt_tokens: 31,
+                completion_tokens: 9,
+                estimated: false,
+            }
+        );
+        assert_eq!(response.content.as_deref(), Some("Reading."));
+        let calls = response.tool_calls.expect("tool call");
+        assert_eq!(calls[0].id, "toolu_1");
+        assert_eq!(calls[0].function.name, "read_file");
+        assert_eq!(calls[0].function.arguments, "{\"path\":\"a.txt\"}");
+    }
+
+    #[test]
+    fn closed_tool_calls_keep_definitions_and_disable_tool_choice() {
+        let tool_defs = vec![ToolDef {
+            tool_type: "function".to_string(),
+            function: crate::tools::FunctionDef {
+                name: "read_file".to_string(),
+                description: "Read".to_string(),
+                parameters: serde_json::json!({"type": "object"}),
+            },
+        }];
+        let offered = OfferedTools {
+            defs: &tool_defs,
+            calls_allowed: false,
+        };
+        let messages = [Message {
+            role: "user".to_string(),
+            content: Some("Summarize.".to_string()),
+            tool_calls: None,
+            tool_call_id: None,
+        }];
+
+        let openai = AgenticLoop::new(AgenticConfig::default(), Arc::new(ToolRegistry::new()));
+        let (_, body) = openai
+            .request_url_and_body(&messages, offered, "llama3.2", false)
+            .expect("openai body");
+        assert_eq!(body["tools"][0]["function"]["name"], "read_file");
+        assert_eq!(body["tool_choice"], "none");
+
+        let anthropic = AgenticLoop::new(
+            AgenticConfig {
+                provider: LlmProvider::Anthropic,
+                ..AgenticConfig::default()
+            },
+            Arc::new(ToolRegistry::new()),
+        );
+        let (_, body) = anthropic
+            .request_url_and_body(&messages, offered, "claude", false)
+            .expect("anthropic body");
+        assert_eq!(body["tools"][0]["name"], "read_file");
+        assert_eq!(body["tool_choice"]["type"], "none");
+
+        let (_, body) = openai
+            .request_url_and_body(
+                &messages,
+                OfferedTools {
+                    calls_allowed: true,
+                    ..offered
+                },
+                "llama3.2",
+                false,
+            )
+            .expect("open body");
+        assert!(body.get("tool_choice").is_none());
+    }
+
+    #[tokio::test]
+    async fn missing_model_falls_back_to_next_configured_model() {
+        use axum::http::StatusCode;
+        use axum::response::IntoResponse;
+        use axum::routing::post;
+        use axum::{Json, Router};
+
+        async fn completion(Json(body): Json<serde_json::Value>) -> axum::response::Response {
+            if body["model"] != "backup" {
+                return (
+                    StatusCode::NOT_FOUND,
+                    Json(serde_json::json!({"error": "model 'primary' not found"})),
+                )
+                    .into_response();
+            }
+            Json(serde_json::json!({
+                "choices": [{"message": {"role": "assistant", "content": "From backup."}}]
+            }))
+            .into_response()
+        }
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind mock provider");
+        let address = listener.local_addr().expect("mock provider address");
+        let server = tokio::spawn(async move {
+            axum::serve(
+                listener,
+                Router::new().route("/chat/completions", post(completion)),
+            )
+            .await
+            .expect("serve mock provider");
+        });
+
+        let loop_runner = AgenticLoop::new(
+            AgenticConfig {
+                api_url: format!("http://{address}"),
+                model: "primary".to_string(),
+                fallback_models: vec!["primary".to_string(), "backup".to_string()],
+                ..AgenticConfig::default()
+            },
+            Arc::new(ToolRegistry::new()),
+        );
+        let context = ToolContext {
+            working_directory: ".".to_string(),
+            allowed_roots: Vec::new(),
+            username: "tester".to_string(),
+            conversation_id: None,
+            autonomous: false,
+            auto_approve_local: false,
+            allowed_tools: None,
+            disallowed_tools: Vec::new(),
+            outbound_action_rate_limit: None,
+            generation_observer: None,
+            progress: None,
+            approval_waiter: None,
+            timeouts: crate::tools::ToolTimeoutPolicy::default(),
+        };
+        let result = loop_runner
+            .run("system", "hello", &context)
+            .await
+            .expect("fallback model answers");
+
+        server.abort();
+        assert_eq!(result.response.as_deref(), Some("From backup."));
+        assert_eq!(result.termination, AgenticTermination::Completed);
+        assert_eq!(result.model_fallbacks.len(), 1);
+        assert!(result.model_fallbacks[0].contains("falling back to 'backup'"));
+    }
+
+    struct CountingShellTool {
+        executions: Arc<std::sync::atomic::AtomicUsize>,
+    }
+
+    #[async_trait::async_trait]
+    impl crate::tools::Tool for CountingShellTool {
+        fn name(&self) -> &str {
+            "shell"
+        }
+
+        fn description(&self) -> &str {
+            "Counts executions"
+        }
+
+        fn parameters_schema(&self) -> serde_json::Value {
+            serde_json::json!({"type": "object", "properties": {}})
+        }
+
+        async fn execute(
+            &self,
+            _params: serde_json::Value,
+            _ctx: &ToolContext,
+        ) -> Result<ToolOutput> {
+            self.executions.fetch_add(1, Ordering::SeqCst);
+            Ok(ToolOutput::Text("ran".to_string()))
+        }
+    }
+
+    #[tokio::test]
+    async fn plan_only_simulates_tool_calls_without_executing_them() {
+        use axum::routing::post;
+        use axum::{Json, Router};
+
+        async fn completion(Json(body): Json<serde_json::Value>) -> Json<serde_json::Value> {
+            let messages = body["messages"].as_array().cloned().unwrap_or_default();
+            let tool_reply = messages
+                .iter()
+                .find(|message| message["role"] == "tool")
+                .and_then(|message| message["content"].as_str());
+            Json(match tool_reply {
+                Some(reply) => serde_json::json!({
+                    "choices": [{"message": {"role": "assistant", "content": format!("Plan: {reply}")}}]
+                }),
+                None => serde_json::json!({
+                    "choices": [{"message": {
+                        "role": "assistant",
+                        "content": null,
+                        "tool_calls": [{
+                            "id": "call_1",
+                            "type": "function",
+                            "function": {"name": "shell", "arguments": "{\"command\":\"rm -rf build\"}"}
+                        }]
+                    }}]
+                }),
+            })
+        }
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind mock provider");
+        let address = listener.local_addr().expect("mock provider address");
+        let server = tokio::spawn(async move {
+            axum::serve(
+                listener,
+                Router::new().route("/chat/completions", post(completion)),
+            )
+            .await
+            .expect("serve mock provider");
+        });
+
+        let executions = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let registry = Arc::new(ToolRegistry::new());
+        registry
+            .register(Arc::new(CountingShellTool {
+                executions: executions.clone(),
+            }))
+            .await;
+        let loop_runner = AgenticLoop::new(
+            AgenticConfig {
+                api_url: format!("http://{address}"),
+                plan_only: true,
+                ..AgenticConfig::default()
+            },
+            registry,
+        );
+        let context = ToolContext {
+            working_directory: ".".to_string(),
+            allowed_roots: Vec::new(),
+            username: "tester".to_string(),
+            conversation_id: None,
+            autonomous: false,
+            auto_approve_local: false,
+            allowed_tools: None,
+            disallowed_tools: Vec::new(),
+            outbound_action_rate_limit: None,
+            generation_observer: None,
+            progress: None,
+            approval_waiter: None,
+            timeouts: crate::tools::ToolTimeoutPolicy::default(),
+        };
+        let result = loop_runner
+            .run("system", "clean the build", &context)
+            .await
+            .expect("plan-only run");
+
+        server.abort();
+        assert_eq!(executions.load(Ordering::SeqCst), 0);
+        assert_eq!(result.tool_calls_made.len(), 1);
+        let record = &result.tool_calls_made[0];
+        assert_eq!(record.tool_name, "shell");
+        assert_eq!(record.arguments["command"], "rm -rf build");
+        match &record.output {
+            ToolOutput::Text(text) => assert!(text.starts_with("[DRY RUN] would execute shell")),
+            other => panic!("expected simulated text output, got {:?}", other),
+        }
+        assert!(result
+            .response
+            .as_deref()
+            .is_some_and(|response| response.contains("[DRY RUN] would execute shell")));
+    }
+
+    #[tokio::test]
+    async fn tool_budget_truncates_a_burst_and_asks_for_a_summary() {
+        use axum::routing::post;
+        use axum::{Json, Router};
+
+        async fn completion(Json(body): Json<serde_json::Value>) -> Json<serde_json::Value> {
+            let messages = body["messages"].as_array().cloned().unwrap_or_default();
+            let nudged = messages.iter().any(|message| {
+                message["role"] == "user"
+                    && message["content"]
+                        .as_str()
+                        .is_some_and(|content| content.starts_with("Tool budget exhausted"))
+            });
+            if nudged {
+                // Earlier tool turns stay valid; only new calls are refused.
+                assert!(body["tools"]
+                    .as_array()
+                    .is_some_and(|tools| !tools.is_empty()));
+                assert_eq!(body["tool_choice"], "none");
+                let skipped = messages
+                    .iter()
+                    .filter(|message| {
+                        message["role"] == "tool"
+                            && message["content"]
+                                .as_str()
+                                .is_some_and(|content| content.starts_with("[Skipped"))
+                    })
+                    .count();
+                return Json(serde_json::json!({
+                    "choices": [{"message": {"role": "assistant", "content": format!("Summary; {skipped} skipped.")}}]
+                }));
+            }
+            let tool_calls: Vec<serde_json::Value> = (0..5)
+                .map(|idx| {
+                    serde_json::json!({
+                        "id": format!("call_{idx}"),
+                        "type": "function",
+                        "function": {"name": "shell", "arguments": "{\"command\":\"ls\"}"}
+                    })
+                })
+                .collect();
+            Json(serde_json::json!({
+                "choices": [{"message": {"role": "assistant", "content": null, "tool_calls": tool_calls}}]
+            }))
+        }
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind mock provider");
+        let address = listener.local_addr().expect("mock provider address");
+        let server = tokio::spawn(async move {
+            axum::serve(
+                listener,
+                Router::new().route("/chat/completions", post(completion)),
+            )
+            .await
+            .expect("serve mock provider");
+        });
+
+        let executions = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let registry = Arc::new(ToolRegistry::new());
+        registry
+            .register(Arc::new(CountingShellTool {
+                executions: executions.clone(),
+            }))
+            .await;
+        let loop_runner = AgenticLoop::new(
+            AgenticConfig {
+                api_url: format!("http://{address}"),
+                max_tool_calls_per_turn: Some(3),
+                ..AgenticConfig::default()
+            },
+            registry,
+        );
+        let context = ToolContext {
+            working_directory: ".".to_string(),
+            allowed_roots: Vec::new(),
+            username: "tester".to_string(),
+            conversation_id: None,
+            autonomous: false,
+            auto_approve_local: false,
+            allowed_tools: None,
+            disallowed_tools: Vec::new(),
+            outbound_action_rate_limit: None,
+            generation_observer: None,
+            progress: None,
+            approval_waiter: None,
+            timeouts: crate::tools::ToolTimeoutPolicy::default(),
+        };
+        let result = loop_runner
+            .run("system", "list everything", &context)
+            .await
+            .expect("budgeted run");
+
+        server.abort();
+        assert_eq!(result.tool_calls_made.len(), 3);
+        assert_eq!(result.termination, AgenticTermination::Completed);
+        assert_eq!(result.response.as_deref(), Some("Summary; 2 skipped."));
+        assert_eq!(result.iterations, 2);
+        assert_eq!(executions.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn repeated_unknown_tool_calls_withhold_tools_and_force_an_answer() {
+        use axum::routing::post;
+        use axum::{Json, Router};
+
+        async fn completion(Json(body): Json<serde_json::Value>) -> Json<serde_json::Value> {
+            let messages = body["messages"].as_array().cloned().unwrap_or_default();
+            let nudged = messages.iter().any(|message| {
+                message["role"] == "user"
+                    && message["content"]
+                        .as_str()
+                        .is_some_and(|content| content.contains("tools that do not exist"))
+            });
+            if nudged {
+                // Earlier tool turns stay valid; only new calls are refused.
+                assert!(body["tools"]
+                    .as_array()
+                    .is_some_and(|tools| !tools.is_empty()));
+                assert_eq!(body["tool_choice"], "none");
+                return Json(serde_json::json!({
+                    "choices": [{"message": {"role": "assistant", "content": "I could not run that."}}]
+                }));
+            }
+            if let Some(last_tool) = messages.iter().rev().find(|m| m["role"] == "tool") {
+                assert!(last_tool["content"]
+                    .as_str()
+                    .is_some_and(|content| content.contains("Available tools: shell.")));
+            }
+            Json(serde_json::json!({
+                "choices": [{"message": {"role": "assistant", "content": null, "tool_calls": [{
+                    "id": format!("call_{}", messages.len()),
+                    "type": "function",
+                    "function": {"name": "run_terminal", "arguments": "{}"}
+                }]}}]
+            }))
+        }
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind mock provider");
+        let address = listener.local_addr().expect("mock provider address");
+        let server = tokio::spawn(async move {
+            axum::serve(
+                listener,
+                Router::new().route("/chat/completions", post(completion)),
+            )
+            .await
+            .expect("serve mock provider");
+        });
+
+        let registry = Arc::new(ToolRegistry::new());
+        registry
+            .register(Arc::new(CountingShellTool {
+                executions: Arc::new(std::sync::atomic::AtomicUsize::new(0)),
+            }))
+            .await;
+        let loop_runner = AgenticLoop::new(
+            AgenticConfig {
+                api_url: format!("http://{address}"),
+                ..AgenticConfig::default()
+            },
+            registry,
+        );
+        let context = ToolContext {
+            working_directory: ".".to_string(),
+            allowed_roots: Vec::new(),
+            username: "tester".to_string(),
+            conversation_id: None,
+            autonomous: false,
+            auto_approve_local: false,
+            allowed_tools: None,
+            disallowed_tools: Vec::new(),
+            outbound_action_rate_limit: None,
+            generation_observer: None,
+            progress: None,
+            approval_waiter: None,
+            timeouts: crate::tools::ToolTimeoutPolicy::default(),
+        };
+        let result = loop_runner
+            .run("system", "open a terminal", &context)
+            .await
+            .expect("run with unknown tools");
+
+        server.abort();
+        assert_eq!(result.tool_calls_made.len(), MAX_UNKNOWN_TOOL_CALLS_PER_RUN);
+        assert!(result
+            .tool_calls_made
+            .iter()
+            .all(|call| call.output.is_unknown_tool_error()));
+        assert_eq!(result.termination, AgenticTermination::Completed);
+        assert_eq!(result.response.as_deref(), Some("I could not run that."));
+        assert_eq!(result.iterations, MAX_UNKNOWN_TOOL_CALLS_PER_RUN + 1);
+    }
+
+    #[test]
+    fn only_missing_or_unreachable_models_trigger_fallback() {
+        assert!(model_unavailable(&anyhow::anyhow!(
+            "LLM API error 404 Not Found: {{\"error\":\"model 'x' not found\"}}"
+        )));
+        assert!(model_unavailable(&anyhow::anyhow!(
+            "LLM API error 400 Bad Request: The model `x` does not exist"
+        )));
+        assert!(!model_unavailable(&anyhow::anyhow!(
+            "LLM API error 400 Bad Request: context length exceeded"
+        )));
+    }
+
     #[tokio::test]
     async fn cancelled_run_reports_explicit_termination() {
         let cancellation_generation = Arc::new(AtomicU64::new(1));
@@ -1188,6 +2138,7 @@ mod tests {
         let loop_runner = AgenticLoop::new(config, Arc::new(ToolRegistry::new()));
         let context = ToolContext {
             working_directory: ".".to_string(),
+            allowed_roots: Vec::new(),
             username: "tester".to_string(),
             conversation_id: None,
             autonomous: true,
@@ -1196,6 +2147,9 @@ mod tests {
             disallowed_tools: Vec::new(),
             outbound_action_rate_limit: None,
             generation_observer: None,
+            progress: None,
+            approval_waiter: None,
+            timeouts: crate::tools::ToolTimeoutPolicy::default(),
         };
 
         let result = loop_runner
@@ -1207,6 +2161,39 @@ mod tests {
         assert!(!result.hit_limit);
     }
 
+    #[test]
+    fn conversation_generation_cancels_independently_of_global_stop() {
+        let global = Arc::new(AtomicU64::new(0));
+        let conversation = Arc::new(AtomicU64::new(0));
+        let loop_runner = AgenticLoop::new(
+            AgenticConfig {
+                cancel_generation: Some(Arc::clone(&global)),
+                start_generation: 0,
+                conversation_cancel_generation: Some(Arc::clone(&conversation)),
+                conversation_start_generation: 0,
+                ..AgenticConfig::default()
+            },
+            Arc::new(ToolRegistry::new()),
+        );
+        assert!(!loop_runner.is_cancelled());
+        conversation.fetch_add(1, Ordering::SeqCst);
+        assert!(loop_runner.is_cancelled());
+
+        let other_conversation = AgenticLoop::new(
+            AgenticConfig {
+                cancel_generation: Some(Arc::clone(&global)),
+                start_generation: 0,
+                conversation_cancel_generation: Some(Arc::new(AtomicU64::new(0))),
+                conversation_start_generation: 0,
+                ..AgenticConfig::default()
+            },
+            Arc::new(ToolRegistry::new()),
+        );
+        assert!(!other_conversation.is_cancelled());
+        global.fetch_add(1, Ordering::SeqCst);
+        assert!(other_conversation.is_cancelled());
+    }
+
     #[test]
     fn strips_thinking_blocks_from_visible_response() {
         let (visible, thoughts) =
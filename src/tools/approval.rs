@@ -1,12 +1,23 @@
 //! Approval gate system for dangerous tool operations.
 //!
 //! Provides configurable policies that determine which tool calls need
-//! user approval before execution, and which are auto-approved.
+//! user approval before execution, and which are auto-approved. Also defines
+//! the call signatures used by the persisted approval memory: operator-approved
+//! (tool, arguments) pairs that later identical calls reuse.
 
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Component, Path, PathBuf};
 use tokio::sync::RwLock;
 
+use crate::database::RememberedToolApproval;
+
+/// Top-level argument names treated as filesystem paths. These match a
+/// remembered approval by directory prefix; every other argument must match
+/// exactly.
+pub const PATH_ARGUMENT_KEYS: &[&str] = &["path", "file_path", "directory", "working_directory"];
+
 /// Policy for a specific tool or category
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ApprovalPolicy {
@@ -152,6 +163,109 @@ impl Default for ApprovalGate {
     }
 }
 
+/// Normalized form of one tool call for approval-memory matching.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ApprovalSignature {
+    pub tool_name: String,
+    /// Arguments other than string path arguments, compared exactly. JSON
+    /// object equality already ignores key order.
+    pub arguments: Value,
+    /// String path arguments, resolved against the working directory and
+    /// lexically normalized (no symlink resolution: the target may not exist).
+    pub paths: BTreeMap<String, String>,
+}
+
+impl ApprovalSignature {
+    pub fn from_call(tool_name: &str, params: &Value, working_directory: &str) -> Self {
+        let mut arguments = params.clone();
+        let mut paths = BTreeMap::new();
+        if let Value::Object(map) = &mut arguments {
+            for key in PATH_ARGUMENT_KEYS {
+                if let Some(Value::String(raw)) = map.get(*key) {
+                    paths.insert(key.to_string(), normalize_path(raw, working_directory));
+                    map.remove(*key);
+                }
+            }
+        }
+        Self {
+            tool_name: tool_name.to_string(),
+            arguments,
+            paths,
+        }
+    }
+
+    /// Build the record to persist when the operator approves this call.
+    /// With a `scope`, every path argument may fall anywhere under it;
+    /// without one, each path is scoped to exactly what was approved (and
+    /// anything beneath it).
+    pub fn remember(
+        &self,
+        provider: &str,
+        scope: Option<&str>,
+        working_directory: &str,
+    ) -> RememberedToolApproval {
+        let scope = scope
+            .map(str::trim)
+            .filter(|scope| !scope.is_empty())
+            .map(|scope| normalize_path(scope, working_directory));
+        let path_scopes = self
+            .paths
+            .iter()
+            .map(|(key, path)| (key.clone(), scope.clone().unwrap_or_else(|| path.clone())))
+            .collect();
+        RememberedToolApproval {
+            id: uuid::Uuid::new_v4().to_string(),
+            tool_name: self.tool_name.clone(),
+            provider: provider.to_string(),
+            arguments: self.arguments.clone(),
+            path_scopes,
+            created_at: chrono::Utc::now(),
+        }
+    }
+
+    /// Whether a remembered approval covers this call. The provider must
+    /// match so a different plugin reusing the tool name inherits nothing,
+    /// and the set of path arguments must be identical.
+    pub fn is_covered_by(&self, approval: &RememberedToolApproval, provider: &str) -> bool {
+        approval.tool_name == self.tool_name
+            && approval.provider == provider
+            && approval.arguments == self.arguments
+            && approval.path_scopes.len() == self.paths.len()
+            && self.paths.iter().all(|(key, path)| {
+                approval
+                    .path_scopes
+                    .get(key)
+                    .is_some_and(|prefix| path_is_within(path, prefix))
+            })
+    }
+}
+
+/// Resolve `raw` against `working_directory` and fold `.`/`..` components.
+pub fn normalize_path(raw: &str, working_directory: &str) -> String {
+    let raw = Path::new(raw.trim());
+    let joined = if raw.is_absolute() {
+        raw.to_path_buf()
+    } else {
+        Path::new(working_directory).join(raw)
+    };
+    let mut normalized = PathBuf::new();
+    for component in joined.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other.as_os_str()),
+        }
+    }
+    normalized.to_string_lossy().into_owned()
+}
+
+/// Component-wise prefix test, so `/srv/app` does not cover `/srv/application`.
+fn path_is_within(path: &str, prefix: &str) -> bool {
+    Path::new(path).starts_with(Path::new(prefix))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -215,6 +329,73 @@ mod tests {
         assert!(matches!(result, ApprovalDecision::NeedsApproval { .. }));
     }
 
+    #[test]
+    fn path_arguments_match_by_prefix_and_other_arguments_exactly() {
+        let approved = ApprovalSignature::from_call(
+            "read_file",
+            &serde_json::json!({"path": "notes/today.md", "limit": 20}),
+            "/home/op/project",
+        );
+        assert_eq!(approved.paths["path"], "/home/op/project/notes/today.md");
+        let scoped = approved.remember("builtin", Some("notes"), "/home/op/project");
+        assert_eq!(scoped.path_scopes["path"], "/home/op/project/notes");
+
+        let call =
+            |params: Value| ApprovalSignature::from_call("read_file", &params, "/home/op/project");
+        let covered = |params: Value| call(params).is_covered_by(&scoped, "builtin");
+        assert!(covered(
+            serde_json::json!({"limit": 20, "path": "./notes/sub/../other.md"})
+        ));
+        assert!(covered(
+            serde_json::json!({"path": "/home/op/project/notes", "limit": 20})
+        ));
+        assert!(!covered(
+            serde_json::json!({"path": "notes/../secrets.md", "limit": 20})
+        ));
+        assert!(!covered(
+            serde_json::json!({"path": "notes-old/a.md", "limit": 20})
+        ));
+        assert!(!covered(
+            serde_json::json!({"path": "notes/a.md", "limit": 21})
+        ));
+        assert!(!covered(serde_json::json!({"path": "notes/a.md"})));
+        assert!(
+            !call(serde_json::json!({"path": "notes/a.md", "limit": 20}))
+                .is_covered_by(&scoped, "plugin:other")
+        );
+
+        let exact = approved.remember("builtin", None, "/home/op/project");
+        assert!(
+            call(serde_json::json!({"path": "notes/today.md", "limit": 20}))
+                .is_covered_by(&exact, "builtin")
+        );
+        assert!(
+            !call(serde_json::json!({"path": "notes/tomorrow.md", "limit": 20}))
+                .is_covered_by(&exact, "builtin")
+        );
+    }
+
+    #[test]
+    fn non_path_tools_require_identical_arguments() {
+        let approved = ApprovalSignature::from_call(
+            "shell",
+            &serde_json::json!({"command": "cargo test", "working_directory": "/repo"}),
+            "/",
+        );
+        let remembered = approved.remember("builtin", None, "/");
+        let covered = |params: Value| {
+            ApprovalSignature::from_call("shell", &params, "/")
+                .is_covered_by(&remembered, "builtin")
+        };
+        assert!(covered(
+            serde_json::json!({"working_directory": "/repo/", "command": "cargo test"})
+        ));
+        assert!(!covered(
+            serde_json::json!({"command": "cargo test; rm -rf ~", "working_directory": "/repo"})
+        ));
+        assert!(!covered(serde_json::json!({"command": "cargo test"})));
+    }
+
     #[tokio::test]
     async fn test_clear_session_approvals() {
         let gate = ApprovalGate::new();
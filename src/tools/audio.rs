@@ -0,0 +1,836 @@
+//! Audio tools for voice notes and spoken replies.
+//!
+//! - `transcribe_audio`: turns a local recording into text through either a
+//!   local whisper.cpp binary (`whisper_binary_path`) or an OpenAI-compatible
+//!   `/audio/transcriptions` endpoint (`transcription_api_url`). The local
+//!   binary wins when both are configured.
+//! - `speak`: synthesizes speech with piper (`tts_piper_binary`) or an
+//!   OpenAI-compatible `/audio/speech` endpoint (`tts_api_url`), plays it
+//!   when an audio player is available, and publishes the wav to chat.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::Utc;
+use serde_json::{json, Value};
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+use crate::config::AgentConfig;
+
+use super::{Tool, ToolCategory, ToolContext, ToolOutput};
+
+/// Upload cap for the endpoint backend (the OpenAI API limit).
+const MAX_AUDIO_BYTES: usize = 25 * 1024 * 1024;
+const AUDIO_BACKEND_TIMEOUT: Duration = Duration::from_secs(300);
+const DEFAULT_TRANSCRIPTION_MODEL: &str = "whisper-1";
+const MULTIPART_BOUNDARY: &str = "ponderer-transcribe-audio-boundary";
+
+/// Formats whisper.cpp decodes without an external converter.
+const WHISPER_CPP_EXTENSIONS: &[&str] = &["wav", "mp3", "ogg", "flac"];
+/// Formats accepted by OpenAI-compatible transcription endpoints.
+const ENDPOINT_EXTENSIONS: &[&str] = &["wav", "mp3", "ogg", "flac", "m4a", "webm", "mp4"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum TranscriptionBackend {
+    WhisperCpp {
+        binary: String,
+        model: Option<String>,
+    },
+    Endpoint {
+        url: String,
+        model: String,
+        api_key: Option<String>,
+    },
+}
+
+impl TranscriptionBackend {
+    fn from_config(config: &AgentConfig) -> Option<Self> {
+        if let Some(binary) = non_empty(&config.whisper_binary_path) {
+            return Some(Self::WhisperCpp {
+                binary,
+                model: non_empty(&config.whisper_model_path),
+            });
+        }
+        non_empty(&config.transcription_api_url).map(|url| Self::Endpoint {
+            url: openai_audio_url(&url, "audio/transcriptions"),
+            model: non_empty(&config.transcription_model)
+                .unwrap_or_else(|| DEFAULT_TRANSCRIPTION_MODEL.to_string()),
+            api_key: non_empty(&config.transcription_api_key),
+        })
+    }
+
+    fn supported_extensions(&self) -> &'static [&'static str] {
+        match self {
+            Self::WhisperCpp { .. } => WHISPER_CPP_EXTENSIONS,
+            Self::Endpoint { .. } => ENDPOINT_EXTENSIONS,
+        }
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            Self::WhisperCpp { .. } => "whisper.cpp",
+            Self::Endpoint { .. } => "transcription endpoint",
+        }
+    }
+}
+
+pub struct TranscribeAudioTool;
+
+impl TranscribeAudioTool {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl Tool for TranscribeAudioTool {
+    fn name(&self) -> &str {
+        "transcribe_audio"
+    }
+
+    fn description(&self) -> &str {
+        "Transcribe a local audio file (for example a voice note) to text with the configured whisper.cpp binary or transcription endpoint."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "path": {
+                    "type": "string",
+                    "description": "Path to an audio file (absolute or relative)"
+                }
+            },
+            "required": ["path"]
+        })
+    }
+
+    async fn execute(&self, params: Value, _ctx: &ToolContext) -> Result<ToolOutput> {
+        let path = match params.get("path").and_then(Value::as_str) {
+            Some(path) if !path.trim().is_empty() => path.trim(),
+            _ => return Ok(ToolOutput::Error("Missing 'path' parameter".to_string())),
+        };
+        let Some(backend) = TranscriptionBackend::from_config(&AgentConfig::load()) else {
+            return Ok(ToolOutput::Error(
+                "No transcription backend configured; set whisper_binary_path or transcription_api_url"
+                    .to_string(),
+            ));
+        };
+        Ok(transcribe(&backend, path).await)
+    }
+
+    fn requires_approval(&self) -> bool {
+        false
+    }
+
+    fn category(&self) -> ToolCategory {
+        ToolCategory::General
+    }
+}
+
+impl Default for TranscribeAudioTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+async fn transcribe(backend: &TranscriptionBackend, path: &str) -> ToolOutput {
+    let abs_path = std::fs::canonicalize(path)
+        .map(|p| p.display().to_string())
+        .unwrap_or_else(|_| path.to_string());
+    let Some(extension) = audio_extension(&abs_path) else {
+        return ToolOutput::Error(format!("'{}' has no audio file extension", abs_path));
+    };
+    let supported = backend.supported_extensions();
+    if !supported.contains(&extension.as_str()) {
+        return ToolOutput::Error(format!(
+            "Unsupported audio format '.{}' for {} (supported: {})",
+            extension,
+            backend.label(),
+            supported.join(", ")
+        ));
+    }
+    if !Path::new(&abs_path).is_file() {
+        return ToolOutput::Error(format!("Audio file '{}' not found", abs_path));
+    }
+
+    let transcript = match backend {
+        TranscriptionBackend::WhisperCpp { binary, model } => {
+            run_whisper_cpp(binary, model.as_deref(), &abs_path).await
+        }
+        TranscriptionBackend::Endpoint {
+            url,
+            model,
+            api_key,
+        } => post_to_endpoint(url, model, api_key.as_deref(), &abs_path, &extension).await,
+    };
+    match transcript {
+        Ok(text) if text.is_empty() => {
+            ToolOutput::Text(format!("(no speech detected in '{}')", abs_path))
+        }
+        Ok(text) => ToolOutput::Text(text),
+        Err(error) => {
+            ToolOutput::Error(format!("Transcription of '{}' failed: {}", abs_path, error))
+        }
+    }
+}
+
+async fn run_whisper_cpp(
+    binary: &str,
+    model: Option<&str>,
+    path: &str,
+) -> std::result::Result<String, String> {
+    if let Some(model) = model {
+        if !Path::new(model).is_file() {
+            return Err(format!("whisper model '{}' not found", model));
+        }
+    }
+    let mut command = Command::new(binary);
+    if let Some(model) = model {
+        command.arg("-m").arg(model);
+    }
+    // -nt drops timestamps and -np suppresses progress chatter, leaving only
+    // the transcript on stdout.
+    command.arg("-f").arg(path).arg("-nt").arg("-np");
+    command.kill_on_drop(true);
+
+    let output = match tokio::time::timeout(AUDIO_BACKEND_TIMEOUT, command.output()).await {
+        Ok(Ok(output)) => output,
+        Ok(Err(error)) if error.kind() == std::io::ErrorKind::NotFound => {
+            return Err(format!("whisper.cpp binary '{}' not found", binary))
+        }
+        Ok(Err(error)) => return Err(format!("failed to run '{}': {}", binary, error)),
+        Err(_) => {
+            return Err(format!(
+                "whisper.cpp timed out after {}s",
+                AUDIO_BACKEND_TIMEOUT.as_secs()
+            ))
+        }
+    };
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!(
+            "'{}' exited with {}: {}",
+            binary,
+            output.status,
+            stderr.trim()
+        ));
+    }
+    Ok(collapse_transcript_lines(&String::from_utf8_lossy(
+        &output.stdout,
+    )))
+}
+
+async fn post_to_endpoint(
+    url: &str,
+    model: &str,
+    api_key: Option<&str>,
+    path: &str,
+    extension: &str,
+) -> std::result::Result<String, String> {
+    let size = std::fs::metadata(path)
+        .map_err(|e| format!("failed to read file: {}", e))?
+        .len();
+    if size == 0 {
+        return Err("file is empty".to_string());
+    }
+    if size > MAX_AUDIO_BYTES as u64 {
+        return Err(format!(
+            "file too large ({} bytes, max {})",
+            size, MAX_AUDIO_BYTES
+        ));
+    }
+    let bytes = std::fs::read(path).map_err(|e| format!("failed to read file: {}", e))?;
+    let filename = Path::new(path)
+        .file_name()
+        .and_then(OsStr::to_str)
+        .unwrap_or("audio");
+    let body = multipart_body(model, filename, audio_mime_type(extension), &bytes);
+
+    let client = crate::http_client::build_http_client_with_timeout(Some(AUDIO_BACKEND_TIMEOUT));
+    let mut request = client
+        .post(url)
+        .header(
+            reqwest::header::CONTENT_TYPE,
+            format!("multipart/form-data; boundary={}", MULTIPART_BOUNDARY),
+        )
+        .body(body);
+    if let Some(api_key) = api_key {
+        request = request.bearer_auth(api_key);
+    }
+    let response = request
+        .send()
+        .await
+        .map_err(|e| format!("request to {} failed: {}", url, e))?;
+    let status = response.status();
+    let text = response
+        .text()
+        .await
+        .map_err(|e| format!("failed to read response: {}", e))?;
+    if !status.is_success() {
+        return Err(format!(
+            "endpoint returned HTTP {}: {}",
+            status,
+            text.trim()
+        ));
+    }
+    let parsed: Value =
+        serde_json::from_str(&text).map_err(|e| format!("invalid JSON response: {}", e))?;
+    parsed
+        .get("text")
+        .and_then(Value::as_str)
+        .map(|text| text.trim().to_string())
+        .ok_or_else(|| "response has no 'text' field".to_string())
+}
+
+/// Accepts a full `/<route>` URL or an OpenAI-style base URL.
+fn openai_audio_url(raw: &str, route: &str) -> String {
+    let trimmed = raw.trim().trim_end_matches('/');
+    if trimmed.ends_with(&format!("/{}", route)) {
+        trimmed.to_string()
+    } else if trimmed.ends_with("/v1") {
+        format!("{}/{}", trimmed, route)
+    } else {
+        format!("{}/v1/{}", trimmed, route)
+    }
+}
+
+fn non_empty(value: &Option<String>) -> Option<String> {
+    value
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(str::to_string)
+}
+
+fn audio_extension(path: &str) -> Option<String> {
+    Path::new(path)
+        .extension()
+        .and_then(OsStr::to_str)
+        .map(|s| s.to_ascii_lowercase())
+}
+
+fn audio_mime_type(extension: &str) -> &'static str {
+    match extension {
+        "wav" => "audio/wav",
+        "mp3" => "audio/mpeg",
+        "ogg" => "audio/ogg",
+        "flac" => "audio/flac",
+        "m4a" | "mp4" => "audio/mp4",
+        "webm" => "audio/webm",
+        _ => "application/octet-stream",
+    }
+}
+
+/// reqwest's multipart feature is not enabled, so the two-field form is
+/// assembled by hand.
+fn multipart_body(model: &str, filename: &str, mime_type: &str, bytes: &[u8]) -> Vec<u8> {
+    let mut body = Vec::with_capacity(bytes.len() + 512);
+    body.extend_from_slice(
+        format!(
+            "--{b}\r\nContent-Disposition: form-data; name=\"model\"\r\n\r\n{model}\r\n\
+             --{b}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"{name}\"\r\n\
+             Content-Type: {mime_type}\r\n\r\n",
+            b = MULTIPART_BOUNDARY,
+            name = filename.replace('"', "_"),
+        )
+        .as_bytes(),
+    );
+    body.extend_from_slice(bytes);
+    body.extend_from_slice(format!("\r\n--{}--\r\n", MULTIPART_BOUNDARY).as_bytes());
+    body
+}
+
+fn collapse_transcript_lines(stdout: &str) -> String {
+    stdout
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+const DEFAULT_TTS_MODEL: &str = "tts-1";
+const DEFAULT_TTS_VOICE: &str = "alloy";
+const MAX_SPEAK_CHARS: usize = 4_000;
+/// How long a player must survive to count as playing; players that cannot
+/// open an audio device exit well within this.
+const PLAYBACK_PROBE: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum SpeechBackend {
+    Piper {
+        binary: String,
+        model: Option<String>,
+    },
+    Endpoint {
+        url: String,
+        voice: String,
+        api_key: Option<String>,
+    },
+}
+
+impl SpeechBackend {
+    fn from_config(config: &AgentConfig) -> Option<Self> {
+        if let Some(binary) = non_empty(&config.tts_piper_binary) {
+            return Some(Self::Piper {
+                binary,
+                model: non_empty(&config.tts_piper_model),
+            });
+        }
+        non_empty(&config.tts_api_url).map(|url| Self::Endpoint {
+            url: openai_audio_url(&url, "audio/speech"),
+            voice: non_empty(&config.tts_voice).unwrap_or_else(|| DEFAULT_TTS_VOICE.to_string()),
+            api_key: non_empty(&config.tts_api_key),
+        })
+    }
+}
+
+pub struct SpeakTool;
+
+impl SpeakTool {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl Tool for SpeakTool {
+    fn name(&self) -> &str {
+        "speak"
+    }
+
+    fn description(&self) -> &str {
+        "Speak text aloud through the configured text-to-speech backend and publish the audio file to chat."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "text": {
+                    "type": "string",
+                    "description": "What to say"
+                },
+                "voice": {
+                    "type": "string",
+                    "description": "Optional voice: an endpoint voice name, or a piper .onnx model (path or name next to the default model)"
+                }
+            },
+            "required": ["text"]
+        })
+    }
+
+    async fn execute(&self, params: Value, ctx: &ToolContext) -> Result<ToolOutput> {
+        let text = match params.get("text").and_then(Value::as_str) {
+            Some(text) if !text.trim().is_empty() => text.trim(),
+            _ => return Ok(ToolOutput::Error("Missing 'text' parameter".to_string())),
+        };
+        if text.chars().count() > MAX_SPEAK_CHARS {
+            return Ok(ToolOutput::Error(format!(
+                "Text too long to speak ({} chars, max {})",
+                text.chars().count(),
+                MAX_SPEAK_CHARS
+            )));
+        }
+        let voice = params
+            .get("voice")
+            .and_then(Value::as_str)
+            .map(str::trim)
+            .filter(|voice| !voice.is_empty());
+
+        let config = AgentConfig::load();
+        if !config.enable_tts {
+            return Ok(ToolOutput::Error(
+                "Text-to-speech is disabled; set enable_tts first".to_string(),
+            ));
+        }
+        let Some(backend) = SpeechBackend::from_config(&config) else {
+            return Ok(ToolOutput::Error(
+                "No text-to-speech backend configured; set tts_piper_binary or tts_api_url"
+                    .to_string(),
+            ));
+        };
+
+        let output_path = PathBuf::from(&ctx.working_directory).join(format!(
+            "speech_{}.wav",
+            Utc::now().format("%Y%m%d_%H%M%S%3f")
+        ));
+        let synthesized = match &backend {
+            SpeechBackend::Piper { binary, model } => {
+                let model = match voice {
+                    Some(voice) => Some(resolve_piper_voice(voice, model.as_deref())),
+                    None => model.clone(),
+                };
+                run_piper(binary, model.as_deref(), text, &output_path).await
+            }
+            SpeechBackend::Endpoint {
+                url,
+                voice: default_voice,
+                api_key,
+            } => {
+                let voice = voice.unwrap_or(default_voice);
+                request_speech(url, voice, api_key.as_deref(), text, &output_path).await
+            }
+        };
+        if let Err(error) = synthesized {
+            return Ok(ToolOutput::Error(format!(
+                "Speech synthesis failed: {}",
+                error
+            )));
+        }
+
+        let abs_path = std::fs::canonicalize(&output_path)
+            .map(|p| p.display().to_string())
+            .unwrap_or_else(|_| output_path.display().to_string());
+        let playback = play_audio_file(&abs_path).await;
+        let played = playback.is_ok();
+        if let Err(reason) = &playback {
+            tracing::info!("speak: local playback unavailable: {}", reason);
+        }
+        Ok(ToolOutput::Json(json!({
+            "status": "ok",
+            "path": abs_path,
+            "played": played,
+            "playback_error": playback.err(),
+            "media": [
+                {
+                    "path": abs_path,
+                    "media_kind": "audio",
+                    "mime_type": "audio/wav",
+                    "source": "speak",
+                    // Let the chat client play it when the host could not.
+                    "auto_play": !played
+                }
+            ]
+        })))
+    }
+
+    fn requires_approval(&self) -> bool {
+        true
+    }
+
+    fn category(&self) -> ToolCategory {
+        ToolCategory::General
+    }
+}
+
+impl Default for SpeakTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A bare voice name resolves to `<name>.onnx` beside the default model.
+fn resolve_piper_voice(voice: &str, default_model: Option<&str>) -> String {
+    let path = Path::new(voice);
+    if path.components().count() > 1 {
+        return voice.to_string();
+    }
+    let file = if voice.ends_with(".onnx") {
+        voice.to_string()
+    } else {
+        format!("{}.onnx", voice)
+    };
+    match default_model.and_then(|model| Path::new(model).parent()) {
+        Some(dir) if !dir.as_os_str().is_empty() => dir.join(file).display().to_string(),
+        _ => file,
+    }
+}
+
+async fn run_piper(
+    binary: &str,
+    model: Option<&str>,
+    text: &str,
+    output_path: &Path,
+) -> std::result::Result<(), String> {
+    let Some(model) = model else {
+        return Err("piper needs a voice model; set tts_piper_model".to_string());
+    };
+    if !Path::new(model).is_file() {
+        return Err(format!("piper model '{}' not found", model));
+    }
+    let mut child = match Command::new(binary)
+        .arg("--model")
+        .arg(model)
+        .arg("--output_file")
+        .arg(output_path)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+    {
+        Ok(child) => child,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => {
+            return Err(format!("piper binary '{}' not found", binary))
+        }
+        Err(error) => return Err(format!("failed to run '{}': {}", binary, error)),
+    };
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(text.as_bytes())
+            .await
+            .map_err(|e| format!("failed to send text to piper: {}", e))?;
+    }
+    let output = tokio::time::timeout(AUDIO_BACKEND_TIMEOUT, child.wait_with_output())
+        .await
+        .map_err(|_| format!("piper timed out after {}s", AUDIO_BACKEND_TIMEOUT.as_secs()))?
+        .map_err(|e| format!("failed to wait for piper: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "'{}' exited with {}: {}",
+            binary,
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(())
+}
+
+async fn request_speech(
+    url: &str,
+    voice: &str,
+    api_key: Option<&str>,
+    text: &str,
+    output_path: &Path,
+) -> std::result::Result<(), String> {
+    let client = crate::http_client::build_http_client_with_timeout(Some(AUDIO_BACKEND_TIMEOUT));
+    let mut request = client.post(url).json(&json!({
+        "model": DEFAULT_TTS_MODEL,
+        "input": text,
+        "voice": voice,
+        "response_format": "wav"
+    }));
+    if let Some(api_key) = api_key {
+        request = request.bearer_auth(api_key);
+    }
+    let response = request
+        .send()
+        .await
+        .map_err(|e| format!("request to {} failed: {}", url, e))?;
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        return Err(format!(
+            "endpoint returned HTTP {}: {}",
+            status,
+            body.trim()
+        ));
+    }
+    let bytes = response
+        .bytes()
+        .await
+        .map_err(|e| format!("failed to read audio: {}", e))?;
+    if bytes.is_empty() {
+        return Err("endpoint returned no audio".to_string());
+    }
+    std::fs::write(output_path, &bytes)
+        .map_err(|e| format!("failed to write '{}': {}", output_path.display(), e))
+}
+
+fn audio_players(path: &str) -> Vec<(&'static str, Vec<String>)> {
+    let path = path.to_string();
+    if cfg!(target_os = "macos") {
+        vec![("afplay", vec![path])]
+    } else if cfg!(target_os = "windows") {
+        vec![(
+            "powershell",
+            vec![
+                "-NoProfile".to_string(),
+                "-Command".to_string(),
+                format!(
+                    "(New-Object Media.SoundPlayer '{}').PlaySync()",
+                    path.replace('\'', "''")
+                ),
+            ],
+        )]
+    } else {
+        vec![
+            ("paplay", vec![path.clone()]),
+            ("pw-play", vec![path.clone()]),
+            ("aplay", vec!["-q".to_string(), path.clone()]),
+            (
+                "ffplay",
+                vec![
+                    "-nodisp".to_string(),
+                    "-autoexit".to_string(),
+                    "-loglevel".to_string(),
+                    "quiet".to_string(),
+                    path,
+                ],
+            ),
+        ]
+    }
+}
+
+/// Starts the first player that survives `PLAYBACK_PROBE` (or finishes
+/// cleanly) and leaves it running in the background. Every failure is
+/// collected so a headless host reports why nothing played.
+async fn play_audio_file(path: &str) -> std::result::Result<(), String> {
+    let mut failures = Vec::new();
+    for (cmd, args) in audio_players(path) {
+        let mut child = match Command::new(cmd)
+            .args(&args)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+        {
+            Ok(child) => child,
+            Err(error) => {
+                failures.push(format!("{}: {}", cmd, error));
+                continue;
+            }
+        };
+        match tokio::time::timeout(PLAYBACK_PROBE, child.wait()).await {
+            Ok(Ok(status)) if status.success() => return Ok(()),
+            Ok(Ok(status)) => failures.push(format!("{}: exited with {}", cmd, status)),
+            Ok(Err(error)) => failures.push(format!("{}: {}", cmd, error)),
+            Err(_) => {
+                tokio::spawn(async move {
+                    let _ = child.wait().await;
+                });
+                return Ok(());
+            }
+        }
+    }
+    Err(failures.join("; "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn whisper(binary: &str) -> TranscriptionBackend {
+        TranscriptionBackend::WhisperCpp {
+            binary: binary.to_string(),
+            model: None,
+        }
+    }
+
+    #[test]
+    fn backend_prefers_local_binary_and_normalizes_endpoint_urls() {
+        let config = AgentConfig {
+            whisper_binary_path: Some(" /opt/whisper-cli ".to_string()),
+            transcription_api_url: Some("http://127.0.0.1:8080/v1/".to_string()),
+            ..AgentConfig::default()
+        };
+        assert_eq!(
+            TranscriptionBackend::from_config(&config),
+            Some(whisper("/opt/whisper-cli"))
+        );
+
+        let config = AgentConfig {
+            whisper_binary_path: None,
+            ..config
+        };
+        let config = AgentConfig {
+            llm_api_key: Some("sk-llm".to_string()),
+            ..config
+        };
+        match TranscriptionBackend::from_config(&config) {
+            Some(TranscriptionBackend::Endpoint {
+                url,
+                model,
+                api_key,
+            }) => {
+                assert_eq!(url, "http://127.0.0.1:8080/v1/audio/transcriptions");
+                assert_eq!(model, DEFAULT_TRANSCRIPTION_MODEL);
+                assert_eq!(api_key, None, "the LLM key must not be sent");
+            }
+            other => panic!("expected endpoint backend, got {other:?}"),
+        }
+        assert_eq!(
+            TranscriptionBackend::from_config(&AgentConfig::default()),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn unsupported_formats_and_missing_binaries_are_reported() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let m4a = dir.path().join("memo.m4a");
+        std::fs::write(&m4a, b"not really audio").expect("write m4a");
+        match transcribe(&whisper("whisper-cli"), m4a.to_str().unwrap()).await {
+            ToolOutput::Error(message) => {
+                assert!(message.contains("Unsupported audio format '.m4a' for whisper.cpp"))
+            }
+            other => panic!("expected error, got {other:?}"),
+        }
+
+        let wav = dir.path().join("memo.wav");
+        std::fs::write(&wav, b"RIFF").expect("write wav");
+        let missing = whisper("/nonexistent/ponderer-whisper-cli");
+        match transcribe(&missing, wav.to_str().unwrap()).await {
+            ToolOutput::Error(message) => assert!(
+                message
+                    .contains("whisper.cpp binary '/nonexistent/ponderer-whisper-cli' not found"),
+                "{message}"
+            ),
+            other => panic!("expected error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn piper_voice_names_resolve_beside_the_default_model() {
+        assert_eq!(
+            resolve_piper_voice("en_GB-alba-medium", Some("/voices/en_US-amy-low.onnx")),
+            "/voices/en_GB-alba-medium.onnx"
+        );
+        assert_eq!(
+            resolve_piper_voice("/other/voice.onnx", Some("/voices/en_US-amy-low.onnx")),
+            "/other/voice.onnx"
+        );
+        assert_eq!(resolve_piper_voice("amy", None), "amy.onnx");
+    }
+
+    #[tokio::test]
+    async fn missing_piper_binary_and_model_are_reported() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let out = dir.path().join("speech.wav");
+        let error = run_piper("piper", None, "hi", &out).await.unwrap_err();
+        assert!(error.contains("set tts_piper_model"), "{error}");
+
+        let model = dir.path().join("voice.onnx");
+        std::fs::write(&model, b"onnx").expect("write model");
+        let error = run_piper("/nonexistent/ponderer-piper", model.to_str(), "hi", &out)
+            .await
+            .unwrap_err();
+        assert!(
+            error.contains("piper binary '/nonexistent/ponderer-piper' not found"),
+            "{error}"
+        );
+    }
+
+    #[test]
+    fn multipart_body_carries_model_and_file_parts() {
+        let body = multipart_body("whisper-1", "memo.wav", "audio/wav", b"RIFFDATA");
+        let body = String::from_utf8(body).unwrap();
+        assert!(body.contains("name=\"model\"\r\n\r\nwhisper-1\r\n"));
+        assert!(body.contains("filename=\"memo.wav\"\r\nContent-Type: audio/wav\r\n\r\nRIFFDATA"));
+        assert!(body.ends_with(&format!("--{}--\r\n", MULTIPART_BOUNDARY)));
+    }
+
+    #[tokio::test]
+    async fn oversized_uploads_are_rejected_before_reading() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let path = dir.path().join("long.wav");
+        let file = std::fs::File::create(&path).expect("create wav");
+        file.set_len(MAX_AUDIO_BYTES as u64 + 1).expect("grow wav");
+        let error = post_to_endpoint(
+            "http://127.0.0.1:9/audio/transcriptions",
+            DEFAULT_TRANSCRIPTION_MODEL,
+            None,
+            path.to_str().unwrap(),
+            "wav",
+        )
+        .await
+        .unwrap_err();
+        assert!(error.starts_with("file too large"), "{error}");
+    }
+}
@@ -0,0 +1,296 @@
+//! System clipboard tool backed by platform clipboard commands.
+//!
+//! Shells out to `pbpaste`/`pbcopy` on macOS, `wl-paste`/`wl-copy` under
+//! Wayland, `xclip`/`xsel` under X11, and PowerShell on Windows instead of
+//! linking a clipboard crate. Headless sessions (no display server) and hosts
+//! without any clipboard utility get a readable error rather than a panic.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use std::process::Stdio;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+use super::{Tool, ToolCategory, ToolContext, ToolOutput};
+
+const MAX_CLIPBOARD_CHARS: usize = 100_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct ClipboardBackend {
+    read: (&'static str, &'static [&'static str]),
+    write: (&'static str, &'static [&'static str]),
+}
+
+const MACOS_BACKEND: ClipboardBackend = ClipboardBackend {
+    read: ("pbpaste", &[]),
+    write: ("pbcopy", &[]),
+};
+const WAYLAND_BACKEND: ClipboardBackend = ClipboardBackend {
+    read: ("wl-paste", &["--no-newline"]),
+    write: ("wl-copy", &[]),
+};
+const XCLIP_BACKEND: ClipboardBackend = ClipboardBackend {
+    read: ("xclip", &["-selection", "clipboard", "-o"]),
+    write: ("xclip", &["-selection", "clipboard", "-i"]),
+};
+const XSEL_BACKEND: ClipboardBackend = ClipboardBackend {
+    read: ("xsel", &["--clipboard", "--output"]),
+    write: ("xsel", &["--clipboard", "--input"]),
+};
+const WINDOWS_BACKEND: ClipboardBackend = ClipboardBackend {
+    read: (
+        "powershell",
+        &["-NoProfile", "-Command", "Get-Clipboard -Raw"],
+    ),
+    write: (
+        "powershell",
+        &["-NoProfile", "-Command", "$input | Set-Clipboard"],
+    ),
+};
+
+/// Candidate backends for the current session, in preference order.
+fn clipboard_backends(
+    os: &str,
+    has_wayland: bool,
+    has_x11: bool,
+) -> std::result::Result<Vec<ClipboardBackend>, String> {
+    match os {
+        "macos" => Ok(vec![MACOS_BACKEND]),
+        "windows" => Ok(vec![WINDOWS_BACKEND]),
+        _ => {
+            let mut backends = Vec::new();
+            if has_wayland {
+                backends.push(WAYLAND_BACKEND);
+            }
+            if has_x11 {
+                backends.push(XCLIP_BACKEND);
+                backends.push(XSEL_BACKEND);
+            }
+            if backends.is_empty() {
+                return Err(
+                    "No clipboard is available: this is a headless session (neither WAYLAND_DISPLAY nor DISPLAY is set)"
+                        .to_string(),
+                );
+            }
+            Ok(backends)
+        }
+    }
+}
+
+fn session_backends() -> std::result::Result<Vec<ClipboardBackend>, String> {
+    let set = |key: &str| std::env::var_os(key).is_some_and(|value| !value.is_empty());
+    clipboard_backends(std::env::consts::OS, set("WAYLAND_DISPLAY"), set("DISPLAY"))
+}
+
+async fn read_clipboard(backends: &[ClipboardBackend]) -> std::result::Result<String, String> {
+    let mut failures = Vec::new();
+    for backend in backends {
+        let (cmd, args) = backend.read;
+        match Command::new(cmd)
+            .args(args)
+            .stdin(Stdio::null())
+            .output()
+            .await
+        {
+            Ok(output) if output.status.success() => {
+                return Ok(String::from_utf8_lossy(&output.stdout).into_owned());
+            }
+            Ok(output) => failures.push(format!(
+                "{} exited with {}: {}",
+                cmd,
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            )),
+            Err(error) => failures.push(format!("{}: {}", cmd, error)),
+        }
+    }
+    Err(format!(
+        "Failed to read clipboard ({})",
+        failures.join("; ")
+    ))
+}
+
+async fn write_clipboard(
+    backends: &[ClipboardBackend],
+    content: &str,
+) -> std::result::Result<(), String> {
+    let mut failures = Vec::new();
+    for backend in backends {
+        let (cmd, args) = backend.write;
+        match write_with(cmd, args, content).await {
+            Ok(()) => return Ok(()),
+            Err(error) => failures.push(format!("{}: {}", cmd, error)),
+        }
+    }
+    Err(format!(
+        "Failed to write clipboard ({})",
+        failures.join("; ")
+    ))
+}
+
+async fn write_with(cmd: &str, args: &[&str], content: &str) -> Result<()> {
+    let mut child = Command::new(cmd)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(content.as_bytes()).await?;
+    }
+    let output = child.wait_with_output().await?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "exited with {}: {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
+pub struct ClipboardTool;
+
+impl ClipboardTool {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl Tool for ClipboardTool {
+    fn name(&self) -> &str {
+        "clipboard"
+    }
+
+    fn description(&self) -> &str {
+        "Read text from or write text to the system clipboard. Use action 'read' to get the current clipboard text, or action 'write' with 'content' to replace it."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "action": {
+                    "type": "string",
+                    "enum": ["read", "write"],
+                    "description": "Whether to read or write the clipboard"
+                },
+                "content": {
+                    "type": "string",
+                    "description": "Text to place on the clipboard (required for 'write')"
+                }
+            },
+            "required": ["action"]
+        })
+    }
+
+    async fn execute(&self, params: Value, _ctx: &ToolContext) -> Result<ToolOutput> {
+        let action = params
+            .get("action")
+            .and_then(Value::as_str)
+            .map(str::trim)
+            .unwrap_or("");
+        let content = params.get("content").and_then(Value::as_str);
+        if !matches!(action, "read" | "write") {
+            return Ok(ToolOutput::Error(
+                "'action' must be either 'read' or 'write'".to_string(),
+            ));
+        }
+        if action == "write" && content.is_none() {
+            return Ok(ToolOutput::Error(
+                "Missing required 'content' parameter for action 'write'".to_string(),
+            ));
+        }
+
+        let backends = match session_backends() {
+            Ok(backends) => backends,
+            Err(reason) => return Ok(ToolOutput::Error(reason)),
+        };
+
+        match content.filter(|_| action == "write") {
+            Some(content) => match write_clipboard(&backends, content).await {
+                Ok(()) => Ok(ToolOutput::Text(format!(
+                    "Copied {} characters to the clipboard",
+                    content.chars().count()
+                ))),
+                Err(reason) => Ok(ToolOutput::Error(reason)),
+            },
+            None => match read_clipboard(&backends).await {
+                Ok(text) if text.chars().count() > MAX_CLIPBOARD_CHARS => {
+                    let truncated: String = text.chars().take(MAX_CLIPBOARD_CHARS).collect();
+                    Ok(ToolOutput::Text(format!(
+                        "{}\n\n[clipboard truncated to {} characters]",
+                        truncated, MAX_CLIPBOARD_CHARS
+                    )))
+                }
+                Ok(text) => Ok(ToolOutput::Text(text)),
+                Err(reason) => Ok(ToolOutput::Error(reason)),
+            },
+        }
+    }
+
+    fn requires_approval(&self) -> bool {
+        true
+    }
+
+    /// Reads only; writes replace what the operator copied.
+    fn auto_approves(&self, params: &Value) -> bool {
+        params.get("action").and_then(Value::as_str).map(str::trim) == Some("read")
+    }
+
+    fn category(&self) -> ToolCategory {
+        ToolCategory::General
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_reads_skip_the_approval_gate() {
+        let tool = ClipboardTool::new();
+        assert!(tool.requires_approval());
+        assert!(tool.auto_approves(&json!({ "action": "read" })));
+        assert!(!tool.auto_approves(&json!({ "action": "write", "content": "hi" })));
+        assert!(!tool.auto_approves(&json!({})));
+    }
+
+    #[test]
+    fn headless_linux_session_reports_missing_display() {
+        let error = clipboard_backends("linux", false, false).expect_err("headless");
+        assert!(error.contains("headless"));
+    }
+
+    #[test]
+    fn backends_follow_platform_and_display_server() {
+        assert_eq!(
+            clipboard_backends("macos", false, false).unwrap(),
+            vec![MACOS_BACKEND]
+        );
+        assert_eq!(
+            clipboard_backends("linux", true, true).unwrap(),
+            vec![WAYLAND_BACKEND, XCLIP_BACKEND, XSEL_BACKEND]
+        );
+        assert_eq!(
+            clipboard_backends("linux", false, true).unwrap(),
+            vec![XCLIP_BACKEND, XSEL_BACKEND]
+        );
+    }
+
+    #[tokio::test]
+    async fn missing_clipboard_utilities_are_reported_as_errors() {
+        let backend = ClipboardBackend {
+            read: ("ponderer-no-such-clipboard-binary", &[]),
+            write: ("ponderer-no-such-clipboard-binary", &[]),
+        };
+        let read = read_clipboard(&[backend]).await.expect_err("no binary");
+        assert!(read.starts_with("Failed to read clipboard"));
+        let write = write_clipboard(&[backend], "text")
+            .await
+            .expect_err("no binary");
+        assert!(write.starts_with("Failed to write clipboard"));
+    }
+}
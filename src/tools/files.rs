@@ -1,12 +1,18 @@
-//! File system tools (read, write, list, patch).
+//! File system tools (read, write, list, patch, search, move, delete).
 //!
 //! Provides the agent with safe file system access.
-//! Read and list are auto-approved; write and patch require approval.
+//! Read, list, and search are auto-approved; write, patch, move, and delete
+//! require approval. Move and delete are confined to the working directory,
+//! and delete moves into `.ponderer/trash/` unless configured to hard-delete.
 
 use anyhow::Result;
 use async_trait::async_trait;
 
+use std::path::{Component, Path, PathBuf};
+
+use super::unified_diff::apply_unified_diff;
 use super::{Tool, ToolCategory, ToolContext, ToolOutput};
+use crate::config::AgentConfig;
 
 /// Maximum file size we'll read (10MB)
 const MAX_READ_BYTES: u64 = 10 * 1024 * 1024;
@@ -14,6 +20,27 @@ const MAX_READ_BYTES: u64 = 10 * 1024 * 1024;
 /// Maximum number of directory entries to list
 const MAX_LIST_ENTRIES: usize = 500;
 
+/// Default and hard cap for recursive `list_directory` depth
+const DEFAULT_LIST_DEPTH: usize = 3;
+const MAX_LIST_DEPTH: usize = 10;
+
+/// Directories a recursive listing shows but does not descend into unless
+/// named in `include_dirs`
+const HEAVY_LIST_DIRS: &[&str] = &["node_modules", "target", ".git"];
+
+/// Files larger than this are skipped by `search_files` (1MB)
+const MAX_SEARCH_FILE_BYTES: u64 = 1024 * 1024;
+
+/// Default and hard cap for `search_files` matches
+const DEFAULT_SEARCH_MATCHES: usize = 100;
+const MAX_SEARCH_MATCHES: usize = 1000;
+
+/// Matched lines longer than this are truncated in search output
+const MAX_SEARCH_LINE_CHARS: usize = 400;
+
+/// Trash for `delete_file`, relative to the working directory
+const TRASH_DIR: &str = ".ponderer/trash";
+
 // ============================================================================
 // ReadFileTool
 // ============================================================================
@@ -26,6 +53,168 @@ impl ReadFileTool {
     }
 }
 
+/// Which part of a file `read_file` returns.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ReadRange {
+    /// Whole file, capped at `read_file_max_bytes`.
+    All,
+    /// 1-based inclusive line range; `None` reads to the last line.
+    Lines { start: usize, end: Option<usize> },
+    /// Up to `len` bytes starting at the 0-based byte `offset`.
+    Bytes { offset: u64, len: u64 },
+}
+
+impl ReadRange {
+    /// `max_bytes` selects byte mode, where `offset` is a byte offset;
+    /// otherwise `offset`/`limit` keep their original line meaning.
+    fn from_params(params: &serde_json::Value) -> std::result::Result<Self, String> {
+        if let Some(max_bytes) = params["max_bytes"].as_u64() {
+            if max_bytes == 0 {
+                return Err("'max_bytes' must be at least 1".to_string());
+            }
+            return Ok(Self::Bytes {
+                offset: params["offset"].as_u64().unwrap_or(0),
+                len: max_bytes.min(MAX_READ_BYTES),
+            });
+        }
+
+        let start_line = params["start_line"].as_u64();
+        let end_line = params["end_line"].as_u64();
+        if start_line.is_some() || end_line.is_some() {
+            let start = start_line.unwrap_or(1).max(1) as usize;
+            let end = end_line.map(|end| end as usize);
+            if let Some(end) = end.filter(|end| *end < start) {
+                return Err(format!(
+                    "'end_line' ({}) is before 'start_line' ({})",
+                    end, start
+                ));
+            }
+            return Ok(Self::Lines { start, end });
+        }
+
+        let offset = params["offset"].as_u64();
+        let limit = params["limit"].as_u64();
+        if offset.is_none() && limit.is_none() {
+            return Ok(Self::All);
+        }
+        let start = offset.unwrap_or(1).max(1) as usize;
+        Ok(Self::Lines {
+            start,
+            end: limit.map(|limit| start - 1 + limit as usize),
+        })
+    }
+}
+
+fn number_lines(lines: &[&str], first_line: usize) -> String {
+    lines
+        .iter()
+        .enumerate()
+        .map(|(i, line)| format!("{:>4} | {}", first_line + i, line))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Renders a whole-file read, stopping at the last whole line that fits in
+/// `max_bytes` and appending a marker that says how to read the rest.
+fn render_capped(content: &str, max_bytes: usize) -> String {
+    let lines: Vec<&str> = content.lines().collect();
+    let mut used = 0usize;
+    let mut shown = 0usize;
+    for line in &lines {
+        let cost = line.len() + 1;
+        if used + cost > max_bytes {
+            break;
+        }
+        used += cost;
+        shown += 1;
+    }
+
+    let mut result = number_lines(&lines[..shown], 1);
+    if shown < lines.len() {
+        result.push_str(&format!(
+            "\n\n[Truncated after line {} of {} ({} of {} bytes). \
+             Use start_line/end_line or max_bytes/offset to read more.]",
+            shown,
+            lines.len(),
+            used,
+            content.len()
+        ));
+    }
+    result
+}
+
+fn render_lines(content: &str, start: usize, end: Option<usize>) -> String {
+    let lines: Vec<&str> = content.lines().collect();
+    let from = (start - 1).min(lines.len());
+    let to = end.unwrap_or(lines.len()).min(lines.len()).max(from);
+    if from == to {
+        return format!(
+            "[No lines in range {}-{}; file has {} lines, {} bytes]",
+            start,
+            end.map(|end| end.to_string()).unwrap_or_default(),
+            lines.len(),
+            content.len()
+        );
+    }
+    format!(
+        "[Lines {}-{} of {} total, {} bytes]\n{}",
+        from + 1,
+        to,
+        lines.len(),
+        content.len(),
+        number_lines(&lines[from..to], from + 1)
+    )
+}
+
+/// Decodes a byte window that may begin or end inside a multi-byte
+/// character, dropping the partial characters at either edge.
+fn decode_byte_window(bytes: &[u8]) -> (usize, String) {
+    let skipped = bytes
+        .iter()
+        .take(3)
+        .take_while(|byte| (**byte & 0xC0) == 0x80)
+        .count();
+    let body = &bytes[skipped..];
+    let text = match std::str::from_utf8(body) {
+        Ok(text) => text.to_string(),
+        Err(error) if error.error_len().is_none() => {
+            String::from_utf8_lossy(&body[..error.valid_up_to()]).into_owned()
+        }
+        Err(_) => String::from_utf8_lossy(body).into_owned(),
+    };
+    (skipped, text)
+}
+
+/// Reads a byte window by seeking, so files beyond `MAX_READ_BYTES` can be
+/// paged through.
+async fn read_byte_range(path: &str, offset: u64, len: u64, total: u64) -> Result<String> {
+    use tokio::io::{AsyncReadExt, AsyncSeekExt};
+
+    if offset >= total {
+        return Ok(format!(
+            "[Offset {} is past the end of the file ({} bytes)]",
+            offset, total
+        ));
+    }
+    let mut file = tokio::fs::File::open(path).await?;
+    file.seek(std::io::SeekFrom::Start(offset)).await?;
+    let mut bytes = Vec::new();
+    file.take(len).read_to_end(&mut bytes).await?;
+    if bytes.contains(&0) {
+        return Ok(format!("[Binary file: {} bytes]", total));
+    }
+
+    let (skipped, text) = decode_byte_window(&bytes);
+    let start = offset + skipped as u64;
+    Ok(format!(
+        "[Bytes {}-{} of {} total]\n{}",
+        start,
+        start + text.len() as u64,
+        total,
+        text
+    ))
+}
+
 #[async_trait]
 impl Tool for ReadFileTool {
     fn name(&self) -> &str {
@@ -34,6 +223,9 @@ impl Tool for ReadFileTool {
 
     fn description(&self) -> &str {
         "Read the contents of a file. Returns the file content as text. \
+         Pass start_line/end_line for a line range or max_bytes/offset for a byte \
+         range to page through large files; the slice starts with a header giving \
+         the file's total size. Reads without a range are capped and marked when truncated. \
          For binary files, returns a description of the file type and size."
     }
 
@@ -45,13 +237,26 @@ impl Tool for ReadFileTool {
                     "type": "string",
                     "description": "Path to the file (absolute or relative to working directory)"
                 },
+                "start_line": {
+                    "type": "integer",
+                    "description": "First line to return (1-indexed, inclusive)"
+                },
+                "end_line": {
+                    "type": "integer",
+                    "description": "Last line to return (inclusive, default: end of file)"
+                },
+                "max_bytes": {
+                    "type": "integer",
+                    "description": "Return at most this many bytes starting at byte 'offset'"
+                },
                 "offset": {
                     "type": "integer",
-                    "description": "Line number to start reading from (1-indexed, default: 1)"
+                    "description": "With max_bytes: 0-indexed byte offset (default: 0). \
+                                    Otherwise: line number to start reading from (1-indexed, default: 1)"
                 },
                 "limit": {
                     "type": "integer",
-                    "description": "Maximum number of lines to read (default: all)"
+                    "description": "Maximum number of lines to read from 'offset' (default: all)"
                 }
             },
             "required": ["path"]
@@ -64,6 +269,11 @@ impl Tool for ReadFileTool {
             None => return Ok(ToolOutput::Error("Missing 'path' parameter".to_string())),
         };
 
+        let range = match ReadRange::from_params(&params) {
+            Ok(range) => range,
+            Err(error) => return Ok(ToolOutput::Error(error)),
+        };
+
         let path = resolve_path(path_str, &ctx.working_directory);
 
         // Check file exists and size
@@ -84,9 +294,18 @@ impl Tool for ReadFileTool {
             )));
         }
 
+        if let ReadRange::Bytes { offset, len } = range {
+            return Ok(
+                match read_byte_range(&path, offset, len, metadata.len()).await {
+                    Ok(text) => ToolOutput::Text(text),
+                    Err(e) => ToolOutput::Error(format!("Failed to read '{}': {}", path_str, e)),
+                },
+            );
+        }
+
         if metadata.len() > MAX_READ_BYTES {
             return Ok(ToolOutput::Error(format!(
-                "File too large ({} bytes, max {}). Use offset/limit to read portions.",
+                "File too large ({} bytes, max {}). Use max_bytes/offset to read portions.",
                 metadata.len(),
                 MAX_READ_BYTES
             )));
@@ -103,33 +322,11 @@ impl Tool for ReadFileTool {
             }
         };
 
-        // Apply offset/limit
-        let offset = params["offset"].as_u64().unwrap_or(1).max(1) as usize;
-        let limit = params["limit"].as_u64().map(|l| l as usize);
-
-        let lines: Vec<&str> = content.lines().collect();
-        let start = (offset - 1).min(lines.len());
-        let end = match limit {
-            Some(l) => (start + l).min(lines.len()),
-            None => lines.len(),
+        let result = match range {
+            ReadRange::Lines { start, end } => render_lines(&content, start, end),
+            _ => render_capped(&content, AgentConfig::load().read_file_max_bytes),
         };
 
-        let selected: Vec<String> = lines[start..end]
-            .iter()
-            .enumerate()
-            .map(|(i, line)| format!("{:>4} | {}", start + i + 1, line))
-            .collect();
-
-        let mut result = selected.join("\n");
-        if end < lines.len() {
-            result.push_str(&format!(
-                "\n\n[Showing lines {}-{} of {}]",
-                start + 1,
-                end,
-                lines.len()
-            ));
-        }
-
         Ok(ToolOutput::Text(result))
     }
 
@@ -249,7 +446,10 @@ impl Tool for ListDirectoryTool {
     }
 
     fn description(&self) -> &str {
-        "List files and directories in a given path. Shows names, sizes, and types."
+        "List files and directories in a given path. Shows names, sizes, and types. \
+         With recursive=true it returns an indented tree (two spaces per level) that \
+         honors .gitignore files and does not descend into node_modules, target, or \
+         .git unless they are named in include_dirs."
     }
 
     fn parameters_schema(&self) -> serde_json::Value {
@@ -262,7 +462,20 @@ impl Tool for ListDirectoryTool {
                 },
                 "recursive": {
                     "type": "boolean",
-                    "description": "List recursively (default: false, max depth: 3)"
+                    "description": "List recursively (default: false)"
+                },
+                "max_depth": {
+                    "type": "integer",
+                    "description": "Levels to list when recursive (default: 3, max: 10)"
+                },
+                "max_entries": {
+                    "type": "integer",
+                    "description": "Entries to return when recursive (default and max: 500)"
+                },
+                "include_dirs": {
+                    "type": "array",
+                    "items": {"type": "string"},
+                    "description": "Heavy directory names to descend into anyway, e.g. [\"target\"]"
                 }
             }
         })
@@ -291,13 +504,42 @@ impl Tool for ListDirectoryTool {
             )));
         }
 
-        let max_depth = if recursive { 3 } else { 1 };
+        let options = if recursive {
+            ListOptions {
+                max_depth: params["max_depth"]
+                    .as_u64()
+                    .map_or(DEFAULT_LIST_DEPTH, |depth| {
+                        (depth as usize).clamp(1, MAX_LIST_DEPTH)
+                    }),
+                max_entries: params["max_entries"]
+                    .as_u64()
+                    .map_or(MAX_LIST_ENTRIES, |cap| {
+                        (cap as usize).clamp(1, MAX_LIST_ENTRIES)
+                    }),
+                include_dirs: params["include_dirs"]
+                    .as_array()
+                    .map(|names| {
+                        names
+                            .iter()
+                            .filter_map(|name| name.as_str())
+                            .map(str::to_string)
+                            .collect()
+                    })
+                    .unwrap_or_default(),
+                respect_gitignore: true,
+            }
+        } else {
+            ListOptions {
+                max_depth: 1,
+                max_entries: MAX_LIST_ENTRIES,
+                include_dirs: Vec::new(),
+                respect_gitignore: false,
+            }
+        };
         let mut entries = Vec::new();
-        list_dir_recursive(&path, &path, max_depth, 0, &mut entries).await;
-
-        if entries.len() > MAX_LIST_ENTRIES {
-            entries.truncate(MAX_LIST_ENTRIES);
-            entries.push(format!("[Truncated at {} entries]", MAX_LIST_ENTRIES));
+        let truncated = list_dir_recursive(&path, &path, &options, 0, &[], &mut entries).await;
+        if truncated {
+            entries.push(format!("[Truncated at {} entries]", options.max_entries));
         }
 
         Ok(ToolOutput::Text(entries.join("\n")))
@@ -333,7 +575,9 @@ impl Tool for PatchFileTool {
     fn description(&self) -> &str {
         "Apply a targeted edit to a file by replacing a specific string with new content. \
          The old_string must match exactly (including whitespace). \
-         Fails if old_string is not found or matches multiple locations."
+         Fails if old_string is not found or matches multiple locations. \
+         Alternatively pass unified_diff with standard ---/+++/@@ hunks for this one file; \
+         hunks may drift from their line numbers, and if any hunk is rejected the file is left unchanged."
     }
 
     fn parameters_schema(&self) -> serde_json::Value {
@@ -351,9 +595,13 @@ impl Tool for PatchFileTool {
                 "new_string": {
                     "type": "string",
                     "description": "Replacement string"
+                },
+                "unified_diff": {
+                    "type": "string",
+                    "description": "Unified diff hunks to apply instead of old_string/new_string"
                 }
             },
-            "required": ["path", "old_string", "new_string"]
+            "required": ["path"]
         })
     }
 
@@ -362,6 +610,9 @@ impl Tool for PatchFileTool {
             Some(p) => p,
             None => return Ok(ToolOutput::Error("Missing 'path' parameter".to_string())),
         };
+        if let Some(diff) = params["unified_diff"].as_str() {
+            return Ok(apply_diff_to_file(path_str, diff, ctx).await);
+        }
         let old_string = match params["old_string"].as_str() {
             Some(s) => s,
             None => {
@@ -430,6 +681,422 @@ impl Tool for PatchFileTool {
     }
 }
 
+async fn apply_diff_to_file(path_str: &str, diff: &str, ctx: &ToolContext) -> ToolOutput {
+    // Patch a symlink's target: renaming over the link itself would replace
+    // it with a regular file.
+    let path = match tokio::fs::canonicalize(resolve_path(path_str, &ctx.working_directory)).await {
+        Ok(path) => path,
+        Err(e) => return ToolOutput::Error(format!("Cannot read '{}': {}", path_str, e)),
+    };
+    let read = async {
+        let permissions = tokio::fs::metadata(&path).await?.permissions();
+        Ok::<_, std::io::Error>((tokio::fs::read_to_string(&path).await?, permissions))
+    };
+    let (content, permissions) = match read.await {
+        Ok(read) => read,
+        Err(e) => return ToolOutput::Error(format!("Cannot read '{}': {}", path_str, e)),
+    };
+    let application = match apply_unified_diff(&content, diff) {
+        Ok(application) => application,
+        Err(reason) => {
+            return ToolOutput::Error(format!(
+                "Invalid unified_diff for '{}': {}",
+                path_str, reason
+            ))
+        }
+    };
+    let Some(new_content) = application.content.as_deref() else {
+        let rejects: Vec<String> = application
+            .rejected()
+            .map(|hunk| {
+                format!(
+                    "hunk {}: {}",
+                    hunk.hunk,
+                    hunk.reason.as_deref().unwrap_or("rejected")
+                )
+            })
+            .collect();
+        return ToolOutput::Error(format!(
+            "{} of {} hunks rejected for '{}'; file left unchanged ({})",
+            rejects.len(),
+            application.hunks.len(),
+            path_str,
+            rejects.join("; ")
+        ));
+    };
+
+    // Write beside the target and rename so a failed write cannot leave a
+    // half-patched file behind; the staging file takes the original's mode.
+    let mut staging = path.clone().into_os_string();
+    staging.push(".patch-tmp");
+    let staging = PathBuf::from(staging);
+    let written = async {
+        tokio::fs::write(&staging, new_content).await?;
+        tokio::fs::set_permissions(&staging, permissions).await?;
+        tokio::fs::rename(&staging, &path).await
+    }
+    .await;
+    match written {
+        Ok(()) => {
+            tracing::info!(
+                "PatchFileTool: applied {} hunks to {}",
+                application.hunks.len(),
+                path.display()
+            );
+            ToolOutput::Json(serde_json::json!({
+                "path": path_str,
+                "applied": true,
+                "hunks": application.hunks,
+            }))
+        }
+        Err(e) => {
+            let _ = tokio::fs::remove_file(&staging).await;
+            ToolOutput::Error(format!("Failed to write '{}': {}", path_str, e))
+        }
+    }
+}
+
+// ============================================================================
+// SearchFilesTool
+// ============================================================================
+
+pub struct SearchFilesTool;
+
+impl SearchFilesTool {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl Tool for SearchFilesTool {
+    fn name(&self) -> &str {
+        "search_files"
+    }
+
+    fn description(&self) -> &str {
+        "Search file contents under the working directory with a regular expression. \
+         Returns matching lines with their relative path and 1-based line number. \
+         Binary files, hidden entries, and files over 1MB are skipped."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "pattern": {
+                    "type": "string",
+                    "description": "Regular expression to search for"
+                },
+                "glob": {
+                    "type": "string",
+                    "description": "Optional file filter, e.g. '*.rs' or 'src/**/*.md'. Patterns without '/' match file names."
+                },
+                "max_matches": {
+                    "type": "integer",
+                    "description": "Maximum matching lines to return (default: 100, max: 1000)"
+                }
+            },
+            "required": ["pattern"]
+        })
+    }
+
+    async fn execute(&self, params: serde_json::Value, ctx: &ToolContext) -> Result<ToolOutput> {
+        let pattern = match params["pattern"].as_str() {
+            Some(p) if !p.is_empty() => p,
+            _ => return Ok(ToolOutput::Error("Missing 'pattern' parameter".to_string())),
+        };
+        let regex = match regex_lite::Regex::new(pattern) {
+            Ok(re) => re,
+            Err(e) => {
+                return Ok(ToolOutput::Error(format!(
+                    "Invalid pattern '{}': {}",
+                    pattern, e
+                )))
+            }
+        };
+        let glob = params["glob"]
+            .as_str()
+            .map(str::trim)
+            .filter(|g| !g.is_empty())
+            .map(str::to_string);
+        let max_matches = params["max_matches"]
+            .as_u64()
+            .map(|n| n as usize)
+            .unwrap_or(DEFAULT_SEARCH_MATCHES)
+            .clamp(1, MAX_SEARCH_MATCHES);
+
+        let root = std::path::PathBuf::from(&ctx.working_directory);
+        if !root.is_dir() {
+            return Ok(ToolOutput::Error(format!(
+                "Working directory '{}' is not a directory",
+                ctx.working_directory
+            )));
+        }
+
+        let outcome = tokio::task::spawn_blocking(move || {
+            search_dir(&root, &regex, glob.as_deref(), max_matches)
+        })
+        .await?;
+
+        Ok(ToolOutput::Json(serde_json::json!({
+            "matches": outcome.matches,
+            "truncated": outcome.truncated,
+            "files_searched": outcome.files_searched,
+        })))
+    }
+
+    fn requires_approval(&self) -> bool {
+        false // Read-only, confined to the working directory
+    }
+
+    fn category(&self) -> ToolCategory {
+        ToolCategory::FileSystem
+    }
+}
+
+// ============================================================================
+// MoveFileTool
+// ============================================================================
+
+pub struct MoveFileTool;
+
+impl MoveFileTool {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl Tool for MoveFileTool {
+    fn name(&self) -> &str {
+        "move_file"
+    }
+
+    fn description(&self) -> &str {
+        "Move or rename a file or directory inside the working directory. \
+         Creates the destination's parent directories as needed. Refuses to \
+         replace an existing destination unless 'overwrite' is true."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "source": {
+                    "type": "string",
+                    "description": "Path to move (absolute or relative to working directory)"
+                },
+                "destination": {
+                    "type": "string",
+                    "description": "New path, including the file name"
+                },
+                "overwrite": {
+                    "type": "boolean",
+                    "description": "Replace an existing destination file (default: false)"
+                }
+            },
+            "required": ["source", "destination"]
+        })
+    }
+
+    async fn execute(&self, params: serde_json::Value, ctx: &ToolContext) -> Result<ToolOutput> {
+        let source_str = match params["source"].as_str() {
+            Some(p) => p,
+            None => return Ok(ToolOutput::Error("Missing 'source' parameter".to_string())),
+        };
+        let destination_str = match params["destination"].as_str() {
+            Some(p) => p,
+            None => {
+                return Ok(ToolOutput::Error(
+                    "Missing 'destination' parameter".to_string(),
+                ))
+            }
+        };
+        let overwrite = params["overwrite"].as_bool().unwrap_or(false);
+
+        let source = match confine_path(source_str, &ctx.working_directory, &ctx.allowed_roots) {
+            Ok((path, _)) => path,
+            Err(reason) => return Ok(ToolOutput::Error(reason)),
+        };
+        let destination =
+            match confine_path(destination_str, &ctx.working_directory, &ctx.allowed_roots) {
+                Ok((path, _)) => path,
+                Err(reason) => return Ok(ToolOutput::Error(reason)),
+            };
+
+        if tokio::fs::symlink_metadata(&source).await.is_err() {
+            return Ok(ToolOutput::Error(format!(
+                "Source not found: {}",
+                source_str
+            )));
+        }
+        if let Ok(existing) = tokio::fs::symlink_metadata(&destination).await {
+            if existing.is_dir() {
+                return Ok(ToolOutput::Error(format!(
+                    "Destination '{}' is an existing directory; name the full target path",
+                    destination_str
+                )));
+            }
+            if !overwrite {
+                return Ok(ToolOutput::Error(format!(
+                    "Destination '{}' already exists; pass overwrite=true to replace it",
+                    destination_str
+                )));
+            }
+        }
+
+        if let Some(parent) = destination.parent() {
+            if let Err(e) = tokio::fs::create_dir_all(parent).await {
+                return Ok(ToolOutput::Error(format!(
+                    "Failed to create directories: {}",
+                    e
+                )));
+            }
+        }
+
+        match tokio::fs::rename(&source, &destination).await {
+            Ok(()) => {
+                tracing::info!(
+                    "MoveFileTool: moved {} to {}",
+                    source.display(),
+                    destination.display()
+                );
+                Ok(ToolOutput::Text(format!(
+                    "Moved {} to {}",
+                    source_str, destination_str
+                )))
+            }
+            Err(e) => Ok(ToolOutput::Error(format!(
+                "Failed to move '{}' to '{}': {}",
+                source_str, destination_str, e
+            ))),
+        }
+    }
+
+    fn requires_approval(&self) -> bool {
+        true
+    }
+
+    fn category(&self) -> ToolCategory {
+        ToolCategory::FileSystem
+    }
+}
+
+// ============================================================================
+// DeleteFileTool
+// ============================================================================
+
+pub struct DeleteFileTool;
+
+impl DeleteFileTool {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl Tool for DeleteFileTool {
+    fn name(&self) -> &str {
+        "delete_file"
+    }
+
+    fn description(&self) -> &str {
+        "Delete a file or directory inside the working directory. By default it \
+         is moved to .ponderer/trash/ so it can be restored; the output gives \
+         the trash path."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "path": {
+                    "type": "string",
+                    "description": "Path to delete (absolute or relative to working directory)"
+                }
+            },
+            "required": ["path"]
+        })
+    }
+
+    async fn execute(&self, params: serde_json::Value, ctx: &ToolContext) -> Result<ToolOutput> {
+        let path_str = match params["path"].as_str() {
+            Some(p) => p,
+            None => return Ok(ToolOutput::Error("Missing 'path' parameter".to_string())),
+        };
+
+        // Trash lives under the root holding the entry, so the move never
+        // crosses into another repository or filesystem.
+        let (path, root) = match confine_path(path_str, &ctx.working_directory, &ctx.allowed_roots)
+        {
+            Ok(confined) => confined,
+            Err(reason) => return Ok(ToolOutput::Error(reason)),
+        };
+        let metadata = match tokio::fs::symlink_metadata(&path).await {
+            Ok(metadata) => metadata,
+            Err(_) => return Ok(ToolOutput::Error(format!("Not found: {}", path_str))),
+        };
+        let trash_dir = root.join(TRASH_DIR);
+        if path.starts_with(&trash_dir) || trash_dir.starts_with(&path) {
+            return Ok(ToolOutput::Error(format!(
+                "Refusing to delete '{}': it is or contains the trash directory",
+                path_str
+            )));
+        }
+
+        if AgentConfig::load().file_delete_hard {
+            let removed = if metadata.is_dir() {
+                tokio::fs::remove_dir_all(&path).await
+            } else {
+                tokio::fs::remove_file(&path).await
+            };
+            return match removed {
+                Ok(()) => {
+                    tracing::info!("DeleteFileTool: deleted {}", path.display());
+                    Ok(ToolOutput::Json(serde_json::json!({
+                        "deleted": path_str,
+                        "trash_path": null,
+                        "hard_deleted": true,
+                    })))
+                }
+                Err(e) => Ok(ToolOutput::Error(format!(
+                    "Failed to delete '{}': {}",
+                    path_str, e
+                ))),
+            };
+        }
+
+        let trash_path = match move_to_trash(&path, &trash_dir).await {
+            Ok(trash_path) => trash_path,
+            Err(e) => {
+                return Ok(ToolOutput::Error(format!(
+                    "Failed to move '{}' to the trash: {}",
+                    path_str, e
+                )))
+            }
+        };
+        tracing::info!(
+            "DeleteFileTool: moved {} to {}",
+            path.display(),
+            trash_path.display()
+        );
+        Ok(ToolOutput::Json(serde_json::json!({
+            "deleted": path_str,
+            "trash_path": trash_path.to_string_lossy(),
+            "hard_deleted": false,
+        })))
+    }
+
+    fn requires_approval(&self) -> bool {
+        true
+    }
+
+    fn category(&self) -> ToolCategory {
+        ToolCategory::FileSystem
+    }
+}
+
 // ============================================================================
 // Helpers
 // ============================================================================
@@ -471,22 +1138,186 @@ fn resolve_path(path: &str, working_dir: &str) -> String {
     }
 }
 
+/// Resolve `path` like `resolve_path`, then require it to name an entry inside
+/// the working directory or one of `allowed_roots` once `.` / `..` and
+/// symlinked parent directories are resolved. The final component is left
+/// unresolved, so a symlink is itself moved or deleted rather than its target.
+/// Returns the confined path and the canonical root that contains it.
+fn confine_path(
+    path: &str,
+    working_dir: &str,
+    allowed_roots: &[PathBuf],
+) -> std::result::Result<(PathBuf, PathBuf), String> {
+    let working_root = std::fs::canonicalize(working_dir).map_err(|e| {
+        format!(
+            "Working directory '{}' is not accessible: {}",
+            working_dir, e
+        )
+    })?;
+    // A configured root that does not exist (yet) simply admits nothing.
+    let roots: Vec<PathBuf> = std::iter::once(working_root)
+        .chain(
+            allowed_roots
+                .iter()
+                .filter_map(|root| std::fs::canonicalize(root).ok()),
+        )
+        .collect();
+
+    let mut normalized = PathBuf::new();
+    for component in Path::new(&resolve_path(path, working_dir)).components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    let outside = || {
+        if allowed_roots.is_empty() {
+            format!("Path '{}' is outside the working directory", path)
+        } else {
+            format!(
+                "Path '{}' is outside the working directory and allowed roots",
+                path
+            )
+        }
+    };
+    let (Some(parent), Some(name)) = (normalized.parent(), normalized.file_name()) else {
+        return Err(outside());
+    };
+
+    // Canonicalize the deepest existing ancestor; the rest does not exist yet.
+    let mut existing = parent;
+    let mut missing = Vec::new();
+    while !existing.exists() {
+        let (Some(up), Some(segment)) = (existing.parent(), existing.file_name()) else {
+            return Err(outside());
+        };
+        missing.push(segment);
+        existing = up;
+    }
+    let mut confined = std::fs::canonicalize(existing).map_err(|_| outside())?;
+    confined.extend(missing.into_iter().rev());
+    confined.push(name);
+
+    if roots.contains(&confined) {
+        return Err(outside());
+    }
+    match roots.into_iter().find(|root| confined.starts_with(root)) {
+        Some(root) => Ok((confined, root)),
+        None => Err(outside()),
+    }
+}
+
+/// Move `path` into `trash_dir` under a timestamped name, never replacing an
+/// earlier trashed entry, and return where it landed.
+async fn move_to_trash(path: &Path, trash_dir: &Path) -> std::io::Result<PathBuf> {
+    tokio::fs::create_dir_all(trash_dir).await?;
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    let stamp = chrono::Utc::now().format("%Y%m%d-%H%M%S");
+    let mut trash_path = trash_dir.join(format!("{}-{}", stamp, name));
+    let mut attempt = 1;
+    while tokio::fs::symlink_metadata(&trash_path).await.is_ok() {
+        attempt += 1;
+        trash_path = trash_dir.join(format!("{}-{}-{}", stamp, attempt, name));
+    }
+    tokio::fs::rename(path, &trash_path).await?;
+    Ok(trash_path)
+}
+
+struct ListOptions {
+    max_depth: usize,
+    max_entries: usize,
+    include_dirs: Vec<String>,
+    respect_gitignore: bool,
+}
+
+/// One `.gitignore` pattern, matched against paths relative to `base`.
+#[derive(Clone)]
+struct IgnoreRule {
+    base: std::path::PathBuf,
+    pattern: String,
+    negated: bool,
+    dir_only: bool,
+    /// Contains a `/` before its end, so it matches the relative path rather
+    /// than the entry name at any level.
+    anchored: bool,
+}
+
+impl IgnoreRule {
+    fn parse(base: &std::path::Path, line: &str) -> Option<Self> {
+        let line = line.trim_end();
+        if line.is_empty() || line.starts_with('#') {
+            return None;
+        }
+        let (negated, line) = match line.strip_prefix('!') {
+            Some(rest) => (true, rest),
+            None => (false, line),
+        };
+        let (dir_only, line) = match line.strip_suffix('/') {
+            Some(rest) => (true, rest),
+            None => (false, line),
+        };
+        let anchored = line.contains('/');
+        let pattern = line.trim_start_matches('/');
+        if pattern.is_empty() {
+            return None;
+        }
+        Some(Self {
+            base: base.to_path_buf(),
+            pattern: pattern.to_string(),
+            negated,
+            dir_only,
+            anchored,
+        })
+    }
+
+    fn matches(&self, path: &std::path::Path, is_dir: bool) -> bool {
+        if self.dir_only && !is_dir {
+            return false;
+        }
+        let Ok(relative) = path.strip_prefix(&self.base) else {
+            return false;
+        };
+        if self.anchored {
+            let relative = relative.to_string_lossy().replace('\\', "/");
+            glob_matches(&self.pattern, &relative)
+        } else {
+            path.file_name()
+                .is_some_and(|name| glob_matches(&self.pattern, &name.to_string_lossy()))
+        }
+    }
+}
+
+/// The last matching rule decides, so a later `!pattern` re-includes.
+fn is_gitignored(rules: &[IgnoreRule], path: &std::path::Path, is_dir: bool) -> bool {
+    rules
+        .iter()
+        .rev()
+        .find(|rule| rule.matches(path, is_dir))
+        .is_some_and(|rule| !rule.negated)
+}
+
+/// Appends one line per entry, indented two spaces per level. Returns true
+/// when `max_entries` cut the listing short.
 async fn list_dir_recursive(
     base: &str,
     current: &str,
-    max_depth: usize,
+    options: &ListOptions,
     depth: usize,
+    inherited_rules: &[IgnoreRule],
     entries: &mut Vec<String>,
-) {
-    if depth >= max_depth || entries.len() >= MAX_LIST_ENTRIES {
-        return;
+) -> bool {
+    if depth >= options.max_depth {
+        return false;
     }
 
     let mut read_dir = match tokio::fs::read_dir(current).await {
         Ok(rd) => rd,
         Err(e) => {
             entries.push(format!("  [error reading {}: {}]", current, e));
-            return;
+            return false;
         }
     };
 
@@ -498,6 +1329,18 @@ async fn list_dir_recursive(
     // Sort by name
     items.sort_by_key(|e| e.file_name());
 
+    let mut rules = inherited_rules.to_vec();
+    if options.respect_gitignore {
+        let current_path = std::path::Path::new(current);
+        if let Ok(content) = tokio::fs::read_to_string(current_path.join(".gitignore")).await {
+            rules.extend(
+                content
+                    .lines()
+                    .filter_map(|line| IgnoreRule::parse(current_path, line)),
+            );
+        }
+    }
+
     for entry in items {
         let name = entry.file_name().to_string_lossy().to_string();
 
@@ -506,33 +1349,185 @@ async fn list_dir_recursive(
             continue;
         }
 
-        let indent = "  ".repeat(depth);
+        let meta = entry.metadata().await.ok();
+        let is_dir = meta.as_ref().is_some_and(|meta| meta.is_dir());
         let rel_path = entry.path();
+        if is_gitignored(&rules, &rel_path, is_dir) {
+            continue;
+        }
+        if entries.len() >= options.max_entries {
+            return true;
+        }
+
+        let indent = "  ".repeat(depth);
         let rel = rel_path
             .strip_prefix(base)
             .unwrap_or(&rel_path)
             .to_string_lossy();
 
-        if let Ok(meta) = entry.metadata().await {
-            if meta.is_dir() {
+        match meta {
+            Some(_) if is_dir => {
+                let heavy = options.max_depth > 1
+                    && HEAVY_LIST_DIRS.contains(&name.as_str())
+                    && !options
+                        .include_dirs
+                        .iter()
+                        .any(|included| included == &name);
+                if heavy {
+                    entries.push(format!("{}{}/  (skipped)", indent, rel));
+                    continue;
+                }
                 entries.push(format!("{}{}/", indent, rel));
                 // Recurse into subdirectories (box the future to avoid deep recursion)
-                Box::pin(list_dir_recursive(
+                let truncated = Box::pin(list_dir_recursive(
                     base,
                     &entry.path().to_string_lossy(),
-                    max_depth,
+                    options,
                     depth + 1,
+                    &rules,
                     entries,
                 ))
                 .await;
-            } else {
+                if truncated {
+                    return true;
+                }
+            }
+            Some(meta) => {
                 let size = format_size(meta.len());
                 entries.push(format!("{}{}  ({})", indent, rel, size));
             }
-        } else {
-            entries.push(format!("{}{}", indent, rel));
+            None => entries.push(format!("{}{}", indent, rel)),
         }
     }
+    false
+}
+
+#[derive(Debug, Default)]
+struct SearchOutcome {
+    matches: Vec<serde_json::Value>,
+    truncated: bool,
+    files_searched: usize,
+}
+
+/// Walk `root` depth-first (sorted, hidden entries and symlinks skipped so the
+/// search cannot leave the working directory) collecting regex matches.
+fn search_dir(
+    root: &std::path::Path,
+    regex: &regex_lite::Regex,
+    glob: Option<&str>,
+    max_matches: usize,
+) -> SearchOutcome {
+    let mut outcome = SearchOutcome::default();
+    let mut stack = vec![root.to_path_buf()];
+
+    while let Some(dir) = stack.pop() {
+        let Ok(read_dir) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        let mut items: Vec<_> = read_dir.filter_map(|entry| entry.ok()).collect();
+        // Reverse-sorted so popping the stack visits directories in name order.
+        items.sort_by_key(|entry| std::cmp::Reverse(entry.file_name()));
+
+        let mut files = Vec::new();
+        for entry in items {
+            if entry.file_name().to_string_lossy().starts_with('.') {
+                continue;
+            }
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            if file_type.is_dir() {
+                stack.push(entry.path());
+            } else if file_type.is_file() {
+                files.push(entry.path());
+            }
+        }
+
+        for path in files.into_iter().rev() {
+            let rel = path
+                .strip_prefix(root)
+                .unwrap_or(&path)
+                .to_string_lossy()
+                .replace('\\', "/");
+            if let Some(glob) = glob {
+                let target = if glob.contains('/') {
+                    rel.as_str()
+                } else {
+                    rel.rsplit('/').next().unwrap_or(&rel)
+                };
+                if !glob_matches(glob, target) {
+                    continue;
+                }
+            }
+            if std::fs::metadata(&path)
+                .map(|m| m.len() > MAX_SEARCH_FILE_BYTES)
+                .unwrap_or(true)
+            {
+                continue;
+            }
+            let Ok(bytes) = std::fs::read(&path) else {
+                continue;
+            };
+            if bytes.iter().take(8192).any(|b| *b == 0) {
+                continue; // Binary
+            }
+            outcome.files_searched += 1;
+
+            let content = String::from_utf8_lossy(&bytes);
+            for (idx, line) in content.lines().enumerate() {
+                if !regex.is_match(line) {
+                    continue;
+                }
+                if outcome.matches.len() >= max_matches {
+                    outcome.truncated = true;
+                    return outcome;
+                }
+                let line: String = line.chars().take(MAX_SEARCH_LINE_CHARS).collect();
+                outcome.matches.push(serde_json::json!({
+                    "path": rel,
+                    "line_number": idx + 1,
+                    "line": line,
+                }));
+            }
+        }
+    }
+
+    outcome
+}
+
+/// Minimal glob matcher: `*` matches within a path segment, `**` matches
+/// across segments, and `?` matches a single non-separator character.
+fn glob_matches(pattern: &str, text: &str) -> bool {
+    fn matches(p: &[char], t: &[char]) -> bool {
+        match p.first() {
+            None => t.is_empty(),
+            Some('*') if p.get(1) == Some(&'*') => {
+                // `**/` can also match zero directories.
+                let rest = if p.get(2) == Some(&'/') {
+                    &p[3..]
+                } else {
+                    &p[2..]
+                };
+                (0..=t.len()).any(|i| matches(rest, &t[i..])) || matches(&p[2..], t)
+            }
+            Some('*') => {
+                for i in 0..=t.len() {
+                    if matches(&p[1..], &t[i..]) {
+                        return true;
+                    }
+                    if t.get(i) == Some(&'/') {
+                        break;
+                    }
+                }
+                false
+            }
+            Some('?') => t.first().is_some_and(|c| *c != '/') && matches(&p[1..], &t[1..]),
+            Some(c) => t.first() == Some(c) && matches(&p[1..], &t[1..]),
+        }
+    }
+    let p: Vec<char> = pattern.chars().collect();
+    let t: Vec<char> = text.chars().collect();
+    matches(&p, &t)
 }
 
 fn format_size(bytes: u64) -> String {
@@ -554,6 +1549,7 @@ mod tests {
     fn test_ctx() -> ToolContext {
         ToolContext {
             working_directory: "/tmp".to_string(),
+            allowed_roots: Vec::new(),
             username: "test".to_string(),
             conversation_id: None,
             autonomous: false,
@@ -562,6 +1558,9 @@ mod tests {
             disallowed_tools: Vec::new(),
             outbound_action_rate_limit: None,
             generation_observer: None,
+            progress: None,
+            approval_waiter: None,
+            timeouts: crate::tools::ToolTimeoutPolicy::default(),
         }
     }
 
@@ -607,6 +1606,70 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn read_file_returns_line_range_with_total_header() {
+        let dir = tempfile::tempdir().unwrap();
+        let file_path = dir.path().join("log.txt");
+        let content: String = (1..=500).map(|n| format!("entry {}\n", n)).collect();
+        std::fs::write(&file_path, &content).unwrap();
+
+        let tool = ReadFileTool::new();
+        let params = serde_json::json!({
+            "path": file_path.to_string_lossy(),
+            "start_line": 400,
+            "end_line": 402
+        });
+        match tool.execute(params, &test_ctx()).await.unwrap() {
+            ToolOutput::Text(text) => {
+                let mut lines = text.lines();
+                assert_eq!(
+                    lines.next(),
+                    Some(format!("[Lines 400-402 of 500 total, {} bytes]", content.len()).as_str())
+                );
+                assert_eq!(lines.next(), Some(" 400 | entry 400"));
+                assert_eq!(lines.last(), Some(" 402 | entry 402"));
+            }
+            other => panic!("Expected Text, got {:?}", other),
+        }
+
+        let inverted = serde_json::json!({
+            "path": file_path.to_string_lossy(),
+            "start_line": 10,
+            "end_line": 5
+        });
+        assert!(matches!(
+            tool.execute(inverted, &test_ctx()).await.unwrap(),
+            ToolOutput::Error(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn read_file_byte_range_trims_partial_characters() {
+        let dir = tempfile::tempdir().unwrap();
+        let file_path = dir.path().join("utf8.txt");
+        // 'é' is two bytes, so byte 1 falls inside the first character.
+        std::fs::write(&file_path, "éabcdé").unwrap();
+
+        let tool = ReadFileTool::new();
+        let params = serde_json::json!({
+            "path": file_path.to_string_lossy(),
+            "max_bytes": 6,
+            "offset": 1
+        });
+        match tool.execute(params, &test_ctx()).await.unwrap() {
+            ToolOutput::Text(text) => assert_eq!(text, "[Bytes 2-6 of 8 total]\nabcd"),
+            other => panic!("Expected Text, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn capped_read_stops_at_whole_line_with_marker() {
+        let text = render_capped("alpha\nbeta\ngamma\n", 12);
+        assert!(text.starts_with("   1 | alpha\n   2 | beta\n\n[Truncated after line 2 of 3"));
+        assert!(text.contains("(11 of 17 bytes)"));
+        assert_eq!(render_capped("alpha\n", 12), "   1 | alpha");
+    }
+
     #[tokio::test]
     async fn test_write_file() {
         let dir = tempfile::tempdir().unwrap();
@@ -662,6 +1725,106 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn recursive_listing_caps_depth_and_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("a/b/c/d")).unwrap();
+        std::fs::write(dir.path().join("a/b/c/d/deep.txt"), "").unwrap();
+        std::fs::write(dir.path().join("a/top.txt"), "").unwrap();
+        let tool = ListDirectoryTool::new();
+        let path = dir.path().to_string_lossy().to_string();
+
+        let result = tool
+            .execute(
+                serde_json::json!({"path": path, "recursive": true, "max_depth": 2}),
+                &test_ctx(),
+            )
+            .await
+            .unwrap();
+        let ToolOutput::Text(text) = result else {
+            panic!("Expected Text, got {:?}", result);
+        };
+        assert!(text.contains("a/\n"));
+        assert!(text.contains("  a/b/"));
+        assert!(text.contains("  a/top.txt"));
+        assert!(!text.contains("a/b/c"));
+
+        let result = tool
+            .execute(
+                serde_json::json!({"path": path, "recursive": true, "max_depth": 10, "max_entries": 3}),
+                &test_ctx(),
+            )
+            .await
+            .unwrap();
+        let ToolOutput::Text(text) = result else {
+            panic!("Expected Text, got {:?}", result);
+        };
+        assert_eq!(text.lines().count(), 4);
+        assert!(text.ends_with("[Truncated at 3 entries]"));
+    }
+
+    #[tokio::test]
+    async fn recursive_listing_respects_gitignore_and_skips_heavy_dirs() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join(".gitignore"),
+            "# build output\n*.log\n/dist/\nsecrets/\n!keep.log\n",
+        )
+        .unwrap();
+        std::fs::create_dir_all(dir.path().join("src/secrets")).unwrap();
+        std::fs::create_dir_all(dir.path().join("dist")).unwrap();
+        std::fs::create_dir_all(dir.path().join("target/debug")).unwrap();
+        std::fs::create_dir_all(dir.path().join("node_modules/left-pad")).unwrap();
+        std::fs::write(dir.path().join("src/main.rs"), "").unwrap();
+        std::fs::write(dir.path().join("src/secrets/key.pem"), "").unwrap();
+        std::fs::write(dir.path().join("src/debug.log"), "").unwrap();
+        std::fs::write(dir.path().join("src/keep.log"), "").unwrap();
+        std::fs::write(dir.path().join("dist/bundle.js"), "").unwrap();
+        std::fs::write(dir.path().join("src/.gitignore"), "main.rs\n").unwrap();
+        std::fs::write(dir.path().join("README.md"), "").unwrap();
+        let tool = ListDirectoryTool::new();
+        let path = dir.path().to_string_lossy().to_string();
+
+        let result = tool
+            .execute(
+                serde_json::json!({"path": path, "recursive": true}),
+                &test_ctx(),
+            )
+            .await
+            .unwrap();
+        let ToolOutput::Text(text) = result else {
+            panic!("Expected Text, got {:?}", result);
+        };
+        assert!(text.contains("README.md"));
+        assert!(text.contains("src/keep.log"));
+        assert!(!text.contains("debug.log"));
+        assert!(!text.contains("secrets"));
+        assert!(!text.contains("dist"));
+        assert!(!text.contains("src/main.rs"));
+        assert!(text.contains("node_modules/  (skipped)"));
+        assert!(text.contains("target/  (skipped)"));
+        assert!(!text.contains("left-pad"));
+
+        let result = tool
+            .execute(
+                serde_json::json!({"path": path, "recursive": true, "include_dirs": ["target"]}),
+                &test_ctx(),
+            )
+            .await
+            .unwrap();
+        let ToolOutput::Text(text) = result else {
+            panic!("Expected Text, got {:?}", result);
+        };
+        assert!(text.contains("  target/debug/"));
+        assert!(text.contains("node_modules/  (skipped)"));
+
+        let flat = tool
+            .execute(serde_json::json!({"path": path}), &test_ctx())
+            .await
+            .unwrap();
+        assert!(flat.to_llm_string().contains("dist/"));
+    }
+
     #[tokio::test]
     async fn test_patch_file() {
         let dir = tempfile::tempdir().unwrap();
@@ -717,6 +1880,117 @@ mod tests {
         assert!(matches!(result, ToolOutput::Error(_)));
     }
 
+    const DIFF_ORIGINAL: &str = "alpha\nbeta\ngamma\ndelta\nepsilon\nzeta\neta\ntheta\n";
+
+    async fn patch_with_diff(original: &str, diff: &str) -> (ToolOutput, String) {
+        let dir = tempfile::tempdir().unwrap();
+        let file_path = dir.path().join("notes.txt");
+        std::fs::write(&file_path, original).unwrap();
+        let params = serde_json::json!({
+            "path": file_path.to_string_lossy(),
+            "unified_diff": diff
+        });
+        let result = PatchFileTool::new()
+            .execute(params, &test_ctx())
+            .await
+            .unwrap();
+        (result, std::fs::read_to_string(&file_path).unwrap())
+    }
+
+    #[tokio::test]
+    async fn unified_diff_applies_cleanly() {
+        let diff = "--- a/notes.txt\n+++ b/notes.txt\n@@ -2,3 +2,3 @@\n beta\n-gamma\n+GAMMA\n delta\n@@ -6,2 +6,3 @@\n zeta\n+zeta-and-a-half\n eta\n";
+        let (result, content) = patch_with_diff(DIFF_ORIGINAL, diff).await;
+        match result {
+            ToolOutput::Json(report) => {
+                assert_eq!(report["hunks"][0]["applied"], true);
+                assert_eq!(report["hunks"][0]["offset"], 0);
+                assert_eq!(report["hunks"][1]["line"], 6);
+            }
+            other => panic!("Expected Json, got {:?}", other),
+        }
+        assert_eq!(
+            content,
+            "alpha\nbeta\nGAMMA\ndelta\nepsilon\nzeta\nzeta-and-a-half\neta\ntheta\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn unified_diff_tolerates_context_drift() {
+        // Three lines were added at the top since the model last read the file.
+        let drifted = format!("one\ntwo\nthree\n{}", DIFF_ORIGINAL);
+        let diff = "@@ -4,3 +4,3 @@\n delta\n-epsilon\n+EPSILON\n zeta  \n";
+        let (result, content) = patch_with_diff(&drifted, diff).await;
+        match result {
+            ToolOutput::Json(report) => {
+                assert_eq!(report["hunks"][0]["offset"], 3);
+                assert_eq!(report["hunks"][0]["line"], 7);
+            }
+            other => panic!("Expected Json, got {:?}", other),
+        }
+        assert!(content.contains("delta\nEPSILON\nzeta\n"));
+    }
+
+    #[tokio::test]
+    async fn conflicting_hunk_rejects_without_modifying_file() {
+        let diff = "@@ -2,3 +2,3 @@\n beta\n-gamma\n+GAMMA\n delta\n@@ -7,2 +7,2 @@\n-not-in-file\n+replacement\n eta\n";
+        let (result, content) = patch_with_diff(DIFF_ORIGINAL, diff).await;
+        match result {
+            ToolOutput::Error(reason) => {
+                assert!(reason.contains("1 of 2 hunks rejected"));
+                assert!(reason.contains("hunk 2"));
+                assert!(reason.contains("file left unchanged"));
+            }
+            other => panic!("Expected Error, got {:?}", other),
+        }
+        assert_eq!(content, DIFF_ORIGINAL);
+    }
+
+    #[tokio::test]
+    async fn unified_diff_keeps_crlf_line_endings() {
+        let original = DIFF_ORIGINAL.replace('\n', "\r\n");
+        let diff = "@@ -2,3 +2,3 @@\n beta\n-gamma\n+GAMMA\n delta\n";
+        let (result, content) = patch_with_diff(&original, diff).await;
+        assert!(result.is_success());
+        assert_eq!(
+            content,
+            DIFF_ORIGINAL
+                .replace("gamma", "GAMMA")
+                .replace('\n', "\r\n")
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn unified_diff_keeps_mode_and_edits_through_symlinks() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let script = dir.path().join("build.sh");
+        std::fs::write(&script, DIFF_ORIGINAL).unwrap();
+        std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+        let link = dir.path().join("build-link.sh");
+        std::os::unix::fs::symlink(&script, &link).unwrap();
+
+        let params = serde_json::json!({
+            "path": link.to_string_lossy(),
+            "unified_diff": "@@ -2,3 +2,3 @@\n beta\n-gamma\n+GAMMA\n delta\n"
+        });
+        let result = PatchFileTool::new()
+            .execute(params, &test_ctx())
+            .await
+            .unwrap();
+        assert!(result.is_success());
+
+        let mode = std::fs::metadata(&script).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o755);
+        assert!(std::fs::symlink_metadata(&link)
+            .unwrap()
+            .file_type()
+            .is_symlink());
+        assert!(std::fs::read_to_string(&script).unwrap().contains("GAMMA"));
+    }
+
     #[test]
     fn test_resolve_path_absolute() {
         // Multi-component absolute paths pass through unchanged.
@@ -753,11 +2027,284 @@ mod tests {
         assert_eq!(format_size(1_500_000), "1.4 MB");
     }
 
+    #[tokio::test]
+    async fn test_search_files() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("src/agent")).unwrap();
+        std::fs::create_dir_all(dir.path().join(".git")).unwrap();
+        std::fs::write(
+            dir.path().join("src/agent/mod.rs"),
+            "fn a() {}\nself.begin_chat_turn(id);\n",
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("src/notes.md"), "begin_chat_turn docs\n").unwrap();
+        std::fs::write(dir.path().join(".git/HEAD"), "begin_chat_turn\n").unwrap();
+        std::fs::write(dir.path().join("blob.bin"), b"begin_chat_turn\0\x01").unwrap();
+
+        let mut ctx = test_ctx();
+        ctx.working_directory = dir.path().to_string_lossy().to_string();
+        let tool = SearchFilesTool::new();
+
+        let result = tool
+            .execute(serde_json::json!({"pattern": "begin_chat_turn"}), &ctx)
+            .await
+            .unwrap();
+        let ToolOutput::Json(value) = result else {
+            panic!("expected JSON output");
+        };
+        let matches = value["matches"].as_array().unwrap();
+        assert_eq!(matches.len(), 2);
+        // Files in a directory are searched before its subdirectories.
+        assert_eq!(matches[0]["path"], "src/notes.md");
+        assert_eq!(matches[1]["path"], "src/agent/mod.rs");
+        assert_eq!(matches[1]["line_number"], 2);
+        assert_eq!(value["truncated"], false);
+
+        let result = tool
+            .execute(
+                serde_json::json!({"pattern": "begin_\\w+", "glob": "*.rs", "max_matches": 5}),
+                &ctx,
+            )
+            .await
+            .unwrap();
+        let ToolOutput::Json(value) = result else {
+            panic!("expected JSON output");
+        };
+        assert_eq!(value["matches"].as_array().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_search_files_truncates_and_rejects_bad_pattern() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.txt"), "x\nx\nx\n").unwrap();
+        let mut ctx = test_ctx();
+        ctx.working_directory = dir.path().to_string_lossy().to_string();
+        let tool = SearchFilesTool::new();
+
+        let result = tool
+            .execute(serde_json::json!({"pattern": "x", "max_matches": 2}), &ctx)
+            .await
+            .unwrap();
+        let ToolOutput::Json(value) = result else {
+            panic!("expected JSON output");
+        };
+        assert_eq!(value["matches"].as_array().unwrap().len(), 2);
+        assert_eq!(value["truncated"], true);
+
+        let result = tool
+            .execute(serde_json::json!({"pattern": "("}), &ctx)
+            .await
+            .unwrap();
+        assert!(matches!(result, ToolOutput::Error(_)));
+    }
+
+    #[test]
+    fn test_glob_matches() {
+        assert!(glob_matches("*.rs", "mod.rs"));
+        assert!(!glob_matches("*.rs", "mod.md"));
+        assert!(glob_matches("src/**/*.rs", "src/agent/mod.rs"));
+        assert!(glob_matches("src/**/*.rs", "src/lib.rs"));
+        assert!(!glob_matches("src/*.rs", "src/agent/mod.rs"));
+        assert!(glob_matches("?.txt", "a.txt"));
+    }
+
     #[test]
     fn test_approval_requirements() {
         assert!(!ReadFileTool::new().requires_approval());
         assert!(!ListDirectoryTool::new().requires_approval());
+        assert!(!SearchFilesTool::new().requires_approval());
         assert!(WriteFileTool::new().requires_approval());
         assert!(PatchFileTool::new().requires_approval());
+        assert!(MoveFileTool::new().requires_approval());
+        assert!(DeleteFileTool::new().requires_approval());
+    }
+
+    #[tokio::test]
+    async fn move_file_renames_within_the_working_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("draft.md"), "notes").unwrap();
+        std::fs::write(dir.path().join("taken.md"), "keep").unwrap();
+        let mut ctx = test_ctx();
+        ctx.working_directory = dir.path().to_string_lossy().to_string();
+        let tool = MoveFileTool::new();
+
+        let result = tool
+            .execute(
+                serde_json::json!({"source": "draft.md", "destination": "archive/2026/final.md"}),
+                &ctx,
+            )
+            .await
+            .unwrap();
+        assert!(result.is_success(), "{:?}", result);
+        assert!(!dir.path().join("draft.md").exists());
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("archive/2026/final.md")).unwrap(),
+            "notes"
+        );
+
+        let result = tool
+            .execute(
+                serde_json::json!({"source": "archive/2026/final.md", "destination": "taken.md"}),
+                &ctx,
+            )
+            .await
+            .unwrap();
+        assert!(matches!(result, ToolOutput::Error(ref e) if e.contains("already exists")));
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("taken.md")).unwrap(),
+            "keep"
+        );
+    }
+
+    #[tokio::test]
+    async fn move_and_delete_reject_paths_outside_the_working_directory() {
+        let outer = tempfile::tempdir().unwrap();
+        let sandbox = outer.path().join("sandbox");
+        std::fs::create_dir_all(&sandbox).unwrap();
+        std::fs::write(sandbox.join("inside.txt"), "in").unwrap();
+        std::fs::write(outer.path().join("outside.txt"), "out").unwrap();
+        let mut ctx = test_ctx();
+        ctx.working_directory = sandbox.to_string_lossy().to_string();
+
+        let escape = tool_error(
+            MoveFileTool::new()
+                .execute(
+                    serde_json::json!({"source": "inside.txt", "destination": "../stolen.txt"}),
+                    &ctx,
+                )
+                .await
+                .unwrap(),
+        );
+        assert!(escape.contains("outside the working directory"));
+        assert!(sandbox.join("inside.txt").exists());
+        assert!(!outer.path().join("stolen.txt").exists());
+
+        let absolute = outer.path().join("outside.txt");
+        let escape = tool_error(
+            DeleteFileTool::new()
+                .execute(
+                    serde_json::json!({"path": absolute.to_string_lossy()}),
+                    &ctx,
+                )
+                .await
+                .unwrap(),
+        );
+        assert!(escape.contains("outside the working directory"));
+        assert!(absolute.exists());
+
+        let root = tool_error(
+            DeleteFileTool::new()
+                .execute(serde_json::json!({"path": "sub/../.."}), &ctx)
+                .await
+                .unwrap(),
+        );
+        assert!(root.contains("outside the working directory"));
+    }
+
+    #[tokio::test]
+    async fn confined_tools_reach_a_second_allowed_root() {
+        let outer = tempfile::tempdir().unwrap();
+        let first = outer.path().join("repo-a");
+        let second = outer.path().join("repo-b");
+        std::fs::create_dir_all(&first).unwrap();
+        std::fs::create_dir_all(second.join("docs")).unwrap();
+        std::fs::write(second.join("docs/draft.md"), "notes").unwrap();
+        std::fs::write(second.join("stale.log"), "old").unwrap();
+        let mut ctx = test_ctx();
+        ctx.working_directory = first.to_string_lossy().to_string();
+        ctx.allowed_roots = vec![second.clone()];
+
+        let result = MoveFileTool::new()
+            .execute(
+                serde_json::json!({
+                    "source": second.join("docs/draft.md").to_string_lossy(),
+                    "destination": second.join("docs/final.md").to_string_lossy(),
+                }),
+                &ctx,
+            )
+            .await
+            .unwrap();
+        assert!(result.is_success(), "{:?}", result);
+        assert!(second.join("docs/final.md").exists());
+
+        let result = DeleteFileTool::new()
+            .execute(
+                serde_json::json!({"path": second.join("stale.log").to_string_lossy()}),
+                &ctx,
+            )
+            .await
+            .unwrap();
+        assert!(result.is_success(), "{:?}", result);
+        assert!(!second.join("stale.log").exists());
+        assert!(second.join(TRASH_DIR).is_dir());
+        assert!(!first.join(TRASH_DIR).exists());
+    }
+
+    #[tokio::test]
+    async fn allowed_roots_do_not_admit_escapes_or_the_roots_themselves() {
+        let outer = tempfile::tempdir().unwrap();
+        let first = outer.path().join("repo-a");
+        let second = outer.path().join("repo-b");
+        std::fs::create_dir_all(&first).unwrap();
+        std::fs::create_dir_all(&second).unwrap();
+        std::fs::write(outer.path().join("secret.txt"), "keep").unwrap();
+        let mut ctx = test_ctx();
+        ctx.working_directory = first.to_string_lossy().to_string();
+        ctx.allowed_roots = vec![second.clone()];
+
+        let escape = format!("{}/../secret.txt", second.to_string_lossy());
+        let error = tool_error(
+            DeleteFileTool::new()
+                .execute(serde_json::json!({"path": escape}), &ctx)
+                .await
+                .unwrap(),
+        );
+        assert!(error.contains("outside the working directory and allowed roots"));
+        assert!(outer.path().join("secret.txt").exists());
+
+        let error = tool_error(
+            DeleteFileTool::new()
+                .execute(serde_json::json!({"path": second.to_string_lossy()}), &ctx)
+                .await
+                .unwrap(),
+        );
+        assert!(error.contains("outside the working directory and allowed roots"));
+        assert!(second.is_dir());
+    }
+
+    #[tokio::test]
+    async fn delete_file_moves_into_trash_and_reports_the_trash_path() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("old.log"), "stale").unwrap();
+        let mut ctx = test_ctx();
+        ctx.working_directory = dir.path().to_string_lossy().to_string();
+
+        let result = DeleteFileTool::new()
+            .execute(serde_json::json!({"path": "old.log"}), &ctx)
+            .await
+            .unwrap();
+        let ToolOutput::Json(report) = result else {
+            panic!("Expected Json, got {:?}", result);
+        };
+        assert_eq!(report["hard_deleted"], false);
+        let trash_path = std::path::PathBuf::from(report["trash_path"].as_str().unwrap());
+        assert!(!dir.path().join("old.log").exists());
+        assert!(trash_path.starts_with(dir.path().canonicalize().unwrap().join(TRASH_DIR)));
+        assert_eq!(std::fs::read_to_string(&trash_path).unwrap(), "stale");
+
+        let trash = tool_error(
+            DeleteFileTool::new()
+                .execute(serde_json::json!({"path": ".ponderer"}), &ctx)
+                .await
+                .unwrap(),
+        );
+        assert!(trash.contains("trash directory"));
+    }
+
+    fn tool_error(output: ToolOutput) -> String {
+        match output {
+            ToolOutput::Error(reason) => reason,
+            other => panic!("Expected Error, got {:?}", other),
+        }
     }
 }
@@ -0,0 +1,232 @@
+//! Operator focus-session tool backed by the `agent_state` table.
+//!
+//! One tool, `focus_session`, with three actions:
+//! - `start`: begin (or replace) a session lasting `minutes` (default 50).
+//! - `stop`: end the running session early, without a check-in.
+//! - `status`: report the running session, if any.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::Utc;
+use serde_json::{json, Value};
+
+use crate::agent::focus::{
+    clear_focus_session, load_focus_session, save_focus_session, FocusKind, FocusSession,
+    DEFAULT_FOCUS_MINUTES, MAX_FOCUS_MINUTES,
+};
+use crate::config::AgentConfig;
+use crate::database::AgentDatabase;
+
+use super::{Tool, ToolCategory, ToolContext, ToolOutput};
+
+fn open_database() -> Result<AgentDatabase> {
+    let config = AgentConfig::load();
+    AgentDatabase::new(&config.database_path).with_context(|| {
+        format!(
+            "Failed to open database at '{}' for focus session tool",
+            config.database_path
+        )
+    })
+}
+
+/// Builds the session for action `start` from `minutes`, `kind`, and `check_in`.
+fn session_from_params(params: &Value) -> std::result::Result<FocusSession, String> {
+    let minutes = match params.get("minutes") {
+        None | Some(Value::Null) => DEFAULT_FOCUS_MINUTES,
+        Some(value) => match value.as_u64() {
+            Some(minutes) if (1..=MAX_FOCUS_MINUTES).contains(&minutes) => minutes,
+            _ => {
+                return Err(format!(
+                    "'minutes' must be a whole number from 1 to {}",
+                    MAX_FOCUS_MINUTES
+                ))
+            }
+        },
+    };
+    let kind = match params.get("kind").and_then(Value::as_str) {
+        None => FocusKind::Deep,
+        Some(raw) => FocusKind::parse(raw)
+            .ok_or_else(|| format!("Unknown kind '{}'; expected 'deep' or 'light'", raw))?,
+    };
+    let check_in = params
+        .get("check_in")
+        .and_then(Value::as_bool)
+        .unwrap_or(true);
+    Ok(FocusSession::start(Utc::now(), minutes, kind, check_in))
+}
+
+fn session_json(session: &FocusSession) -> Value {
+    json!({
+        "started_at": session.started_at,
+        "ends_at": session.ends_at,
+        "kind": session.kind,
+        "check_in": session.check_in,
+        "remaining_minutes": session.remaining_minutes(Utc::now()),
+    })
+}
+
+pub struct FocusSessionTool;
+
+impl FocusSessionTool {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl Tool for FocusSessionTool {
+    fn name(&self) -> &str {
+        "focus_session"
+    }
+
+    fn description(&self) -> &str {
+        "Start, stop, or check an operator focus session. Use 'start' when the operator asks for focus time (e.g. \"start a 50-minute focus session\"): proactive chat posts are held and orientation runs less often until it ends, then a short check-in is posted. 'deep' (default) also holds reminders and Interrupt posts; 'light' only holds Surface posts and social check-ins. 'stop' ends it early; 'status' reports the running session."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "action": {
+                    "type": "string",
+                    "enum": ["start", "stop", "status"],
+                    "description": "What to do (default: status)"
+                },
+                "minutes": {
+                    "type": "integer",
+                    "description": "Session length for 'start' (1-480, default 50)"
+                },
+                "kind": {
+                    "type": "string",
+                    "enum": ["deep", "light"],
+                    "description": "How much to hold back during the session (default: deep)"
+                },
+                "check_in": {
+                    "type": "boolean",
+                    "description": "Post a chat check-in when the session ends (default: true)"
+                }
+            }
+        })
+    }
+
+    async fn execute(&self, params: Value, _ctx: &ToolContext) -> Result<ToolOutput> {
+        let action = params
+            .get("action")
+            .and_then(Value::as_str)
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .unwrap_or("status");
+
+        let db = match open_database() {
+            Ok(db) => db,
+            Err(error) => return Ok(ToolOutput::Error(error.to_string())),
+        };
+        let current = match load_focus_session(&db) {
+            Ok(session) => session.filter(|session| session.is_active(Utc::now())),
+            Err(error) => {
+                return Ok(ToolOutput::Error(format!(
+                    "Failed to read focus session: {}",
+                    error
+                )))
+            }
+        };
+
+        match action {
+            "start" => {
+                let session = match session_from_params(&params) {
+                    Ok(session) => session,
+                    Err(reason) => return Ok(ToolOutput::Error(reason)),
+                };
+                if let Err(error) = save_focus_session(&db, &session) {
+                    return Ok(ToolOutput::Error(format!(
+                        "Failed to start focus session: {}",
+                        error
+                    )));
+                }
+                Ok(ToolOutput::Json(json!({
+                    "status": "ok",
+                    "replaced_running_session": current.is_some(),
+                    "session": session_json(&session),
+                })))
+            }
+            "stop" => {
+                let Some(session) = current else {
+                    return Ok(ToolOutput::Error("No focus session is running".to_string()));
+                };
+                if let Err(error) = clear_focus_session(&db) {
+                    return Ok(ToolOutput::Error(format!(
+                        "Failed to stop focus session: {}",
+                        error
+                    )));
+                }
+                Ok(ToolOutput::Json(json!({
+                    "status": "ok",
+                    "stopped": true,
+                    "session": session_json(&session),
+                })))
+            }
+            "status" => Ok(ToolOutput::Json(json!({
+                "status": "ok",
+                "active": current.is_some(),
+                "session": current.as_ref().map(session_json),
+            }))),
+            other => Ok(ToolOutput::Error(format!(
+                "Unknown action '{}'; expected 'start', 'stop', or 'status'",
+                other
+            ))),
+        }
+    }
+
+    /// Starting or stopping a session changes what the operator hears, so
+    /// autonomous calls need approval.
+    fn requires_approval(&self) -> bool {
+        true
+    }
+
+    fn auto_approves(&self, params: &Value) -> bool {
+        params
+            .get("action")
+            .and_then(Value::as_str)
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .is_none_or(|action| action == "status")
+    }
+
+    fn category(&self) -> ToolCategory {
+        ToolCategory::Memory
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn start_params_default_to_a_deep_fifty_minute_session() {
+        let session = session_from_params(&json!({})).expect("defaults");
+        assert_eq!(session.duration_minutes(), 50);
+        assert_eq!(session.kind, FocusKind::Deep);
+        assert!(session.check_in);
+
+        let light =
+            session_from_params(&json!({"minutes": 25, "kind": "Light", "check_in": false}))
+                .expect("light session");
+        assert_eq!(light.duration_minutes(), 25);
+        assert_eq!(light.kind, FocusKind::Light);
+        assert!(!light.check_in);
+
+        assert!(session_from_params(&json!({"minutes": 0})).is_err());
+        assert!(session_from_params(&json!({"minutes": 481})).is_err());
+        assert!(session_from_params(&json!({"kind": "nap"})).is_err());
+    }
+
+    #[test]
+    fn only_status_skips_the_autonomous_approval_gate() {
+        let tool = FocusSessionTool::new();
+        assert!(tool.requires_approval());
+        assert!(tool.auto_approves(&json!({})));
+        assert!(tool.auto_approves(&json!({"action": "status"})));
+        assert!(!tool.auto_approves(&json!({"action": "start", "minutes": 480})));
+        assert!(!tool.auto_approves(&json!({"action": "stop"})));
+    }
+}
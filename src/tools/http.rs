@@ -1,7 +1,10 @@
-//! HTTP/web fetch tool with safety guardrails.
+//! HTTP/web fetch tools with safety guardrails.
 //!
-//! Supports GET/POST/PUT/DELETE, blocks localhost/private destinations by default,
-//! runs outbound leak checks, enforces a timeout, and truncates response bodies.
+//! `http_fetch` supports GET/POST/PUT/DELETE, blocks localhost/private destinations
+//! by default, runs outbound leak checks, enforces a timeout, and truncates response
+//! bodies. `http_get` is the read-only variant: it returns the body itself as text
+//! or JSON, validates every redirect hop, and takes its private-host override from
+//! config rather than from the model.
 
 use anyhow::{Context, Result};
 use async_trait::async_trait;
@@ -12,7 +15,10 @@ use std::collections::BTreeMap;
 use std::net::IpAddr;
 use std::time::Duration;
 
-use crate::http_client::build_http_client_with_timeout;
+use crate::config::AgentConfig;
+use crate::http_client::{
+    build_http_client_with_timeout, build_http_client_without_redirects, is_private_or_local_ip,
+};
 
 use super::safety::{detect_leaks, SafetyVerdict};
 use super::{Tool, ToolCategory, ToolContext, ToolOutput};
@@ -21,6 +27,9 @@ const DEFAULT_TIMEOUT_SECS: u64 = 30;
 const MAX_TIMEOUT_SECS: u64 = 30;
 const DEFAULT_MAX_RESPONSE_BYTES: usize = 64 * 1024;
 const MAX_MAX_RESPONSE_BYTES: usize = 512 * 1024;
+const HTTP_GET_TIMEOUT_SECS: u64 = 20;
+const HTTP_GET_MAX_RESPONSE_BYTES: usize = 256 * 1024;
+const HTTP_GET_MAX_REDIRECTS: usize = 5;
 
 pub struct HttpFetchTool;
 
@@ -209,31 +218,8 @@ impl Tool for HttpFetchTool {
             .cloned()
             .unwrap_or_else(|| "application/octet-stream".to_string());
 
-        let mut body_bytes: Vec<u8> = Vec::new();
-        let mut total_read: usize = 0;
-        let mut truncated = false;
-        while let Some(chunk) = response
-            .chunk()
-            .await
-            .context("Failed reading response body")?
-        {
-            total_read += chunk.len();
-            if body_bytes.len() < max_response_bytes {
-                let remaining = max_response_bytes - body_bytes.len();
-                let take = min(remaining, chunk.len());
-                body_bytes.extend_from_slice(&chunk[..take]);
-                if take < chunk.len() {
-                    truncated = true;
-                    break;
-                }
-            } else {
-                truncated = true;
-                break;
-            }
-        }
-        if total_read > body_bytes.len() {
-            truncated = true;
-        }
+        let (body_bytes, total_read, truncated) =
+            read_body_capped(&mut response, max_response_bytes).await?;
 
         let body_preview = String::from_utf8_lossy(&body_bytes).to_string();
 
@@ -267,6 +253,244 @@ impl Tool for HttpFetchTool {
     }
 }
 
+pub struct HttpGetTool;
+
+impl HttpGetTool {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl Tool for HttpGetTool {
+    fn name(&self) -> &str {
+        "http_get"
+    }
+
+    fn description(&self) -> &str {
+        "Fetch a URL with a read-only GET and return its body: parsed JSON for JSON responses, text otherwise. Large bodies are truncated."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "url": {
+                    "type": "string",
+                    "description": "http:// or https:// URL to fetch"
+                },
+                "headers": {
+                    "type": "object",
+                    "description": "Optional HTTP headers as key/value strings",
+                    "additionalProperties": { "type": "string" }
+                }
+            },
+            "required": ["url"]
+        })
+    }
+
+    async fn execute(&self, params: Value, _ctx: &ToolContext) -> Result<ToolOutput> {
+        let url_input = match params.get("url").and_then(Value::as_str).map(str::trim) {
+            Some(v) if !v.is_empty() => v,
+            _ => {
+                return Ok(ToolOutput::Error(
+                    "Missing required 'url' parameter".to_string(),
+                ))
+            }
+        };
+        let url = match Url::parse(url_input) {
+            Ok(url) => url,
+            Err(e) => {
+                return Ok(ToolOutput::Error(format!(
+                    "Invalid URL '{}': {}",
+                    url_input, e
+                )))
+            }
+        };
+        let headers = match parse_headers(params.get("headers")) {
+            Ok(headers) => headers,
+            Err(e) => return Ok(ToolOutput::Error(e)),
+        };
+        let allow_private_hosts = AgentConfig::load().http_allow_private_hosts;
+
+        let client =
+            build_http_client_without_redirects(Some(Duration::from_secs(HTTP_GET_TIMEOUT_SECS)));
+        let (final_url, mut response) = match get_following_redirects(
+            &client,
+            url,
+            &headers,
+            allow_private_hosts,
+            HTTP_GET_MAX_REDIRECTS,
+        )
+        .await
+        {
+            Ok(result) => result,
+            Err(reason) => return Ok(ToolOutput::Error(reason)),
+        };
+
+        let status = response.status();
+        let content_type = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or("")
+            .to_ascii_lowercase();
+        if !is_textual_content_type(&content_type) {
+            return Ok(ToolOutput::Error(format!(
+                "Refusing non-text response from {} (content-type '{}')",
+                final_url, content_type
+            )));
+        }
+
+        let (body_bytes, _, truncated) =
+            match read_body_capped(&mut response, HTTP_GET_MAX_RESPONSE_BYTES).await {
+                Ok(body) => body,
+                Err(e) => return Ok(ToolOutput::Error(format!("{:#}", e))),
+            };
+        let body = String::from_utf8_lossy(&body_bytes).to_string();
+
+        if !status.is_success() {
+            return Ok(ToolOutput::Error(format!(
+                "GET {} returned HTTP {}: {}",
+                final_url,
+                status,
+                body.chars().take(500).collect::<String>()
+            )));
+        }
+
+        if !truncated && content_type.contains("json") {
+            if let Ok(value) = serde_json::from_str::<Value>(&body) {
+                return Ok(ToolOutput::Json(value));
+            }
+        }
+        if truncated {
+            return Ok(ToolOutput::Text(format!(
+                "{}\n\n[response truncated at {} bytes]",
+                body, HTTP_GET_MAX_RESPONSE_BYTES
+            )));
+        }
+        Ok(ToolOutput::Text(body))
+    }
+
+    fn requires_approval(&self) -> bool {
+        true
+    }
+
+    fn category(&self) -> ToolCategory {
+        ToolCategory::Network
+    }
+}
+
+/// Credential headers dropped when a redirect leaves the original origin.
+const CROSS_ORIGIN_STRIPPED_HEADERS: &[&str] = &["authorization", "cookie", "proxy-authorization"];
+
+/// Issues a GET and follows up to `max_redirects` 3xx hops by hand, running
+/// the leak and destination checks against each hop. Credential headers are
+/// not carried to another origin. Returns the final URL and response.
+async fn get_following_redirects(
+    client: &reqwest::Client,
+    mut url: Url,
+    headers: &BTreeMap<String, String>,
+    allow_private_hosts: bool,
+    max_redirects: usize,
+) -> std::result::Result<(Url, reqwest::Response), String> {
+    let origin = url.origin();
+    let mut headers = headers.clone();
+    for _ in 0..=max_redirects {
+        if !matches!(url.scheme(), "http" | "https") {
+            return Err("Only http:// and https:// URLs are supported".to_string());
+        }
+        if url.origin() != origin {
+            headers.retain(|key, _| {
+                !CROSS_ORIGIN_STRIPPED_HEADERS.contains(&key.to_ascii_lowercase().as_str())
+            });
+        }
+        check_outbound_for_leaks(&url, &headers, None, None)?;
+        validate_destination_safety(&url, allow_private_hosts).await?;
+
+        let mut req = client.get(url.clone());
+        for (key, value) in &headers {
+            req = req.header(key, value);
+        }
+        let response = req
+            .send()
+            .await
+            .map_err(|e| format!("HTTP request failed: {}", e))?;
+        if !response.status().is_redirection() {
+            return Ok((url, response));
+        }
+        let Some(location) = response
+            .headers()
+            .get(reqwest::header::LOCATION)
+            .and_then(|value| value.to_str().ok())
+        else {
+            return Ok((url, response));
+        };
+        url = url
+            .join(location)
+            .map_err(|e| format!("Invalid redirect location '{}': {}", location, e))?;
+    }
+    Err(format!(
+        "Too many redirects (more than {}) starting from the requested URL",
+        max_redirects
+    ))
+}
+
+/// Missing content types are treated as text; the body is decoded lossily.
+fn is_textual_content_type(content_type: &str) -> bool {
+    let mime = content_type.split(';').next().unwrap_or("").trim();
+    mime.is_empty()
+        || mime.starts_with("text/")
+        || mime.ends_with("+json")
+        || mime.ends_with("+xml")
+        || matches!(
+            mime,
+            "application/json"
+                | "application/xml"
+                | "application/javascript"
+                | "application/x-javascript"
+                | "application/ecmascript"
+                | "application/x-www-form-urlencoded"
+                | "application/yaml"
+                | "application/x-yaml"
+                | "application/toml"
+        )
+}
+
+/// Reads at most `max_bytes` of the body, returning the bytes kept, the bytes
+/// read, and whether the body was cut short.
+async fn read_body_capped(
+    response: &mut reqwest::Response,
+    max_bytes: usize,
+) -> Result<(Vec<u8>, usize, bool)> {
+    let mut body_bytes: Vec<u8> = Vec::new();
+    let mut total_read: usize = 0;
+    let mut truncated = false;
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .context("Failed reading response body")?
+    {
+        total_read += chunk.len();
+        if body_bytes.len() < max_bytes {
+            let remaining = max_bytes - body_bytes.len();
+            let take = min(remaining, chunk.len());
+            body_bytes.extend_from_slice(&chunk[..take]);
+            if take < chunk.len() {
+                truncated = true;
+                break;
+            }
+        } else {
+            truncated = true;
+            break;
+        }
+    }
+    if total_read > body_bytes.len() {
+        truncated = true;
+    }
+    Ok((body_bytes, total_read, truncated))
+}
+
 fn parse_headers(value: Option<&Value>) -> std::result::Result<BTreeMap<String, String>, String> {
     let mut headers = BTreeMap::new();
     let Some(headers_value) = value else {
@@ -335,27 +559,6 @@ async fn validate_destination_safety(
     Ok(warnings)
 }
 
-fn is_private_or_local_ip(ip: IpAddr) -> bool {
-    match ip {
-        IpAddr::V4(v4) => {
-            v4.is_private()
-                || v4.is_loopback()
-                || v4.is_link_local()
-                || v4.is_broadcast()
-                || v4.is_documentation()
-                || v4.is_unspecified()
-                || v4.is_multicast()
-        }
-        IpAddr::V6(v6) => {
-            v6.is_loopback()
-                || v6.is_unspecified()
-                || v6.is_multicast()
-                || v6.is_unique_local()
-                || v6.is_unicast_link_local()
-        }
-    }
-}
-
 fn check_outbound_for_leaks(
     url: &Url,
     headers: &BTreeMap<String, String>,
@@ -411,6 +614,114 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn textual_content_types_are_accepted() {
+        assert!(is_textual_content_type("text/html; charset=utf-8"));
+        assert!(is_textual_content_type("application/json"));
+        assert!(is_textual_content_type("application/vnd.github+json"));
+        assert!(is_textual_content_type(""));
+        assert!(!is_textual_content_type("image/png"));
+        assert!(!is_textual_content_type("application/octet-stream"));
+    }
+
+    #[tokio::test]
+    async fn redirect_chains_are_capped() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        use tokio::net::TcpListener;
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind");
+        let address = listener.local_addr().expect("local address");
+        let server = tokio::spawn(async move {
+            loop {
+                let Ok((mut stream, _)) = listener.accept().await else {
+                    break;
+                };
+                let mut request = [0_u8; 1024];
+                let _ = stream.read(&mut request).await;
+                let _ = stream
+                    .write_all(
+                        b"HTTP/1.1 302 Found\r\nLocation: /again\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                    )
+                    .await;
+            }
+        });
+
+        let client = build_http_client_without_redirects(Some(Duration::from_secs(5)));
+        let url = Url::parse(&format!("http://{address}/start")).unwrap();
+        let headers = BTreeMap::new();
+
+        let blocked = get_following_redirects(&client, url.clone(), &headers, false, 3)
+            .await
+            .expect_err("loopback is blocked by default");
+        assert!(blocked.contains("Blocked private/local IP"));
+
+        let looped = get_following_redirects(&client, url, &headers, true, 3)
+            .await
+            .expect_err("redirect loop is capped");
+        assert!(looped.contains("Too many redirects"));
+        server.abort();
+    }
+
+    #[tokio::test]
+    async fn redirects_drop_credentials_across_origins_and_recheck_leaks() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        use tokio::net::TcpListener;
+
+        let landing = TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind landing");
+        let landing_address = landing.local_addr().expect("landing address");
+        let landing_server = tokio::spawn(async move {
+            let (mut stream, _) = landing.accept().await.expect("accept");
+            let mut request = [0_u8; 2048];
+            let read = stream.read(&mut request).await.expect("read");
+            let _ = stream
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\nok")
+                .await;
+            String::from_utf8_lossy(&request[..read]).to_ascii_lowercase()
+        });
+
+        let start = TcpListener::bind("127.0.0.1:0").await.expect("bind start");
+        let start_address = start.local_addr().expect("start address");
+        let start_server = tokio::spawn(async move {
+            let locations = [
+                format!("http://{landing_address}/landing"),
+                "/next?key=sk-abcdefghijklmnopqrstuvwxyz1234567890".to_string(),
+            ];
+            for location in locations {
+                let Ok((mut stream, _)) = start.accept().await else {
+                    break;
+                };
+                let mut request = [0_u8; 1024];
+                let _ = stream.read(&mut request).await;
+                let response = format!(
+                    "HTTP/1.1 302 Found\r\nLocation: {location}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                );
+                let _ = stream.write_all(response.as_bytes()).await;
+            }
+        });
+
+        let client = build_http_client_without_redirects(Some(Duration::from_secs(5)));
+        let url = Url::parse(&format!("http://{start_address}/start")).unwrap();
+        let headers = BTreeMap::from([
+            ("Authorization".to_string(), "Bearer local".to_string()),
+            ("Accept".to_string(), "text/plain".to_string()),
+        ]);
+        let (final_url, _) = get_following_redirects(&client, url.clone(), &headers, true, 3)
+            .await
+            .expect("cross-origin redirect");
+        assert_eq!(final_url.port(), Some(landing_address.port()));
+        let landing_request = landing_server.await.expect("landing request");
+        assert!(landing_request.contains("accept: text/plain"));
+        assert!(!landing_request.contains("authorization"));
+
+        let leaked = get_following_redirects(&client, url, &BTreeMap::new(), true, 3)
+            .await
+            .expect_err("secret in the redirect target is blocked");
+        assert!(leaked.starts_with("Outbound payload blocked"), "{leaked}");
+        start_server.abort();
+    }
+
     #[test]
     fn parse_headers_requires_string_values() {
         let bad = json!({"x-test": 5});
@@ -1,7 +1,9 @@
 //! Memory-oriented tools for agentic recall and note-taking.
 //!
 //! - `search_memory`: query persisted working memory entries.
+//! - `search_journal`: full-text search over the agent's own journal entries.
 //! - `write_memory`: create or update a working-memory note.
+//! - `pin_concern`: pin or unpin a tracked concern so it stays at the top of priority context.
 //! - `write_session_handoff`: write a cross-session continuity note injected at the top of next-session context.
 //! - `private_chat_mode`: inspect/set/toggle runtime private-chat mode (`agentic` vs `direct`).
 //! - `scratch_note`: read/write/append/clear a task-scoped scratchpad (ephemeral, cleared when task is done).
@@ -9,18 +11,40 @@
 
 use anyhow::{Context, Result};
 use async_trait::async_trait;
+use chrono::{Duration as ChronoDuration, Utc};
 use serde_json::{json, Value};
 
+use crate::agent::concerns::{Concern, ConcernsManager};
 use crate::agent::AgentEvent;
 use crate::config::{
     normalize_private_chat_mode, AgentConfig, PRIVATE_CHAT_MODE_AGENTIC, PRIVATE_CHAT_MODE_DIRECT,
 };
 use crate::database::AgentDatabase;
+use crate::llm_client::LlmClient;
+use crate::memory::candidate_backends::{embedding_text, rank_by_embedding_above};
+use crate::memory::WorkingMemoryEntry;
 
 use super::{Tool, ToolCategory, ToolContext, ToolOutput};
 
 const DEFAULT_SEARCH_LIMIT: usize = 8;
 const MAX_SEARCH_LIMIT: usize = 50;
+/// Most recent entries considered per semantic search request.
+const MAX_SEMANTIC_CANDIDATES: usize = 256;
+/// Cosine similarity below which a semantic match is treated as unrelated.
+const MIN_SEMANTIC_SIMILARITY: f32 = 0.3;
+/// Longest `write_memory` TTL (one year).
+const MAX_MEMORY_TTL_HOURS: i64 = 24 * 365;
+
+/// Reads an optional array-of-strings parameter, skipping non-string items.
+fn string_list_param(params: &Value, name: &str) -> Option<Vec<String>> {
+    params.get(name).and_then(Value::as_array).map(|items| {
+        items
+            .iter()
+            .filter_map(Value::as_str)
+            .map(str::to_string)
+            .collect()
+    })
+}
 
 fn open_database() -> Result<AgentDatabase> {
     let config = AgentConfig::load();
@@ -47,7 +71,7 @@ impl Tool for MemorySearchTool {
     }
 
     fn description(&self) -> &str {
-        "Search persistent working memory notes by query text."
+        "Search persistent working memory notes by query text, optionally restricted to notes carrying all given tags."
     }
 
     fn parameters_schema(&self) -> Value {
@@ -61,6 +85,15 @@ impl Tool for MemorySearchTool {
                 "limit": {
                     "type": "integer",
                     "description": "Maximum number of matches to return (1-50)"
+                },
+                "semantic": {
+                    "type": "boolean",
+                    "description": "Rank by embedding similarity when an embedding model is configured (default true)"
+                },
+                "tags": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Only return notes carrying every one of these tags (e.g. [\"project:ponderer\"])"
                 }
             },
             "required": ["query"]
@@ -85,20 +118,53 @@ impl Tool for MemorySearchTool {
             .map(|v| (v as usize).clamp(1, MAX_SEARCH_LIMIT))
             .unwrap_or(DEFAULT_SEARCH_LIMIT);
 
+        let semantic = params
+            .get("semantic")
+            .and_then(Value::as_bool)
+            .unwrap_or(true);
+        let tags = string_list_param(&params, "tags").unwrap_or_default();
+
+        let config = AgentConfig::load();
         let db = match open_database() {
             Ok(db) => db,
             Err(e) => return Ok(ToolOutput::Error(e.to_string())),
         };
-        let matches = match db.search_working_memory(query, limit) {
-            Ok(items) => items,
-            Err(e) => return Ok(ToolOutput::Error(format!("Memory search failed: {}", e))),
+
+        let semantic_matches = match config.embedding_model.as_deref().map(str::trim) {
+            Some(model) if semantic && !model.is_empty() => {
+                match semantic_search(&config, model, &db, query, &tags, limit).await {
+                    Ok(items) if !items.is_empty() => Some(items),
+                    Ok(_) => None,
+                    Err(e) => {
+                        tracing::warn!("Semantic memory search failed, using lexical: {}", e);
+                        None
+                    }
+                }
+            }
+            _ => None,
+        };
+        let ranking = if semantic_matches.is_some() {
+            "semantic"
+        } else {
+            "lexical"
+        };
+        let matches = match semantic_matches {
+            Some(items) => items,
+            None => match db.search_working_memory_with_tags(query, &tags, limit) {
+                Ok(items) => items,
+                Err(e) => return Ok(ToolOutput::Error(format!("Memory search failed: {}", e))),
+            },
         };
+        let tag_map = db.get_working_memory_tag_map().unwrap_or_default();
 
         Ok(ToolOutput::Json(json!({
             "status": "ok",
             "query": query,
+            "ranking": ranking,
+            "tags": tags,
             "match_count": matches.len(),
             "matches": matches.into_iter().map(|entry| json!({
+                "tags": tag_map.get(&entry.key).cloned().unwrap_or_default(),
                 "key": entry.key,
                 "content": entry.content,
                 "updated_at": entry.updated_at.to_rfc3339(),
@@ -111,6 +177,149 @@ impl Tool for MemorySearchTool {
     }
 }
 
+/// Embed the query, plus any recent entry without a current stored embedding,
+/// with the configured embeddings endpoint, then rank with the same cosine
+/// scoring `vector_v4` uses. New vectors are stored for later searches.
+async fn semantic_search(
+    config: &AgentConfig,
+    model: &str,
+    db: &AgentDatabase,
+    query: &str,
+    tags: &[String],
+    limit: usize,
+) -> Result<Vec<WorkingMemoryEntry>> {
+    let mut entries = db.get_working_memory_with_tags(tags)?;
+    entries.truncate(MAX_SEMANTIC_CANDIDATES);
+    if entries.is_empty() {
+        return Ok(entries);
+    }
+    let mut stored = db.working_memory_embeddings(&entries, model)?;
+    let missing: Vec<&WorkingMemoryEntry> = entries
+        .iter()
+        .filter(|entry| !stored.contains_key(&entry.key))
+        .collect();
+
+    let client = LlmClient::new(
+        config.llm_api_url.clone(),
+        config.llm_api_key.clone().unwrap_or_default(),
+        config.llm_model.clone(),
+    )
+    .with_embedding_endpoint(config.embedding_api_url.clone())
+    .with_embedding_api_key(config.embedding_api_key.clone())
+    .with_embedding_batch_size(config.embedding_batch_size);
+    let mut inputs = Vec::with_capacity(missing.len() + 1);
+    inputs.push(query.to_string());
+    inputs.extend(
+        missing
+            .iter()
+            .map(|entry| embedding_text(&entry.key, &entry.content)),
+    );
+
+    let mut vectors = client.embed(&inputs, model).await?.into_iter();
+    let query_vector = vectors.next().unwrap_or_default();
+    for (entry, vector) in missing.into_iter().zip(vectors) {
+        if let Err(e) = db.store_working_memory_embedding(entry, model, &vector) {
+            tracing::warn!("Failed to store memory embedding: {}", e);
+        }
+        stored.insert(entry.key.clone(), vector);
+    }
+
+    let rows = entries
+        .into_iter()
+        .filter_map(|entry| {
+            let vector = stored.remove(&entry.key)?;
+            Some((entry, vector))
+        })
+        .collect();
+    Ok(rank_by_embedding_above(
+        &query_vector,
+        rows,
+        limit,
+        MIN_SEMANTIC_SIMILARITY,
+    ))
+}
+
+pub struct JournalSearchTool;
+
+impl JournalSearchTool {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl Tool for JournalSearchTool {
+    fn name(&self) -> &str {
+        "search_journal"
+    }
+
+    fn description(&self) -> &str {
+        "Search your own past journal entries and reflections by keywords, best matches first."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "query": {
+                    "type": "string",
+                    "description": "Words to look for in journal content and triggers"
+                },
+                "limit": {
+                    "type": "integer",
+                    "description": "Maximum number of entries to return (1-50)"
+                }
+            },
+            "required": ["query"]
+        })
+    }
+
+    async fn execute(&self, params: Value, _ctx: &ToolContext) -> Result<ToolOutput> {
+        let query = params
+            .get("query")
+            .and_then(Value::as_str)
+            .map(str::trim)
+            .unwrap_or("");
+        if query.is_empty() {
+            return Ok(ToolOutput::Error(
+                "Missing required 'query' parameter".to_string(),
+            ));
+        }
+
+        let limit = params
+            .get("limit")
+            .and_then(Value::as_u64)
+            .map(|v| (v as usize).clamp(1, MAX_SEARCH_LIMIT))
+            .unwrap_or(DEFAULT_SEARCH_LIMIT);
+
+        let db = match open_database() {
+            Ok(db) => db,
+            Err(e) => return Ok(ToolOutput::Error(e.to_string())),
+        };
+        let entries = match db.search_journal(query, limit) {
+            Ok(entries) => entries,
+            Err(e) => return Ok(ToolOutput::Error(format!("Journal search failed: {}", e))),
+        };
+
+        Ok(ToolOutput::Json(json!({
+            "status": "ok",
+            "query": query,
+            "match_count": entries.len(),
+            "entries": entries.into_iter().map(|entry| json!({
+                "id": entry.id,
+                "timestamp": entry.timestamp.to_rfc3339(),
+                "entry_type": entry.entry_type.as_db_str(),
+                "content": entry.content,
+                "trigger": entry.context.trigger,
+            })).collect::<Vec<_>>()
+        })))
+    }
+
+    fn category(&self) -> ToolCategory {
+        ToolCategory::Memory
+    }
+}
+
 pub struct MemoryWriteTool;
 
 impl MemoryWriteTool {
@@ -126,7 +335,8 @@ impl Tool for MemoryWriteTool {
     }
 
     fn description(&self) -> &str {
-        "Write a persistent memory note by key. Supports replace or append mode."
+        "Write a persistent memory note by key. Supports replace or append mode. \
+         Set ttl_hours for ephemeral notes that should disappear after a while."
     }
 
     fn parameters_schema(&self) -> Value {
@@ -145,6 +355,15 @@ impl Tool for MemoryWriteTool {
                     "type": "string",
                     "enum": ["replace", "append"],
                     "description": "replace overwrites; append adds content to the existing note"
+                },
+                "ttl_hours": {
+                    "type": "integer",
+                    "description": "Hours until the note expires (1-8760). Omit to keep it permanently; each write resets the expiry."
+                },
+                "tags": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Tags for the note (e.g. [\"project:ponderer\"]); replaces existing tags. Omit to keep them, or pass [] to clear."
                 }
             },
             "required": ["key", "content"]
@@ -178,6 +397,21 @@ impl Tool for MemoryWriteTool {
                 "Invalid 'mode' parameter. Use 'replace' or 'append'.".to_string(),
             ));
         }
+        let expires_at = match params.get("ttl_hours").filter(|value| !value.is_null()) {
+            None => None,
+            Some(value) => match value
+                .as_i64()
+                .filter(|hours| (1..=MAX_MEMORY_TTL_HOURS).contains(hours))
+            {
+                Some(hours) => Some(Utc::now() + ChronoDuration::hours(hours)),
+                None => {
+                    return Ok(ToolOutput::Error(format!(
+                        "Invalid 'ttl_hours' parameter. Use a whole number of hours from 1 to {}.",
+                        MAX_MEMORY_TTL_HOURS
+                    )))
+                }
+            },
+        };
 
         let db = match open_database() {
             Ok(db) => db,
@@ -198,9 +432,15 @@ impl Tool for MemoryWriteTool {
             content.to_string()
         };
 
-        if let Err(e) = db.set_working_memory(key, &final_content) {
+        if let Err(e) = db.set_working_memory_with_expiry(key, &final_content, expires_at) {
             return Ok(ToolOutput::Error(format!("Failed to write memory: {}", e)));
         }
+        if let Some(tags) = string_list_param(&params, "tags") {
+            if let Err(e) = db.set_working_memory_tags(key, &tags) {
+                return Ok(ToolOutput::Error(format!("Failed to tag memory: {}", e)));
+            }
+        }
+        let tags = db.get_working_memory_tags(key).unwrap_or_default();
         if let Err(e) =
             db.append_daily_activity_log(&format!("write_memory key='{}' mode='{}'", key, mode))
         {
@@ -212,6 +452,8 @@ impl Tool for MemoryWriteTool {
             "key": key,
             "mode": mode,
             "bytes_written": final_content.len(),
+            "expires_at": expires_at.map(|at| at.to_rfc3339()),
+            "tags": tags,
         })))
     }
 
@@ -220,6 +462,143 @@ impl Tool for MemoryWriteTool {
     }
 }
 
+pub struct PinConcernTool;
+
+impl PinConcernTool {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl Tool for PinConcernTool {
+    fn name(&self) -> &str {
+        "pin_concern"
+    }
+
+    fn description(&self) -> &str {
+        "Pin or unpin one of your tracked concerns. Pinned concerns never decay to dormant and are always listed first in concern priority context."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "concern": {
+                    "type": "string",
+                    "description": "Concern id, or words from its summary (e.g. 'tax filing')"
+                },
+                "pinned": {
+                    "type": "boolean",
+                    "description": "true to pin (default), false to unpin"
+                }
+            },
+            "required": ["concern"]
+        })
+    }
+
+    async fn execute(&self, params: Value, _ctx: &ToolContext) -> Result<ToolOutput> {
+        let reference = params
+            .get("concern")
+            .and_then(Value::as_str)
+            .map(str::trim)
+            .unwrap_or("");
+        if reference.is_empty() {
+            return Ok(ToolOutput::Error(
+                "Missing required 'concern' parameter".to_string(),
+            ));
+        }
+        let pinned = params
+            .get("pinned")
+            .and_then(Value::as_bool)
+            .unwrap_or(true);
+
+        let db = match open_database() {
+            Ok(db) => db,
+            Err(e) => return Ok(ToolOutput::Error(e.to_string())),
+        };
+        let concerns = match db.get_all_concerns() {
+            Ok(concerns) => concerns,
+            Err(e) => return Ok(ToolOutput::Error(format!("Failed to load concerns: {}", e))),
+        };
+        let target = match resolve_concern_reference(&concerns, reference) {
+            Ok(concern) => concern,
+            Err(reason) => return Ok(ToolOutput::Error(reason)),
+        };
+
+        match ConcernsManager::pin_concern(&db, &target.id, pinned) {
+            Ok(Some(concern)) => Ok(ToolOutput::Json(json!({
+                "status": "ok",
+                "id": concern.id,
+                "summary": concern.summary,
+                "pinned": concern.pinned,
+                "salience": concern.salience.as_db_str(),
+            }))),
+            Ok(None) => Ok(ToolOutput::Error(format!(
+                "Concern '{}' no longer exists",
+                target.id
+            ))),
+            Err(e) => Ok(ToolOutput::Error(format!("Failed to pin concern: {}", e))),
+        }
+    }
+
+    fn category(&self) -> ToolCategory {
+        ToolCategory::Memory
+    }
+}
+
+/// Resolves a concern by exact id, then by summary containing the reference,
+/// then by summary containing every word of the reference.
+fn resolve_concern_reference<'a>(
+    concerns: &'a [Concern],
+    reference: &str,
+) -> std::result::Result<&'a Concern, String> {
+    if let Some(concern) = concerns.iter().find(|concern| concern.id == reference) {
+        return Ok(concern);
+    }
+
+    let needle = reference.to_lowercase();
+    let words = needle
+        .split(|ch: char| !ch.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>();
+    let mut matches = concerns
+        .iter()
+        .filter(|concern| concern.summary.to_lowercase().contains(&needle))
+        .collect::<Vec<_>>();
+    if matches.is_empty() && !words.is_empty() {
+        matches = concerns
+            .iter()
+            .filter(|concern| {
+                let summary = concern.summary.to_lowercase();
+                words.iter().all(|word| summary.contains(word))
+            })
+            .collect();
+    }
+
+    let describe = |list: &[&Concern]| {
+        list.iter()
+            .take(10)
+            .map(|concern| format!("{} ({})", concern.id, concern.summary))
+            .collect::<Vec<_>>()
+            .join("; ")
+    };
+    match matches.as_slice() {
+        [concern] => Ok(concern),
+        [] => Err(format!(
+            "No concern matches '{}'. Known concerns: {}",
+            reference,
+            describe(&concerns.iter().collect::<Vec<_>>())
+        )),
+        many => Err(format!(
+            "'{}' matches {} concerns; pass one id: {}",
+            reference,
+            many.len(),
+            describe(many)
+        )),
+    }
+}
+
 /// Base working-memory key used for conversation-scoped cross-session handoff notes.
 pub const SESSION_HANDOFF_KEY: &str = "session-handoff";
 pub const PRIVATE_CHAT_MODE_STATE_KEY: &str = "private-chat-mode";
@@ -651,6 +1030,45 @@ impl Tool for FlagUncertaintyTool {
 mod tests {
     use super::*;
 
+    #[test]
+    fn concern_references_resolve_by_id_phrase_or_words() {
+        let concern = |id: &str, summary: &str| Concern {
+            id: id.to_string(),
+            created_at: chrono::Utc::now(),
+            last_touched: chrono::Utc::now(),
+            summary: summary.to_string(),
+            concern_type: crate::agent::concerns::ConcernType::Reminder {
+                trigger_time: None,
+                trigger_condition: None,
+            },
+            salience: crate::agent::concerns::Salience::Active,
+            my_thoughts: String::new(),
+            related_memory_keys: Vec::new(),
+            context: Default::default(),
+            pinned: false,
+        };
+        let concerns = vec![
+            concern("c-1", "Finish tax filing before April"),
+            concern("c-2", "Refactor scheduler"),
+            concern("c-3", "Refactor journal"),
+        ];
+
+        assert_eq!(
+            resolve_concern_reference(&concerns, "c-2").unwrap().id,
+            "c-2"
+        );
+        assert_eq!(
+            resolve_concern_reference(&concerns, "tax-filing")
+                .unwrap()
+                .id,
+            "c-1"
+        );
+        let ambiguous = resolve_concern_reference(&concerns, "refactor").unwrap_err();
+        assert!(ambiguous.contains("matches 2 concerns"));
+        let missing = resolve_concern_reference(&concerns, "garden").unwrap_err();
+        assert!(missing.contains("No concern matches"));
+    }
+
     #[test]
     fn handoff_keys_are_conversation_scoped() {
         assert_eq!(session_handoff_key(None), SESSION_HANDOFF_KEY);
@@ -10,26 +10,37 @@
 
 pub mod agentic;
 pub mod approval;
+pub mod audio;
+pub mod clipboard;
 pub mod effect_policy;
 pub mod files;
+pub mod focus;
 pub mod http;
 pub mod memory;
 pub mod plugin_workbench;
+pub mod reminders;
 pub mod runtime_plugin;
 pub mod safety;
 pub mod scheduled_jobs;
 pub mod shell;
+pub mod unified_diff;
 pub mod vision;
+pub mod web_search;
 
 pub use effect_policy::{EffectiveToolPolicy, ToolApprovalMinimum, ToolRateLimitClass};
 
+use crate::database::RememberedToolApproval;
 use crate::generation_telemetry::GenerationObserver;
 use crate::plugin_contract::PluginEffectDeclaration;
 use anyhow::Result;
 use async_trait::async_trait;
 use effect_policy::resolve_tool_effect_policy;
+use futures_util::future::BoxFuture;
 use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
 use std::collections::{HashMap, HashSet, VecDeque};
+use std::hash::{Hash, Hasher};
+use std::path::PathBuf;
 use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
 use std::sync::{Arc, Mutex as StdMutex};
 use std::time::{Duration, Instant};
@@ -50,6 +61,51 @@ pub enum ToolCategory {
     General,
 }
 
+impl ToolCategory {
+    /// Parse the lowercase keys used by config maps (`shell`, `filesystem`, ...).
+    pub fn from_config_key(raw: &str) -> Option<Self> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "filesystem" | "file_system" | "files" => Some(Self::FileSystem),
+            "shell" => Some(Self::Shell),
+            "network" | "http" => Some(Self::Network),
+            "memory" => Some(Self::Memory),
+            "general" => Some(Self::General),
+            _ => None,
+        }
+    }
+}
+
+/// Default host-side deadline around a single tool execution.
+pub const DEFAULT_TOOL_EXECUTION_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Host-side execution deadlines applied by `ToolRegistry::execute_call`.
+///
+/// Tools may enforce tighter limits of their own (the shell tool caps commands
+/// at 300 seconds); this policy is the backstop that keeps a hung tool from
+/// stalling the whole agentic loop.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ToolTimeoutPolicy {
+    /// Deadline for categories without an override. `None` disables it.
+    pub default: Option<Duration>,
+    /// Per-category overrides of `default`.
+    pub by_category: HashMap<ToolCategory, Duration>,
+}
+
+impl ToolTimeoutPolicy {
+    pub fn for_category(&self, category: &ToolCategory) -> Option<Duration> {
+        self.by_category.get(category).copied().or(self.default)
+    }
+}
+
+impl Default for ToolTimeoutPolicy {
+    fn default() -> Self {
+        Self {
+            default: Some(DEFAULT_TOOL_EXECUTION_TIMEOUT),
+            by_category: HashMap::new(),
+        }
+    }
+}
+
 /// The result of executing a tool
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ToolOutput {
@@ -57,6 +113,13 @@ pub enum ToolOutput {
     Text(String),
     /// Successful structured output
     Json(serde_json::Value),
+    /// Successful raw output such as generated images or audio. The bytes are
+    /// never sent to the LLM; chat media extraction writes them to a file.
+    Binary {
+        mime_type: String,
+        bytes: Vec<u8>,
+        suggested_name: Option<String>,
+    },
     /// Tool execution failed
     Error(String),
     /// Tool needs user approval before proceeding
@@ -75,6 +138,14 @@ impl ToolOutput {
             ToolOutput::Json(v) => {
                 serde_json::to_string_pretty(v).unwrap_or_else(|_| v.to_string())
             }
+            ToolOutput::Binary {
+                mime_type,
+                bytes,
+                suggested_name,
+            } => match suggested_name {
+                Some(name) => format!("[BINARY] {}, {} bytes ({})", mime_type, bytes.len(), name),
+                None => format!("[BINARY] {}, {} bytes", mime_type, bytes.len()),
+            },
             ToolOutput::Error(e) => format!("[ERROR] {}", e),
             ToolOutput::NeedsApproval { tool, reason, .. } => {
                 format!("[NEEDS APPROVAL] Tool '{}': {}", tool, reason)
@@ -82,16 +153,65 @@ impl ToolOutput {
         }
     }
 
-    /// Returns true if this output represents success (Text or Json)
+    /// Returns true if this output represents success (Text, Json, or Binary)
     pub fn is_success(&self) -> bool {
-        matches!(self, ToolOutput::Text(_) | ToolOutput::Json(_))
+        matches!(
+            self,
+            ToolOutput::Text(_) | ToolOutput::Json(_) | ToolOutput::Binary { .. }
+        )
+    }
+
+    /// Returns true for the error `execute_call` gives a call to a tool that
+    /// is not registered.
+    pub fn is_unknown_tool_error(&self) -> bool {
+        matches!(self, ToolOutput::Error(e) if e.starts_with(UNKNOWN_TOOL_ERROR_PREFIX))
+    }
+}
+
+/// Leading text of the error returned for a call to an unregistered tool.
+pub const UNKNOWN_TOOL_ERROR_PREFIX: &str = "Unknown tool";
+
+/// Steers the model back to a real tool: names every tool the context offers.
+fn unknown_tool_message(name: &str, mut available: Vec<&str>) -> String {
+    if available.is_empty() {
+        return format!(
+            "{}: {}. No tools are available in this context; answer without calling a tool.",
+            UNKNOWN_TOOL_ERROR_PREFIX, name
+        );
     }
+    available.sort_unstable();
+    format!(
+        "{}: {}. Available tools: {}. Call one of these by its exact name, or answer without a tool.",
+        UNKNOWN_TOOL_ERROR_PREFIX,
+        name,
+        available.join(", ")
+    )
+}
+
+/// Receives incremental output from long-running tools as `(tool_name, line)`.
+pub type ToolProgressSink = Arc<dyn Fn(&str, &str) + Send + Sync>;
+
+/// Operator decision on a call held at the approval gate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ApprovalVerdict {
+    Approved,
+    Denied,
+    /// No decision in time; the call is reported as `NeedsApproval`.
+    Undecided,
 }
 
+/// Holds a gated call `(call, reason, conversation_id)` until the operator
+/// decides or the waiter gives up.
+pub type ToolApprovalWaiter =
+    Arc<dyn Fn(&ToolCall, &str, Option<&str>) -> BoxFuture<'static, ApprovalVerdict> + Send + Sync>;
+
 /// Context passed to tools during execution
 pub struct ToolContext {
     /// Current working directory for file/shell operations
     pub working_directory: String,
+    /// Further directories that path-confined file tools accept besides
+    /// `working_directory` (e.g. a second repository).
+    pub allowed_roots: Vec<PathBuf>,
     /// The agent's username (for attribution)
     pub username: String,
     /// Conversation scope for tools that persist resumable private state.
@@ -111,9 +231,34 @@ pub struct ToolContext {
     pub outbound_action_rate_limit: Option<Arc<ToolInvocationRateLimit>>,
     /// Observability lane inherited by tools that make their own model calls.
     pub generation_observer: Option<GenerationObserver>,
+    /// Optional live-progress lane for tools that opt into streaming output.
+    pub progress: Option<ToolProgressSink>,
+    /// Consulted when a call needs approval; without one the call returns
+    /// `NeedsApproval` immediately.
+    pub approval_waiter: Option<ToolApprovalWaiter>,
+    /// Execution deadlines enforced by the registry around `Tool::execute`.
+    pub timeouts: ToolTimeoutPolicy,
 }
 
 impl ToolContext {
+    /// Hash of the allow/deny policy, insensitive to case and list order, so
+    /// contexts that expose the same tools share a definitions cache entry.
+    fn tool_policy_hash(&self) -> u64 {
+        let normalize = |names: &[String]| {
+            let mut names: Vec<String> = names.iter().map(|n| n.to_ascii_lowercase()).collect();
+            names.sort();
+            names.dedup();
+            names
+        };
+        let mut hasher = DefaultHasher::new();
+        self.allowed_tools
+            .as_deref()
+            .map(normalize)
+            .hash(&mut hasher);
+        normalize(&self.disallowed_tools).hash(&mut hasher);
+        hasher.finish()
+    }
+
     pub fn allows_tool(&self, tool_name: &str) -> bool {
         if self
             .disallowed_tools
@@ -135,6 +280,14 @@ impl ToolContext {
 #[derive(Debug)]
 struct ToolRateLimitEntry {
     reserved_at: Instant,
+    quota_group: Option<String>,
+}
+
+/// Which budget refused a reservation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum RateLimitDenial {
+    Global,
+    Group(String),
 }
 
 /// A process-wide rolling limiter shared by every autonomous tool context.
@@ -146,7 +299,8 @@ struct ToolRateLimitEntry {
 pub struct ToolInvocationRateLimit {
     limited_tools: HashSet<String>,
     max_actions: AtomicU32,
-    window: Duration,
+    window_millis: AtomicU64,
+    group_limits: StdMutex<HashMap<String, u32>>,
     entries: StdMutex<VecDeque<ToolRateLimitEntry>>,
 }
 
@@ -161,7 +315,8 @@ impl ToolInvocationRateLimit {
                 .filter(|name| !name.is_empty())
                 .collect(),
             max_actions: AtomicU32::new(max_actions),
-            window,
+            window_millis: AtomicU64::new(duration_millis(window)),
+            group_limits: StdMutex::new(HashMap::new()),
             entries: StdMutex::new(VecDeque::new()),
         }
     }
@@ -175,12 +330,38 @@ impl ToolInvocationRateLimit {
         self.max_actions.store(max_actions, Ordering::SeqCst);
     }
 
+    /// Replace the per-group caps (keyed by `Tool::quota_group`). A group cap
+    /// only narrows the shared budget; groups without a cap draw from the
+    /// global quota alone.
+    pub fn set_group_limits(&self, limits: HashMap<String, u32>) {
+        *self
+            .group_limits
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = limits;
+    }
+
+    /// Change the rolling window length. Existing reservations are re-evaluated
+    /// against the new length on the next prune, so shrinking the window can
+    /// release slots immediately.
+    pub fn set_window(&self, window: Duration) {
+        self.window_millis
+            .store(duration_millis(window), Ordering::SeqCst);
+    }
+
+    pub fn window(&self) -> Duration {
+        Duration::from_millis(self.window_millis.load(Ordering::SeqCst))
+    }
+
     pub fn active_count(&self) -> u32 {
+        self.active_count_at(Instant::now())
+    }
+
+    fn active_count_at(&self, now: Instant) -> u32 {
         let mut entries = self
             .entries
             .lock()
             .unwrap_or_else(|poisoned| poisoned.into_inner());
-        self.prune_locked(&mut entries, Instant::now());
+        self.prune_locked(&mut entries, now);
         entries.len().try_into().unwrap_or(u32::MAX)
     }
 
@@ -188,7 +369,18 @@ impl ToolInvocationRateLimit {
         &self,
         tool_name: &str,
         rate_limit: ToolRateLimitClass,
-    ) -> std::result::Result<(), ()> {
+        quota_group: Option<&str>,
+    ) -> std::result::Result<(), RateLimitDenial> {
+        self.try_reserve_at(tool_name, rate_limit, quota_group, Instant::now())
+    }
+
+    fn try_reserve_at(
+        &self,
+        tool_name: &str,
+        rate_limit: ToolRateLimitClass,
+        quota_group: Option<&str>,
+        now: Instant,
+    ) -> std::result::Result<(), RateLimitDenial> {
         let selected_by_legacy_name = self
             .limited_tools
             .contains(&tool_name.trim().to_ascii_lowercase());
@@ -197,33 +389,56 @@ impl ToolInvocationRateLimit {
         }
         let max_actions = self.max_actions.load(Ordering::SeqCst);
         if max_actions == 0 {
-            return Err(());
+            return Err(RateLimitDenial::Global);
         }
+        let group_limit = quota_group.and_then(|group| {
+            self.group_limits
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner())
+                .get(group)
+                .copied()
+        });
 
-        let now = Instant::now();
         let mut entries = self
             .entries
             .lock()
             .unwrap_or_else(|poisoned| poisoned.into_inner());
         self.prune_locked(&mut entries, now);
         if entries.len() >= max_actions as usize {
-            return Err(());
+            return Err(RateLimitDenial::Global);
+        }
+        if let (Some(group), Some(limit)) = (quota_group, group_limit) {
+            let group_count = entries
+                .iter()
+                .filter(|entry| entry.quota_group.as_deref() == Some(group))
+                .count();
+            if group_count >= limit as usize {
+                return Err(RateLimitDenial::Group(group.to_string()));
+            }
         }
 
-        entries.push_back(ToolRateLimitEntry { reserved_at: now });
+        entries.push_back(ToolRateLimitEntry {
+            reserved_at: now,
+            quota_group: quota_group.map(str::to_string),
+        });
         Ok(())
     }
 
     fn prune_locked(&self, entries: &mut VecDeque<ToolRateLimitEntry>, now: Instant) {
+        let window = self.window();
         while entries
             .front()
-            .is_some_and(|entry| now.duration_since(entry.reserved_at) >= self.window)
+            .is_some_and(|entry| now.saturating_duration_since(entry.reserved_at) >= window)
         {
             entries.pop_front();
         }
     }
 }
 
+fn duration_millis(duration: Duration) -> u64 {
+    duration.as_millis().try_into().unwrap_or(u64::MAX)
+}
+
 /// A tool provides the agent with a local capability.
 ///
 /// Unlike Skills (which poll external services), Tools are invoked on-demand
@@ -263,6 +478,13 @@ pub trait Tool: Send + Sync {
         false
     }
 
+    /// Whether this particular call may skip `Autonomous` approval, for
+    /// tools whose safe inputs are configured (e.g. allow-listed shell
+    /// commands). Never bypasses `Always` effects.
+    fn auto_approves(&self, _params: &serde_json::Value) -> bool {
+        false
+    }
+
     /// Semantic effects caused by this tool. Runtime plugins provide these
     /// through their handshake manifest; legacy built-ins may return none.
     fn effects(&self) -> &[PluginEffectDeclaration] {
@@ -284,10 +506,24 @@ pub trait Tool: Send + Sync {
         "host.builtin"
     }
 
+    /// Budget group for per-source outbound-action caps. Runtime-plugin
+    /// proxies report their plugin id; built-ins share only the global quota.
+    fn quota_group(&self) -> Option<&str> {
+        None
+    }
+
     /// Category for grouping and policy application
     fn category(&self) -> ToolCategory {
         ToolCategory::General
     }
+
+    /// Whether the tool bounds its own execution. The registry deadline is
+    /// skipped for these: runtime-plugin proxies carry their RPC deadline,
+    /// and dropping one mid-response would leave the plugin's stdio stream
+    /// out of step for the next call.
+    fn enforces_own_deadline(&self) -> bool {
+        false
+    }
 }
 
 /// OpenAI-format function definition for LLM function-calling
@@ -355,13 +591,25 @@ struct ToolAuthorizationFingerprint {
 struct ToolRegistryState {
     tools: HashMap<String, RegisteredTool>,
     session_approved: HashMap<String, ToolAuthorizationFingerprint>,
+    /// Persisted approval memory, loaded at startup and written through by the agent.
+    remembered_approvals: Vec<RememberedToolApproval>,
+    /// Bumped on every register/deregister; identifies the current tool set.
+    tool_set_version: u64,
 }
 
+/// Upper bound on cached definition vectors; contexts are few in practice,
+/// so overflowing simply starts over.
+const MAX_CACHED_DEFINITION_SETS: usize = 64;
+
 pub struct ToolRegistry {
     /// Tools and approvals share one lock so registration, grant, and lookup
     /// observe one coherent authorization generation.
     state: RwLock<ToolRegistryState>,
     next_registration_generation: AtomicU64,
+    /// Filtered definitions keyed by (tool set version, context policy hash).
+    /// Filled under the state read lock and cleared under the write lock, so
+    /// an entry never outlives the tool set it was built from.
+    definitions_cache: StdMutex<HashMap<(u64, u64), Vec<ToolDef>>>,
 }
 
 impl ToolRegistry {
@@ -369,9 +617,18 @@ impl ToolRegistry {
         Self {
             state: RwLock::new(ToolRegistryState::default()),
             next_registration_generation: AtomicU64::new(1),
+            definitions_cache: StdMutex::new(HashMap::new()),
         }
     }
 
+    fn invalidate_definitions(&self, state: &mut ToolRegistryState) {
+        state.tool_set_version += 1;
+        self.definitions_cache
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clear();
+    }
+
     /// Grant session-level approval for a tool so it runs without prompting for the rest of the session.
     pub async fn grant_session_approval(&self, tool_name: &str) {
         let mut state = self.state.write().await;
@@ -389,6 +646,58 @@ impl ToolRegistry {
         tracing::info!("Session approval granted for tool: {}", tool_name);
     }
 
+    /// Replace the approval memory, typically with the rows persisted in the database.
+    pub async fn load_remembered_approvals(&self, approvals: Vec<RememberedToolApproval>) {
+        self.state.write().await.remembered_approvals = approvals;
+    }
+
+    /// Remember an operator-approved call so later calls with a matching
+    /// signature skip the approval gate. Returns the new record for the caller
+    /// to persist, or `None` for an unknown tool.
+    pub async fn remember_approval(
+        &self,
+        tool_name: &str,
+        params: &serde_json::Value,
+        scope: Option<&str>,
+        working_directory: &str,
+    ) -> Option<RememberedToolApproval> {
+        let mut state = self.state.write().await;
+        let Some(provider) = state
+            .tools
+            .get(tool_name)
+            .map(|registered| registered.authorization_fingerprint.provider.clone())
+        else {
+            tracing::warn!(
+                "Ignored remembered approval for unknown tool: {}",
+                tool_name
+            );
+            return None;
+        };
+        let approval = approval::ApprovalSignature::from_call(tool_name, params, working_directory)
+            .remember(&provider, scope, working_directory);
+        state.remembered_approvals.push(approval.clone());
+        tracing::info!(
+            "Remembered approval {} for tool: {}",
+            approval.id,
+            tool_name
+        );
+        Some(approval)
+    }
+
+    /// Drop one remembered approval; returns whether it existed.
+    pub async fn forget_remembered_approval(&self, id: &str) -> bool {
+        let mut state = self.state.write().await;
+        let before = state.remembered_approvals.len();
+        state
+            .remembered_approvals
+            .retain(|approval| approval.id != id);
+        state.remembered_approvals.len() != before
+    }
+
+    pub async fn remembered_approvals(&self) -> Vec<RememberedToolApproval> {
+        self.state.read().await.remembered_approvals.clone()
+    }
+
     /// Register a tool. Overwrites any existing tool with the same name.
     pub async fn register(&self, tool: Arc<dyn Tool>) {
         let name = tool.name().to_string();
@@ -426,13 +735,18 @@ impl ToolRegistry {
                 effect_policy,
             },
         );
+        self.invalidate_definitions(&mut state);
     }
 
     /// Remove a tool by name.
     pub async fn deregister(&self, name: &str) -> bool {
         let mut state = self.state.write().await;
         state.session_approved.remove(name);
-        state.tools.remove(name).is_some()
+        let removed = state.tools.remove(name).is_some();
+        if removed {
+            self.invalidate_definitions(&mut state);
+        }
+        removed
     }
 
     /// Get a tool by name.
@@ -481,9 +795,22 @@ impl ToolRegistry {
     }
 
     /// Generate tool definitions filtered by execution context policy.
+    ///
+    /// Results are cached per tool set and allow/deny policy, so repeated
+    /// iterations of one agentic loop do not rebuild every schema.
     pub async fn tool_definitions_for_context(&self, ctx: &ToolContext) -> Vec<ToolDef> {
         let state = self.state.read().await;
-        state
+        let key = (state.tool_set_version, ctx.tool_policy_hash());
+        if let Some(defs) = self
+            .definitions_cache
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .get(&key)
+        {
+            return defs.clone();
+        }
+
+        let defs: Vec<ToolDef> = state
             .tools
             .values()
             .filter(|registered| ctx.allows_tool(registered.tool.name()))
@@ -495,41 +822,77 @@ impl ToolRegistry {
                     parameters: registered.tool.parameters_schema(),
                 },
             })
-            .collect()
+            .collect();
+
+        let mut cache = self
+            .definitions_cache
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if cache.len() >= MAX_CACHED_DEFINITION_SETS {
+            cache.clear();
+        }
+        cache.insert(key, defs.clone());
+        defs
     }
 
     /// Execute a tool call, handling approval checks.
     ///
     /// Returns `ToolOutput::NeedsApproval` if the tool requires approval
-    /// and the context indicates autonomous mode.
+    /// and the context indicates autonomous mode. A context with an
+    /// `approval_waiter` asks it first: approval runs the call, denial
+    /// returns an error.
+    ///
+    /// A call to an unregistered tool gets an error listing the tools the
+    /// context allows, so the model can correct the name.
     pub async fn execute_call(&self, call: &ToolCall, ctx: &ToolContext) -> ToolCallResult {
-        if !ctx.allows_tool(&call.name) {
-            return ToolCallResult {
-                name: call.name.clone(),
-                output: ToolOutput::Error(format!(
-                    "Tool '{}' is disabled for this context",
-                    call.name
-                )),
-            };
-        }
-
-        let (tool, category, effect_policy, session_ok) = {
+        let (tool, category, effect_policy, session_ok, remembered_ok, quota_group) = {
             let state = self.state.read().await;
             let Some(registered) = state.tools.get(&call.name) else {
+                let available = state
+                    .tools
+                    .keys()
+                    .map(String::as_str)
+                    .filter(|name| ctx.allows_tool(name))
+                    .collect();
                 return ToolCallResult {
                     name: call.name.clone(),
-                    output: ToolOutput::Error(format!("Unknown tool: {}", call.name)),
+                    output: ToolOutput::Error(unknown_tool_message(&call.name, available)),
                 };
             };
+            if !ctx.allows_tool(&call.name) {
+                return ToolCallResult {
+                    name: call.name.clone(),
+                    output: ToolOutput::Error(format!(
+                        "Tool '{}' is disabled for this context",
+                        call.name
+                    )),
+                };
+            }
             let session_ok = state
                 .session_approved
                 .get(&call.name)
                 .is_some_and(|approved| approved == &registered.authorization_fingerprint);
+            let mut remembered = state
+                .remembered_approvals
+                .iter()
+                .filter(|approval| approval.tool_name == call.name)
+                .peekable();
+            let remembered_ok = remembered.peek().is_some() && {
+                let signature = approval::ApprovalSignature::from_call(
+                    &call.name,
+                    &call.arguments,
+                    &ctx.working_directory,
+                );
+                let provider = &registered.authorization_fingerprint.provider;
+                remembered.any(|approval| signature.is_covered_by(approval, provider))
+            };
             (
                 Arc::clone(&registered.tool),
                 registered.tool.category(),
                 registered.effect_policy.clone(),
                 session_ok,
+                remembered_ok,
+                registered.tool.quota_group().map(str::to_string),
             )
         };
 
@@ -549,30 +912,65 @@ impl ToolRegistry {
             && !effect_policy.is_outbound_action()
             && (matches!(category, ToolCategory::FileSystem | ToolCategory::Shell)
                 || has_only_local_effects);
-        if effect_policy.requires_approval(ctx.autonomous) && !session_ok && !loose_local_ok {
+        // Evaluated last: the shell allow-list reloads config on each check.
+        let allow_listed_ok = || {
+            effect_policy.approval == ToolApprovalMinimum::Autonomous
+                && tool.auto_approves(&call.arguments)
+        };
+        if effect_policy.requires_approval(ctx.autonomous)
+            && !session_ok
+            && !remembered_ok
+            && !loose_local_ok
+            && !allow_listed_ok()
+        {
             let scope = match effect_policy.approval {
                 ToolApprovalMinimum::Always => "for this effect",
                 ToolApprovalMinimum::Autonomous => "in autonomous mode",
                 ToolApprovalMinimum::None => "by policy",
             };
-            return ToolCallResult {
-                name: call.name.clone(),
-                output: ToolOutput::NeedsApproval {
-                    tool: call.name.clone(),
-                    params: call.arguments.clone(),
-                    reason: format!("Tool '{}' requires approval {}", call.name, scope),
-                },
+            let reason = format!("Tool '{}' requires approval {}", call.name, scope);
+            let verdict = match ctx.approval_waiter.as_ref() {
+                Some(waiter) => waiter(call, &reason, ctx.conversation_id.as_deref()).await,
+                None => ApprovalVerdict::Undecided,
             };
+            match verdict {
+                ApprovalVerdict::Approved => {}
+                ApprovalVerdict::Denied => {
+                    return ToolCallResult {
+                        name: call.name.clone(),
+                        output: ToolOutput::Error(format!(
+                            "Operator denied the call to tool '{}'",
+                            call.name
+                        )),
+                    };
+                }
+                ApprovalVerdict::Undecided => {
+                    return ToolCallResult {
+                        name: call.name.clone(),
+                        output: ToolOutput::NeedsApproval {
+                            tool: call.name.clone(),
+                            params: call.arguments.clone(),
+                            reason,
+                        },
+                    };
+                }
+            }
         }
 
         if let Some(limit) = ctx.outbound_action_rate_limit.as_ref() {
-            match limit.try_reserve(&call.name, effect_policy.rate_limit) {
+            match limit.try_reserve(&call.name, effect_policy.rate_limit, quota_group.as_deref()) {
                 Ok(()) => {}
-                Err(()) => {
-                    let message = format!(
-                        "Tool '{}' is temporarily disabled by the rolling outbound-action limit",
-                        call.name
-                    );
+                Err(denial) => {
+                    let message = match denial {
+                        RateLimitDenial::Global => format!(
+                            "Tool '{}' is temporarily disabled by the rolling outbound-action limit",
+                            call.name
+                        ),
+                        RateLimitDenial::Group(group) => format!(
+                            "Tool '{}' is temporarily disabled by the rolling outbound-action limit for '{}'",
+                            call.name, group
+                        ),
+                    };
                     return ToolCallResult {
                         name: call.name.clone(),
                         output: ToolOutput::Error(message),
@@ -583,7 +981,30 @@ impl ToolRegistry {
 
         // Execute after reserving. A failed/ambiguous response keeps its slot:
         // only the remote system can know whether dispatch caused a side effect.
-        let output = match tool.execute(call.arguments.clone(), ctx).await {
+        // A timeout is equally ambiguous, so it keeps the slot too.
+        let execution = tool.execute(call.arguments.clone(), ctx);
+        let deadline = if tool.enforces_own_deadline() {
+            None
+        } else {
+            ctx.timeouts.for_category(&category)
+        };
+        let result = match deadline {
+            Some(limit) => match tokio::time::timeout(limit, execution).await {
+                Ok(result) => result,
+                Err(_) => {
+                    return ToolCallResult {
+                        name: call.name.clone(),
+                        output: ToolOutput::Error(format!(
+                            "Tool '{}' timed out after {}s",
+                            call.name,
+                            limit.as_secs_f64()
+                        )),
+                    };
+                }
+            },
+            None => execution.await,
+        };
+        let output = match result {
             Ok(output) => output,
             Err(e) => ToolOutput::Error(format!("Tool execution failed: {}", e)),
         };
@@ -772,6 +1193,7 @@ mod tests {
     fn test_ctx() -> ToolContext {
         ToolContext {
             working_directory: "/tmp".to_string(),
+            allowed_roots: Vec::new(),
             username: "test".to_string(),
             conversation_id: None,
             autonomous: false,
@@ -780,9 +1202,154 @@ mod tests {
             disallowed_tools: Vec::new(),
             outbound_action_rate_limit: None,
             generation_observer: None,
+            progress: None,
+            approval_waiter: None,
+            timeouts: ToolTimeoutPolicy::default(),
+        }
+    }
+
+    struct SleepyTool;
+
+    #[async_trait]
+    impl Tool for SleepyTool {
+        fn name(&self) -> &str {
+            "sleepy"
+        }
+
+        fn description(&self) -> &str {
+            "Sleeps far longer than any test deadline"
+        }
+
+        fn parameters_schema(&self) -> serde_json::Value {
+            serde_json::json!({"type": "object", "properties": {}})
+        }
+
+        async fn execute(
+            &self,
+            _params: serde_json::Value,
+            _ctx: &ToolContext,
+        ) -> Result<ToolOutput> {
+            tokio::time::sleep(Duration::from_secs(30)).await;
+            Ok(ToolOutput::Text("woke up".to_string()))
+        }
+
+        fn category(&self) -> ToolCategory {
+            ToolCategory::Shell
+        }
+    }
+
+    #[tokio::test]
+    async fn hung_tool_execution_times_out_with_error() {
+        let registry = ToolRegistry::new();
+        registry.register(Arc::new(SleepyTool)).await;
+        let mut ctx = test_ctx();
+        ctx.timeouts.default = Some(Duration::from_millis(20));
+        let call = ToolCall {
+            name: "sleepy".to_string(),
+            arguments: serde_json::json!({}),
+        };
+
+        let result = registry.execute_call(&call, &ctx).await;
+        assert!(matches!(result.output, ToolOutput::Error(_)));
+        assert!(result.output.to_llm_string().contains("timed out after"));
+    }
+
+    struct SelfTimedTool;
+
+    #[async_trait]
+    impl Tool for SelfTimedTool {
+        fn name(&self) -> &str {
+            "self_timed"
+        }
+
+        fn description(&self) -> &str {
+            "Outlasts the test deadline but bounds itself"
+        }
+
+        fn parameters_schema(&self) -> serde_json::Value {
+            serde_json::json!({"type": "object", "properties": {}})
+        }
+
+        async fn execute(
+            &self,
+            _params: serde_json::Value,
+            _ctx: &ToolContext,
+        ) -> Result<ToolOutput> {
+            tokio::time::sleep(Duration::from_millis(60)).await;
+            Ok(ToolOutput::Text("finished".to_string()))
+        }
+
+        fn enforces_own_deadline(&self) -> bool {
+            true
         }
     }
 
+    #[tokio::test]
+    async fn self_timed_tools_are_not_cut_off_by_the_registry_deadline() {
+        let registry = ToolRegistry::new();
+        registry.register(Arc::new(SelfTimedTool)).await;
+        let mut ctx = test_ctx();
+        ctx.timeouts.default = Some(Duration::from_millis(10));
+
+        let result = registry
+            .execute_call(
+                &ToolCall {
+                    name: "self_timed".to_string(),
+                    arguments: serde_json::json!({}),
+                },
+                &ctx,
+            )
+            .await;
+        assert_eq!(result.output.to_llm_string(), "finished");
+    }
+
+    #[tokio::test]
+    async fn category_timeout_overrides_default() {
+        let registry = ToolRegistry::new();
+        registry.register(Arc::new(SleepyTool)).await;
+        registry.register(Arc::new(EchoTool)).await;
+        let mut ctx = test_ctx();
+        ctx.timeouts.default = None;
+        ctx.timeouts
+            .by_category
+            .insert(ToolCategory::Shell, Duration::from_millis(20));
+
+        let sleepy = registry
+            .execute_call(
+                &ToolCall {
+                    name: "sleepy".to_string(),
+                    arguments: serde_json::json!({}),
+                },
+                &ctx,
+            )
+            .await;
+        assert!(sleepy.output.to_llm_string().contains("timed out after"));
+
+        let echo = registry
+            .execute_call(
+                &ToolCall {
+                    name: "echo".to_string(),
+                    arguments: serde_json::json!({"message": "fast"}),
+                },
+                &ctx,
+            )
+            .await;
+        assert_eq!(echo.output.to_llm_string(), "fast");
+    }
+
+    #[test]
+    fn tool_category_config_keys_parse() {
+        assert_eq!(
+            ToolCategory::from_config_key("Shell"),
+            Some(ToolCategory::Shell)
+        );
+        assert_eq!(
+            ToolCategory::from_config_key("filesystem"),
+            Some(ToolCategory::FileSystem)
+        );
+        assert_eq!(ToolCategory::from_config_key("unknown"), None);
+    }
+
     #[tokio::test]
     async fn test_registry_register_and_get() {
         let registry = ToolRegistry::new();
@@ -834,9 +1401,51 @@ mod tests {
 
         let result = registry.execute_call(&call, &test_ctx()).await;
         assert!(!result.output.is_success());
+        assert!(result.output.is_unknown_tool_error());
         assert!(result.output.to_llm_string().contains("Unknown tool"));
     }
 
+    #[tokio::test]
+    async fn unknown_tool_error_lists_tools_available_in_context() {
+        let registry = ToolRegistry::new();
+        registry.register(Arc::new(EchoTool)).await;
+        registry.register(Arc::new(DangerousTool)).await;
+        registry.register(Arc::new(FailingTool)).await;
+
+        let call = ToolCall {
+            name: "search_the_web".to_string(),
+            arguments: serde_json::json!({}),
+        };
+        let mut ctx = test_ctx();
+        ctx.disallowed_tools = vec!["failing".to_string()];
+        let result = registry.execute_call(&call, &ctx).await;
+        assert!(result.output.is_unknown_tool_error());
+        assert_eq!(
+            result.output.to_llm_string(),
+            "[ERROR] Unknown tool: search_the_web. Available tools: dangerous, echo. \
+             Call one of these by its exact name, or answer without a tool."
+        );
+
+        ctx.allowed_tools = Some(Vec::new());
+        let result = registry.execute_call(&call, &ctx).await;
+        assert!(result.output.is_unknown_tool_error());
+        assert!(result
+            .output
+            .to_llm_string()
+            .contains("No tools are available in this context"));
+
+        let disabled = registry
+            .execute_call(
+                &ToolCall {
+                    name: "echo".to_string(),
+                    arguments: serde_json::json!({"message": "hi"}),
+                },
+                &ctx,
+            )
+            .await;
+        assert!(!disabled.output.is_unknown_tool_error());
+    }
+
     #[tokio::test]
     async fn test_approval_required_in_autonomous_mode() {
         let registry = ToolRegistry::new();
@@ -860,6 +1469,87 @@ mod tests {
         assert!(result.output.is_success());
     }
 
+    #[tokio::test]
+    async fn approval_waiter_verdict_decides_gated_calls() {
+        let registry = ToolRegistry::new();
+        registry.register(Arc::new(DangerousTool)).await;
+        let call = ToolCall {
+            name: "dangerous".to_string(),
+            arguments: serde_json::json!({}),
+        };
+        let waiter_for = |verdict: ApprovalVerdict| -> ToolApprovalWaiter {
+            Arc::new(move |_, _, _| Box::pin(async move { verdict }))
+        };
+
+        let mut ctx = test_ctx();
+        ctx.autonomous = true;
+        ctx.approval_waiter = Some(waiter_for(ApprovalVerdict::Approved));
+        let approved = registry.execute_call(&call, &ctx).await;
+        assert!(matches!(approved.output, ToolOutput::Text(ref text) if text == "executed"));
+
+        ctx.approval_waiter = Some(waiter_for(ApprovalVerdict::Denied));
+        let denied = registry.execute_call(&call, &ctx).await;
+        assert!(matches!(denied.output, ToolOutput::Error(ref e) if e.contains("denied")));
+
+        ctx.approval_waiter = Some(waiter_for(ApprovalVerdict::Undecided));
+        let undecided = registry.execute_call(&call, &ctx).await;
+        assert!(matches!(undecided.output, ToolOutput::NeedsApproval { .. }));
+    }
+
+    struct AllowListedTool;
+
+    #[async_trait]
+    impl Tool for AllowListedTool {
+        fn name(&self) -> &str {
+            "allow_listed"
+        }
+
+        fn description(&self) -> &str {
+            "Approval-gated tool that auto-approves one safe input"
+        }
+
+        fn parameters_schema(&self) -> serde_json::Value {
+            serde_json::json!({"type": "object", "properties": {}})
+        }
+
+        async fn execute(
+            &self,
+            _params: serde_json::Value,
+            _ctx: &ToolContext,
+        ) -> Result<ToolOutput> {
+            Ok(ToolOutput::Text("executed".to_string()))
+        }
+
+        fn requires_approval(&self) -> bool {
+            true
+        }
+
+        fn auto_approves(&self, params: &serde_json::Value) -> bool {
+            params["command"].as_str() == Some("git status")
+        }
+
+        fn category(&self) -> ToolCategory {
+            ToolCategory::Shell
+        }
+    }
+
+    #[tokio::test]
+    async fn auto_approved_calls_skip_autonomous_approval_only_for_safe_input() {
+        let registry = ToolRegistry::new();
+        registry.register(Arc::new(AllowListedTool)).await;
+        let mut ctx = test_ctx();
+        ctx.autonomous = true;
+        let call = |command: &str| ToolCall {
+            name: "allow_listed".to_string(),
+            arguments: serde_json::json!({"command": command}),
+        };
+
+        let safe = registry.execute_call(&call("git status"), &ctx).await;
+        assert!(safe.output.is_success());
+        let other = registry.execute_call(&call("git push"), &ctx).await;
+        assert!(matches!(other.output, ToolOutput::NeedsApproval { .. }));
+    }
+
     #[tokio::test]
     async fn loose_context_auto_approves_local_but_not_outbound_effects() {
         let registry = ToolRegistry::new();
@@ -1051,6 +1741,64 @@ mod tests {
         assert!(matches!(result.output, ToolOutput::NeedsApproval { .. }));
     }
 
+    #[tokio::test]
+    async fn remembered_approval_covers_only_matching_signatures() {
+        let registry = ToolRegistry::new();
+        registry.register(Arc::new(DangerousTool)).await;
+        let mut ctx = test_ctx();
+        ctx.autonomous = true;
+        let call = |path: &str, mode: &str| ToolCall {
+            name: "dangerous".to_string(),
+            arguments: serde_json::json!({"path": path, "mode": mode}),
+        };
+        assert!(matches!(
+            registry
+                .execute_call(&call("logs/a.txt", "tail"), &ctx)
+                .await
+                .output,
+            ToolOutput::NeedsApproval { .. }
+        ));
+
+        let approval = registry
+            .remember_approval(
+                "dangerous",
+                &call("logs/a.txt", "tail").arguments,
+                Some("logs"),
+                &ctx.working_directory,
+            )
+            .await
+            .expect("known tool");
+        assert_eq!(approval.path_scopes["path"], "/tmp/logs");
+        assert!(registry
+            .remember_approval("missing", &serde_json::json!({}), None, "/tmp")
+            .await
+            .is_none());
+
+        let covered = registry
+            .execute_call(&call("/tmp/logs/b.txt", "tail"), &ctx)
+            .await;
+        assert!(covered.output.is_success());
+        for uncovered in [
+            call("/tmp/other.txt", "tail"),
+            call("logs/b.txt", "truncate"),
+        ] {
+            assert!(matches!(
+                registry.execute_call(&uncovered, &ctx).await.output,
+                ToolOutput::NeedsApproval { .. }
+            ));
+        }
+
+        assert!(registry.forget_remembered_approval(&approval.id).await);
+        assert!(registry.remembered_approvals().await.is_empty());
+        assert!(matches!(
+            registry
+                .execute_call(&call("logs/b.txt", "tail"), &ctx)
+                .await
+                .output,
+            ToolOutput::NeedsApproval { .. }
+        ));
+    }
+
     #[tokio::test]
     async fn deregister_then_reregister_clears_exact_instance_approval() {
         let registry = ToolRegistry::new();
@@ -1125,6 +1873,72 @@ mod tests {
             .contains("rolling outbound-action limit"));
     }
 
+    #[test]
+    fn rolling_limit_releases_slots_once_the_window_elapses() {
+        let limiter = ToolInvocationRateLimit::for_outbound_effects(2, Duration::from_secs(5));
+        let start = Instant::now();
+        let outbound = ToolRateLimitClass::OutboundAction;
+
+        assert!(limiter
+            .try_reserve_at("post", outbound, None, start)
+            .is_ok());
+        assert!(limiter
+            .try_reserve_at("post", outbound, None, start + Duration::from_secs(1))
+            .is_ok());
+        assert!(limiter
+            .try_reserve_at("post", outbound, None, start + Duration::from_secs(2))
+            .is_err());
+        assert_eq!(limiter.active_count_at(start + Duration::from_secs(2)), 2);
+
+        // The first reservation ages out at exactly one window length.
+        assert_eq!(limiter.active_count_at(start + Duration::from_secs(5)), 1);
+        assert_eq!(limiter.active_count_at(start + Duration::from_secs(6)), 0);
+        assert!(limiter
+            .try_reserve_at("post", outbound, None, start + Duration::from_secs(6))
+            .is_ok());
+    }
+
+    #[test]
+    fn group_limits_cap_one_source_without_touching_others() {
+        let limiter = ToolInvocationRateLimit::for_outbound_effects(10, Duration::from_secs(3600));
+        limiter.set_group_limits(HashMap::from([("telegram_bridge".to_string(), 1)]));
+        let start = Instant::now();
+        let outbound = ToolRateLimitClass::OutboundAction;
+
+        assert!(limiter
+            .try_reserve_at("reply", outbound, Some("telegram_bridge"), start)
+            .is_ok());
+        assert_eq!(
+            limiter.try_reserve_at("reply", outbound, Some("telegram_bridge"), start),
+            Err(RateLimitDenial::Group("telegram_bridge".to_string()))
+        );
+        for _ in 0..3 {
+            assert!(limiter
+                .try_reserve_at("post", outbound, Some("graphchan"), start)
+                .is_ok());
+        }
+        assert!(limiter
+            .try_reserve_at("post", outbound, None, start)
+            .is_ok());
+        assert_eq!(limiter.active_count_at(start), 5);
+    }
+
+    #[test]
+    fn shrinking_the_rolling_window_releases_old_reservations() {
+        let limiter = ToolInvocationRateLimit::for_outbound_effects(1, Duration::from_secs(3600));
+        let start = Instant::now();
+        let outbound = ToolRateLimitClass::OutboundAction;
+
+        assert!(limiter
+            .try_reserve_at("post", outbound, None, start)
+            .is_ok());
+        assert_eq!(limiter.active_count_at(start + Duration::from_secs(30)), 1);
+
+        limiter.set_window(Duration::from_secs(10));
+        assert_eq!(limiter.window(), Duration::from_secs(10));
+        assert_eq!(limiter.active_count_at(start + Duration::from_secs(30)), 0);
+    }
+
     #[tokio::test]
     async fn test_deregister() {
         let registry = ToolRegistry::new();
@@ -1157,4 +1971,71 @@ mod tests {
         assert_eq!(defs.len(), 1);
         assert_eq!(defs[0].function.name, "echo");
     }
+
+    struct CountingSchemaTool {
+        name: &'static str,
+        schema_builds: Arc<AtomicU32>,
+    }
+
+    #[async_trait]
+    impl Tool for CountingSchemaTool {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        fn description(&self) -> &str {
+            "Counts how often its schema is built"
+        }
+
+        fn parameters_schema(&self) -> serde_json::Value {
+            self.schema_builds.fetch_add(1, Ordering::SeqCst);
+            serde_json::json!({"type": "object", "properties": {}})
+        }
+
+        async fn execute(
+            &self,
+            _params: serde_json::Value,
+            _ctx: &ToolContext,
+        ) -> Result<ToolOutput> {
+            Ok(ToolOutput::Text("ok".to_string()))
+        }
+    }
+
+    #[tokio::test]
+    async fn context_definitions_are_cached_until_the_tool_set_changes() {
+        let registry = ToolRegistry::new();
+        let schema_builds = Arc::new(AtomicU32::new(0));
+        registry
+            .register(Arc::new(CountingSchemaTool {
+                name: "counted",
+                schema_builds: schema_builds.clone(),
+            }))
+            .await;
+        registry.register(Arc::new(EchoTool)).await;
+        let after_register = schema_builds.load(Ordering::SeqCst);
+
+        let mut ctx = test_ctx();
+        ctx.disallowed_tools = vec!["Echo".to_string()];
+        let first = registry.tool_definitions_for_context(&ctx).await;
+        assert_eq!(first.len(), 1);
+        assert_eq!(schema_builds.load(Ordering::SeqCst), after_register + 1);
+
+        // Same policy spelled differently in a fresh context hits the cache.
+        let mut same_policy = test_ctx();
+        same_policy.disallowed_tools = vec!["echo".to_string(), "ECHO".to_string()];
+        let second = registry.tool_definitions_for_context(&same_policy).await;
+        assert_eq!(second.len(), 1);
+        assert_eq!(schema_builds.load(Ordering::SeqCst), after_register + 1);
+
+        // A different policy is a separate entry.
+        let everything = registry.tool_definitions_for_context(&test_ctx()).await;
+        assert_eq!(everything.len(), 2);
+        assert_eq!(schema_builds.load(Ordering::SeqCst), after_register + 2);
+
+        // Deregistration invalidates every entry.
+        assert!(registry.deregister("echo").await);
+        let rebuilt = registry.tool_definitions_for_context(&test_ctx()).await;
+        assert_eq!(rebuilt.len(), 1);
+        assert_eq!(schema_builds.load(Ordering::SeqCst), after_register + 3);
+    }
 }
@@ -139,6 +139,7 @@ mod tests {
     fn context() -> ToolContext {
         ToolContext {
             working_directory: ".".to_string(),
+            allowed_roots: Vec::new(),
             username: "Ponderer".to_string(),
             conversation_id: None,
             autonomous: true,
@@ -147,6 +148,9 @@ mod tests {
             disallowed_tools: Vec::new(),
             outbound_action_rate_limit: None,
             generation_observer: None,
+            progress: None,
+            approval_waiter: None,
+            timeouts: crate::tools::ToolTimeoutPolicy::default(),
         }
     }
 
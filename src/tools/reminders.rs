@@ -0,0 +1,240 @@
+//! Time-based reminder tool backed by the `reminders` table.
+//!
+//! One tool, `schedule_reminder`, with three actions:
+//! - `set`: store a reminder due at an RFC 3339 `due_at` or `in_minutes` from now.
+//! - `list`: show undelivered reminders in due order.
+//! - `cancel`: remove an undelivered reminder by ID.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use serde_json::{json, Value};
+
+use crate::config::AgentConfig;
+use crate::database::AgentDatabase;
+
+use super::{Tool, ToolCategory, ToolContext, ToolOutput};
+
+const DEFAULT_LIST_LIMIT: usize = 24;
+const MAX_LIST_LIMIT: usize = 200;
+/// One year; anything further out is almost certainly a unit mistake.
+pub(crate) const MAX_REMINDER_MINUTES: u64 = 525_600;
+
+fn open_database() -> Result<AgentDatabase> {
+    let config = AgentConfig::load();
+    AgentDatabase::new(&config.database_path).with_context(|| {
+        format!(
+            "Failed to open database at '{}' for reminder tool",
+            config.database_path
+        )
+    })
+}
+
+/// Resolves the due time from `due_at` (preferred) or `in_minutes`.
+fn resolve_due_at(
+    params: &Value,
+    now: DateTime<Utc>,
+) -> std::result::Result<DateTime<Utc>, String> {
+    if let Some(raw) = params
+        .get("due_at")
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+    {
+        return DateTime::parse_from_rfc3339(raw)
+            .map(|due_at| due_at.with_timezone(&Utc))
+            .map_err(|error| {
+                format!(
+                    "'due_at' must be an RFC 3339 timestamp such as 2025-01-31T15:00:00Z ({})",
+                    error
+                )
+            });
+    }
+    match params.get("in_minutes").and_then(Value::as_u64) {
+        Some(minutes) if minutes <= MAX_REMINDER_MINUTES => {
+            Ok(now + ChronoDuration::minutes(minutes as i64))
+        }
+        Some(_) => Err(format!(
+            "'in_minutes' must be at most {} (one year)",
+            MAX_REMINDER_MINUTES
+        )),
+        None => Err("Provide either 'due_at' or 'in_minutes' for action 'set'".to_string()),
+    }
+}
+
+pub struct ScheduleReminderTool;
+
+impl ScheduleReminderTool {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl Tool for ScheduleReminderTool {
+    fn name(&self) -> &str {
+        "schedule_reminder"
+    }
+
+    fn description(&self) -> &str {
+        "Set, list, or cancel one-shot reminders. Action 'set' stores 'content' due at 'due_at' (RFC 3339) or 'in_minutes' from now; the heartbeat surfaces it once due. Action 'list' shows pending reminders; action 'cancel' removes one by 'reminder_id'."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "action": {
+                    "type": "string",
+                    "enum": ["set", "list", "cancel"],
+                    "description": "What to do (default: set)"
+                },
+                "content": {
+                    "type": "string",
+                    "description": "What to be reminded about (required for 'set')"
+                },
+                "due_at": {
+                    "type": "string",
+                    "description": "RFC 3339 due time, e.g. 2025-01-31T15:00:00Z"
+                },
+                "in_minutes": {
+                    "type": "integer",
+                    "description": "Minutes from now until the reminder is due (used when 'due_at' is absent)"
+                },
+                "reminder_id": {
+                    "type": "string",
+                    "description": "ID of the reminder to cancel (required for 'cancel')"
+                },
+                "limit": {
+                    "type": "integer",
+                    "description": "Maximum reminders to list (1-200)"
+                }
+            }
+        })
+    }
+
+    async fn execute(&self, params: Value, _ctx: &ToolContext) -> Result<ToolOutput> {
+        let action = params
+            .get("action")
+            .and_then(Value::as_str)
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .unwrap_or("set");
+
+        match action {
+            "set" => {
+                let Some(content) = params
+                    .get("content")
+                    .and_then(Value::as_str)
+                    .map(str::trim)
+                    .filter(|value| !value.is_empty())
+                else {
+                    return Ok(ToolOutput::Error(
+                        "Missing required 'content' parameter for action 'set'".to_string(),
+                    ));
+                };
+                let due_at = match resolve_due_at(&params, Utc::now()) {
+                    Ok(due_at) => due_at,
+                    Err(reason) => return Ok(ToolOutput::Error(reason)),
+                };
+                let db = match open_database() {
+                    Ok(db) => db,
+                    Err(error) => return Ok(ToolOutput::Error(error.to_string())),
+                };
+                match db.create_reminder(content, due_at) {
+                    Ok(reminder) => Ok(ToolOutput::Json(json!({
+                        "status": "ok",
+                        "reminder": reminder,
+                    }))),
+                    Err(error) => Ok(ToolOutput::Error(format!(
+                        "Failed to create reminder: {}",
+                        error
+                    ))),
+                }
+            }
+            "list" => {
+                let limit = params
+                    .get("limit")
+                    .and_then(Value::as_u64)
+                    .map(|value| (value as usize).clamp(1, MAX_LIST_LIMIT))
+                    .unwrap_or(DEFAULT_LIST_LIMIT);
+                let db = match open_database() {
+                    Ok(db) => db,
+                    Err(error) => return Ok(ToolOutput::Error(error.to_string())),
+                };
+                match db.list_pending_reminders(limit) {
+                    Ok(reminders) => Ok(ToolOutput::Json(json!({
+                        "status": "ok",
+                        "count": reminders.len(),
+                        "reminders": reminders,
+                    }))),
+                    Err(error) => Ok(ToolOutput::Error(format!(
+                        "Failed to list reminders: {}",
+                        error
+                    ))),
+                }
+            }
+            "cancel" => {
+                let Some(reminder_id) = params
+                    .get("reminder_id")
+                    .and_then(Value::as_str)
+                    .map(str::trim)
+                    .filter(|value| !value.is_empty())
+                else {
+                    return Ok(ToolOutput::Error(
+                        "Missing required 'reminder_id' parameter for action 'cancel'".to_string(),
+                    ));
+                };
+                let db = match open_database() {
+                    Ok(db) => db,
+                    Err(error) => return Ok(ToolOutput::Error(error.to_string())),
+                };
+                match db.cancel_reminder(reminder_id) {
+                    Ok(true) => Ok(ToolOutput::Json(json!({
+                        "status": "ok",
+                        "cancelled": true,
+                        "reminder_id": reminder_id,
+                    }))),
+                    Ok(false) => Ok(ToolOutput::Error(format!(
+                        "Reminder '{}' was not found or was already delivered",
+                        reminder_id
+                    ))),
+                    Err(error) => Ok(ToolOutput::Error(format!(
+                        "Failed to cancel reminder '{}': {}",
+                        reminder_id, error
+                    ))),
+                }
+            }
+            other => Ok(ToolOutput::Error(format!(
+                "Unknown action '{}'; expected 'set', 'list', or 'cancel'",
+                other
+            ))),
+        }
+    }
+
+    fn category(&self) -> ToolCategory {
+        ToolCategory::Memory
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn due_at_prefers_timestamp_then_relative_minutes() {
+        let now = Utc::now();
+        let explicit = resolve_due_at(
+            &json!({"due_at": "2030-05-01T09:30:00+02:00", "in_minutes": 5}),
+            now,
+        )
+        .expect("explicit due_at");
+        assert_eq!(explicit.to_rfc3339(), "2030-05-01T07:30:00+00:00");
+
+        let relative = resolve_due_at(&json!({"in_minutes": 25}), now).expect("relative");
+        assert_eq!(relative - now, ChronoDuration::minutes(25));
+
+        assert!(resolve_due_at(&json!({}), now).is_err());
+        assert!(resolve_due_at(&json!({"due_at": "tomorrow"}), now).is_err());
+    }
+}
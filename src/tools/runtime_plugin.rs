@@ -79,9 +79,17 @@ impl Tool for RuntimePluginToolProxy {
         &self.authorization_provider
     }
 
+    fn quota_group(&self) -> Option<&str> {
+        Some(&self.plugin_id)
+    }
+
     fn category(&self) -> ToolCategory {
         self.manifest.category.as_tool_category()
     }
+
+    fn enforces_own_deadline(&self) -> bool {
+        true
+    }
 }
 
 #[cfg(test)]
@@ -2,6 +2,9 @@
 //!
 //! Wraps all tool input/output with validation, leak detection, and sanitization.
 //! Prevents prompt injection from tool output and catches secret leaks.
+//! Also holds the deterministic deny-list (`ShellPolicy`) checked before any
+//! shell command is spawned, and the allow-list matcher that lets configured
+//! read-only commands skip autonomous approval.
 
 use serde_json::Value;
 
@@ -133,6 +136,225 @@ pub fn check_output(tool_name: &str, output: &str) -> Result<String, String> {
     Ok(sanitize_output(tool_name, output))
 }
 
+// ============================================================================
+// Shell policy
+// ============================================================================
+
+/// Destructive device families matched by the `dd`/redirect rules; `/dev/null`
+/// and friends stay usable.
+const BLOCK_DEVICE_PATTERN: &str = r"/dev/(?:sd|hd|vd|xvd|nvme|mmcblk|disk|rdisk|mapper/|md)";
+
+/// Built-in shell deny rules as `(name, spec)`; config can disable them by name.
+fn default_shell_rules() -> Vec<(&'static str, String)> {
+    vec![
+        (
+            "rm-rf-root",
+            r"regex:(?:^|[\s;&|])rm\s+(?:-\S+\s+)*(?:-[a-zA-Z]*[rR][a-zA-Z]*|--recursive)\s+(?:-\S+\s+)*(?:/|/\*|~|~/|\$HOME|\$HOME/)(?:$|[\s;&|])"
+                .to_string(),
+        ),
+        (
+            "mkfs",
+            r"regex:(?:^|[\s;&|])mkfs(?:\.\w+)?(?:$|\s)".to_string(),
+        ),
+        (
+            "dd-to-device",
+            format!(r"regex:(?:^|[\s;&|])dd\s[^;&|]*\bof={}", BLOCK_DEVICE_PATTERN),
+        ),
+        (
+            "redirect-to-device",
+            format!(r"regex:>\s*{}", BLOCK_DEVICE_PATTERN),
+        ),
+        (
+            "fork-bomb",
+            r"regex:[\w:]+\(\)\s*\{\s*[\w:]+\s*\|\s*[\w:]+\s*&\s*\}".to_string(),
+        ),
+    ]
+}
+
+#[derive(Debug, Clone)]
+enum ShellMatcher {
+    /// Matches when any `;`/`&`/`|`/newline-separated segment starts with the text.
+    Prefix(String),
+    Regex(regex_lite::Regex),
+}
+
+/// One named deny rule.
+#[derive(Debug, Clone)]
+pub struct ShellRule {
+    pub name: String,
+    matcher: ShellMatcher,
+}
+
+impl ShellRule {
+    /// Parse a `regex:<pattern>` or `prefix:<text>` spec; a bare spec is a prefix.
+    pub fn parse(name: &str, spec: &str) -> Result<Self, String> {
+        let matcher = if let Some(pattern) = spec.strip_prefix("regex:") {
+            let regex = regex_lite::Regex::new(pattern)
+                .map_err(|e| format!("Invalid shell rule regex '{}': {}", pattern, e))?;
+            ShellMatcher::Regex(regex)
+        } else {
+            let text = spec.strip_prefix("prefix:").unwrap_or(spec).trim();
+            if text.is_empty() {
+                return Err(format!("Shell rule '{}' has an empty prefix", name));
+            }
+            ShellMatcher::Prefix(text.to_string())
+        };
+        Ok(Self {
+            name: name.to_string(),
+            matcher,
+        })
+    }
+
+    fn matches(&self, command: &str) -> bool {
+        match &self.matcher {
+            ShellMatcher::Regex(regex) => regex.is_match(command),
+            ShellMatcher::Prefix(prefix) => command
+                .split([';', '&', '|', '\n'])
+                .any(|segment| segment.trim_start().starts_with(prefix.as_str())),
+        }
+    }
+}
+
+/// Deterministic deny-list for shell commands, applied before spawning and
+/// independent of approval: an approved call is still refused.
+#[derive(Debug, Clone)]
+pub struct ShellPolicy {
+    rules: Vec<ShellRule>,
+}
+
+impl Default for ShellPolicy {
+    fn default() -> Self {
+        Self::new(&[], &[])
+    }
+}
+
+impl ShellPolicy {
+    /// Built-in rules minus `disabled` (by name), plus `extra` specs. Extra
+    /// rules are named after their spec; an invalid one is skipped with a warning.
+    pub fn new(disabled: &[String], extra: &[String]) -> Self {
+        let is_disabled = |name: &str| {
+            disabled
+                .iter()
+                .any(|disabled| disabled.trim().eq_ignore_ascii_case(name))
+        };
+        let mut rules = Vec::new();
+        for (name, spec) in default_shell_rules() {
+            if is_disabled(name) {
+                continue;
+            }
+            match ShellRule::parse(name, &spec) {
+                Ok(rule) => rules.push(rule),
+                Err(error) => {
+                    tracing::warn!("Built-in shell rule '{}' is invalid: {}", name, error)
+                }
+            }
+        }
+        for spec in extra
+            .iter()
+            .map(|spec| spec.trim())
+            .filter(|spec| !spec.is_empty())
+        {
+            match ShellRule::parse(spec, spec) {
+                Ok(rule) => rules.push(rule),
+                Err(error) => tracing::warn!("Ignoring shell rule: {}", error),
+            }
+        }
+        Self { rules }
+    }
+
+    pub fn rules(&self) -> &[ShellRule] {
+        &self.rules
+    }
+
+    /// Block the command if any rule matches, naming the first matching rule.
+    /// Rules also see the command with program paths reduced to their
+    /// basename, so `/bin/rm -rf /` meets the `rm` rule.
+    pub fn check(&self, command: &str) -> SafetyVerdict {
+        let normalized = normalize_program_paths(command);
+        match self
+            .rules
+            .iter()
+            .find(|rule| rule.matches(command) || rule.matches(&normalized))
+        {
+            Some(rule) => SafetyVerdict::Block(format!(
+                "Command blocked by shell safety rule '{}'",
+                rule.name
+            )),
+            None => SafetyVerdict::Allow,
+        }
+    }
+}
+
+/// Commands that run the next word as a program.
+const PROGRAM_WRAPPERS: &[&str] = &[
+    "sudo", "doas", "env", "nice", "nohup", "exec", "command", "time",
+];
+
+/// Rewrites each program word (the first word of a `;`/`&`/`|`/`(`/newline
+/// segment, or the word after a wrapper such as `sudo`) to its basename.
+/// Flags and `NAME=value` assignments in program position are passed over.
+fn normalize_program_paths(command: &str) -> String {
+    fn flush(word: &mut String, out: &mut String, expect_program: &mut bool) {
+        if word.is_empty() {
+            return;
+        }
+        if !*expect_program || word.starts_with('-') || word.contains('=') {
+            out.push_str(word);
+        } else {
+            let base = word.rsplit('/').next().unwrap_or_default();
+            *expect_program = PROGRAM_WRAPPERS.contains(&base);
+            out.push_str(if base.is_empty() { word } else { base });
+        }
+        word.clear();
+    }
+
+    let mut out = String::with_capacity(command.len());
+    let mut word = String::new();
+    let mut expect_program = true;
+    for c in command.chars() {
+        if c.is_whitespace() || matches!(c, ';' | '&' | '|' | '(' | ')') {
+            flush(&mut word, &mut out, &mut expect_program);
+            out.push(c);
+            if matches!(c, ';' | '&' | '|' | '(' | '\n') {
+                expect_program = true;
+            }
+        } else {
+            word.push(c);
+        }
+    }
+    flush(&mut word, &mut out, &mut expect_program);
+    out
+}
+
+// ============================================================================
+// Shell auto-approval
+// ============================================================================
+
+/// Characters that chain, redirect, substitute, or quote in `/bin/sh`. A
+/// command containing any of them is never auto-approved.
+const SHELL_METACHARACTERS: &[char] = &[
+    ';', '&', '|', '<', '>', '`', '$', '(', ')', '{', '}', '\\', '\'', '"', '\n', '\r',
+];
+
+/// Whether `command` may skip autonomous approval under `prefixes`.
+///
+/// Matching is by whole words anchored at the start: `git status` covers
+/// `git status --short` but not `git statusx` or `echo x; git status`.
+/// Commands with shell metacharacters never match, whatever the prefix.
+pub fn shell_command_is_auto_approved(command: &str, prefixes: &[String]) -> bool {
+    if command.contains(SHELL_METACHARACTERS) {
+        return false;
+    }
+    let words: Vec<&str> = command.split_whitespace().collect();
+    if words.is_empty() {
+        return false;
+    }
+    prefixes.iter().any(|prefix| {
+        let prefix_words: Vec<&str> = prefix.split_whitespace().collect();
+        !prefix_words.is_empty() && words.starts_with(&prefix_words)
+    })
+}
+
 // ============================================================================
 // Helpers
 // ============================================================================
@@ -246,6 +468,116 @@ mod tests {
         assert!(check_output("shell", output).is_err());
     }
 
+    fn blocked_rule(policy: &ShellPolicy, command: &str) -> Option<String> {
+        match policy.check(command) {
+            SafetyVerdict::Block(reason) => Some(reason),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn default_shell_policy_blocks_destructive_commands() {
+        let policy = ShellPolicy::default();
+        for (command, rule) in [
+            ("rm -rf /", "rm-rf-root"),
+            ("/bin/rm -rf /", "rm-rf-root"),
+            ("sudo /usr/bin/rm -rf ~", "rm-rf-root"),
+            ("ls; ../../sbin/mkfs.ext4 /dev/sdb1", "mkfs"),
+            ("sudo rm -r -f --no-preserve-root /", "rm-rf-root"),
+            ("cd /tmp && rm -fr ~", "rm-rf-root"),
+            ("mkfs.ext4 /dev/sdb1", "mkfs"),
+            ("dd if=image.iso of=/dev/sda bs=4M", "dd-to-device"),
+            ("cat junk > /dev/nvme0n1", "redirect-to-device"),
+            (":(){ :|:& };:", "fork-bomb"),
+        ] {
+            let reason = blocked_rule(&policy, command)
+                .unwrap_or_else(|| panic!("'{}' should be blocked", command));
+            assert!(reason.contains(rule), "{}: {}", command, reason);
+        }
+    }
+
+    #[test]
+    fn default_shell_policy_allows_ordinary_commands() {
+        let policy = ShellPolicy::default();
+        for command in [
+            "ls -la /",
+            "rm -rf ./build /tmp/scratch",
+            "dd if=/dev/zero of=/dev/null count=1",
+            "echo done > /dev/null",
+            "git status",
+            "/usr/bin/rm -rf ./build",
+            "ls /bin/rm",
+        ] {
+            assert!(
+                blocked_rule(&policy, command).is_none(),
+                "'{}' should be allowed",
+                command
+            );
+        }
+    }
+
+    #[test]
+    fn config_can_add_and_remove_shell_rules() {
+        let policy = ShellPolicy::new(
+            &["mkfs".to_string()],
+            &[
+                "prefix:sudo ".to_string(),
+                "regex:\\bshutdown\\b".to_string(),
+                "regex:(".to_string(),
+            ],
+        );
+        assert!(blocked_rule(&policy, "mkfs.ext4 /dev/sdb1").is_none());
+        assert!(blocked_rule(&policy, "make && sudo make install")
+            .is_some_and(|reason| reason.contains("prefix:sudo")));
+        assert!(blocked_rule(&policy, "shutdown -h now").is_some());
+        assert!(blocked_rule(&policy, "echo pseudo").is_none());
+        assert_eq!(policy.rules().len(), default_shell_rules().len() - 1 + 2);
+    }
+
+    #[test]
+    fn auto_approval_is_word_anchored_and_refuses_metacharacters() {
+        let prefixes: Vec<String> = ["git status", "ls", "cat", "rg"]
+            .iter()
+            .map(|prefix| prefix.to_string())
+            .collect();
+        for command in [
+            "git status",
+            "  git   status --short",
+            "ls -la src",
+            "cat README.md",
+            "rg TODO src/",
+        ] {
+            assert!(
+                shell_command_is_auto_approved(command, &prefixes),
+                "'{}' should be auto-approved",
+                command
+            );
+        }
+        for command in [
+            "git push",
+            "git statusx",
+            "lsblk",
+            "echo hi; ls",
+            "ls; rm -rf ./build",
+            "ls && rm file",
+            "cat file | sh",
+            "cat $(which rm)",
+            "cat `id`",
+            "ls > listing.txt",
+            "cat 'a b'",
+            "ls\nrm file",
+            "",
+        ] {
+            assert!(
+                !shell_command_is_auto_approved(command, &prefixes),
+                "'{}' should need approval",
+                command
+            );
+        }
+        assert!(!shell_command_is_auto_approved("ls", &[]));
+        assert!(!shell_command_is_auto_approved("ls", &["  ".to_string()]));
+    }
+
     #[test]
     fn test_check_output_passes_clean() {
         let output = "Hello, world!";
@@ -1,16 +1,24 @@
 //! Shell command execution tool.
 //!
 //! Allows the agent to run shell commands on the host system.
-//! Always requires approval (configurable via ApprovalGate).
+//! Always requires approval (configurable via ApprovalGate), and commands
+//! matching the `safety::ShellPolicy` deny-list are refused outright.
+//! Commands matching `auto_approved_shell_prefixes` skip autonomous approval.
+//! With `stream: true`, stdout/stderr lines are forwarded through
+//! `ToolContext::progress` while the command runs.
 
 use anyhow::Result;
 use async_trait::async_trait;
+use std::process::Stdio;
 use std::sync::Arc;
 use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
 
+use crate::config::AgentConfig;
 use crate::process_registry::ProcessRegistry;
 
-use super::{Tool, ToolCategory, ToolContext, ToolOutput};
+use super::safety::{shell_command_is_auto_approved, SafetyVerdict, ShellPolicy};
+use super::{Tool, ToolCategory, ToolContext, ToolOutput, ToolProgressSink};
 
 /// Default command timeout in seconds
 const DEFAULT_TIMEOUT_SECS: u64 = 30;
@@ -21,6 +29,111 @@ const MAX_TIMEOUT_SECS: u64 = 300;
 /// Maximum output size before truncation (bytes)
 const MAX_OUTPUT_BYTES: usize = 100_000;
 
+/// Captured output of a streamed command, capped at `MAX_OUTPUT_BYTES`.
+#[derive(Debug, Default)]
+struct CappedOutput {
+    text: String,
+    truncated: bool,
+}
+
+impl CappedOutput {
+    fn push_line(&mut self, line: &str) {
+        if self.truncated {
+            return;
+        }
+        if self.text.len() + line.len() + 1 > MAX_OUTPUT_BYTES {
+            self.truncated = true;
+            return;
+        }
+        self.text.push_str(line);
+        self.text.push('\n');
+    }
+
+    fn finish(mut self, stream: &str) -> String {
+        if self.truncated {
+            self.text.push_str(&format!("\n[{} truncated]", stream));
+        }
+        self.text
+    }
+}
+
+/// Reads `reader` line by line into a capped buffer, reporting every line to
+/// `sink` (stderr lines are prefixed so the operator can tell them apart).
+/// Invalid UTF-8 is replaced rather than ending the stream.
+async fn stream_lines<R: AsyncRead + Unpin>(
+    reader: R,
+    stream: &str,
+    sink: &ToolProgressSink,
+) -> CappedOutput {
+    let mut captured = CappedOutput::default();
+    let mut reader = BufReader::new(reader);
+    let mut buf = Vec::new();
+    loop {
+        buf.clear();
+        match reader.read_until(b'\n', &mut buf).await {
+            Ok(0) | Err(_) => break,
+            Ok(_) => {}
+        }
+        if buf.ends_with(b"\n") {
+            buf.pop();
+            if buf.ends_with(b"\r") {
+                buf.pop();
+            }
+        }
+        let line = String::from_utf8_lossy(&buf);
+        if stream == "stderr" {
+            sink("shell", &format!("stderr: {}", line));
+        } else {
+            sink("shell", &line);
+        }
+        captured.push_line(&line);
+    }
+    captured
+}
+
+/// Runs `command`, streaming output lines to `sink` and returning the full
+/// capped stdout/stderr plus exit code once the process exits.
+async fn run_streaming(
+    command: &str,
+    working_dir: &str,
+    sink: &ToolProgressSink,
+) -> std::io::Result<(String, String, i32)> {
+    let mut child = tokio::process::Command::new("/bin/sh")
+        .arg("-c")
+        .arg(command)
+        .current_dir(working_dir)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()?;
+    let stdout = child.stdout.take().expect("stdout is piped");
+    let stderr = child.stderr.take().expect("stderr is piped");
+    let (stdout, stderr) = tokio::join!(
+        stream_lines(stdout, "stdout", sink),
+        stream_lines(stderr, "stderr", sink)
+    );
+    let status = child.wait().await?;
+    Ok((
+        stdout.finish("stdout"),
+        stderr.finish("stderr"),
+        status.code().unwrap_or(-1),
+    ))
+}
+
+fn format_command_result(exit_code: i32, stdout: &str, stderr: &str) -> String {
+    let mut result_text = String::new();
+    result_text.push_str(&format!("Exit code: {}\n", exit_code));
+
+    if !stdout.is_empty() {
+        result_text.push_str(&format!("\n--- stdout ---\n{}", stdout));
+    }
+    if !stderr.is_empty() {
+        result_text.push_str(&format!("\n--- stderr ---\n{}", stderr));
+    }
+    result_text
+}
+
 pub struct ShellTool {
     process_registry: Arc<ProcessRegistry>,
 }
@@ -61,6 +174,10 @@ impl Tool for ShellTool {
                 "background": {
                     "type": "boolean",
                     "description": "When true, start the command as a tracked background process and return immediately"
+                },
+                "stream": {
+                    "type": "boolean",
+                    "description": "When true, report stdout/stderr lines live while the command runs. Use for long builds or tests; leave off for quick commands"
                 }
             },
             "required": ["command"]
@@ -73,6 +190,13 @@ impl Tool for ShellTool {
             None => return Ok(ToolOutput::Error("Missing 'command' parameter".to_string())),
         };
 
+        let config = AgentConfig::load();
+        let policy = ShellPolicy::new(&config.shell_disabled_rules, &config.shell_deny_rules);
+        if let SafetyVerdict::Block(reason) = policy.check(command) {
+            tracing::warn!("ShellTool refused '{}': {}", command, reason);
+            return Ok(ToolOutput::Error(reason));
+        }
+
         let working_dir = params["working_directory"]
             .as_str()
             .unwrap_or(&ctx.working_directory);
@@ -82,6 +206,7 @@ impl Tool for ShellTool {
             .unwrap_or(DEFAULT_TIMEOUT_SECS)
             .min(MAX_TIMEOUT_SECS);
         let background = params["background"].as_bool().unwrap_or(false);
+        let stream = params["stream"].as_bool().unwrap_or(false);
 
         if background {
             let process = self.process_registry.start(command, working_dir).await?;
@@ -104,6 +229,27 @@ impl Tool for ShellTool {
             timeout_secs
         );
 
+        if let Some(sink) = ctx.progress.as_ref().filter(|_| stream) {
+            let result = tokio::time::timeout(
+                Duration::from_secs(timeout_secs),
+                run_streaming(command, working_dir, sink),
+            )
+            .await;
+            return match result {
+                Ok(Ok((stdout, stderr, exit_code))) => Ok(ToolOutput::Text(format_command_result(
+                    exit_code, &stdout, &stderr,
+                ))),
+                Ok(Err(e)) => Ok(ToolOutput::Error(format!(
+                    "Failed to execute command: {}",
+                    e
+                ))),
+                Err(_) => Ok(ToolOutput::Error(format!(
+                    "Command timed out after {} seconds",
+                    timeout_secs
+                ))),
+            };
+        }
+
         // Execute command
         let result = tokio::time::timeout(
             Duration::from_secs(timeout_secs),
@@ -131,15 +277,7 @@ impl Tool for ShellTool {
                     stderr.push_str("\n[stderr truncated]");
                 }
 
-                let mut result_text = String::new();
-                result_text.push_str(&format!("Exit code: {}\n", exit_code));
-
-                if !stdout.is_empty() {
-                    result_text.push_str(&format!("\n--- stdout ---\n{}", stdout));
-                }
-                if !stderr.is_empty() {
-                    result_text.push_str(&format!("\n--- stderr ---\n{}", stderr));
-                }
+                let result_text = format_command_result(exit_code, &stdout, &stderr);
 
                 if exit_code == 0 {
                     Ok(ToolOutput::Text(result_text))
@@ -163,6 +301,14 @@ impl Tool for ShellTool {
         true
     }
 
+    fn auto_approves(&self, params: &serde_json::Value) -> bool {
+        let Some(command) = params["command"].as_str() else {
+            return false;
+        };
+        let prefixes = AgentConfig::load().auto_approved_shell_prefixes;
+        !prefixes.is_empty() && shell_command_is_auto_approved(command, &prefixes)
+    }
+
     fn category(&self) -> ToolCategory {
         ToolCategory::Shell
     }
@@ -181,6 +327,7 @@ mod tests {
     fn test_ctx() -> ToolContext {
         ToolContext {
             working_directory: "/tmp".to_string(),
+            allowed_roots: Vec::new(),
             username: "test".to_string(),
             conversation_id: None,
             autonomous: false,
@@ -189,6 +336,9 @@ mod tests {
             disallowed_tools: Vec::new(),
             outbound_action_rate_limit: None,
             generation_observer: None,
+            progress: None,
+            approval_waiter: None,
+            timeouts: crate::tools::ToolTimeoutPolicy::default(),
         }
     }
 
@@ -229,6 +379,62 @@ mod tests {
         assert!(matches!(result, ToolOutput::Error(_)));
     }
 
+    #[tokio::test]
+    async fn denied_command_is_refused_before_spawning() {
+        let tool = ShellTool::new(Arc::new(ProcessRegistry::new()));
+        let params = serde_json::json!({"command": "mkfs.ext4 /dev/sdz9", "background": true});
+        let result = tool.execute(params, &test_ctx()).await.unwrap();
+        match result {
+            ToolOutput::Error(reason) => assert!(reason.contains("'mkfs'")),
+            other => panic!("Expected Error, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn streamed_command_reports_lines_and_keeps_full_output() {
+        let lines = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink_lines = Arc::clone(&lines);
+        let mut ctx = test_ctx();
+        ctx.progress = Some(Arc::new(move |tool: &str, line: &str| {
+            sink_lines
+                .lock()
+                .unwrap()
+                .push(format!("{}: {}", tool, line));
+        }));
+        let tool = ShellTool::new(Arc::new(ProcessRegistry::new()));
+        let params = serde_json::json!({
+            "command": "echo one; printf 'bad \\377 byte\\n'; echo two; echo oops >&2",
+            "stream": true
+        });
+        let result = tool.execute(params, &ctx).await.unwrap();
+
+        match result {
+            ToolOutput::Text(text) => {
+                assert!(text.contains("Exit code: 0"));
+                assert!(text.contains("--- stdout ---\none\nbad \u{FFFD} byte\ntwo\n"));
+                assert!(text.contains("--- stderr ---\noops\n"));
+            }
+            other => panic!("Expected Text, got {:?}", other),
+        }
+        let lines = lines.lock().unwrap();
+        assert!(lines.contains(&"shell: one".to_string()));
+        assert!(lines.contains(&"shell: two".to_string()));
+        assert!(lines.contains(&"shell: stderr: oops".to_string()));
+    }
+
+    #[test]
+    fn capped_output_annotates_truncation() {
+        let mut captured = CappedOutput::default();
+        let line = "x".repeat(MAX_OUTPUT_BYTES / 2);
+        captured.push_line(&line);
+        captured.push_line(&line);
+        captured.push_line("after cap");
+        let text = captured.finish("stdout");
+        assert!(text.len() <= MAX_OUTPUT_BYTES + "\n[stdout truncated]".len());
+        assert!(!text.contains("after cap"));
+        assert!(text.ends_with("[stdout truncated]"));
+    }
+
     #[test]
     fn test_requires_approval() {
         let tool = ShellTool::new(Arc::new(ProcessRegistry::new()));
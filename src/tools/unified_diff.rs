@@ -0,0 +1,358 @@
+//! Unified diff parsing and all-or-nothing application for `patch_file`.
+//!
+//! Hunks are located near their declared line number, tolerating line drift
+//! anywhere after the previous hunk, trailing-whitespace differences, and up
+//! to `MAX_FUZZ` unmatched context lines at either edge (GNU `patch` style
+//! fuzz). If any hunk is rejected nothing is returned to write.
+
+use serde::Serialize;
+
+/// Maximum number of leading/trailing context lines that may be ignored.
+const MAX_FUZZ: usize = 2;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum HunkLine {
+    Context(String),
+    Remove(String),
+    Add(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Hunk {
+    old_start: usize,
+    lines: Vec<HunkLine>,
+}
+
+impl Hunk {
+    fn old_lines(&self) -> Vec<&str> {
+        self.lines
+            .iter()
+            .filter_map(|line| match line {
+                HunkLine::Context(text) | HunkLine::Remove(text) => Some(text.as_str()),
+                HunkLine::Add(_) => None,
+            })
+            .collect()
+    }
+
+    /// Replacement for `matched`, the file lines the trimmed hunk matched.
+    /// Context keeps the file's own text so whitespace-insensitive matches
+    /// do not rewrite untouched lines.
+    fn replacement(&self, front: usize, back: usize, matched: &[String]) -> Vec<String> {
+        let mut file_lines = matched.iter();
+        let mut replacement = Vec::new();
+        for line in &self.lines[front..self.lines.len() - back] {
+            match line {
+                HunkLine::Context(_) => replacement.extend(file_lines.next().cloned()),
+                HunkLine::Remove(_) => {
+                    file_lines.next();
+                }
+                HunkLine::Add(text) => replacement.push(text.clone()),
+            }
+        }
+        replacement
+    }
+
+    fn leading_context(&self) -> usize {
+        self.lines
+            .iter()
+            .take_while(|line| matches!(line, HunkLine::Context(_)))
+            .count()
+    }
+
+    fn trailing_context(&self) -> usize {
+        self.lines
+            .iter()
+            .rev()
+            .take_while(|line| matches!(line, HunkLine::Context(_)))
+            .count()
+    }
+}
+
+/// Outcome for one hunk. `line` is 1-based in the patched file and `offset`
+/// is the drift from the header's declared position.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct HunkOutcome {
+    pub hunk: usize,
+    pub applied: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>,
+    pub offset: isize,
+    pub fuzz: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+/// Result of applying a diff. `content` is `Some` only when every hunk applied.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffApplication {
+    pub content: Option<String>,
+    pub hunks: Vec<HunkOutcome>,
+}
+
+impl DiffApplication {
+    pub fn rejected(&self) -> impl Iterator<Item = &HunkOutcome> {
+        self.hunks.iter().filter(|hunk| !hunk.applied)
+    }
+}
+
+fn parse_range_start(range: &str) -> Option<usize> {
+    range.split(',').next()?.parse().ok()
+}
+
+fn parse_hunk_header(line: &str) -> Option<usize> {
+    let rest = line.strip_prefix("@@")?;
+    let end = rest.find("@@")?;
+    let mut ranges = rest[..end].split_whitespace();
+    let old = ranges.next()?.strip_prefix('-')?;
+    ranges.next()?.strip_prefix('+')?;
+    parse_range_start(old)
+}
+
+fn is_file_header(lines: &[&str], index: usize) -> bool {
+    lines[index].starts_with("--- ")
+        && lines
+            .get(index + 1)
+            .is_some_and(|next| next.starts_with("+++ "))
+}
+
+fn parse_unified_diff(diff: &str) -> Result<Vec<Hunk>, String> {
+    let lines: Vec<&str> = diff.lines().collect();
+    let mut hunks: Vec<Hunk> = Vec::new();
+    let mut file_headers = 0usize;
+    let mut current: Option<Hunk> = None;
+    let mut index = 0usize;
+    while index < lines.len() {
+        let line = lines[index];
+        if is_file_header(&lines, index) {
+            hunks.extend(current.take());
+            file_headers += 1;
+            if file_headers > 1 {
+                return Err(
+                    "Diff touches more than one file; send one file per patch_file call"
+                        .to_string(),
+                );
+            }
+            index += 2;
+            continue;
+        }
+        if line.starts_with("@@") {
+            hunks.extend(current.take());
+            let old_start = parse_hunk_header(line)
+                .ok_or_else(|| format!("Malformed hunk header: '{}'", line))?;
+            current = Some(Hunk {
+                old_start,
+                lines: Vec::new(),
+            });
+            index += 1;
+            continue;
+        }
+        // Lines before the first hunk are preamble such as `diff --git` or `index`.
+        if let Some(hunk) = current.as_mut() {
+            if let Some(text) = line.strip_prefix(' ') {
+                hunk.lines.push(HunkLine::Context(text.to_string()));
+            } else if let Some(text) = line.strip_prefix('-') {
+                hunk.lines.push(HunkLine::Remove(text.to_string()));
+            } else if let Some(text) = line.strip_prefix('+') {
+                hunk.lines.push(HunkLine::Add(text.to_string()));
+            } else if line.is_empty() {
+                // Editors and models often strip the space from blank context lines.
+                hunk.lines.push(HunkLine::Context(String::new()));
+            } else if !line.starts_with('\\') {
+                return Err(format!(
+                    "Unexpected line in hunk {}: '{}'",
+                    hunks.len() + 1,
+                    line
+                ));
+            }
+        }
+        index += 1;
+    }
+    hunks.extend(current);
+    if hunks.is_empty() {
+        return Err("Diff contains no '@@' hunks".to_string());
+    }
+    if let Some(position) = hunks.iter().position(|hunk| {
+        !hunk
+            .lines
+            .iter()
+            .any(|line| !matches!(line, HunkLine::Context(_)))
+    }) {
+        return Err(format!("Hunk {} has no changes", position + 1));
+    }
+    Ok(hunks)
+}
+
+fn lines_match(file_line: &str, hunk_line: &str) -> bool {
+    file_line.trim_end() == hunk_line.trim_end()
+}
+
+/// Finds the match for `needle` at or after `min_start` closest to `expected`.
+fn find_closest(
+    haystack: &[String],
+    needle: &[&str],
+    min_start: usize,
+    expected: usize,
+) -> Option<usize> {
+    if needle.len() > haystack.len() {
+        return None;
+    }
+    let last_start = haystack.len() - needle.len();
+    if min_start > last_start {
+        return None;
+    }
+    let matches_at = |start: usize| {
+        haystack[start..start + needle.len()]
+            .iter()
+            .zip(needle)
+            .all(|(file_line, hunk_line)| lines_match(file_line, hunk_line))
+    };
+    let expected = expected.clamp(min_start, last_start);
+    (0..=last_start - min_start).find_map(|distance| {
+        let forward = expected + distance;
+        if forward <= last_start && matches_at(forward) {
+            return Some(forward);
+        }
+        let backward = expected.checked_sub(distance)?;
+        (backward >= min_start && matches_at(backward)).then_some(backward)
+    })
+}
+
+/// Applies `diff` to `original`. Every hunk is attempted so the report lists
+/// all rejects, but `content` is produced only if none were rejected. The
+/// file keeps its line ending, taken from its first line.
+pub fn apply_unified_diff(original: &str, diff: &str) -> Result<DiffApplication, String> {
+    let hunks = parse_unified_diff(diff)?;
+    let trailing_newline = original.ends_with('\n');
+    let line_ending = match original.find('\n') {
+        Some(end) if original[..end].ends_with('\r') => "\r\n",
+        _ => "\n",
+    };
+    let mut working: Vec<String> = original.lines().map(str::to_string).collect();
+    let mut outcomes = Vec::with_capacity(hunks.len());
+    let mut delta: isize = 0;
+    let mut min_start = 0usize;
+
+    for (index, hunk) in hunks.iter().enumerate() {
+        let old = hunk.old_lines();
+        let declared = hunk.old_start.saturating_sub(1);
+        // A pure insertion's header names the line it follows, not a line it replaces.
+        let declared = if old.is_empty() {
+            hunk.old_start
+        } else {
+            declared
+        };
+        let expected = (declared as isize + delta).max(0) as usize;
+        let leading = hunk.leading_context();
+        let trailing = hunk.trailing_context();
+
+        let located = (0..=MAX_FUZZ).find_map(|fuzz| {
+            let front = fuzz.min(leading);
+            let back = fuzz.min(trailing);
+            if fuzz > 0 && front + back == 0 {
+                return None;
+            }
+            if front + back >= old.len() && !old.is_empty() {
+                return None;
+            }
+            let needle = &old[front..old.len() - back];
+            let start = if needle.is_empty() {
+                Some(expected.clamp(min_start, working.len().max(min_start)))
+            } else {
+                find_closest(&working, needle, min_start, expected + front)
+            }?;
+            Some((fuzz, front, back, start))
+        });
+
+        match located {
+            Some((fuzz, front, back, start)) => {
+                let old_len = old.len() - front - back;
+                let replacement = hunk.replacement(front, back, &working[start..start + old_len]);
+                let new_len = replacement.len();
+                working.splice(start..start + old_len, replacement);
+                let offset = start as isize - front as isize - declared as isize - delta;
+                outcomes.push(HunkOutcome {
+                    hunk: index + 1,
+                    applied: true,
+                    line: Some(start + 1),
+                    offset,
+                    fuzz,
+                    reason: None,
+                });
+                delta += new_len as isize - old_len as isize;
+                min_start = start + new_len;
+            }
+            None => outcomes.push(HunkOutcome {
+                hunk: index + 1,
+                applied: false,
+                line: None,
+                offset: 0,
+                fuzz: 0,
+                reason: Some(format!(
+                    "context near line {} not found in the file",
+                    hunk.old_start
+                )),
+            }),
+        }
+    }
+
+    let content = outcomes.iter().all(|outcome| outcome.applied).then(|| {
+        let mut content = working.join(line_ending);
+        if trailing_newline && !content.is_empty() {
+            content.push_str(line_ending);
+        }
+        content
+    });
+    Ok(DiffApplication {
+        content,
+        hunks: outcomes,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ORIGINAL: &str =
+        "fn main() {\n    let a = 1;\n    let b = 2;\n    println!(\"{}\", a + b);\n}\n";
+
+    #[test]
+    fn parses_headers_and_rejects_multi_file_diffs() {
+        let hunks = parse_unified_diff("--- a/x\n+++ b/x\n@@ -3 +3 @@\n-old\n+new\n").unwrap();
+        assert_eq!(hunks.len(), 1);
+        assert_eq!(hunks[0].old_start, 3);
+
+        let error = parse_unified_diff("--- a/x\n+++ b/x\n@@ -1 +1 @@\n-a\n+b\n--- a/y\n+++ b/y\n")
+            .unwrap_err();
+        assert!(error.contains("more than one file"));
+        assert!(parse_unified_diff("just text").is_err());
+    }
+
+    #[test]
+    fn pure_insertion_lands_after_declared_line() {
+        let diff = "@@ -2,0 +3,1 @@\n+    let c = 3;\n";
+        let result = apply_unified_diff(ORIGINAL, diff).unwrap();
+        assert_eq!(
+            result.content.unwrap(),
+            "fn main() {\n    let a = 1;\n    let c = 3;\n    let b = 2;\n    println!(\"{}\", a + b);\n}\n"
+        );
+    }
+
+    #[test]
+    fn fuzz_ignores_mismatched_outer_context() {
+        let diff = "@@ -1,4 +1,4 @@\n fn start() {\n     let a = 1;\n-    let b = 2;\n+    let b = 20;\n     println!(\"{}\", a + b);\n";
+        let result = apply_unified_diff(ORIGINAL, diff).unwrap();
+        assert_eq!(result.hunks[0].fuzz, 1);
+        assert!(result.content.unwrap().contains("let b = 20;"));
+    }
+
+    #[test]
+    fn crlf_files_keep_their_line_endings() {
+        let original = ORIGINAL.replace('\n', "\r\n");
+        let diff = "@@ -3 +3 @@\n-    let b = 2;\n+    let b = 20;\n";
+        let result = apply_unified_diff(&original, diff).unwrap();
+        assert_eq!(
+            result.content.unwrap(),
+            ORIGINAL.replace("= 2;", "= 20;").replace('\n', "\r\n")
+        );
+    }
+}
@@ -16,10 +16,13 @@ use tokio::process::Command;
 use crate::config::AgentConfig;
 use crate::generation_telemetry::GenerationSource;
 use crate::llm_client::LlmClient;
+use crate::llm_provider::LlmProvider;
+use crate::presence::WindowBounds;
 
 use super::{Tool, ToolCategory, ToolContext, ToolOutput};
 
 const MAX_IMAGE_BYTES: usize = 15 * 1024 * 1024;
+const MAX_OCR_TEXT_CHARS: usize = 4000;
 
 pub struct EvaluateLocalImageTool;
 
@@ -116,7 +119,8 @@ impl Tool for EvaluateLocalImageTool {
             normalize_api_url_for_chat(&config.llm_api_url),
             config.llm_api_key.unwrap_or_default(),
             chosen_model.clone(),
-        );
+        )
+        .with_provider(LlmProvider::from_config(&config.llm_provider));
         if let Some(observer) = &ctx.generation_observer {
             llm_client =
                 llm_client.with_generation_observer(observer.with_source(GenerationSource::Vision));
@@ -495,6 +499,90 @@ pub async fn capture_screen_to_path(output_path: &Path) -> Result<()> {
     anyhow::bail!("Screen capture is not supported on this OS")
 }
 
+/// Extract on-screen text from an image with the `tesseract` CLI.
+///
+/// Returns `Ok(None)` when the image contains no recognizable text. A missing
+/// binary or failed run is an error so callers can fall back to image-only.
+pub async fn extract_text_with_ocr(image_path: &Path) -> Result<Option<String>> {
+    let output = Command::new("tesseract")
+        .arg(image_path)
+        .arg("stdout")
+        .output()
+        .await
+        .context("Failed to execute 'tesseract' (is it installed and on PATH?)")?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!(
+            "tesseract failed (status {}): {}",
+            output.status,
+            stderr.trim()
+        );
+    }
+    Ok(normalize_ocr_text(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Blacks out `regions` (clamped to the image) and re-encodes as PNG.
+/// Regions are in desktop coordinates and are scaled by the image size over
+/// `desktop_size`, rounding outward, so HiDPI screenshots are fully covered.
+pub fn redact_image_regions(
+    image_bytes: &[u8],
+    regions: &[WindowBounds],
+    desktop_size: (u32, u32),
+) -> Result<Vec<u8>> {
+    let mut image = image::load_from_memory(image_bytes)
+        .context("Failed to decode screenshot for redaction")?
+        .to_rgba8();
+    let (image_width, image_height) = image.dimensions();
+    let (desktop_width, desktop_height) = desktop_size;
+    if desktop_width == 0 || desktop_height == 0 {
+        anyhow::bail!("desktop size is empty");
+    }
+    let scale_x = image_width as f64 / desktop_width as f64;
+    let scale_y = image_height as f64 / desktop_height as f64;
+    let to_pixels = |value: f64, scale: f64, limit: u32, round: fn(f64) -> f64| {
+        round(value * scale).clamp(0.0, limit as f64) as u32
+    };
+    for region in regions {
+        let x = region.x as f64;
+        let y = region.y as f64;
+        let left = to_pixels(x, scale_x, image_width, f64::floor);
+        let top = to_pixels(y, scale_y, image_height, f64::floor);
+        let right = to_pixels(x + region.width as f64, scale_x, image_width, f64::ceil);
+        let bottom = to_pixels(y + region.height as f64, scale_y, image_height, f64::ceil);
+        for y in top..bottom {
+            for x in left.min(right)..right {
+                image.put_pixel(x, y, image::Rgba([0, 0, 0, 255]));
+            }
+        }
+    }
+    let mut encoded = Vec::new();
+    image::DynamicImage::ImageRgba8(image)
+        .write_to(
+            &mut std::io::Cursor::new(&mut encoded),
+            image::ImageFormat::Png,
+        )
+        .context("Failed to encode redacted screenshot")?;
+    Ok(encoded)
+}
+
+fn normalize_ocr_text(raw: &str) -> Option<String> {
+    let joined = raw
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n");
+    if joined.is_empty() {
+        return None;
+    }
+    if joined.chars().count() <= MAX_OCR_TEXT_CHARS {
+        return Some(joined);
+    }
+    let mut truncated: String = joined.chars().take(MAX_OCR_TEXT_CHARS).collect();
+    truncated.push_str("\n[ocr text truncated]");
+    Some(truncated)
+}
+
 async fn capture_camera_to_path(
     output_path: &Path,
     device_index: u32,
@@ -718,6 +806,64 @@ fn mime_type_from_path(path: &str) -> String {
 mod tests {
     use super::*;
 
+    #[test]
+    fn redaction_blacks_out_clamped_regions() {
+        let mut source = Vec::new();
+        image::DynamicImage::ImageRgba8(image::RgbaImage::from_pixel(
+            4,
+            4,
+            image::Rgba([255, 255, 255, 255]),
+        ))
+        .write_to(
+            &mut std::io::Cursor::new(&mut source),
+            image::ImageFormat::Png,
+        )
+        .unwrap();
+
+        let region = WindowBounds {
+            x: -2,
+            y: 2,
+            width: 4,
+            height: 10,
+        };
+        let redacted = redact_image_regions(&source, &[region], (4, 4)).unwrap();
+        let image = image::load_from_memory(&redacted).unwrap().to_rgba8();
+        assert_eq!(image.get_pixel(0, 3), &image::Rgba([0, 0, 0, 255]));
+        assert_eq!(image.get_pixel(1, 2), &image::Rgba([0, 0, 0, 255]));
+        assert_eq!(image.get_pixel(2, 3), &image::Rgba([255, 255, 255, 255]));
+        assert_eq!(image.get_pixel(0, 1), &image::Rgba([255, 255, 255, 255]));
+    }
+
+    #[test]
+    fn redaction_scales_logical_bounds_onto_screenshot_pixels() {
+        let mut source = Vec::new();
+        image::DynamicImage::ImageRgba8(image::RgbaImage::from_pixel(
+            200,
+            100,
+            image::Rgba([255, 255, 255, 255]),
+        ))
+        .write_to(
+            &mut std::io::Cursor::new(&mut source),
+            image::ImageFormat::Png,
+        )
+        .unwrap();
+        let window = WindowBounds {
+            x: 10,
+            y: 10,
+            width: 20,
+            height: 10,
+        };
+
+        let redacted = redact_image_regions(&source, &[window], (100, 50)).expect("redact");
+        let image = image::load_from_memory(&redacted).unwrap().to_rgba8();
+        let black = image::Rgba([0, 0, 0, 255]);
+        assert_eq!(*image.get_pixel(20, 20), black);
+        assert_eq!(*image.get_pixel(59, 39), black);
+        assert_ne!(*image.get_pixel(60, 20), black);
+        assert_ne!(*image.get_pixel(20, 40), black);
+        assert!(redact_image_regions(&source, &[window], (0, 50)).is_err());
+    }
+
     #[test]
     fn detects_supported_images() {
         assert!(is_supported_image_path("a.png"));
@@ -733,6 +879,21 @@ mod tests {
         assert_eq!(media_kind_from_path("d.bin"), "file");
     }
 
+    #[test]
+    fn ocr_text_drops_blank_lines_and_is_capped() {
+        assert_eq!(normalize_ocr_text("  \n\n \u{c}"), None);
+        assert_eq!(
+            normalize_ocr_text("  Error: disk full \n\n  [OK]  \n"),
+            Some("Error: disk full\n[OK]".to_string())
+        );
+        let long = "x".repeat(MAX_OCR_TEXT_CHARS + 10);
+        let capped = normalize_ocr_text(&long).expect("text");
+        let body = capped
+            .strip_suffix("\n[ocr text truncated]")
+            .expect("truncation marker");
+        assert_eq!(body.chars().count(), MAX_OCR_TEXT_CHARS);
+    }
+
     #[test]
     fn normalizes_v1_api_urls() {
         assert_eq!(
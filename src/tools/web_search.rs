@@ -0,0 +1,347 @@
+//! Web search tool backed by an operator-configured search endpoint.
+//!
+//! Supports SearXNG (`format=json`) and Brave Search API response shapes and
+//! normalizes both into a `[{title, url, snippet}]` JSON array. The endpoint is
+//! trusted operator configuration, so it is not subject to the private-host
+//! blocking `http_fetch` applies to model-chosen URLs; only the query text is
+//! leak-checked before it leaves the machine.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::time::Duration;
+
+use crate::config::AgentConfig;
+use crate::http_client::build_http_client_with_timeout;
+
+use super::safety::{detect_leaks, SafetyVerdict};
+use super::{Tool, ToolCategory, ToolContext, ToolOutput};
+
+const DEFAULT_MAX_RESULTS: usize = 5;
+const MAX_MAX_RESULTS: usize = 20;
+const SEARCH_TIMEOUT_SECS: u64 = 20;
+const MAX_SNIPPET_CHARS: usize = 500;
+
+pub const WEB_SEARCH_PROVIDER_SEARXNG: &str = "searxng";
+pub const WEB_SEARCH_PROVIDER_BRAVE: &str = "brave";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum WebSearchProvider {
+    Searxng,
+    Brave,
+}
+
+impl WebSearchProvider {
+    fn from_config(raw: &str) -> Self {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            WEB_SEARCH_PROVIDER_BRAVE => Self::Brave,
+            _ => Self::Searxng,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct WebSearchSettings {
+    endpoint: Url,
+    provider: WebSearchProvider,
+    api_key: Option<String>,
+}
+
+impl WebSearchSettings {
+    fn from_config(config: &AgentConfig) -> std::result::Result<Self, String> {
+        let raw = config
+            .web_search_url
+            .as_deref()
+            .map(str::trim)
+            .filter(|url| !url.is_empty())
+            .ok_or_else(|| {
+                "Web search is not configured. Set `web_search_url` to a SearXNG or Brave Search endpoint.".to_string()
+            })?;
+        let endpoint =
+            Url::parse(raw).map_err(|e| format!("Invalid web_search_url '{}': {}", raw, e))?;
+        if !matches!(endpoint.scheme(), "http" | "https") {
+            return Err("web_search_url must use http:// or https://".to_string());
+        }
+        Ok(Self {
+            endpoint,
+            provider: WebSearchProvider::from_config(&config.web_search_provider),
+            api_key: config
+                .web_search_api_key
+                .as_deref()
+                .map(str::trim)
+                .filter(|key| !key.is_empty())
+                .map(str::to_string),
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WebSearchResult {
+    pub title: String,
+    pub url: String,
+    pub snippet: String,
+}
+
+pub struct WebSearchTool;
+
+impl WebSearchTool {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl Tool for WebSearchTool {
+    fn name(&self) -> &str {
+        "web_search"
+    }
+
+    fn description(&self) -> &str {
+        "Search the web through the configured search endpoint and return result titles, URLs, and snippets."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "query": {
+                    "type": "string",
+                    "description": "Search query text"
+                },
+                "max_results": {
+                    "type": "integer",
+                    "description": "Maximum results to return (default 5, max 20)"
+                }
+            },
+            "required": ["query"]
+        })
+    }
+
+    async fn execute(&self, params: Value, _ctx: &ToolContext) -> Result<ToolOutput> {
+        let query = match params.get("query").and_then(Value::as_str).map(str::trim) {
+            Some(v) if !v.is_empty() => v,
+            _ => {
+                return Ok(ToolOutput::Error(
+                    "Missing required 'query' parameter".to_string(),
+                ))
+            }
+        };
+        let max_results = params
+            .get("max_results")
+            .and_then(Value::as_u64)
+            .map(|v| v as usize)
+            .unwrap_or(DEFAULT_MAX_RESULTS)
+            .clamp(1, MAX_MAX_RESULTS);
+
+        match detect_leaks(query) {
+            SafetyVerdict::Allow => {}
+            SafetyVerdict::Warn(reason) => {
+                tracing::warn!("Web search query leak warning: {}", reason);
+            }
+            SafetyVerdict::Block(reason) => {
+                return Ok(ToolOutput::Error(format!(
+                    "Search query blocked: {}",
+                    reason
+                )))
+            }
+        }
+
+        let settings = match WebSearchSettings::from_config(&AgentConfig::load()) {
+            Ok(settings) => settings,
+            Err(reason) => return Ok(ToolOutput::Error(reason)),
+        };
+
+        match search(&settings, query, max_results).await {
+            Ok(results) => Ok(ToolOutput::Json(json!(results))),
+            Err(reason) => Ok(ToolOutput::Error(reason)),
+        }
+    }
+
+    fn requires_approval(&self) -> bool {
+        true
+    }
+
+    fn category(&self) -> ToolCategory {
+        ToolCategory::Network
+    }
+}
+
+async fn search(
+    settings: &WebSearchSettings,
+    query: &str,
+    max_results: usize,
+) -> std::result::Result<Vec<WebSearchResult>, String> {
+    let mut url = settings.endpoint.clone();
+    {
+        let mut pairs = url.query_pairs_mut();
+        pairs.append_pair("q", query);
+        match settings.provider {
+            WebSearchProvider::Searxng => {
+                pairs.append_pair("format", "json");
+            }
+            WebSearchProvider::Brave => {
+                pairs.append_pair("count", &max_results.to_string());
+            }
+        }
+    }
+
+    let client = build_http_client_with_timeout(Some(Duration::from_secs(SEARCH_TIMEOUT_SECS)));
+    let mut request = client.get(url).header("Accept", "application/json");
+    if let Some(key) = settings.api_key.as_deref() {
+        request = match settings.provider {
+            WebSearchProvider::Brave => request.header("X-Subscription-Token", key),
+            WebSearchProvider::Searxng => request.bearer_auth(key),
+        };
+    }
+
+    let response = request
+        .send()
+        .await
+        .map_err(|e| format!("Web search request failed: {}", e))?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(format!(
+            "Web search endpoint returned HTTP {}",
+            status.as_u16()
+        ));
+    }
+    let body: Value = response
+        .json()
+        .await
+        .map_err(|e| format!("Web search response was not valid JSON: {}", e))?;
+
+    Ok(parse_search_results(settings.provider, &body, max_results))
+}
+
+fn parse_search_results(
+    provider: WebSearchProvider,
+    body: &Value,
+    max_results: usize,
+) -> Vec<WebSearchResult> {
+    let (items, snippet_key) = match provider {
+        WebSearchProvider::Searxng => (body.get("results"), "content"),
+        WebSearchProvider::Brave => (
+            body.get("web").and_then(|web| web.get("results")),
+            "description",
+        ),
+    };
+
+    items
+        .and_then(Value::as_array)
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|item| {
+                    let url = item.get("url").and_then(Value::as_str)?.trim();
+                    if url.is_empty() {
+                        return None;
+                    }
+                    let title = item
+                        .get("title")
+                        .and_then(Value::as_str)
+                        .unwrap_or(url)
+                        .trim();
+                    let snippet = item
+                        .get(snippet_key)
+                        .and_then(Value::as_str)
+                        .unwrap_or("")
+                        .trim();
+                    Some(WebSearchResult {
+                        title: title.to_string(),
+                        url: url.to_string(),
+                        snippet: snippet.chars().take(MAX_SNIPPET_CHARS).collect(),
+                    })
+                })
+                .take(max_results)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    #[tokio::test]
+    async fn searxng_search_parses_mocked_result_array() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind");
+        let address = listener.local_addr().expect("local address");
+        let server = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.expect("accept");
+            let mut request = vec![0_u8; 4096];
+            let read = stream.read(&mut request).await.expect("read request");
+            let request = String::from_utf8_lossy(&request[..read]).to_string();
+            let body = json!({
+                "results": [
+                    {"title": "Rust", "url": "https://www.rust-lang.org", "content": "A language"},
+                    {"title": "No URL"},
+                    {"title": "Crates", "url": "https://crates.io", "content": "Registry"},
+                    {"title": "Docs", "url": "https://docs.rs", "content": "Docs"}
+                ]
+            })
+            .to_string();
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            stream
+                .write_all(response.as_bytes())
+                .await
+                .expect("write response");
+            request
+        });
+
+        let settings = WebSearchSettings {
+            endpoint: Url::parse(&format!("http://{address}/search")).unwrap(),
+            provider: WebSearchProvider::Searxng,
+            api_key: None,
+        };
+        let results = search(&settings, "rust lang", 2)
+            .await
+            .expect("mocked search should succeed");
+
+        assert_eq!(
+            results,
+            vec![
+                WebSearchResult {
+                    title: "Rust".to_string(),
+                    url: "https://www.rust-lang.org".to_string(),
+                    snippet: "A language".to_string(),
+                },
+                WebSearchResult {
+                    title: "Crates".to_string(),
+                    url: "https://crates.io".to_string(),
+                    snippet: "Registry".to_string(),
+                },
+            ]
+        );
+        let request = server.await.expect("server task");
+        assert!(request.starts_with("GET /search?q=rust+lang&format=json "));
+    }
+
+    #[test]
+    fn brave_results_use_description_as_snippet() {
+        let body = json!({
+            "web": {
+                "results": [
+                    {"title": "Example", "url": "https://example.com", "description": "An example"}
+                ]
+            }
+        });
+        let results = parse_search_results(WebSearchProvider::Brave, &body, 5);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].snippet, "An example");
+    }
+
+    #[test]
+    fn unconfigured_endpoint_is_reported() {
+        let config = AgentConfig::default();
+        let error = WebSearchSettings::from_config(&config).expect_err("no endpoint configured");
+        assert!(error.contains("web_search_url"));
+    }
+}